@@ -174,6 +174,8 @@ pub async fn base_node_test_config_with_rng<R: Rng>(
             // during test operations. The default 30s can cause flaky failures when
             // transient connections expire before being promoted or before operations complete.
             transient_ttl_secs: Some(120),
+            hosting_budget: None,
+            hosting_min_ttl_secs: None,
             total_bandwidth_limit: None,
             min_bandwidth_per_connection: None,
             streaming_enabled: None,
@@ -67,7 +67,15 @@ def main() -> None:
     # The deployer account is always used as admin; the args file "admin" field
     # is ignored in favor of --deployer-acct (avoids identity name mismatches in CI).
     admin_identity = args.deployer_acct
+    # The treasurer defaults to the deployer; set "treasurer" to a Stellar CLI
+    # identity name to split treasury withdrawals from the admin role.
+    treasurer_identity = constructor_args.get("treasurer") or admin_identity
+    # The treasury account receives the non-burned share of deposits and
+    # defaults to the treasurer.
+    treasury_identity = constructor_args.get("treasury") or treasurer_identity
     burn_bps = constructor_args.get("burn_bps", 3000)
+    # Optional SEP-40 price-oracle contract ID for USD valuation of deposits.
+    price_oracle = constructor_args.get("price_oracle")
 
     # Step 1: Upload WASM
     print("=== Uploading WASM ===")
@@ -86,6 +94,12 @@ def main() -> None:
     deployer_address = run_capture([
         "stellar", "keys", "address", admin_identity,
     ])
+    treasurer_address = run_capture([
+        "stellar", "keys", "address", treasurer_identity,
+    ])
+    treasury_address = run_capture([
+        "stellar", "keys", "address", treasury_identity,
+    ])
 
     # Get native XLM SAC address for the target network
     native_xlm_address = run_capture([
@@ -95,16 +109,21 @@ def main() -> None:
     ])
     print(f"  Native XLM SAC address: {native_xlm_address}")
 
-    contract_id = run_capture([
+    deploy_cmd = [
         "stellar", "contract", "deploy",
         "--wasm-hash", wasm_hash,
         "--source-account", args.deployer_acct,
         "--network", args.network,
         "--",
         "--admin", deployer_address,
+        "--treasurer", treasurer_address,
+        "--treasury", treasury_address,
         "--burn_bps", str(burn_bps),
         "--token", native_xlm_address,
-    ])
+    ]
+    if price_oracle:
+        deploy_cmd += ["--price_oracle", price_oracle]
+    contract_id = run_capture(deploy_cmd)
     print(f"  Contract ID: {contract_id}")
 
     # Step 3: Save deployment info
@@ -117,8 +136,11 @@ def main() -> None:
         "contract_id": contract_id,
         "wasm_hash": wasm_hash,
         "admin": deployer_address,
+        "treasurer": treasurer_address,
+        "treasury": treasury_address,
         "burn_bps": burn_bps,
         "token": native_xlm_address,
+        "price_oracle": price_oracle,
         "network": args.network,
     }
 
@@ -127,6 +149,17 @@ def main() -> None:
         f.write("\n")
 
     print(f"=== Deployment saved to {DEPLOYMENTS_FILE} ===")
+
+    # Payouts (claims, refunds, withdrawals) are made from the treasury
+    # account through a token allowance it grants the service contract.
+    print("=== Treasury allowance required ===")
+    print(f"  The treasury ({treasury_identity}) must approve the contract as a spender:")
+    print(
+        f"  stellar contract invoke --id {native_xlm_address}"
+        f" --source-account {treasury_identity} --network {args.network}"
+        f" -- approve --from {treasury_address} --spender {contract_id}"
+        f" --amount <max_payout_stroops> --expiration_ledger <ledger>"
+    )
     print(f"=== Done: {contract_id} ===")
 
 
@@ -0,0 +1,64 @@
+use ed25519_dalek::{Signature, VerifyingKey};
+use sha2::{Digest, Sha256};
+
+use crate::types::{hex_decode, hex_decode_32, DepositMap, DepositProof, RelayRecord};
+
+/// Relayers remembered in `diagnostics.recent_relays`, newest last.
+pub const RECENT_RELAYS: usize = 64;
+
+/// Domain separator, so an attribution signature can't be replayed as any
+/// other message signed with the relayer's Stellar key.
+const ATTRIBUTION_DOMAIN: &[u8] = b"lepus-relayer-attribution-v1";
+
+/// The digest a relayer signs to attribute `proof` to itself:
+/// `SHA-256(domain || ledger_seq || offset || total_metas || fields)`, the
+/// integers big-endian and each field (the transaction set, then every
+/// transaction result meta) prefixed with its length.
+pub fn attribution_message(proof: &DepositProof) -> [u8; 32] {
+    let (offset, total_metas) = match proof.part {
+        Some(part) => (part.offset, part.total_metas),
+        None => (0, proof.tx_result_metas.len() as u32),
+    };
+    let mut hasher = Sha256::new();
+    hasher.update(ATTRIBUTION_DOMAIN);
+    hasher.update(proof.ledger_seq.to_be_bytes());
+    hasher.update(offset.to_be_bytes());
+    hasher.update(total_metas.to_be_bytes());
+    for field in std::iter::once(&proof.transaction_set).chain(&proof.tx_result_metas) {
+        hasher.update((field.len() as u64).to_be_bytes());
+        hasher.update(field.as_bytes());
+    }
+    hasher.finalize().into()
+}
+
+/// The relayer `proof` is attributed to, if it carries an attribution whose
+/// signature verifies.
+pub fn verified_relayer(proof: &DepositProof) -> Option<String> {
+    let attribution = proof.attribution.as_ref()?;
+    let public_key = hex_decode_32(&attribution.relayer).ok()?;
+    let signature: [u8; 64] = hex_decode(&attribution.signature).ok()?.try_into().ok()?;
+    VerifyingKey::from_bytes(&public_key)
+        .ok()?
+        .verify_strict(
+            &attribution_message(proof),
+            &Signature::from_bytes(&signature),
+        )
+        .ok()?;
+    Some(attribution.relayer.to_ascii_lowercase())
+}
+
+/// Record who relayed `proof` once it has been applied. Unattributed proofs
+/// and ones whose signature doesn't verify are applied all the same, only
+/// not recorded.
+pub fn record(proof: &DepositProof, map: &mut DepositMap) {
+    let Some(relayer) = verified_relayer(proof) else {
+        return;
+    };
+    let relays = &mut map.diagnostics.recent_relays;
+    relays.push(RelayRecord {
+        ledger_seq: proof.ledger_seq,
+        relayer,
+    });
+    let excess = relays.len().saturating_sub(RECENT_RELAYS);
+    relays.drain(..excess);
+}
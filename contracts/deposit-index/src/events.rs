@@ -1,4 +1,4 @@
-use crate::types::hex_encode;
+use crate::types::{hex_encode, DrainSchedule, PendingRefund};
 use freenet_stdlib::prelude::*;
 use stellar_xdr::curr::{
     ContractEvent, ContractEventBody, ContractEventType, Int128Parts, Limits, ReadXdr, ScVal,
@@ -12,18 +12,95 @@ pub struct ExtractedDeposit {
     pub contract_id: String,
     /// Amount in stroops
     pub amount: i128,
+    /// USD value at deposit time (7 decimals; 0 when unpriced or absent)
+    pub usd_value: i128,
     /// Ledger sequence where the event was emitted
     pub ledger_seq: u32,
 }
 
-/// Decode base64-encoded TransactionResultMeta entries and extract DEPOSIT events
-/// that match the given hvym contract address.
-pub fn extract_deposits(
+/// A streaming-schedule event extracted from transaction metadata.
+#[derive(Debug, Clone)]
+pub struct ExtractedStream {
+    /// Freenet contract ID (hex 32 bytes)
+    pub contract_id: String,
+    /// Drain schedule as published by the Soroban contract
+    pub schedule: DrainSchedule,
+}
+
+/// Any hvym-freenet-service event the index consumes.
+#[derive(Debug, Clone)]
+pub enum ExtractedEvent {
+    /// DEPOSIT or TOPUP: adds to the cumulative deposit.
+    Deposit(ExtractedDeposit),
+    /// STREAM: replaces the entry's drain schedule.
+    Stream(ExtractedStream),
+    /// REFUNDREQ: marks the entry as pending reversal.
+    RefundRequested {
+        contract_id: String,
+        refund: PendingRefund,
+    },
+    /// REFUNDREJ: clears a pending reversal.
+    RefundRejected { contract_id: String },
+    /// REFUND: the deposit was reversed and its on-chain record removed.
+    Refunded { contract_id: String },
+    /// EXPIRE: the deposit was swept to the treasury and its record removed.
+    Expired { contract_id: String },
+    /// CLAWBACK: the deposit was clawed back by the admin and its record removed.
+    ClawedBack { contract_id: String },
+    /// WITHDRAW: the depositor withdrew part (or all, when `remaining_total`
+    /// is 0) of the deposit.
+    Withdrawn {
+        contract_id: String,
+        remaining_total: i128,
+    },
+}
+
+/// Event topics that add to a contract's cumulative deposit.
+///
+/// `DEPOSIT` is emitted for the first funding of a contract ID and `TOPUP`
+/// for every later one; both carry the added amount at data index 1.
+const FUNDING_TOPICS: [&[u8]; 2] = [b"DEPOSIT", b"TOPUP"];
+
+/// Topic of the streaming-schedule event.
+const STREAM_TOPIC: &[u8] = b"STREAM";
+
+/// Topics of the two-phase refund flow.
+const REFUND_REQUEST_TOPIC: &[u8] = b"REFUNDREQ";
+const REFUND_REJECT_TOPIC: &[u8] = b"REFUNDREJ";
+const REFUND_TOPIC: &[u8] = b"REFUND";
+
+/// Topic emitted when an expired deposit is swept to the treasury.
+const EXPIRE_TOPIC: &[u8] = b"EXPIRE";
+
+/// Topic emitted when an emergency clawback is executed.
+const CLAWBACK_TOPIC: &[u8] = b"CLAWBACK";
+
+/// Topic emitted when a depositor withdraws. The treasurer's treasury
+/// withdrawal uses the same symbol without a contract ID topic.
+const WITHDRAW_TOPIC: &[u8] = b"WITHDRAW";
+
+/// Event data layouts the index can parse, keyed by schema version.
+///
+/// - v1: events without a version topic. Trailing fields were appended over
+///   time (e.g. `usd_value`), so they are optional.
+/// - v2: `topics[2] == U32(2)`. Every field of the current layout is required.
+///
+/// Events with any other version are skipped rather than misparsed.
+const SCHEMA_V1: u32 = 1;
+const SCHEMA_V2: u32 = 2;
+
+/// Decode base64-encoded TransactionResultMeta entries and extract the
+/// funding, streaming and refund events that match the given hvym contract
+/// address.
+///
+/// Events are returned in emission order so schedule updates apply after the
+/// funding event that triggered them.
+pub fn extract_events(
     b64_metas: &[String],
     hvym_contract_addr: &[u8; 32],
     ledger_seq: u32,
-) -> Result<Vec<ExtractedDeposit>, ContractError> {
-    let mut deposits = Vec::new();
+) -> Result<Vec<ExtractedEvent>, ContractError> {
+    let mut extracted = Vec::new();
 
     for b64 in b64_metas {
         let meta_bytes = base64::decode(b64)
@@ -36,13 +113,13 @@ pub fn extract_deposits(
         let events = extract_events_from_meta(&result_meta.tx_apply_processing);
 
         for event in events {
-            if let Some(deposit) = try_extract_deposit(event, hvym_contract_addr, ledger_seq) {
-                deposits.push(deposit);
+            if let Some(e) = try_extract_event(event, hvym_contract_addr, ledger_seq) {
+                extracted.push(e);
             }
         }
     }
 
-    Ok(deposits)
+    Ok(extracted)
 }
 
 /// Extract ContractEvent references from TransactionMeta.
@@ -61,19 +138,20 @@ fn extract_events_from_meta(meta: &TransactionMeta) -> Vec<&ContractEvent> {
     }
 }
 
-/// Try to extract a DEPOSIT event from a ContractEvent.
+/// Try to extract an indexed event from a ContractEvent.
 ///
 /// Matches events where:
 /// - type == Contract
 /// - contract_id == hvym_contract_address
-/// - topics[0] == Symbol("DEPOSIT")
+/// - topics[0] == Symbol("DEPOSIT" | "TOPUP" | "STREAM" | "REFUNDREQ" | "REFUNDREJ" | "REFUND"
+///   | "EXPIRE" | "CLAWBACK" | "WITHDRAW")
 /// - topics[1] == Bytes(freenet_contract_id)
-/// - data is a tuple containing amount (i128) and ledger_seq (u32)
-fn try_extract_deposit(
+/// - topics[2], if present, == U32(schema_version)
+fn try_extract_event(
     event: &ContractEvent,
     hvym_contract_addr: &[u8; 32],
     ledger_seq: u32,
-) -> Option<ExtractedDeposit> {
+) -> Option<ExtractedEvent> {
     // Must be a Contract event type
     if event.type_ != ContractEventType::Contract {
         return None;
@@ -89,20 +167,22 @@ fn try_extract_deposit(
     let ContractEventBody::V0(ref v0) = event.body;
 
     let topics = &v0.topics;
-    if topics.len() < 2 {
+    let version = match topics.len() {
+        2 => SCHEMA_V1,
+        3 => match &topics[2] {
+            ScVal::U32(v) => *v,
+            _ => return None,
+        },
+        _ => return None,
+    };
+    if version != SCHEMA_V1 && version != SCHEMA_V2 {
         return None;
     }
 
-    // topics[0] must be Symbol("DEPOSIT")
-    match &topics[0] {
-        ScVal::Symbol(sym) => {
-            let sym_bytes: &[u8] = sym.as_ref();
-            if sym_bytes != b"DEPOSIT" {
-                return None;
-            }
-        }
+    let topic: &[u8] = match &topics[0] {
+        ScVal::Symbol(sym) => sym.as_ref(),
         _ => return None,
-    }
+    };
 
     // topics[1] is the Freenet contract ID (as Bytes)
     let freenet_contract_id = match &topics[1] {
@@ -116,21 +196,66 @@ fn try_extract_deposit(
         _ => return None,
     };
 
-    // data is a tuple: (depositor: Address, amount: i128, burned: i128, ledger: u32)
-    // We care about `amount` (index 1 in the tuple)
-    let amount = extract_amount_from_data(&v0.data)?;
-
-    Some(ExtractedDeposit {
-        contract_id: freenet_contract_id,
-        amount,
-        ledger_seq,
-    })
+    if FUNDING_TOPICS.contains(&topic) {
+        // data is a tuple: (depositor: Address, amount: i128, burned: i128, ledger: u32, ...)
+        // TOPUP appends (previous_total, new_total). Both end with `usd_value`.
+        let amount = extract_amount_from_data(&v0.data)?;
+        let usd_index = if topic == b"TOPUP" { 6 } else { 4 };
+        let usd_value = match version {
+            SCHEMA_V1 => extract_i128_at(&v0.data, usd_index).unwrap_or(0),
+            _ => extract_i128_at(&v0.data, usd_index)?,
+        };
+        Some(ExtractedEvent::Deposit(ExtractedDeposit {
+            contract_id: freenet_contract_id,
+            amount,
+            usd_value,
+            ledger_seq,
+        }))
+    } else if topic == STREAM_TOPIC {
+        let schedule = extract_schedule_from_data(&v0.data)?;
+        Some(ExtractedEvent::Stream(ExtractedStream {
+            contract_id: freenet_contract_id,
+            schedule,
+        }))
+    } else if topic == REFUND_REQUEST_TOPIC {
+        let refund = extract_refund_request_from_data(&v0.data)?;
+        Some(ExtractedEvent::RefundRequested {
+            contract_id: freenet_contract_id,
+            refund,
+        })
+    } else if topic == REFUND_REJECT_TOPIC {
+        Some(ExtractedEvent::RefundRejected {
+            contract_id: freenet_contract_id,
+        })
+    } else if topic == REFUND_TOPIC {
+        Some(ExtractedEvent::Refunded {
+            contract_id: freenet_contract_id,
+        })
+    } else if topic == EXPIRE_TOPIC {
+        Some(ExtractedEvent::Expired {
+            contract_id: freenet_contract_id,
+        })
+    } else if topic == CLAWBACK_TOPIC {
+        Some(ExtractedEvent::ClawedBack {
+            contract_id: freenet_contract_id,
+        })
+    } else if topic == WITHDRAW_TOPIC {
+        // data: (depositor, amount, fee, remaining_total, ledger_seq, recipient)
+        Some(ExtractedEvent::Withdrawn {
+            contract_id: freenet_contract_id,
+            remaining_total: extract_i128_at(&v0.data, 3)?,
+        })
+    } else {
+        None
+    }
 }
 
 /// Extract the deposit amount from the event data.
 ///
 /// The event data from hvym-freenet-service `deposit()` is:
-/// `(caller: Address, amount: i128, burn_amount: i128, ledger_seq: u32)`
+/// `(caller: Address, amount: i128, burn_amount: i128, ledger_seq: u32, usd_value: i128)`
+/// for DEPOSIT, with `(previous_total: i128, new_total: i128)` inserted before
+/// `usd_value` for TOPUP.
 ///
 /// In Soroban, tuples are encoded as ScVal::Vec.
 fn extract_amount_from_data(data: &ScVal) -> Option<i128> {
@@ -152,6 +277,66 @@ fn extract_amount_from_data(data: &ScVal) -> Option<i128> {
     }
 }
 
+/// Extract the i128 at `index` of a tuple, if present.
+///
+/// Used for trailing fields added after the original layout, which events
+/// emitted by older contract builds lack.
+fn extract_i128_at(data: &ScVal, index: usize) -> Option<i128> {
+    let ScVal::Vec(Some(vec)) = data else {
+        return None;
+    };
+    let items: &[ScVal] = vec.as_ref();
+    match items.get(index)? {
+        ScVal::I128(parts) => Some(i128_from_parts(parts)),
+        _ => None,
+    }
+}
+
+/// Extract the drain schedule from STREAM event data:
+/// `(depositor: Address, drain_rate: i128, drained: i128, drain_cap: i128, checkpoint: u32)`
+fn extract_schedule_from_data(data: &ScVal) -> Option<DrainSchedule> {
+    let ScVal::Vec(Some(vec)) = data else {
+        return None;
+    };
+    let items: &[ScVal] = vec.as_ref();
+    if items.len() < 5 {
+        return None;
+    }
+    let as_i128 = |v: &ScVal| match v {
+        ScVal::I128(parts) => Some(i128_from_parts(parts)),
+        _ => None,
+    };
+    let checkpoint = match &items[4] {
+        ScVal::U32(v) => *v,
+        _ => return None,
+    };
+    Some(DrainSchedule {
+        drain_rate: as_i128(&items[1])?,
+        drained: as_i128(&items[2])?,
+        drain_cap: as_i128(&items[3])?,
+        checkpoint,
+    })
+}
+
+/// Extract the pending refund from REFUNDREQ event data:
+/// `(depositor: Address, refundable: i128, unlocks_at: u32, ledger_seq: u32)`
+fn extract_refund_request_from_data(data: &ScVal) -> Option<PendingRefund> {
+    let ScVal::Vec(Some(vec)) = data else {
+        return None;
+    };
+    let items: &[ScVal] = vec.as_ref();
+    if items.len() < 3 {
+        return None;
+    }
+    match (&items[1], &items[2]) {
+        (ScVal::I128(parts), ScVal::U32(unlocks_at)) => Some(PendingRefund {
+            amount: i128_from_parts(parts),
+            unlocks_at: *unlocks_at,
+        }),
+        _ => None,
+    }
+}
+
 /// Convert Int128Parts to i128.
 fn i128_from_parts(parts: &Int128Parts) -> i128 {
     ((parts.hi as i128) << 64) | (parts.lo as i128)
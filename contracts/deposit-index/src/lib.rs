@@ -4,13 +4,18 @@
 //! Relaying nodes submit SCP proofs as contract updates; all subscribing nodes
 //! receive the verified deposit map via normal Freenet state sync.
 
+mod attribution;
 mod events;
 mod hash_chain;
 mod scp;
 mod types;
 
 use freenet_stdlib::prelude::*;
-use types::{DepositEntry, DepositIndexParams, DepositMap, DepositMapSummary, DepositProof};
+use events::{ExtractedDeposit, ExtractedEvent};
+use types::{
+    DepositEntry, DepositIndexParams, DepositMap, DepositMapSummary, DepositProof, DrainSchedule,
+    PartialLedger, ProofDelta,
+};
 
 pub struct Contract;
 
@@ -41,6 +46,11 @@ impl ContractInterface for Contract {
             if entry.total_deposited < 0 {
                 return Ok(ValidateResult::Invalid);
             }
+            if let Some(stream) = &entry.stream {
+                if stream.drain_rate < 0 || stream.drained < 0 || stream.drain_cap < 0 {
+                    return Ok(ValidateResult::Invalid);
+                }
+            }
             if entry.contract_id.len() != 64 {
                 return Ok(ValidateResult::Invalid);
             }
@@ -49,6 +59,19 @@ impl ContractInterface for Contract {
             }
         }
 
+        if map.diagnostics.recent_relays.len() > attribution::RECENT_RELAYS {
+            return Ok(ValidateResult::Invalid);
+        }
+
+        // A partly applied ledger is the next one and still has metas left
+        if let Some(partial) = &map.partial {
+            if partial.ledger_seq <= map.last_ledger_seq
+                || partial.applied_metas >= partial.total_metas
+            {
+                return Ok(ValidateResult::Invalid);
+            }
+        }
+
         Ok(ValidateResult::Valid)
     }
 
@@ -78,17 +101,20 @@ impl ContractInterface for Contract {
         for ud in data {
             match ud {
                 UpdateData::Delta(delta) => {
-                    let proof: DepositProof = serde_json::from_slice(delta.as_ref())
+                    let proofs: ProofDelta = serde_json::from_slice(delta.as_ref())
                         .map_err(|e| ContractError::Deser(format!("proof: {e}")))?;
 
-                    if let Ok(did_change) =
-                        apply_proof(&proof, &params, &network_id, &hvym_addr, &mut map)
-                    {
-                        if did_change {
-                            changed = true;
+                    for proof in proofs.into_proofs() {
+                        if let Ok(did_change) =
+                            apply_proof(&proof, &params, &network_id, &hvym_addr, &mut map)
+                        {
+                            if did_change {
+                                attribution::record(&proof, &mut map);
+                                changed = true;
+                            }
                         }
+                        // Invalid proofs are silently skipped (not an error)
                     }
-                    // Invalid proofs are silently skipped (not an error)
                 }
                 UpdateData::State(new_state_data) if !new_state_data.is_empty() => {
                     // Full state replacement (network sync): accept if higher version
@@ -179,6 +205,12 @@ fn apply_proof(
         return Ok(false);
     }
 
+    // Split proofs: skip the metas already applied from another part
+    let (skip, progress) = unapplied_metas(proof, map)?;
+    if skip == proof.tx_result_metas.len() && map.partial.is_some() {
+        return Ok(false);
+    }
+
     // Stage 1: Decode SCP envelopes
     let envelopes = scp::decode_envelopes(&proof.scp_envelopes)?;
 
@@ -189,26 +221,118 @@ fn apply_proof(
     let _tx_set =
         hash_chain::verify_tx_set_hash(&proof.transaction_set, &stellar_value.tx_set_hash.0)?;
 
-    // Stage 5: Extract DEPOSIT events from transaction result metas
-    let deposits = events::extract_deposits(&proof.tx_result_metas, hvym_addr, proof.ledger_seq)?;
+    // Stage 5: Extract indexed hvym events from transaction result metas
+    let extracted =
+        events::extract_events(&proof.tx_result_metas[skip..], hvym_addr, proof.ledger_seq)?;
 
-    if deposits.is_empty() {
-        // Valid proof but no deposits in this ledger — update ledger tracking
-        map.last_ledger_seq = proof.ledger_seq;
-        return Ok(true);
+    // Merge deposits additively; only withdrawals and record removal lower an entry
+    for event in extracted {
+        match event {
+            ExtractedEvent::Deposit(deposit) => {
+                merge_deposit(map, deposit);
+            }
+            ExtractedEvent::Stream(stream) => {
+                merge_stream(map, &stream.contract_id, stream.schedule);
+            }
+            ExtractedEvent::RefundRequested {
+                contract_id,
+                refund,
+            } => {
+                if let Some(entry) = find_entry(map, &contract_id) {
+                    entry.pending_refund = Some(refund);
+                }
+            }
+            ExtractedEvent::RefundRejected { contract_id } => {
+                if let Some(entry) = find_entry(map, &contract_id) {
+                    entry.pending_refund = None;
+                }
+            }
+            ExtractedEvent::Withdrawn {
+                contract_id,
+                remaining_total,
+            } => {
+                if let Some(entry) = find_entry(map, &contract_id) {
+                    apply_withdrawal(entry, remaining_total);
+                }
+            }
+            ExtractedEvent::Refunded { contract_id }
+            | ExtractedEvent::Expired { contract_id }
+            | ExtractedEvent::ClawedBack { contract_id } => {
+                if let Some(entry) = find_entry(map, &contract_id) {
+                    reset_entry(entry);
+                }
+            }
+        }
     }
 
-    // Merge deposits additively (monotonic: amounts only increase)
-    for deposit in deposits {
-        merge_deposit(map, deposit.contract_id, deposit.amount, deposit.ledger_seq);
+    // A ledger without deposits still advances ledger tracking
+    match progress {
+        Some(partial) => map.partial = Some(partial),
+        None => {
+            map.last_ledger_seq = proof.ledger_seq;
+            map.partial = None;
+        }
     }
-
-    map.last_ledger_seq = proof.ledger_seq;
     Ok(true)
 }
 
+/// How many leading metas of `proof` were already applied from an earlier
+/// part of its ledger, and the ledger's progress once the rest are: `None`
+/// if that completes it.
+///
+/// Parts must arrive in meta order, and a split ledger must complete before
+/// any later ledger is applied. Parts may be split differently from each
+/// other, so a relayer can finish a ledger another one started.
+fn unapplied_metas(
+    proof: &DepositProof,
+    map: &DepositMap,
+) -> Result<(usize, Option<PartialLedger>), ContractError> {
+    let count = proof.tx_result_metas.len() as u32;
+    let (offset, total_metas) = match proof.part {
+        Some(part) => (part.offset, part.total_metas),
+        None => (0, count),
+    };
+    let end = offset
+        .checked_add(count)
+        .filter(|end| *end <= total_metas)
+        .ok_or_else(|| ContractError::Other("proof part exceeds the ledger's metas".into()))?;
+
+    let applied = match &map.partial {
+        None => 0,
+        Some(partial) if partial.ledger_seq != proof.ledger_seq => {
+            return Err(ContractError::Other(format!(
+                "ledger {} is only partly applied",
+                partial.ledger_seq
+            )));
+        }
+        Some(partial) if partial.total_metas != total_metas => {
+            return Err(ContractError::Other("proof part disagrees on the ledger's metas".into()));
+        }
+        Some(partial) => partial.applied_metas,
+    };
+    if offset > applied {
+        return Err(ContractError::Other(format!(
+            "proof part starts at meta {offset}, only {applied} applied"
+        )));
+    }
+
+    let skip = applied.max(offset) - offset;
+    let progress = (end < total_metas).then_some(PartialLedger {
+        ledger_seq: proof.ledger_seq,
+        total_metas,
+        applied_metas: end.max(applied),
+    });
+    Ok((skip.min(count) as usize, progress))
+}
+
 /// Merge a deposit into the map. Amounts are cumulative (additive).
-fn merge_deposit(map: &mut DepositMap, contract_id: String, amount: i128, ledger_seq: u32) {
+fn merge_deposit(map: &mut DepositMap, deposit: ExtractedDeposit) {
+    let ExtractedDeposit {
+        contract_id,
+        amount,
+        usd_value,
+        ledger_seq,
+    } = deposit;
     match map
         .deposits
         .binary_search_by(|e| e.contract_id.cmp(&contract_id))
@@ -216,6 +340,7 @@ fn merge_deposit(map: &mut DepositMap, contract_id: String, amount: i128, ledger
         Ok(idx) => {
             // Existing entry: add amount (monotonic)
             map.deposits[idx].total_deposited += amount;
+            map.deposits[idx].total_usd += usd_value;
             if ledger_seq > map.deposits[idx].last_ledger {
                 map.deposits[idx].last_ledger = ledger_seq;
             }
@@ -227,12 +352,62 @@ fn merge_deposit(map: &mut DepositMap, contract_id: String, amount: i128, ledger
                 DepositEntry {
                     contract_id,
                     total_deposited: amount,
+                    total_usd: usd_value,
                     last_ledger: ledger_seq,
+                    stream: None,
+                    pending_refund: None,
                 },
             );
         }
     }
 }
 
+/// Replace the drain schedule of an existing entry.
+///
+/// The Soroban contract always emits STREAM after the DEPOSIT/TOPUP in the
+/// same invocation, so a schedule for an unknown contract ID is ignored.
+fn merge_stream(map: &mut DepositMap, contract_id: &str, schedule: DrainSchedule) {
+    if let Some(entry) = find_entry(map, contract_id) {
+        entry.stream = Some(schedule);
+    }
+}
+
+fn find_entry<'a>(map: &'a mut DepositMap, contract_id: &str) -> Option<&'a mut DepositEntry> {
+    let idx = map
+        .deposits
+        .binary_search_by(|e| e.contract_id.as_str().cmp(contract_id))
+        .ok()?;
+    Some(&mut map.deposits[idx])
+}
+
+/// Lower an entry to the on-chain total left after a withdrawal.
+///
+/// The USD value is scaled by the same ratio, since it was recorded per
+/// deposit and the contract does not say which deposits were withdrawn.
+fn apply_withdrawal(entry: &mut DepositEntry, remaining_total: i128) {
+    if remaining_total <= 0 {
+        reset_entry(entry);
+        return;
+    }
+    if entry.total_deposited > 0 {
+        entry.total_usd = entry
+            .total_usd
+            .saturating_mul(remaining_total)
+            / entry.total_deposited;
+    }
+    entry.total_deposited = remaining_total;
+}
+
+/// Zero an entry whose on-chain record was removed.
+///
+/// The entry is kept rather than dropped so subscribers see the commitment
+/// fall to zero; a later DEPOSIT for the same ID starts again from zero.
+fn reset_entry(entry: &mut DepositEntry) {
+    entry.total_deposited = 0;
+    entry.total_usd = 0;
+    entry.stream = None;
+    entry.pending_refund = None;
+}
+
 #[cfg(test)]
 mod tests;
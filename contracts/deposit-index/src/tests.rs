@@ -1,9 +1,10 @@
 use super::Contract as DepositContract;
-use crate::scp;
 use crate::types::{
     hex_encode, DepositEntry, DepositIndexParams, DepositMap, DepositMapSummary, DepositProof,
-    ValidatorOrg,
+    Diagnostics, DrainSchedule, PartialLedger, PendingRefund, ProofPart, RelayRecord,
+    RelayerAttribution, ValidatorOrg,
 };
+use crate::{attribution, scp};
 use ed25519_dalek::{Signer, SigningKey};
 use freenet_stdlib::prelude::*;
 use sha2::{Digest, Sha256};
@@ -121,39 +122,56 @@ fn make_tx_set() -> (String, [u8; 32]) {
     (b64, hash)
 }
 
+fn symbol(name: &str) -> ScVal {
+    ScVal::Symbol(stellar_xdr::curr::ScSymbol(
+        name.as_bytes().try_into().unwrap(),
+    ))
+}
+
+fn i128_val(v: i128) -> ScVal {
+    ScVal::I128(Int128Parts {
+        hi: (v >> 64) as i64,
+        lo: v as u64,
+    })
+}
+
 fn make_tx_result_meta_with_deposit(
     hvym_addr: &[u8; 32],
     freenet_id: &[u8; 32],
     amount: i128,
 ) -> String {
+    make_tx_result_meta_with_event(hvym_addr, freenet_id, "DEPOSIT", amount)
+}
+
+/// Build a tx result meta carrying one funding-style event
+/// (`(caller, amount, burned, ledger)` data layout) with the given topic.
+fn make_tx_result_meta_with_event(
+    hvym_addr: &[u8; 32],
+    freenet_id: &[u8; 32],
+    topic: &str,
+    amount: i128,
+) -> String {
+    make_event_meta(
+        hvym_addr,
+        vec![
+            symbol(topic),
+            ScVal::Bytes(stellar_xdr::curr::ScBytes(
+                freenet_id.to_vec().try_into().unwrap(),
+            )),
+        ],
+        vec![ScVal::Void, i128_val(amount), i128_val(0), ScVal::U32(100)],
+    )
+}
+
+/// Build a base64 TransactionResultMeta containing a single contract event.
+fn make_event_meta(hvym_addr: &[u8; 32], topics: Vec<ScVal>, data: Vec<ScVal>) -> String {
     let deposit_event = ContractEvent {
         ext: ExtensionPoint::V0,
         contract_id: Some(ContractId(Hash(*hvym_addr))),
         type_: ContractEventType::Contract,
         body: ContractEventBody::V0(ContractEventV0 {
-            topics: vec![
-                ScVal::Symbol(stellar_xdr::curr::ScSymbol(
-                    "DEPOSIT".as_bytes().try_into().unwrap(),
-                )),
-                ScVal::Bytes(stellar_xdr::curr::ScBytes(
-                    freenet_id.to_vec().try_into().unwrap(),
-                )),
-            ]
-            .try_into()
-            .unwrap(),
-            data: ScVal::Vec(Some(
-                vec![
-                    ScVal::Void,
-                    ScVal::I128(Int128Parts {
-                        hi: (amount >> 64) as i64,
-                        lo: amount as u64,
-                    }),
-                    ScVal::I128(Int128Parts { hi: 0, lo: 0 }),
-                    ScVal::U32(100),
-                ]
-                .try_into()
-                .unwrap(),
-            )),
+            topics: topics.try_into().unwrap(),
+            data: ScVal::Vec(Some(data.try_into().unwrap())),
         }),
     };
 
@@ -214,6 +232,16 @@ fn make_params(
 }
 
 fn make_valid_proof(signers: &[&SigningKey], ledger_seq: u32, amount: i128) -> DepositProof {
+    let meta_b64 =
+        make_tx_result_meta_with_deposit(&make_hvym_address(), &make_freenet_contract_id(), amount);
+    make_proof_with_metas(signers, ledger_seq, vec![meta_b64])
+}
+
+fn make_proof_with_metas(
+    signers: &[&SigningKey],
+    ledger_seq: u32,
+    tx_result_metas: Vec<String>,
+) -> DepositProof {
     let (tx_set_b64, tx_set_hash) = make_tx_set();
     let stellar_value = make_stellar_value(tx_set_hash);
     let network_id = test_network_id();
@@ -227,14 +255,14 @@ fn make_valid_proof(signers: &[&SigningKey], ledger_seq: u32, amount: i128) -> D
         })
         .collect();
 
-    let meta_b64 =
-        make_tx_result_meta_with_deposit(&make_hvym_address(), &make_freenet_contract_id(), amount);
-
     DepositProof {
         ledger_seq,
         scp_envelopes,
         transaction_set: tx_set_b64,
-        tx_result_metas: vec![meta_b64],
+        tx_result_metas,
+        event_kinds: Vec::new(),
+        part: None,
+        attribution: None,
     }
 }
 
@@ -271,14 +299,22 @@ fn test_validate_well_formed() {
             DepositEntry {
                 contract_id: "aa".repeat(32),
                 total_deposited: 1000,
+                total_usd: 0,
                 last_ledger: 100,
+                stream: None,
+                pending_refund: None,
             },
             DepositEntry {
                 contract_id: "bb".repeat(32),
                 total_deposited: 2000,
+                total_usd: 0,
                 last_ledger: 100,
+                stream: None,
+                pending_refund: None,
             },
         ],
+        partial: None,
+        diagnostics: Diagnostics::default(),
     };
     let result = DepositContract::validate_state(
         Parameters::from(vec![]),
@@ -297,14 +333,22 @@ fn test_validate_unsorted() {
             DepositEntry {
                 contract_id: "bb".repeat(32),
                 total_deposited: 2000,
+                total_usd: 0,
                 last_ledger: 100,
+                stream: None,
+                pending_refund: None,
             },
             DepositEntry {
                 contract_id: "aa".repeat(32),
                 total_deposited: 1000,
+                total_usd: 0,
                 last_ledger: 100,
+                stream: None,
+                pending_refund: None,
             },
         ],
+        partial: None,
+        diagnostics: Diagnostics::default(),
     };
     let result = DepositContract::validate_state(
         Parameters::from(vec![]),
@@ -322,8 +366,13 @@ fn test_validate_negative_amount() {
         deposits: vec![DepositEntry {
             contract_id: "aa".repeat(32),
             total_deposited: -100,
+            total_usd: 0,
             last_ledger: 100,
+            stream: None,
+            pending_refund: None,
         }],
+        partial: None,
+        diagnostics: Diagnostics::default(),
     };
     let result = DepositContract::validate_state(
         Parameters::from(vec![]),
@@ -476,6 +525,41 @@ fn test_update_valid_proof() {
     assert!(map.version > 0);
 }
 
+#[test]
+fn test_update_proof_batch() {
+    let org_keys: Vec<Vec<SigningKey>> = (0..3u8)
+        .map(|org| {
+            (0..2u8)
+                .map(|v| make_keypair(org * 10 + v))
+                .collect()
+        })
+        .collect();
+    let params = make_params(&org_keys, 0);
+
+    let all_signers: Vec<&SigningKey> = org_keys.iter().flat_map(|org| org.iter()).collect();
+    let rogue_keys: Vec<SigningKey> = (0..6u8).map(|v| make_keypair(200 + v)).collect();
+    let rogue_refs: Vec<&SigningKey> = rogue_keys.iter().collect();
+    // Out of order, with an invalid proof that is skipped like a lone one
+    let proofs = vec![
+        make_valid_proof(&all_signers, 102, 2_000_000),
+        make_valid_proof(&rogue_refs, 101, 9_000_000),
+        make_valid_proof(&all_signers, 100, 5_000_000),
+    ];
+
+    let proof_bytes = serde_json::to_vec(&proofs).unwrap();
+    let update_data = vec![UpdateData::Delta(StateDelta::from(proof_bytes))];
+
+    let modification =
+        DepositContract::update_state(make_params_bytes(&params), make_empty_state(), update_data)
+            .unwrap();
+    let new_state = modification.new_state.unwrap();
+    let map: DepositMap = serde_json::from_slice(new_state.as_ref()).unwrap();
+
+    assert_eq!(map.deposits.len(), 1);
+    assert_eq!(map.deposits[0].total_deposited, 7_000_000);
+    assert_eq!(map.last_ledger_seq, 102);
+}
+
 #[test]
 fn test_update_invalid_signature() {
     let org_keys: Vec<Vec<SigningKey>> = (0..3u8)
@@ -546,6 +630,8 @@ fn test_update_stale_ledger() {
         version: 5,
         last_ledger_seq: 200,
         deposits: vec![],
+        partial: None,
+        diagnostics: Diagnostics::default(),
     };
 
     let all_signers: Vec<&SigningKey> = org_keys.iter().flat_map(|org| org.iter()).collect();
@@ -604,6 +690,423 @@ fn test_update_monotonic_merge() {
     assert_eq!(map2.last_ledger_seq, 200);
 }
 
+#[test]
+fn test_update_topup_accumulates() {
+    let org_keys: Vec<Vec<SigningKey>> = (0..3u8)
+        .map(|org| {
+            (0..2u8)
+                .map(|v| make_keypair(org * 10 + v))
+                .collect()
+        })
+        .collect();
+    let params = make_params(&org_keys, 0);
+    let all_signers: Vec<&SigningKey> = org_keys.iter().flat_map(|org| org.iter()).collect();
+
+    let deposit = make_valid_proof(&all_signers, 100, 1_000_000);
+    let topup_meta = make_tx_result_meta_with_event(
+        &make_hvym_address(),
+        &make_freenet_contract_id(),
+        "TOPUP",
+        500_000,
+    );
+    let topup = make_proof_with_metas(&all_signers, 200, vec![topup_meta]);
+
+    let update = vec![
+        UpdateData::Delta(StateDelta::from(serde_json::to_vec(&deposit).unwrap())),
+        UpdateData::Delta(StateDelta::from(serde_json::to_vec(&topup).unwrap())),
+    ];
+    let result =
+        DepositContract::update_state(make_params_bytes(&params), make_empty_state(), update);
+    let map: DepositMap =
+        serde_json::from_slice(result.unwrap().new_state.unwrap().as_ref()).unwrap();
+
+    assert_eq!(map.deposits.len(), 1);
+    assert_eq!(map.deposits[0].total_deposited, 1_500_000);
+    assert_eq!(map.deposits[0].last_ledger, 200);
+}
+
+#[test]
+fn test_update_stream_sets_drain_schedule() {
+    let org_keys: Vec<Vec<SigningKey>> = (0..3u8)
+        .map(|org| {
+            (0..2u8)
+                .map(|v| make_keypair(org * 10 + v))
+                .collect()
+        })
+        .collect();
+    let params = make_params(&org_keys, 0);
+    let all_signers: Vec<&SigningKey> = org_keys.iter().flat_map(|org| org.iter()).collect();
+
+    let deposit = make_valid_proof(&all_signers, 100, 1_000_000);
+    let stream_meta = make_event_meta(
+        &make_hvym_address(),
+        vec![
+            symbol("STREAM"),
+            ScVal::Bytes(stellar_xdr::curr::ScBytes(
+                make_freenet_contract_id().to_vec().try_into().unwrap(),
+            )),
+        ],
+        vec![
+            ScVal::Void,
+            i128_val(1_000),
+            i128_val(0),
+            i128_val(700_000),
+            ScVal::U32(200),
+        ],
+    );
+    let stream = make_proof_with_metas(&all_signers, 200, vec![stream_meta]);
+
+    let update = vec![
+        UpdateData::Delta(StateDelta::from(serde_json::to_vec(&deposit).unwrap())),
+        UpdateData::Delta(StateDelta::from(serde_json::to_vec(&stream).unwrap())),
+    ];
+    let result =
+        DepositContract::update_state(make_params_bytes(&params), make_empty_state(), update);
+    let map: DepositMap =
+        serde_json::from_slice(result.unwrap().new_state.unwrap().as_ref()).unwrap();
+
+    let entry = &map.deposits[0];
+    assert_eq!(
+        entry.stream,
+        Some(DrainSchedule {
+            drain_rate: 1_000,
+            drained: 0,
+            drain_cap: 700_000,
+            checkpoint: 200,
+        })
+    );
+    // Funding amount is untouched; consumers derive the live amount from the schedule
+    assert_eq!(entry.total_deposited, 1_000_000);
+}
+
+/// Build a tx result meta carrying one event with the Freenet contract ID
+/// as `topics[1]`.
+fn make_contract_event_meta(topic: &str, data: Vec<ScVal>) -> String {
+    make_event_meta(
+        &make_hvym_address(),
+        vec![
+            symbol(topic),
+            ScVal::Bytes(stellar_xdr::curr::ScBytes(
+                make_freenet_contract_id().to_vec().try_into().unwrap(),
+            )),
+        ],
+        data,
+    )
+}
+
+fn make_versioned_event_meta(topic: &str, version: u32, data: Vec<ScVal>) -> String {
+    make_event_meta(
+        &make_hvym_address(),
+        vec![
+            symbol(topic),
+            ScVal::Bytes(stellar_xdr::curr::ScBytes(
+                make_freenet_contract_id().to_vec().try_into().unwrap(),
+            )),
+            ScVal::U32(version),
+        ],
+        data,
+    )
+}
+
+#[test]
+fn test_update_dispatches_on_schema_version() {
+    let org_keys: Vec<Vec<SigningKey>> = (0..3u8)
+        .map(|org| {
+            (0..2u8)
+                .map(|v| make_keypair(org * 10 + v))
+                .collect()
+        })
+        .collect();
+    let params = make_params(&org_keys, 0);
+    let all_signers: Vec<&SigningKey> = org_keys.iter().flat_map(|org| org.iter()).collect();
+
+    // v2 DEPOSIT with its full layout is indexed
+    let v2 = make_versioned_event_meta(
+        "DEPOSIT",
+        2,
+        vec![
+            ScVal::Void,
+            i128_val(500_000),
+            i128_val(0),
+            ScVal::U32(100),
+            i128_val(42),
+        ],
+    );
+    // v2 DEPOSIT missing `usd_value` is rejected instead of defaulted
+    let v2_short = make_versioned_event_meta(
+        "DEPOSIT",
+        2,
+        vec![ScVal::Void, i128_val(300_000), i128_val(0), ScVal::U32(100)],
+    );
+    // Unknown versions are skipped
+    let v9 = make_versioned_event_meta(
+        "DEPOSIT",
+        9,
+        vec![
+            ScVal::Void,
+            i128_val(700_000),
+            i128_val(0),
+            ScVal::U32(100),
+            i128_val(0),
+        ],
+    );
+    let proof = make_proof_with_metas(&all_signers, 100, vec![v2, v2_short, v9]);
+    let update = vec![UpdateData::Delta(StateDelta::from(
+        serde_json::to_vec(&proof).unwrap(),
+    ))];
+    let result =
+        DepositContract::update_state(make_params_bytes(&params), make_empty_state(), update);
+    let map: DepositMap =
+        serde_json::from_slice(result.unwrap().new_state.unwrap().as_ref()).unwrap();
+
+    assert_eq!(map.deposits.len(), 1);
+    assert_eq!(map.deposits[0].total_deposited, 500_000);
+    assert_eq!(map.deposits[0].total_usd, 42);
+}
+
+#[test]
+fn test_update_refund_lifecycle() {
+    let org_keys: Vec<Vec<SigningKey>> = (0..3u8)
+        .map(|org| {
+            (0..2u8)
+                .map(|v| make_keypair(org * 10 + v))
+                .collect()
+        })
+        .collect();
+    let params = make_params(&org_keys, 0);
+    let all_signers: Vec<&SigningKey> = org_keys.iter().flat_map(|org| org.iter()).collect();
+
+    let deposit = make_valid_proof(&all_signers, 100, 1_000_000);
+    let request_meta = make_contract_event_meta(
+        "REFUNDREQ",
+        vec![ScVal::Void, i128_val(700_000), ScVal::U32(17_380), ScVal::U32(200)],
+    );
+    let request = make_proof_with_metas(&all_signers, 200, vec![request_meta]);
+
+    let update = vec![
+        UpdateData::Delta(StateDelta::from(serde_json::to_vec(&deposit).unwrap())),
+        UpdateData::Delta(StateDelta::from(serde_json::to_vec(&request).unwrap())),
+    ];
+    let result =
+        DepositContract::update_state(make_params_bytes(&params), make_empty_state(), update);
+    let map: DepositMap =
+        serde_json::from_slice(result.unwrap().new_state.unwrap().as_ref()).unwrap();
+
+    // Pending refunds keep the entry and its amount
+    assert_eq!(map.deposits.len(), 1);
+    assert_eq!(map.deposits[0].total_deposited, 1_000_000);
+    assert_eq!(
+        map.deposits[0].pending_refund,
+        Some(PendingRefund {
+            amount: 700_000,
+            unlocks_at: 17_380,
+        })
+    );
+
+    let reject_meta = make_contract_event_meta("REFUNDREJ", vec![ScVal::U32(300)]);
+    let reject = make_proof_with_metas(&all_signers, 300, vec![reject_meta]);
+    let refund_meta = make_contract_event_meta(
+        "REFUND",
+        vec![ScVal::Void, i128_val(700_000), ScVal::U32(400)],
+    );
+    let refund = make_proof_with_metas(&all_signers, 400, vec![refund_meta]);
+
+    let result = DepositContract::update_state(
+        make_params_bytes(&params),
+        make_state(&map),
+        vec![UpdateData::Delta(StateDelta::from(
+            serde_json::to_vec(&reject).unwrap(),
+        ))],
+    );
+    let map: DepositMap =
+        serde_json::from_slice(result.unwrap().new_state.unwrap().as_ref()).unwrap();
+    assert_eq!(map.deposits[0].pending_refund, None);
+
+    let result = DepositContract::update_state(
+        make_params_bytes(&params),
+        make_state(&map),
+        vec![UpdateData::Delta(StateDelta::from(
+            serde_json::to_vec(&refund).unwrap(),
+        ))],
+    );
+    let map: DepositMap =
+        serde_json::from_slice(result.unwrap().new_state.unwrap().as_ref()).unwrap();
+    assert_eq!(map.deposits.len(), 1);
+    assert_eq!(map.deposits[0].total_deposited, 0);
+    assert_eq!(map.last_ledger_seq, 400);
+}
+
+#[test]
+fn test_update_expire_resets_entry() {
+    let org_keys: Vec<Vec<SigningKey>> = (0..3u8)
+        .map(|org| {
+            (0..2u8)
+                .map(|v| make_keypair(org * 10 + v))
+                .collect()
+        })
+        .collect();
+    let params = make_params(&org_keys, 0);
+    let all_signers: Vec<&SigningKey> = org_keys.iter().flat_map(|org| org.iter()).collect();
+
+    let deposit = make_valid_proof(&all_signers, 100, 1_000_000);
+    let expire_meta = make_contract_event_meta(
+        "EXPIRE",
+        vec![ScVal::Void, i128_val(700_000), ScVal::U32(200)],
+    );
+    let expire = make_proof_with_metas(&all_signers, 200, vec![expire_meta]);
+
+    let update = vec![
+        UpdateData::Delta(StateDelta::from(serde_json::to_vec(&deposit).unwrap())),
+        UpdateData::Delta(StateDelta::from(serde_json::to_vec(&expire).unwrap())),
+    ];
+    let result =
+        DepositContract::update_state(make_params_bytes(&params), make_empty_state(), update);
+    let map: DepositMap =
+        serde_json::from_slice(result.unwrap().new_state.unwrap().as_ref()).unwrap();
+
+    assert_eq!(map.deposits.len(), 1);
+    assert_eq!(map.deposits[0].total_deposited, 0);
+}
+
+#[test]
+fn test_update_accumulates_usd_value() {
+    let org_keys: Vec<Vec<SigningKey>> = (0..3u8)
+        .map(|org| {
+            (0..2u8)
+                .map(|v| make_keypair(org * 10 + v))
+                .collect()
+        })
+        .collect();
+    let params = make_params(&org_keys, 0);
+    let all_signers: Vec<&SigningKey> = org_keys.iter().flat_map(|org| org.iter()).collect();
+
+    let deposit_meta = make_contract_event_meta(
+        "DEPOSIT",
+        vec![
+            ScVal::Void,
+            i128_val(1_000_000),
+            i128_val(0),
+            ScVal::U32(100),
+            i128_val(120_000),
+        ],
+    );
+    let topup_meta = make_contract_event_meta(
+        "TOPUP",
+        vec![
+            ScVal::Void,
+            i128_val(500_000),
+            i128_val(0),
+            ScVal::U32(200),
+            i128_val(1_000_000),
+            i128_val(1_500_000),
+            i128_val(50_000),
+        ],
+    );
+    let deposit = make_proof_with_metas(&all_signers, 100, vec![deposit_meta]);
+    let topup = make_proof_with_metas(&all_signers, 200, vec![topup_meta]);
+
+    let update = vec![
+        UpdateData::Delta(StateDelta::from(serde_json::to_vec(&deposit).unwrap())),
+        UpdateData::Delta(StateDelta::from(serde_json::to_vec(&topup).unwrap())),
+    ];
+    let result =
+        DepositContract::update_state(make_params_bytes(&params), make_empty_state(), update);
+    let map: DepositMap =
+        serde_json::from_slice(result.unwrap().new_state.unwrap().as_ref()).unwrap();
+
+    assert_eq!(map.deposits[0].total_deposited, 1_500_000);
+    assert_eq!(map.deposits[0].total_usd, 170_000);
+}
+
+#[test]
+fn test_update_withdraw_lowers_entry() {
+    let org_keys: Vec<Vec<SigningKey>> = (0..3u8)
+        .map(|org| {
+            (0..2u8)
+                .map(|v| make_keypair(org * 10 + v))
+                .collect()
+        })
+        .collect();
+    let params = make_params(&org_keys, 0);
+    let all_signers: Vec<&SigningKey> = org_keys.iter().flat_map(|org| org.iter()).collect();
+
+    let deposit = make_valid_proof(&all_signers, 100, 1_000_000);
+    let partial_meta = make_contract_event_meta(
+        "WITHDRAW",
+        vec![
+            ScVal::Void,
+            i128_val(400_000),
+            i128_val(0),
+            i128_val(600_000),
+            ScVal::U32(200),
+        ],
+    );
+    let partial = make_proof_with_metas(&all_signers, 200, vec![partial_meta]);
+    let full_meta = make_contract_event_meta(
+        "WITHDRAW",
+        vec![
+            ScVal::Void,
+            i128_val(400_000),
+            i128_val(0),
+            i128_val(0),
+            ScVal::U32(300),
+        ],
+    );
+    let full = make_proof_with_metas(&all_signers, 300, vec![full_meta]);
+
+    let update = vec![
+        UpdateData::Delta(StateDelta::from(serde_json::to_vec(&deposit).unwrap())),
+        UpdateData::Delta(StateDelta::from(serde_json::to_vec(&partial).unwrap())),
+    ];
+    let result =
+        DepositContract::update_state(make_params_bytes(&params), make_empty_state(), update);
+    let map: DepositMap =
+        serde_json::from_slice(result.unwrap().new_state.unwrap().as_ref()).unwrap();
+    assert_eq!(map.deposits[0].total_deposited, 600_000);
+
+    let result = DepositContract::update_state(
+        make_params_bytes(&params),
+        make_state(&map),
+        vec![UpdateData::Delta(StateDelta::from(
+            serde_json::to_vec(&full).unwrap(),
+        ))],
+    );
+    let map: DepositMap =
+        serde_json::from_slice(result.unwrap().new_state.unwrap().as_ref()).unwrap();
+    assert_eq!(map.deposits[0].total_deposited, 0);
+}
+
+#[test]
+fn test_update_ignores_unknown_topic() {
+    let org_keys: Vec<Vec<SigningKey>> = (0..3u8)
+        .map(|org| {
+            (0..2u8)
+                .map(|v| make_keypair(org * 10 + v))
+                .collect()
+        })
+        .collect();
+    let params = make_params(&org_keys, 0);
+    let all_signers: Vec<&SigningKey> = org_keys.iter().flat_map(|org| org.iter()).collect();
+
+    let meta = make_tx_result_meta_with_event(
+        &make_hvym_address(),
+        &make_freenet_contract_id(),
+        "OTHER",
+        500_000,
+    );
+    let proof = make_proof_with_metas(&all_signers, 100, vec![meta]);
+    let update = vec![UpdateData::Delta(StateDelta::from(
+        serde_json::to_vec(&proof).unwrap(),
+    ))];
+    let result =
+        DepositContract::update_state(make_params_bytes(&params), make_empty_state(), update);
+    let map: DepositMap =
+        serde_json::from_slice(result.unwrap().new_state.unwrap().as_ref()).unwrap();
+
+    assert!(map.deposits.is_empty());
+    assert_eq!(map.last_ledger_seq, 100);
+}
+
 #[test]
 fn test_update_idempotent() {
     let org_keys: Vec<Vec<SigningKey>> = (0..3u8)
@@ -634,6 +1137,204 @@ fn test_update_idempotent() {
     assert_eq!(map2.deposits[0].total_deposited, 5_000_000);
 }
 
+#[test]
+fn test_update_split_proof_parts() {
+    let org_keys: Vec<Vec<SigningKey>> = (0..3u8)
+        .map(|org| {
+            (0..2u8)
+                .map(|v| make_keypair(org * 10 + v))
+                .collect()
+        })
+        .collect();
+    let params = make_params(&org_keys, 0);
+    let all_signers: Vec<&SigningKey> = org_keys.iter().flat_map(|org| org.iter()).collect();
+    let metas: Vec<String> = [1_000_000, 2_000_000, 4_000_000]
+        .iter()
+        .map(|amount| {
+            make_tx_result_meta_with_deposit(
+                &make_hvym_address(),
+                &make_freenet_contract_id(),
+                *amount,
+            )
+        })
+        .collect();
+    let part = |offset: usize, end: usize| {
+        let mut proof = make_proof_with_metas(&all_signers, 100, metas[offset..end].to_vec());
+        proof.part = Some(ProofPart {
+            offset: offset as u32,
+            total_metas: 3,
+        });
+        proof
+    };
+    let apply = |state: State<'static>, proof: &DepositProof| -> State<'static> {
+        let update = vec![UpdateData::Delta(StateDelta::from(
+            serde_json::to_vec(proof).unwrap(),
+        ))];
+        DepositContract::update_state(make_params_bytes(&params), state, update)
+            .unwrap()
+            .new_state
+            .unwrap()
+    };
+    let decode = |state: &State<'static>| -> DepositMap {
+        serde_json::from_slice(state.as_ref()).unwrap()
+    };
+
+    let state = apply(make_empty_state(), &part(0, 2));
+    let map = decode(&state);
+    assert_eq!(map.deposits[0].total_deposited, 3_000_000);
+    assert_eq!(map.last_ledger_seq, 0);
+    assert_eq!(
+        map.partial,
+        Some(PartialLedger {
+            ledger_seq: 100,
+            total_metas: 3,
+            applied_metas: 2,
+        })
+    );
+
+    // Replaying a part or moving on to a later ledger changes nothing
+    let version = map.version;
+    for proof in [
+        part(0, 2),
+        part(0, 1),
+        make_valid_proof(&all_signers, 101, 8_000_000),
+    ] {
+        let replayed = decode(&apply(state.clone(), &proof));
+        assert_eq!(replayed.version, version);
+        assert_eq!(replayed.deposits[0].total_deposited, 3_000_000);
+    }
+    // Nor does a part that disagrees on the ledger's metas
+    let mut mismatched = part(2, 3);
+    mismatched.part = Some(ProofPart {
+        offset: 2,
+        total_metas: 4,
+    });
+    assert_eq!(decode(&apply(state.clone(), &mismatched)).version, version);
+
+    // A differently split part applies only the metas not yet applied
+    let state = apply(state, &part(1, 3));
+    let map = decode(&state);
+    assert_eq!(map.deposits[0].total_deposited, 7_000_000);
+    assert_eq!(map.last_ledger_seq, 100);
+    assert_eq!(map.partial, None);
+
+    let map = decode(&apply(state, &part(2, 3)));
+    assert_eq!(map.deposits[0].total_deposited, 7_000_000);
+}
+
+fn attribute(proof: &mut DepositProof, relayer: &SigningKey) {
+    let signature = relayer.sign(&attribution::attribution_message(proof));
+    proof.attribution = Some(RelayerAttribution {
+        relayer: hex_encode(&relayer.verifying_key().to_bytes()),
+        signature: hex_encode(&signature.to_bytes()),
+    });
+}
+
+#[test]
+fn test_attribution_message() {
+    let mut proof = DepositProof {
+        ledger_seq: 500,
+        scp_envelopes: vec!["AAAA".to_string()],
+        transaction_set: "BBBB".to_string(),
+        tx_result_metas: vec!["CCCC".to_string()],
+        event_kinds: Vec::new(),
+        part: None,
+        attribution: None,
+    };
+    // Relayers compute the same digest; see the node's DepositProof
+    assert_eq!(
+        hex_encode(&attribution::attribution_message(&proof)),
+        "1bda18c1dc9987fedb88bd4c8cfdf575d1cf67c971b43d15e296acdc0cc7ac07"
+    );
+    proof.part = Some(ProofPart {
+        offset: 0,
+        total_metas: 2,
+    });
+    assert_ne!(
+        hex_encode(&attribution::attribution_message(&proof)),
+        "1bda18c1dc9987fedb88bd4c8cfdf575d1cf67c971b43d15e296acdc0cc7ac07"
+    );
+}
+
+#[test]
+fn test_update_records_relayer_attribution() {
+    let org_keys: Vec<Vec<SigningKey>> = (0..3u8)
+        .map(|org| {
+            (0..2u8)
+                .map(|v| make_keypair(org * 10 + v))
+                .collect()
+        })
+        .collect();
+    let params = make_params(&org_keys, 0);
+    let all_signers: Vec<&SigningKey> = org_keys.iter().flat_map(|org| org.iter()).collect();
+    let relayer = make_keypair(150);
+    let relayer_hex = hex_encode(&relayer.verifying_key().to_bytes());
+
+    let mut attributed = make_valid_proof(&all_signers, 100, 1_000_000);
+    attribute(&mut attributed, &relayer);
+    let unattributed = make_valid_proof(&all_signers, 101, 2_000_000);
+    // Signed for another ledger: applied, but not attributed
+    let mut forged = make_valid_proof(&all_signers, 102, 4_000_000);
+    attribute(&mut forged, &relayer);
+    forged.ledger_seq = 103;
+
+    let apply = |state: State<'static>, proofs: &[DepositProof]| -> DepositMap {
+        let update = vec![UpdateData::Delta(StateDelta::from(
+            serde_json::to_vec(proofs).unwrap(),
+        ))];
+        let new_state = DepositContract::update_state(make_params_bytes(&params), state, update)
+            .unwrap()
+            .new_state
+            .unwrap();
+        serde_json::from_slice(new_state.as_ref()).unwrap()
+    };
+    let map = apply(make_empty_state(), &[attributed.clone(), unattributed, forged]);
+    assert_eq!(map.deposits[0].total_deposited, 7_000_000);
+    assert_eq!(map.last_ledger_seq, 103);
+    assert_eq!(
+        map.diagnostics.recent_relays,
+        vec![RelayRecord {
+            ledger_seq: 100,
+            relayer: relayer_hex.clone(),
+        }]
+    );
+
+    // A proof that changes nothing isn't recorded again
+    let replayed = apply(make_state(&map), &[attributed]);
+    assert_eq!(replayed.diagnostics, map.diagnostics);
+
+    // Only the latest relays are kept
+    let mut map = map;
+    for ledger_seq in 104..104 + attribution::RECENT_RELAYS as u32 {
+        let mut proof = make_valid_proof(&all_signers, ledger_seq, 1);
+        attribute(&mut proof, &relayer);
+        map = apply(make_state(&map), &[proof]);
+    }
+    let relays = &map.diagnostics.recent_relays;
+    assert_eq!(relays.len(), attribution::RECENT_RELAYS);
+    assert_eq!(relays[0].ledger_seq, 104);
+    assert!(matches!(
+        DepositContract::validate_state(
+            Parameters::from(vec![]),
+            make_state(&map),
+            RelatedContracts::new()
+        ),
+        Ok(ValidateResult::Valid)
+    ));
+    map.diagnostics.recent_relays.push(RelayRecord {
+        ledger_seq: 200,
+        relayer: relayer_hex,
+    });
+    assert!(matches!(
+        DepositContract::validate_state(
+            Parameters::from(vec![]),
+            make_state(&map),
+            RelatedContracts::new()
+        ),
+        Ok(ValidateResult::Invalid)
+    ));
+}
+
 #[test]
 fn test_update_wrong_contract_addr() {
     let org_keys: Vec<Vec<SigningKey>> = (0..3u8)
@@ -673,14 +1374,22 @@ fn test_summarize_and_delta() {
             DepositEntry {
                 contract_id: "aa".repeat(32),
                 total_deposited: 1000,
+                total_usd: 0,
                 last_ledger: 100,
+                stream: None,
+                pending_refund: None,
             },
             DepositEntry {
                 contract_id: "bb".repeat(32),
                 total_deposited: 2000,
+                total_usd: 0,
                 last_ledger: 150,
+                stream: None,
+                pending_refund: None,
             },
         ],
+        partial: None,
+        diagnostics: Diagnostics::default(),
     };
 
     let state = make_state(&map);
@@ -705,8 +1414,13 @@ fn test_delta_different_version() {
         deposits: vec![DepositEntry {
             contract_id: "aa".repeat(32),
             total_deposited: 3000,
+            total_usd: 0,
             last_ledger: 200,
+            stream: None,
+            pending_refund: None,
         }],
+        partial: None,
+        diagnostics: Diagnostics::default(),
     };
 
     let state = make_state(&map);
@@ -33,8 +33,44 @@ pub struct DepositEntry {
     pub contract_id: String,
     /// Cumulative deposited amount (stroops)
     pub total_deposited: i128,
+    /// Cumulative USD value at deposit time (7 decimals), for consumers that
+    /// weight commitment by stable value
+    #[serde(default)]
+    pub total_usd: i128,
     /// Ledger sequence of the most recent deposit for this contract
     pub last_ledger: u32,
+    /// Drain schedule from the latest STREAM event (streaming deposits only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stream: Option<DrainSchedule>,
+    /// Refund requested on-chain and awaiting admin approval
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pending_refund: Option<PendingRefund>,
+}
+
+/// Streaming drain parameters, copied verbatim from the hvym-freenet-service
+/// `STREAM` event so the index never re-derives the contract's accounting.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct DrainSchedule {
+    /// Stroops drained per ledger
+    pub drain_rate: i128,
+    /// Amount already drained as of `checkpoint`
+    pub drained: i128,
+    /// Upper bound on the total drained amount
+    pub drain_cap: i128,
+    /// Ledger from which `drain_rate` accrues on top of `drained`
+    pub checkpoint: u32,
+}
+
+/// A refund request from the hvym-freenet-service `REFUNDREQ` event.
+///
+/// The entry still counts as commitment until the matching `REFUND` event
+/// removes it; a `REFUNDREJ` event clears the request.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PendingRefund {
+    /// Refundable amount at request time (stroops)
+    pub amount: i128,
+    /// First ledger at which the admin may approve the refund
+    pub unlocks_at: u32,
 }
 
 /// The full contract state: a versioned deposit map.
@@ -46,6 +82,52 @@ pub struct DepositMap {
     pub last_ledger_seq: u32,
     /// Sorted by contract_id (ascending)
     pub deposits: Vec<DepositEntry>,
+    /// The ledger after `last_ledger_seq`, while only some parts of its
+    /// split proof have been applied
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub partial: Option<PartialLedger>,
+    /// Information about how the state came about, with no bearing on the
+    /// deposits
+    #[serde(default, skip_serializing_if = "Diagnostics::is_empty")]
+    pub diagnostics: Diagnostics,
+}
+
+/// The diagnostics section of the contract state.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Diagnostics {
+    /// Relayers of the most recently applied attributed proofs, oldest
+    /// first, at most `attribution::RECENT_RELAYS` of them
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub recent_relays: Vec<RelayRecord>,
+}
+
+impl Diagnostics {
+    pub fn is_empty(&self) -> bool {
+        self.recent_relays.is_empty()
+    }
+}
+
+/// A relayer whose signed proof for a ledger was applied.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RelayRecord {
+    /// Ledger sequence of the proof
+    pub ledger_seq: u32,
+    /// Relayer's Stellar Ed25519 public key (hex 32 bytes)
+    pub relayer: String,
+}
+
+/// Progress through a ledger whose proof was split into parts.
+///
+/// Parts are applied in meta order; `last_ledger_seq` only advances to the
+/// ledger once all of its metas are applied.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PartialLedger {
+    /// Ledger sequence of the split proof
+    pub ledger_seq: u32,
+    /// Transaction result metas in the ledger
+    pub total_metas: u32,
+    /// Leading metas already applied
+    pub applied_metas: u32,
 }
 
 /// Summary for delta computation.
@@ -67,6 +149,63 @@ pub struct DepositProof {
     pub transaction_set: String,
     /// Transaction result metas containing events (base64-encoded XDR)
     pub tx_result_metas: Vec<String>,
+    /// Topics of the indexed events in this ledger as the relayer saw them
+    /// (e.g. `DEPOSIT`, `WITHDRAW`, `EXPIRE`). Informational only: events are
+    /// always re-extracted from `tx_result_metas`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub event_kinds: Vec<String>,
+    /// Where `tx_result_metas` fall in the ledger when the proof was split
+    /// to fit the UPDATE size limit; absent when they are all of them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub part: Option<ProofPart>,
+    /// The relayer that submitted the proof, if it signed it; recorded in
+    /// the state's diagnostics but never needed to apply the proof
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attribution: Option<RelayerAttribution>,
+}
+
+/// A relayer's signature over a proof, made with its Stellar key.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RelayerAttribution {
+    /// Relayer's Stellar Ed25519 public key (hex 32 bytes)
+    pub relayer: String,
+    /// Ed25519 signature over `attribution::attribution_message` (hex 64
+    /// bytes)
+    pub signature: String,
+}
+
+/// The slice of a ledger's transaction result metas a split proof carries.
+///
+/// Every part repeats the SCP envelopes and transaction set, so each one is
+/// verified on its own.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ProofPart {
+    /// Index of the part's first meta among the ledger's metas
+    pub offset: u32,
+    /// Transaction result metas in the ledger
+    pub total_metas: u32,
+}
+
+/// An UpdateData::Delta: one proof, or a batch of proofs for several ledgers
+/// submitted together.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(untagged)]
+pub enum ProofDelta {
+    Single(DepositProof),
+    Batch(Vec<DepositProof>),
+}
+
+impl ProofDelta {
+    /// The proofs in ledger order, the order they must be applied in.
+    pub fn into_proofs(self) -> Vec<DepositProof> {
+        match self {
+            ProofDelta::Single(proof) => vec![proof],
+            ProofDelta::Batch(mut proofs) => {
+                proofs.sort_by_key(|proof| proof.ledger_seq);
+                proofs
+            }
+        }
+    }
 }
 
 /// Decode a hex string into bytes.
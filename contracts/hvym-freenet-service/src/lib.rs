@@ -1,91 +1,1234 @@
 #![no_std]
 
+mod price_oracle;
 mod storage;
 mod types;
 
 #[cfg(test)]
 mod test;
 
-use soroban_sdk::{contract, contractimpl, symbol_short, token, Address, BytesN, Env};
+use soroban_sdk::{
+    contract, contractimpl, symbol_short, token, xdr::ToXdr, Address, Bytes, BytesN, Env, String,
+    Symbol, Vec,
+};
+use types::{
+    AccessMode, ClawbackProposal, ContractConfig, DataKey, DepositReceipt, DepositRecord,
+    HostingAttestation, RefundRequest,
+};
+
+/// Ledgers a refund request must wait before the admin can approve it
+/// (roughly 24 hours at 5 seconds per ledger).
+pub const REFUND_TIMELOCK_LEDGERS: u32 = 17_280;
+
+/// Ledgers between proposing and executing an emergency clawback, during
+/// which the admin or treasurer may cancel it (roughly 48 hours).
+pub const CLAWBACK_DELAY_LEDGERS: u32 = 34_560;
+
+/// Layout version of every event's data, published as the last topic.
+///
+/// Version 1 events (no version topic) came from builds that appended
+/// fields over time; consumers should ignore versions they do not know.
+pub const EVENT_SCHEMA_VERSION: u32 = 2;
+
+/// Largest page returned by `get_deposits_by_depositor`.
+pub const MAX_PAGE_SIZE: u32 = 50;
 
 #[contract]
 pub struct FreenetService;
 
 #[contractimpl]
 impl FreenetService {
-    /// Initialize the contract with an admin address, burn ratio, and token address.
+    /// Initialize the contract with admin, treasurer and treasury addresses,
+    /// burn ratio, token address, and optional price oracle.
     ///
+    /// `admin` controls configuration and upgrades; `treasurer` controls
+    /// treasury withdrawals. Both may be the same address.
+    /// `treasury` receives the non-burned share of deposits and must approve
+    /// this contract as a token spender so payouts can be made from it.
     /// `burn_bps` is in basis points (0–10000, e.g. 3000 = 30%).
     /// `token` is the native XLM SAC address.
-    pub fn __constructor(env: Env, admin: Address, burn_bps: u32, token: Address) {
+    /// `price_oracle` is a SEP-40 oracle used to value deposits in USD.
+    pub fn __constructor(
+        env: Env,
+        admin: Address,
+        treasurer: Address,
+        treasury: Address,
+        burn_bps: u32,
+        token: Address,
+        price_oracle: Option<Address>,
+    ) {
         assert!(burn_bps <= 10_000, "burn_bps must be <= 10000");
         storage::set_admin(&env, &admin);
+        storage::set_treasurer(&env, &treasurer);
+        storage::set_treasury(&env, &treasury);
         storage::set_burn_bps(&env, burn_bps);
         storage::set_token(&env, &token);
+        storage::set_price_oracle(&env, &price_oracle);
     }
 
-    /// Deposit native XLM for a Freenet contract ID. Non-refundable.
+    /// Deposit native XLM for a Freenet contract ID.
+    ///
+    /// Refundable only through the timelocked `request_refund` /
+    /// `approve_refund` flow.
+    ///
+    /// Splits between SAC burn and the treasury account per `burn_bps`.
+    /// Rejects contract IDs excluded by the current `AccessMode`, and every
+    /// deposit while the contract is paused.
+    /// The first deposit for a contract ID creates its `DepositRecord`;
+    /// subsequent deposits top up the existing record and are accepted only
+    /// from its depositor or a delegate approved via `approve_topup`.
     ///
-    /// Splits between SAC burn and contract treasury per `burn_bps`.
-    /// Emits: `("DEPOSIT", contract_id) → (caller, amount, burn_amount, ledger_seq)`
+    /// Emits on first deposit:
+    /// `("DEPOSIT", contract_id) → (caller, amount, burn_amount, ledger_seq, usd_value)`
+    ///
+    /// Emits on top-up:
+    /// `("TOPUP", contract_id) → (caller, amount, burn_amount, ledger_seq, previous_total,
+    /// new_total, usd_value)`
+    ///
+    /// `usd_value` is the deposit's USD value from the price oracle (7 decimals),
+    /// or 0 when unavailable.
     pub fn deposit(env: Env, caller: Address, contract_id: BytesN<32>, amount: i128) {
+        caller.require_auth();
+        let record = apply_deposit(&env, &caller, &contract_id, amount);
+        if record.drain_rate > 0 {
+            // Top-ups change the drain cap of a streaming record
+            publish_stream(&env, &contract_id, &record);
+        }
+    }
+
+    /// Deposit native XLM on behalf of `depositor`, submitted by a
+    /// registered fee sponsor.
+    ///
+    /// The sponsor submits (and pays fees for) the transaction; `depositor`
+    /// signs an authorization entry for this call and the funds move from
+    /// `depositor`, who is recorded as `DepositRecord.depositor` on first
+    /// deposit. Otherwise behaves like `deposit` and emits the same events.
+    pub fn deposit_sponsored(
+        env: Env,
+        sponsor: Address,
+        depositor: Address,
+        contract_id: BytesN<32>,
+        amount: i128,
+    ) {
+        sponsor.require_auth();
+        assert!(
+            storage::is_listed(&env, &DataKey::Sponsor(sponsor)),
+            "sponsor not registered"
+        );
+        depositor.require_auth();
+        let record = apply_deposit(&env, &depositor, &contract_id, amount);
+        if record.drain_rate > 0 {
+            publish_stream(&env, &contract_id, &record);
+        }
+    }
+
+    /// Deposit XLM as a streaming persistence payment.
+    ///
+    /// Behaves like `deposit`, then sets the record's drain rate to
+    /// `drain_rate` stroops per ledger. The non-burned balance drains at that
+    /// rate until exhausted; drained funds become claimable and stop counting
+    /// as live commitment. Only the depositor can change the rate of an
+    /// existing record.
+    ///
+    /// Emits `DEPOSIT`/`TOPUP` as `deposit` does, followed by:
+    /// `("STREAM", contract_id) → (depositor, drain_rate, drained, drain_cap, drain_checkpoint)`
+    pub fn deposit_stream(
+        env: Env,
+        caller: Address,
+        contract_id: BytesN<32>,
+        amount: i128,
+        drain_rate: i128,
+    ) {
+        caller.require_auth();
+        assert!(drain_rate > 0, "drain_rate must be positive");
+        if let Some(record) = storage::get_deposit(&env, &contract_id) {
+            assert!(
+                caller == record.depositor,
+                "only depositor can set drain rate"
+            );
+        }
+
+        let mut record = apply_deposit(&env, &caller, &contract_id, amount);
+        record.drain_rate = drain_rate;
+        storage::set_deposit(&env, &contract_id, &record);
+        publish_stream(&env, &contract_id, &record);
+    }
+
+    /// Amount drained from a streaming deposit and not yet claimed, in stroops.
+    ///
+    /// Returns 0 for flat deposits and unknown contract IDs.
+    pub fn claimable(env: Env, contract_id: BytesN<32>) -> i128 {
+        storage::get_deposit(&env, &contract_id)
+            .map(|record| record.unclaimed_at(env.ledger().sequence()))
+            .unwrap_or(0)
+    }
+
+    /// Claim the drained, unclaimed pool of a streaming deposit as a node operator.
+    ///
+    /// `operator` must be registered via `register_operator` and present a
+    /// `HostingAttestation` signed by its node key for `contract_id` that has
+    /// not expired (see `HostingAttestation` for the signed message). The operator is paid `reward_bps` of the unclaimed pool;
+    /// the remainder stays in the treasury. Returns the payout.
+    ///
+    /// Emits: `("CLAIM", contract_id) → (operator, payout, claimed_total, ledger_seq)`
+    pub fn claim(
+        env: Env,
+        operator: Address,
+        contract_id: BytesN<32>,
+        attestation: HostingAttestation,
+    ) -> i128 {
+        operator.require_auth();
+        let node_key = storage::get_operator(&env, &operator).expect("operator not registered");
+
+        let ledger_seq = env.ledger().sequence();
+        assert!(attestation.expires_at >= ledger_seq, "attestation expired");
+        let message = attestation_message(&env, &operator, &contract_id, attestation.expires_at);
+        env.crypto()
+            .ed25519_verify(&node_key, &message, &attestation.signature);
+
+        let mut record = storage::get_deposit(&env, &contract_id).expect("no deposit");
+        let unclaimed = record.unclaimed_at(ledger_seq);
+        assert!(unclaimed > 0, "nothing to claim");
+
+        let payout = unclaimed * storage::get_reward_bps(&env) as i128 / 10_000;
+        record.claimed += unclaimed;
+        storage::set_deposit(&env, &contract_id, &record);
+
+        if payout > 0 {
+            pay_from_treasury(&env, &operator, payout);
+        }
+
+        env.events().publish(
+            (symbol_short!("CLAIM"), contract_id, EVENT_SCHEMA_VERSION),
+            (operator, payout, record.claimed, ledger_seq),
+        );
+        payout
+    }
+
+    /// Live commitment for a Freenet contract ID: the deposited amount minus
+    /// whatever has drained from a streaming deposit.
+    pub fn live_amount(env: Env, contract_id: BytesN<32>) -> i128 {
+        storage::get_deposit(&env, &contract_id)
+            .map(|record| record.live_amount(env.ledger().sequence()))
+            .unwrap_or(0)
+    }
+
+    /// Depositor-only: withdraw part of a deposit.
+    ///
+    /// Only the undrained, non-burned balance is withdrawable. The treasury
+    /// keeps `withdraw_fee_bps` of `amount`; the depositor receives the rest.
+    /// The record stays in place with `amount` reduced. The payout goes to
+    /// `to` when given (e.g. a rotated wallet), otherwise to the depositor.
+    /// Returns the payout.
+    ///
+    /// Emits: `("WITHDRAW", contract_id) → (depositor, amount, fee, remaining_total, ledger_seq,
+    /// recipient)`, followed by `STREAM` for streaming records.
+    pub fn withdraw_amount(
+        env: Env,
+        caller: Address,
+        contract_id: BytesN<32>,
+        amount: i128,
+        to: Option<Address>,
+    ) -> i128 {
         caller.require_auth();
         assert!(amount > 0, "amount must be positive");
+        apply_withdraw(&env, &caller, &contract_id, Some(amount), to)
+    }
+
+    /// Depositor-only: withdraw the whole withdrawable balance and remove the
+    /// record. The burned share is not recoverable. The payout goes to `to`
+    /// when given, otherwise to the depositor. Returns the payout.
+    ///
+    /// Emits `WITHDRAW` as `withdraw_amount` does, with `remaining_total` 0.
+    pub fn withdraw(
+        env: Env,
+        caller: Address,
+        contract_id: BytesN<32>,
+        to: Option<Address>,
+    ) -> i128 {
+        caller.require_auth();
+        apply_withdraw(&env, &caller, &contract_id, None, to)
+    }
+
+    /// Depositor-only: allow `delegate` to top up the record of `contract_id`.
+    ///
+    /// Delegates can deposit into the record but cannot withdraw, refund or
+    /// change its settings, such as the drain rate set by `deposit_stream`.
+    /// Approvals belong to the current depositor and do not carry over to a
+    /// record recreated by someone else.
+    ///
+    /// Emits: `("DELEGATE", contract_id) → (delegate, approved, ledger_seq)`
+    pub fn approve_topup(env: Env, owner: Address, delegate: Address, contract_id: BytesN<32>) {
+        set_topup_delegate(&env, &owner, &delegate, &contract_id, true);
+    }
+
+    /// Depositor-only: withdraw a top-up approval granted by `approve_topup`.
+    ///
+    /// Emits: `("DELEGATE", contract_id) → (delegate, approved, ledger_seq)`
+    pub fn revoke_topup(env: Env, owner: Address, delegate: Address, contract_id: BytesN<32>) {
+        set_topup_delegate(&env, &owner, &delegate, &contract_id, false);
+    }
+
+    /// Whether `delegate` may top up the record of `contract_id`.
+    pub fn is_topup_delegate(env: Env, contract_id: BytesN<32>, delegate: Address) -> bool {
+        storage::get_deposit(&env, &contract_id)
+            .map(|record| can_top_up(&env, &contract_id, &record, &delegate))
+            .unwrap_or(false)
+    }
+
+    /// Depositor-only: let the record expire after `horizon` ledgers without
+    /// a deposit or top-up (0 disables expiry).
+    ///
+    /// Emits: `("HORIZON", contract_id) → (horizon, expires_at, ledger_seq)`
+    /// where `expires_at` is 0 when expiry is disabled.
+    pub fn set_expiry(env: Env, caller: Address, contract_id: BytesN<32>, horizon: u32) {
+        caller.require_auth();
+        let mut record = storage::get_deposit(&env, &contract_id).expect("no deposit");
+        assert!(caller == record.depositor, "only depositor can set expiry");
+        record.expiry_horizon = horizon;
+        storage::set_deposit(&env, &contract_id, &record);
+
+        env.events().publish(
+            (symbol_short!("HORIZON"), contract_id, EVENT_SCHEMA_VERSION),
+            (
+                horizon,
+                record.expires_at().unwrap_or(0),
+                env.ledger().sequence(),
+            ),
+        );
+    }
+
+    /// Sweep an expired deposit into the treasury. Permissionless.
+    ///
+    /// Removes the record (with its receipt and any pending refund or
+    /// clawback) so it stops counting as commitment. Everything not yet paid out to
+    /// operators stays in the treasury. Returns the swept amount.
+    ///
+    /// Emits: `("EXPIRE", contract_id) → (depositor, swept, ledger_seq)`
+    pub fn sweep(env: Env, contract_id: BytesN<32>) -> i128 {
+        let record = storage::get_deposit(&env, &contract_id).expect("no deposit");
+        let expires_at = record.expires_at().expect("deposit does not expire");
+        let ledger_seq = env.ledger().sequence();
+        assert!(ledger_seq >= expires_at, "deposit not expired");
+
+        let swept = record.drain_cap() - record.claimed;
+        storage::remove_deposit(&env, &contract_id);
+        storage::unindex_deposit(&env, &record.depositor, &contract_id);
+        storage::remove_receipt(&env, &contract_id);
+        storage::remove_refund(&env, &contract_id);
+        storage::remove_clawback(&env, &contract_id);
+
+        env.events().publish(
+            (symbol_short!("EXPIRE"), contract_id, EVENT_SCHEMA_VERSION),
+            (record.depositor, swept, ledger_seq),
+        );
+        swept
+    }
+
+    /// Depositor-only: request reversal of a mistaken deposit.
+    ///
+    /// Starts a `REFUND_TIMELOCK_LEDGERS` timelock after which the admin may
+    /// approve the refund. The record keeps counting as commitment (and keeps
+    /// draining) until then.
+    ///
+    /// Emits: `("REFUNDREQ", contract_id) → (depositor, refundable, unlocks_at, ledger_seq)`
+    pub fn request_refund(env: Env, caller: Address, contract_id: BytesN<32>) {
+        caller.require_auth();
+        let record = storage::get_deposit(&env, &contract_id).expect("no deposit");
+        assert!(
+            caller == record.depositor,
+            "only depositor can request refund"
+        );
+        assert!(
+            storage::get_refund(&env, &contract_id).is_none(),
+            "refund already requested"
+        );
+
+        let ledger_seq = env.ledger().sequence();
+        let request = RefundRequest {
+            requested_at: ledger_seq,
+            unlocks_at: ledger_seq.saturating_add(REFUND_TIMELOCK_LEDGERS),
+        };
+        storage::set_refund(&env, &contract_id, &request);
+
+        env.events().publish(
+            (
+                Symbol::new(&env, "REFUNDREQ"),
+                contract_id,
+                EVENT_SCHEMA_VERSION,
+            ),
+            (
+                caller,
+                record.refundable_at(ledger_seq),
+                request.unlocks_at,
+                ledger_seq,
+            ),
+        );
+    }
+
+    /// Admin-only: approve a pending refund once its timelock has elapsed.
+    ///
+    /// Returns the undrained, non-burned balance to the depositor and removes
+    /// the deposit record; the burned share is not recoverable. Returns the
+    /// refunded amount.
+    ///
+    /// Emits: `("REFUND", contract_id) → (depositor, refunded, ledger_seq)`
+    pub fn approve_refund(env: Env, caller: Address, contract_id: BytesN<32>) -> i128 {
+        caller.require_auth();
+        let admin = storage::get_admin(&env);
+        assert!(caller == admin, "only admin can approve refunds");
 
-        let burn_bps = storage::get_burn_bps(&env) as i128;
-        let burn_amount = amount * burn_bps / 10_000;
-        let treasury_amount = amount - burn_amount;
+        let request = storage::get_refund(&env, &contract_id).expect("no refund requested");
+        let ledger_seq = env.ledger().sequence();
+        assert!(ledger_seq >= request.unlocks_at, "refund timelock active");
 
-        let token_addr = storage::get_token(&env);
-        let xlm_client = token::Client::new(&env, &token_addr);
+        let record = storage::get_deposit(&env, &contract_id).expect("no deposit");
+        let refunded = record.refundable_at(ledger_seq);
+        storage::remove_refund(&env, &contract_id);
+        storage::remove_deposit(&env, &contract_id);
+        storage::unindex_deposit(&env, &record.depositor, &contract_id);
+        storage::remove_receipt(&env, &contract_id);
+        storage::remove_clawback(&env, &contract_id);
 
-        // Transfer treasury portion to this contract
-        if treasury_amount > 0 {
-            xlm_client.transfer(&caller, &env.current_contract_address(), &treasury_amount);
+        if refunded > 0 {
+            pay_from_treasury(&env, &record.depositor, refunded);
         }
 
-        // Burn the burn portion via SAC burn()
-        if burn_amount > 0 {
-            xlm_client.burn(&caller, &burn_amount);
+        env.events().publish(
+            (symbol_short!("REFUND"), contract_id, EVENT_SCHEMA_VERSION),
+            (record.depositor, refunded, ledger_seq),
+        );
+        refunded
+    }
+
+    /// Admin-only: reject a pending refund request, leaving the deposit intact.
+    ///
+    /// Emits: `("REFUNDREJ", contract_id) → (ledger_seq,)`
+    pub fn reject_refund(env: Env, caller: Address, contract_id: BytesN<32>) {
+        caller.require_auth();
+        let admin = storage::get_admin(&env);
+        assert!(caller == admin, "only admin can reject refunds");
+        assert!(
+            storage::get_refund(&env, &contract_id).is_some(),
+            "no refund requested"
+        );
+        storage::remove_refund(&env, &contract_id);
+
+        env.events().publish(
+            (
+                Symbol::new(&env, "REFUNDREJ"),
+                contract_id,
+                EVENT_SCHEMA_VERSION,
+            ),
+            (env.ledger().sequence(),),
+        );
+    }
+
+    /// Admin-only: propose an emergency clawback of a deposit to `to`.
+    ///
+    /// The clawback can be executed after `CLAWBACK_DELAY_LEDGERS`; until
+    /// then the admin or the treasurer may cancel it.
+    ///
+    /// Emits: `("CLAWPROP", contract_id) → (to, executable_at, ledger_seq)`
+    pub fn propose_clawback(env: Env, caller: Address, contract_id: BytesN<32>, to: Address) {
+        caller.require_auth();
+        let admin = storage::get_admin(&env);
+        assert!(caller == admin, "only admin can propose clawback");
+        assert!(
+            storage::get_deposit(&env, &contract_id).is_some(),
+            "no deposit"
+        );
+        assert!(
+            storage::get_clawback(&env, &contract_id).is_none(),
+            "clawback already proposed"
+        );
+
+        let ledger_seq = env.ledger().sequence();
+        let proposal = ClawbackProposal {
+            to,
+            proposed_at: ledger_seq,
+            executable_at: ledger_seq.saturating_add(CLAWBACK_DELAY_LEDGERS),
+        };
+        storage::set_clawback(&env, &contract_id, &proposal);
+
+        env.events().publish(
+            (symbol_short!("CLAWPROP"), contract_id, EVENT_SCHEMA_VERSION),
+            (proposal.to, proposal.executable_at, ledger_seq),
+        );
+    }
+
+    /// Admin or treasurer: cancel a pending clawback before it executes.
+    ///
+    /// Emits: `("CLAWCANCL", contract_id) → (caller, ledger_seq)`
+    pub fn cancel_clawback(env: Env, caller: Address, contract_id: BytesN<32>) {
+        caller.require_auth();
+        assert!(
+            caller == storage::get_admin(&env) || caller == storage::get_treasurer(&env),
+            "only admin or treasurer can cancel clawback"
+        );
+        assert!(
+            storage::get_clawback(&env, &contract_id).is_some(),
+            "no clawback proposed"
+        );
+        storage::remove_clawback(&env, &contract_id);
+
+        env.events().publish(
+            (
+                symbol_short!("CLAWCANCL"),
+                contract_id,
+                EVENT_SCHEMA_VERSION,
+            ),
+            (caller, env.ledger().sequence()),
+        );
+    }
+
+    /// Admin-only: execute a clawback once its delay has elapsed.
+    ///
+    /// Transfers the non-burned balance not yet paid to operators to the
+    /// proposal's recipient and removes the deposit record. Returns the
+    /// clawed-back amount.
+    ///
+    /// Emits: `("CLAWBACK", contract_id) → (depositor, to, amount, ledger_seq)`
+    pub fn execute_clawback(env: Env, caller: Address, contract_id: BytesN<32>) -> i128 {
+        caller.require_auth();
+        let admin = storage::get_admin(&env);
+        assert!(caller == admin, "only admin can execute clawback");
+
+        let proposal = storage::get_clawback(&env, &contract_id).expect("no clawback proposed");
+        let ledger_seq = env.ledger().sequence();
+        assert!(
+            ledger_seq >= proposal.executable_at,
+            "clawback delay active"
+        );
+
+        let record = storage::get_deposit(&env, &contract_id).expect("no deposit");
+        let amount = record.drain_cap() - record.claimed;
+        storage::remove_clawback(&env, &contract_id);
+        storage::remove_refund(&env, &contract_id);
+        storage::remove_deposit(&env, &contract_id);
+        storage::unindex_deposit(&env, &record.depositor, &contract_id);
+        storage::remove_receipt(&env, &contract_id);
+
+        if amount > 0 {
+            pay_from_treasury(&env, &proposal.to, amount);
         }
 
         env.events().publish(
-            (symbol_short!("DEPOSIT"), contract_id),
-            (caller, amount, burn_amount, env.ledger().sequence()),
+            (symbol_short!("CLAWBACK"), contract_id, EVENT_SCHEMA_VERSION),
+            (record.depositor, proposal.to, amount, ledger_seq),
         );
+        amount
+    }
+
+    /// Query the pending clawback proposal for a Freenet contract ID.
+    pub fn get_clawback(env: Env, contract_id: BytesN<32>) -> Option<ClawbackProposal> {
+        storage::get_clawback(&env, &contract_id)
     }
 
-    /// Admin-only: withdraw XLM from the contract treasury.
+    /// Query the pending refund request for a Freenet contract ID.
+    pub fn get_refund(env: Env, contract_id: BytesN<32>) -> Option<RefundRequest> {
+        storage::get_refund(&env, &contract_id)
+    }
+
+    /// Extend the storage TTL of the given contract IDs' deposit records
+    /// (with their receipts and pending refunds) and of the contract's
+    /// configuration. Permissionless, so relayers can keep entries alive.
+    ///
+    /// Returns how many of `contract_ids` had a deposit record.
+    pub fn bump_ttl(env: Env, contract_ids: Vec<BytesN<32>>) -> u32 {
+        storage::bump_config(&env);
+        let mut bumped = 0;
+        for contract_id in contract_ids.iter() {
+            if storage::bump_deposit_entries(&env, &contract_id) {
+                bumped += 1;
+            }
+        }
+        bumped
+    }
+
+    /// Query how the most recent deposit or top-up for a Freenet contract ID
+    /// was split between burn and treasury.
+    pub fn get_receipt(env: Env, contract_id: BytesN<32>) -> Option<DepositReceipt> {
+        storage::get_receipt(&env, &contract_id)
+    }
+
+    /// Query the deposit record for a Freenet contract ID.
+    pub fn get_deposit(env: Env, contract_id: BytesN<32>) -> Option<DepositRecord> {
+        storage::get_deposit(&env, &contract_id)
+    }
+
+    /// Query the deposit records created by `depositor`, oldest first.
+    ///
+    /// Returns up to `limit` `(contract_id, DepositRecord)` pairs starting at
+    /// position `offset` of the depositor's index. `limit` is capped at
+    /// `MAX_PAGE_SIZE`.
+    pub fn get_deposits_by_depositor(
+        env: Env,
+        depositor: Address,
+        offset: u32,
+        limit: u32,
+    ) -> Vec<(BytesN<32>, DepositRecord)> {
+        let contract_ids = storage::get_depositor_index(&env, &depositor);
+        let end = offset
+            .saturating_add(limit.min(MAX_PAGE_SIZE))
+            .min(contract_ids.len());
+        let mut page = Vec::new(&env);
+        for i in offset..end {
+            let contract_id = contract_ids.get_unchecked(i);
+            if let Some(record) = storage::get_deposit(&env, &contract_id) {
+                page.push_back((contract_id, record));
+            }
+        }
+        page
+    }
+
+    /// Treasurer-only: withdraw XLM from the treasury.
     pub fn admin_withdraw(env: Env, caller: Address, to: Address, amount: i128) {
         caller.require_auth();
-        let admin = storage::get_admin(&env);
-        assert!(caller == admin, "only admin can withdraw");
+        let treasurer = storage::get_treasurer(&env);
+        assert!(caller == treasurer, "only treasurer can withdraw");
         assert!(amount > 0, "amount must be positive");
 
-        let token_addr = storage::get_token(&env);
-        let xlm_client = token::Client::new(&env, &token_addr);
-        xlm_client.transfer(&env.current_contract_address(), &to, &amount);
+        pay_from_treasury(&env, &to, amount);
 
         env.events().publish(
-            (symbol_short!("WITHDRAW"),),
+            (symbol_short!("WITHDRAW"), EVENT_SCHEMA_VERSION),
             (to, amount, env.ledger().sequence()),
         );
     }
 
+    /// Admin-only: set the fee on depositor withdrawals (basis points, 0–10000).
+    ///
+    /// Emits: `("FEEBPS",) → (previous_bps, new_bps)`
+    pub fn set_withdraw_fee_bps(env: Env, caller: Address, fee_bps: u32) {
+        caller.require_auth();
+        let admin = storage::get_admin(&env);
+        assert!(caller == admin, "only admin can set withdraw fee");
+        assert!(fee_bps <= 10_000, "fee_bps must be <= 10000");
+        let previous = storage::get_withdraw_fee_bps(&env);
+        storage::set_withdraw_fee_bps(&env, fee_bps);
+
+        env.events().publish(
+            (symbol_short!("FEEBPS"), EVENT_SCHEMA_VERSION),
+            (previous, fee_bps),
+        );
+    }
+
     /// Admin-only: update the burn ratio (basis points, 0–10000).
+    ///
+    /// Emits: `("BURNBPS",) → (previous_bps, new_bps)`
     pub fn set_burn_bps(env: Env, caller: Address, burn_bps: u32) {
         caller.require_auth();
         let admin = storage::get_admin(&env);
         assert!(caller == admin, "only admin can set burn ratio");
         assert!(burn_bps <= 10_000, "burn_bps must be <= 10000");
+        let previous = storage::get_burn_bps(&env);
         storage::set_burn_bps(&env, burn_bps);
+
+        env.events().publish(
+            (symbol_short!("BURNBPS"), EVENT_SCHEMA_VERSION),
+            (previous, burn_bps),
+        );
+    }
+
+    /// Admin-only: set or clear the SEP-40 price oracle used for USD valuation.
+    ///
+    /// Emits: `("ORACLE",) → (previous_oracle, new_oracle)`
+    pub fn set_price_oracle(env: Env, caller: Address, price_oracle: Option<Address>) {
+        caller.require_auth();
+        let admin = storage::get_admin(&env);
+        assert!(caller == admin, "only admin can set price oracle");
+        let previous = storage::get_price_oracle(&env);
+        storage::set_price_oracle(&env, &price_oracle);
+
+        env.events().publish(
+            (symbol_short!("ORACLE"), EVENT_SCHEMA_VERSION),
+            (previous, price_oracle),
+        );
+    }
+
+    /// Admin-only: choose which contract IDs `deposit` accepts.
+    ///
+    /// Emits: `("ACCESS",) → (previous_mode, new_mode)`
+    pub fn set_access_mode(env: Env, caller: Address, mode: AccessMode) {
+        caller.require_auth();
+        let admin = storage::get_admin(&env);
+        assert!(caller == admin, "only admin can set access mode");
+        let previous = storage::get_access_mode(&env);
+        storage::set_access_mode(&env, mode);
+
+        env.events().publish(
+            (symbol_short!("ACCESS"), EVENT_SCHEMA_VERSION),
+            (previous, mode),
+        );
+    }
+
+    /// Admin-only: add or remove a contract ID on the allowlist.
+    ///
+    /// Emits: `("ALLOWED",) → (contract_id, allowed)`
+    pub fn set_allowed(env: Env, caller: Address, contract_id: BytesN<32>, allowed: bool) {
+        caller.require_auth();
+        let admin = storage::get_admin(&env);
+        assert!(caller == admin, "only admin can edit allowlist");
+        storage::set_listed(&env, &DataKey::Allowed(contract_id.clone()), allowed);
+
+        env.events().publish(
+            (symbol_short!("ALLOWED"), EVENT_SCHEMA_VERSION),
+            (contract_id, allowed),
+        );
+    }
+
+    /// Admin-only: add or remove a contract ID on the denylist.
+    ///
+    /// Emits: `("DENIED",) → (contract_id, denied)`
+    pub fn set_denied(env: Env, caller: Address, contract_id: BytesN<32>, denied: bool) {
+        caller.require_auth();
+        let admin = storage::get_admin(&env);
+        assert!(caller == admin, "only admin can edit denylist");
+        storage::set_listed(&env, &DataKey::Denied(contract_id.clone()), denied);
+
+        env.events().publish(
+            (symbol_short!("DENIED"), EVENT_SCHEMA_VERSION),
+            (contract_id, denied),
+        );
+    }
+
+    /// Admin-only: register or remove a fee sponsor for `deposit_sponsored`.
+    ///
+    /// Emits: `("SPONSOR",) → (sponsor, registered)`
+    pub fn set_sponsor(env: Env, caller: Address, sponsor: Address, registered: bool) {
+        caller.require_auth();
+        let admin = storage::get_admin(&env);
+        assert!(caller == admin, "only admin can edit sponsors");
+        storage::set_listed(&env, &DataKey::Sponsor(sponsor.clone()), registered);
+
+        env.events().publish(
+            (symbol_short!("SPONSOR"), EVENT_SCHEMA_VERSION),
+            (sponsor, registered),
+        );
+    }
+
+    /// Admin-only: suspend new deposits. Withdrawals, refunds and claims
+    /// stay available so depositors can always exit.
+    ///
+    /// Emits: `("PAUSE",) → (caller, ledger_seq)`
+    pub fn pause(env: Env, caller: Address) {
+        caller.require_auth();
+        let admin = storage::get_admin(&env);
+        assert!(caller == admin, "only admin can pause");
+        storage::set_paused(&env, true);
+
+        env.events().publish(
+            (symbol_short!("PAUSE"), EVENT_SCHEMA_VERSION),
+            (caller, env.ledger().sequence()),
+        );
+    }
+
+    /// Admin-only: resume deposits after `pause`.
+    ///
+    /// Emits: `("UNPAUSE",) → (caller, ledger_seq)`
+    pub fn unpause(env: Env, caller: Address) {
+        caller.require_auth();
+        let admin = storage::get_admin(&env);
+        assert!(caller == admin, "only admin can unpause");
+        storage::set_paused(&env, false);
+
+        env.events().publish(
+            (symbol_short!("UNPAUSE"), EVENT_SCHEMA_VERSION),
+            (caller, env.ledger().sequence()),
+        );
+    }
+
+    /// Whether new deposits are currently suspended.
+    pub fn is_paused(env: Env) -> bool {
+        storage::is_paused(&env)
+    }
+
+    /// Admin-only: set the smallest accepted deposit or top-up in stroops.
+    ///
+    /// Emits: `("MINDEP",) → (previous_amount, new_amount)`
+    pub fn set_min_deposit(env: Env, caller: Address, amount: i128) {
+        caller.require_auth();
+        let admin = storage::get_admin(&env);
+        assert!(caller == admin, "only admin can set min deposit");
+        assert!(amount > 0, "min deposit must be positive");
+        let previous = storage::get_min_deposit(&env);
+        storage::set_min_deposit(&env, amount);
+
+        env.events().publish(
+            (symbol_short!("MINDEP"), EVENT_SCHEMA_VERSION),
+            (previous, amount),
+        );
+    }
+
+    /// Semantic version of this contract build.
+    pub fn version(env: Env) -> String {
+        String::from_str(&env, env!("CARGO_PKG_VERSION"))
+    }
+
+    /// Current deposit settings: burn ratio, token, minimum deposit and
+    /// pause state.
+    pub fn config(env: Env) -> ContractConfig {
+        ContractConfig {
+            burn_bps: storage::get_burn_bps(&env),
+            token: storage::get_token(&env),
+            min_deposit: storage::get_min_deposit(&env),
+            paused: storage::is_paused(&env),
+        }
+    }
+
+    /// Whether `sponsor` may submit `deposit_sponsored`.
+    pub fn is_sponsor(env: Env, sponsor: Address) -> bool {
+        storage::is_listed(&env, &DataKey::Sponsor(sponsor))
+    }
+
+    /// Current access-list enforcement mode.
+    pub fn access_mode(env: Env) -> AccessMode {
+        storage::get_access_mode(&env)
+    }
+
+    /// Whether `deposit` would currently accept `contract_id`.
+    pub fn is_fundable(env: Env, contract_id: BytesN<32>) -> bool {
+        is_fundable(&env, &contract_id)
+    }
+
+    /// Admin-only: set the operator share of claimed drain (basis points, 0–10000).
+    ///
+    /// Emits: `("REWARDBPS",) → (previous_bps, new_bps)`
+    pub fn set_reward_bps(env: Env, caller: Address, reward_bps: u32) {
+        caller.require_auth();
+        let admin = storage::get_admin(&env);
+        assert!(caller == admin, "only admin can set reward ratio");
+        assert!(reward_bps <= 10_000, "reward_bps must be <= 10000");
+        let previous = storage::get_reward_bps(&env);
+        storage::set_reward_bps(&env, reward_bps);
+
+        env.events().publish(
+            (symbol_short!("REWARDBPS"), EVENT_SCHEMA_VERSION),
+            (previous, reward_bps),
+        );
+    }
+
+    /// Admin-only: register a node operator's payout address and ed25519 node key.
+    ///
+    /// Emits: `("OPERATOR",) → (operator, node_key)`
+    pub fn register_operator(env: Env, caller: Address, operator: Address, node_key: BytesN<32>) {
+        caller.require_auth();
+        let admin = storage::get_admin(&env);
+        assert!(caller == admin, "only admin can register operators");
+        storage::set_operator(&env, &operator, &node_key);
+
+        env.events().publish(
+            (symbol_short!("OPERATOR"), EVENT_SCHEMA_VERSION),
+            (operator, node_key),
+        );
+    }
+
+    /// Admin-only: remove a registered node operator.
+    ///
+    /// Emits: `("OPREMOVE",) → (operator,)`
+    pub fn remove_operator(env: Env, caller: Address, operator: Address) {
+        caller.require_auth();
+        let admin = storage::get_admin(&env);
+        assert!(caller == admin, "only admin can remove operators");
+        storage::remove_operator(&env, &operator);
+
+        env.events().publish(
+            (symbol_short!("OPREMOVE"), EVENT_SCHEMA_VERSION),
+            (operator,),
+        );
     }
 
     /// Transfer admin to a new address. Admin-only.
+    ///
+    /// Emits: `("ADMIN",) → (previous_admin, new_admin)`
     pub fn set_admin(env: Env, caller: Address, new_admin: Address) {
         caller.require_auth();
         let admin = storage::get_admin(&env);
         assert!(caller == admin, "only admin can transfer admin");
         storage::set_admin(&env, &new_admin);
+
+        env.events().publish(
+            (symbol_short!("ADMIN"), EVENT_SCHEMA_VERSION),
+            (admin, new_admin),
+        );
+    }
+
+    /// Transfer the treasurer role to a new address. Treasurer-only.
+    ///
+    /// Emits: `("TREASURER",) → (previous_treasurer, new_treasurer)`
+    pub fn set_treasurer(env: Env, caller: Address, new_treasurer: Address) {
+        caller.require_auth();
+        let treasurer = storage::get_treasurer(&env);
+        assert!(caller == treasurer, "only treasurer can transfer treasurer");
+        storage::set_treasurer(&env, &new_treasurer);
+
+        env.events().publish(
+            (symbol_short!("TREASURER"), EVENT_SCHEMA_VERSION),
+            (treasurer, new_treasurer),
+        );
+    }
+
+    /// Admin-only: replace the contract code with an uploaded WASM.
+    ///
+    /// Emits: `("UPGRADE",) → (new_wasm_hash, ledger_seq)`
+    pub fn upgrade(env: Env, caller: Address, new_wasm_hash: BytesN<32>) {
+        caller.require_auth();
+        let admin = storage::get_admin(&env);
+        assert!(caller == admin, "only admin can upgrade");
+
+        env.deployer()
+            .update_current_contract_wasm(new_wasm_hash.clone());
+        env.events().publish(
+            (symbol_short!("UPGRADE"), EVENT_SCHEMA_VERSION),
+            (new_wasm_hash, env.ledger().sequence()),
+        );
+    }
+
+    /// Current admin address.
+    pub fn admin(env: Env) -> Address {
+        storage::get_admin(&env)
+    }
+
+    /// Current treasurer address.
+    pub fn treasurer(env: Env) -> Address {
+        storage::get_treasurer(&env)
+    }
+
+    /// Admin-only: move the treasury to a new account.
+    ///
+    /// Only redirects future deposits and payouts; funds already held by the
+    /// previous treasury stay there. The new treasury must approve this
+    /// contract as a token spender before payouts resume.
+    ///
+    /// Emits: `("TREASURY",) → (previous_treasury, new_treasury)`
+    pub fn set_treasury(env: Env, caller: Address, new_treasury: Address) {
+        caller.require_auth();
+        let admin = storage::get_admin(&env);
+        assert!(caller == admin, "only admin can set treasury");
+        let previous = treasury(&env);
+        storage::set_treasury(&env, &new_treasury);
+
+        env.events().publish(
+            (symbol_short!("TREASURY"), EVENT_SCHEMA_VERSION),
+            (previous, new_treasury),
+        );
+    }
+
+    /// Account currently holding the treasury.
+    pub fn treasury(env: Env) -> Address {
+        treasury(&env)
     }
 }
+
+/// Treasury account, falling back to the contract itself for deployments
+/// upgraded from builds that had no separate treasury.
+fn treasury(env: &Env) -> Address {
+    storage::get_treasury(env).unwrap_or_else(|| env.current_contract_address())
+}
+
+/// Pay `amount` out of the treasury to `to`.
+///
+/// A separate treasury account pays through the allowance it granted this
+/// contract, so payouts fail while that allowance is missing or exhausted.
+fn pay_from_treasury(env: &Env, to: &Address, amount: i128) {
+    let xlm_client = token::Client::new(env, &storage::get_token(env));
+    let contract = env.current_contract_address();
+    let treasury = treasury(env);
+    if treasury == contract {
+        xlm_client.transfer(&contract, to, &amount);
+    } else {
+        xlm_client.transfer_from(&contract, &treasury, to, &amount);
+    }
+}
+
+/// Check `contract_id` against the access lists under the current mode.
+fn is_fundable(env: &Env, contract_id: &BytesN<32>) -> bool {
+    match storage::get_access_mode(env) {
+        AccessMode::Open => true,
+        AccessMode::Allowlist => storage::is_listed(env, &DataKey::Allowed(contract_id.clone())),
+        AccessMode::Denylist => !storage::is_listed(env, &DataKey::Denied(contract_id.clone())),
+    }
+}
+
+/// Record or clear a top-up approval after checking `owner` holds the record.
+fn set_topup_delegate(
+    env: &Env,
+    owner: &Address,
+    delegate: &Address,
+    contract_id: &BytesN<32>,
+    approved: bool,
+) {
+    owner.require_auth();
+    let record = storage::get_deposit(env, contract_id).expect("no deposit");
+    assert!(
+        *owner == record.depositor,
+        "only depositor can approve top-ups"
+    );
+    storage::set_listed(
+        env,
+        &DataKey::TopupDelegate(contract_id.clone(), owner.clone(), delegate.clone()),
+        approved,
+    );
+
+    env.events().publish(
+        (
+            symbol_short!("DELEGATE"),
+            contract_id.clone(),
+            EVENT_SCHEMA_VERSION,
+        ),
+        (delegate.clone(), approved, env.ledger().sequence()),
+    );
+}
+
+/// Whether `caller` may add to an existing record: its depositor or a
+/// delegate the depositor approved.
+fn can_top_up(
+    env: &Env,
+    contract_id: &BytesN<32>,
+    record: &DepositRecord,
+    caller: &Address,
+) -> bool {
+    *caller == record.depositor
+        || storage::is_listed(
+            env,
+            &DataKey::TopupDelegate(
+                contract_id.clone(),
+                record.depositor.clone(),
+                caller.clone(),
+            ),
+        )
+}
+
+/// Move `amount` from `caller` (burn + treasury split) and fold it into the
+/// contract ID's deposit record, emitting `DEPOSIT` or `TOPUP` and
+/// replacing the contract ID's `DepositReceipt`. Every deposit also extends
+/// the TTL of the record, its receipt and the contract configuration.
+///
+/// Streaming records are checkpointed at the current ledger before the new
+/// amount is added, so time elapsed before the top-up drains against the
+/// old cap only.
+fn apply_deposit(
+    env: &Env,
+    caller: &Address,
+    contract_id: &BytesN<32>,
+    amount: i128,
+) -> DepositRecord {
+    assert!(amount > 0, "amount must be positive");
+    assert!(
+        amount >= storage::get_min_deposit(env),
+        "amount below min deposit"
+    );
+    assert!(!storage::is_paused(env), "deposits paused");
+    assert!(is_fundable(env, contract_id), "contract_id not fundable");
+    let existing = storage::get_deposit(env, contract_id);
+    if let Some(record) = &existing {
+        assert!(
+            can_top_up(env, contract_id, record, caller),
+            "caller cannot top up"
+        );
+    }
+    storage::bump_config(env);
+
+    let burn_bps = storage::get_burn_bps(env);
+    let burn_amount = amount * burn_bps as i128 / 10_000;
+    let treasury_amount = amount - burn_amount;
+
+    let token_addr = storage::get_token(env);
+    let xlm_client = token::Client::new(env, &token_addr);
+
+    // Transfer treasury portion to the treasury account
+    if treasury_amount > 0 {
+        xlm_client.transfer(caller, &treasury(env), &treasury_amount);
+    }
+
+    // Burn the burn portion via SAC burn()
+    if burn_amount > 0 {
+        xlm_client.burn(caller, &burn_amount);
+    }
+
+    let usd_value = price_oracle::usd_value(env, &token_addr, amount);
+    let ledger_seq = env.ledger().sequence();
+    let record = match existing {
+        Some(mut record) => {
+            let previous_total = record.amount;
+            record.checkpoint(ledger_seq);
+            record.amount += amount;
+            record.burned += burn_amount;
+            record.usd_value += usd_value;
+            record.updated_at = ledger_seq;
+            storage::set_deposit(env, contract_id, &record);
+
+            env.events().publish(
+                (
+                    symbol_short!("TOPUP"),
+                    contract_id.clone(),
+                    EVENT_SCHEMA_VERSION,
+                ),
+                (
+                    caller.clone(),
+                    amount,
+                    burn_amount,
+                    ledger_seq,
+                    previous_total,
+                    record.amount,
+                    usd_value,
+                ),
+            );
+            record
+        }
+        None => {
+            let record = DepositRecord {
+                depositor: caller.clone(),
+                amount,
+                burned: burn_amount,
+                usd_value,
+                created_at: ledger_seq,
+                updated_at: ledger_seq,
+                drain_rate: 0,
+                drained: 0,
+                drain_checkpoint: ledger_seq,
+                claimed: 0,
+                expiry_horizon: 0,
+            };
+            storage::set_deposit(env, contract_id, &record);
+            storage::index_deposit(env, caller, contract_id);
+
+            env.events().publish(
+                (
+                    symbol_short!("DEPOSIT"),
+                    contract_id.clone(),
+                    EVENT_SCHEMA_VERSION,
+                ),
+                (caller.clone(), amount, burn_amount, ledger_seq, usd_value),
+            );
+            record
+        }
+    };
+
+    storage::set_receipt(
+        env,
+        contract_id,
+        &DepositReceipt {
+            caller: caller.clone(),
+            gross: amount,
+            burned: burn_amount,
+            treasury: treasury_amount,
+            burn_bps,
+            usd_value,
+            ledger_seq,
+            record_created_at: record.created_at,
+        },
+    );
+    record
+}
+
+/// Withdraw `amount` (or everything withdrawable when `None`) from the
+/// caller's deposit, charging the withdrawal fee.
+///
+/// A full withdrawal removes the record and its receipt. Withdrawals are
+/// blocked while a clawback is pending so they cannot front-run it.
+fn apply_withdraw(
+    env: &Env,
+    caller: &Address,
+    contract_id: &BytesN<32>,
+    amount: Option<i128>,
+    to: Option<Address>,
+) -> i128 {
+    let mut record = storage::get_deposit(env, contract_id).expect("no deposit");
+    assert!(*caller == record.depositor, "only depositor can withdraw");
+    assert!(
+        storage::get_clawback(env, contract_id).is_none(),
+        "clawback pending"
+    );
+
+    let ledger_seq = env.ledger().sequence();
+    record.checkpoint(ledger_seq);
+    let withdrawable = record.refundable_at(ledger_seq);
+    let amount = amount.unwrap_or(withdrawable);
+    assert!(amount > 0, "nothing to withdraw");
+    assert!(
+        amount <= withdrawable,
+        "amount exceeds withdrawable balance"
+    );
+
+    let fee = amount * storage::get_withdraw_fee_bps(env) as i128 / 10_000;
+    let payout = amount - fee;
+    let full = amount == withdrawable;
+
+    let remaining_total = if full {
+        storage::remove_deposit(env, contract_id);
+        storage::unindex_deposit(env, &record.depositor, contract_id);
+        storage::remove_receipt(env, contract_id);
+        storage::remove_refund(env, contract_id);
+        0
+    } else {
+        record.amount -= amount;
+        record.updated_at = ledger_seq;
+        storage::set_deposit(env, contract_id, &record);
+        record.amount
+    };
+
+    let recipient = to.unwrap_or_else(|| caller.clone());
+    if payout > 0 {
+        pay_from_treasury(env, &recipient, payout);
+    }
+
+    env.events().publish(
+        (
+            symbol_short!("WITHDRAW"),
+            contract_id.clone(),
+            EVENT_SCHEMA_VERSION,
+        ),
+        (
+            caller.clone(),
+            amount,
+            fee,
+            remaining_total,
+            ledger_seq,
+            recipient,
+        ),
+    );
+    if !full && record.drain_rate > 0 {
+        // Withdrawal lowered the drain cap
+        publish_stream(env, contract_id, &record);
+    }
+    payout
+}
+
+/// Publish the streaming parameters of a record so the deposit-index can
+/// compute its live commitment at any later ledger.
+fn publish_stream(env: &Env, contract_id: &BytesN<32>, record: &DepositRecord) {
+    env.events().publish(
+        (
+            symbol_short!("STREAM"),
+            contract_id.clone(),
+            EVENT_SCHEMA_VERSION,
+        ),
+        (
+            record.depositor.clone(),
+            record.drain_rate,
+            record.drained,
+            record.drain_cap(),
+            record.drain_checkpoint,
+        ),
+    );
+}
+
+/// The message a `HostingAttestation` signs: the network ID, this contract's
+/// and the operator's addresses as `ScVal` XDR, `contract_id` and
+/// `expires_at`, so an attestation cannot be replayed on another network,
+/// deployment or operator.
+fn attestation_message(
+    env: &Env,
+    operator: &Address,
+    contract_id: &BytesN<32>,
+    expires_at: u32,
+) -> Bytes {
+    let mut message = Bytes::from_array(env, &env.ledger().network_id().to_array());
+    message.append(&env.current_contract_address().to_xdr(env));
+    message.append(&operator.clone().to_xdr(env));
+    message.extend_from_array(&contract_id.to_array());
+    message.extend_from_array(&expires_at.to_be_bytes());
+    message
+}
@@ -0,0 +1,65 @@
+//! Client for a SEP-40 price-oracle contract (e.g. Reflector), used to value
+//! deposits in USD at deposit time.
+
+use soroban_sdk::{contractclient, contracttype, Address, Env, Symbol};
+
+use crate::storage;
+
+/// Asset identifier as defined by the SEP-40 oracle interface.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub enum Asset {
+    Stellar(Address),
+    Other(Symbol),
+}
+
+/// Price record as defined by the SEP-40 oracle interface.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PriceData {
+    pub price: i128,
+    pub timestamp: u64,
+}
+
+// Only the generated `PriceOracleClient` is used.
+#[allow(dead_code)]
+#[contractclient(name = "PriceOracleClient")]
+pub trait PriceOracle {
+    /// Most recent price of `asset`, scaled by `10^decimals()`.
+    fn lastprice(env: Env, asset: Asset) -> Option<PriceData>;
+    /// Number of decimal places in reported prices.
+    fn decimals(env: Env) -> u32;
+}
+
+/// Oldest price `usd_value` accepts, in seconds before the current ledger
+/// close time (three Reflector update periods).
+pub const MAX_PRICE_AGE_SECS: u64 = 900;
+
+/// USD value of `amount` stroops of `token`, in 7-decimal fixed point (the
+/// same scale as stroops).
+///
+/// Returns 0 when no oracle is configured, it cannot price the token or its
+/// price is older than `MAX_PRICE_AGE_SECS`, so an oracle outage never blocks
+/// deposits.
+pub fn usd_value(env: &Env, token: &Address, amount: i128) -> i128 {
+    let Some(oracle) = storage::get_price_oracle(env) else {
+        return 0;
+    };
+    let client = PriceOracleClient::new(env, &oracle);
+    let price = match client.try_lastprice(&Asset::Stellar(token.clone())) {
+        Ok(Ok(Some(data)))
+            if env.ledger().timestamp().saturating_sub(data.timestamp) <= MAX_PRICE_AGE_SECS =>
+        {
+            data.price
+        }
+        _ => return 0,
+    };
+    let decimals = match client.try_decimals() {
+        Ok(Ok(decimals)) => decimals,
+        _ => return 0,
+    };
+    amount
+        .checked_mul(price)
+        .and_then(|v| v.checked_div(10i128.checked_pow(decimals)?))
+        .unwrap_or(0)
+}
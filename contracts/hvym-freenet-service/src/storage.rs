@@ -1,12 +1,63 @@
-use soroban_sdk::{Address, Env};
+use soroban_sdk::{Address, BytesN, Env, Vec};
 
-use crate::types::DataKey;
+use crate::types::{
+    AccessMode, ClawbackProposal, DataKey, DepositReceipt, DepositRecord, RefundRequest,
+};
 
 /// Bump amount for persistent storage entries (roughly 30 days in ledgers).
 const LEDGER_BUMP: u32 = 518_400;
 /// Threshold for bumping (roughly 15 days).
 const LEDGER_THRESHOLD: u32 = 259_200;
 
+// =============================================================================
+// TTL Maintenance
+// =============================================================================
+
+/// Contract-wide configuration entries kept alive by `bump_config`.
+const CONFIG_KEYS: [DataKey; 11] = [
+    DataKey::Admin,
+    DataKey::Treasurer,
+    DataKey::Treasury,
+    DataKey::BurnBps,
+    DataKey::TokenAddress,
+    DataKey::PriceOracle,
+    DataKey::RewardBps,
+    DataKey::AccessMode,
+    DataKey::WithdrawFeeBps,
+    DataKey::Paused,
+    DataKey::MinDeposit,
+];
+
+/// Extend `key` if it exists. Returns whether it existed.
+fn bump_if_present(env: &Env, key: &DataKey) -> bool {
+    let present = env.storage().persistent().has(key);
+    if present {
+        env.storage()
+            .persistent()
+            .extend_ttl(key, LEDGER_THRESHOLD, LEDGER_BUMP);
+    }
+    present
+}
+
+/// Extend every configuration entry that has been set.
+pub fn bump_config(env: &Env) {
+    for key in CONFIG_KEYS.iter() {
+        bump_if_present(env, key);
+    }
+}
+
+/// Extend the deposit record of `contract_id` together with its receipt and
+/// any pending refund or clawback. Returns whether a deposit record exists.
+pub fn bump_deposit_entries(env: &Env, contract_id: &BytesN<32>) -> bool {
+    if !bump_if_present(env, &DataKey::Deposit(contract_id.clone())) {
+        return false;
+    }
+    bump_if_present(env, &DataKey::Receipt(contract_id.clone()));
+    bump_if_present(env, &DataKey::Refund(contract_id.clone()));
+    bump_if_present(env, &DataKey::Clawback(contract_id.clone()));
+    true
+}
+
 // =============================================================================
 // Admin
 // =============================================================================
@@ -25,6 +76,43 @@ pub fn set_admin(env: &Env, admin: &Address) {
         .extend_ttl(&DataKey::Admin, LEDGER_THRESHOLD, LEDGER_BUMP);
 }
 
+// =============================================================================
+// Treasurer
+// =============================================================================
+
+pub fn get_treasurer(env: &Env) -> Address {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Treasurer)
+        .expect("treasurer not set")
+}
+
+pub fn set_treasurer(env: &Env, treasurer: &Address) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::Treasurer, treasurer);
+    env.storage()
+        .persistent()
+        .extend_ttl(&DataKey::Treasurer, LEDGER_THRESHOLD, LEDGER_BUMP);
+}
+
+// =============================================================================
+// Treasury
+// =============================================================================
+
+/// Treasury account; `None` for contracts upgraded from builds that kept
+/// the treasury in the contract's own balance.
+pub fn get_treasury(env: &Env) -> Option<Address> {
+    env.storage().persistent().get(&DataKey::Treasury)
+}
+
+pub fn set_treasury(env: &Env, treasury: &Address) {
+    env.storage().persistent().set(&DataKey::Treasury, treasury);
+    env.storage()
+        .persistent()
+        .extend_ttl(&DataKey::Treasury, LEDGER_THRESHOLD, LEDGER_BUMP);
+}
+
 // =============================================================================
 // Burn BPS
 // =============================================================================
@@ -62,3 +150,313 @@ pub fn set_token(env: &Env, token: &Address) {
         .persistent()
         .extend_ttl(&DataKey::TokenAddress, LEDGER_THRESHOLD, LEDGER_BUMP);
 }
+
+// =============================================================================
+// Withdraw Fee
+// =============================================================================
+
+/// Withdrawal fee in basis points; defaults to no fee.
+pub fn get_withdraw_fee_bps(env: &Env) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::WithdrawFeeBps)
+        .unwrap_or(0)
+}
+
+pub fn set_withdraw_fee_bps(env: &Env, bps: u32) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::WithdrawFeeBps, &bps);
+    env.storage()
+        .persistent()
+        .extend_ttl(&DataKey::WithdrawFeeBps, LEDGER_THRESHOLD, LEDGER_BUMP);
+}
+
+// =============================================================================
+// Minimum Deposit
+// =============================================================================
+
+/// Smallest accepted deposit; defaults to 1 stroop (any positive amount).
+pub fn get_min_deposit(env: &Env) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::MinDeposit)
+        .unwrap_or(1)
+}
+
+pub fn set_min_deposit(env: &Env, amount: i128) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::MinDeposit, &amount);
+    env.storage()
+        .persistent()
+        .extend_ttl(&DataKey::MinDeposit, LEDGER_THRESHOLD, LEDGER_BUMP);
+}
+
+// =============================================================================
+// Pause
+// =============================================================================
+
+pub fn is_paused(env: &Env) -> bool {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Paused)
+        .unwrap_or(false)
+}
+
+pub fn set_paused(env: &Env, paused: bool) {
+    env.storage().persistent().set(&DataKey::Paused, &paused);
+    env.storage()
+        .persistent()
+        .extend_ttl(&DataKey::Paused, LEDGER_THRESHOLD, LEDGER_BUMP);
+}
+
+// =============================================================================
+// Price Oracle
+// =============================================================================
+
+pub fn get_price_oracle(env: &Env) -> Option<Address> {
+    env.storage().persistent().get(&DataKey::PriceOracle)
+}
+
+pub fn set_price_oracle(env: &Env, oracle: &Option<Address>) {
+    match oracle {
+        Some(address) => {
+            env.storage()
+                .persistent()
+                .set(&DataKey::PriceOracle, address);
+            env.storage().persistent().extend_ttl(
+                &DataKey::PriceOracle,
+                LEDGER_THRESHOLD,
+                LEDGER_BUMP,
+            );
+        }
+        None => env.storage().persistent().remove(&DataKey::PriceOracle),
+    }
+}
+
+// =============================================================================
+// Access Lists
+// =============================================================================
+
+pub fn get_access_mode(env: &Env) -> AccessMode {
+    env.storage()
+        .persistent()
+        .get(&DataKey::AccessMode)
+        .unwrap_or(AccessMode::Open)
+}
+
+pub fn set_access_mode(env: &Env, mode: AccessMode) {
+    env.storage().persistent().set(&DataKey::AccessMode, &mode);
+    env.storage()
+        .persistent()
+        .extend_ttl(&DataKey::AccessMode, LEDGER_THRESHOLD, LEDGER_BUMP);
+}
+
+pub fn is_listed(env: &Env, key: &DataKey) -> bool {
+    let listed = env.storage().persistent().has(key);
+    if listed {
+        env.storage()
+            .persistent()
+            .extend_ttl(key, LEDGER_THRESHOLD, LEDGER_BUMP);
+    }
+    listed
+}
+
+pub fn set_listed(env: &Env, key: &DataKey, listed: bool) {
+    if listed {
+        env.storage().persistent().set(key, &true);
+        env.storage()
+            .persistent()
+            .extend_ttl(key, LEDGER_THRESHOLD, LEDGER_BUMP);
+    } else {
+        env.storage().persistent().remove(key);
+    }
+}
+
+// =============================================================================
+// Operator Rewards
+// =============================================================================
+
+/// Reward share in basis points; defaults to the full claimed amount.
+pub fn get_reward_bps(env: &Env) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::RewardBps)
+        .unwrap_or(10_000)
+}
+
+pub fn set_reward_bps(env: &Env, bps: u32) {
+    env.storage().persistent().set(&DataKey::RewardBps, &bps);
+    env.storage()
+        .persistent()
+        .extend_ttl(&DataKey::RewardBps, LEDGER_THRESHOLD, LEDGER_BUMP);
+}
+
+pub fn get_operator(env: &Env, operator: &Address) -> Option<BytesN<32>> {
+    let key = DataKey::Operator(operator.clone());
+    let node_key = env.storage().persistent().get(&key);
+    if node_key.is_some() {
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, LEDGER_THRESHOLD, LEDGER_BUMP);
+    }
+    node_key
+}
+
+pub fn set_operator(env: &Env, operator: &Address, node_key: &BytesN<32>) {
+    let key = DataKey::Operator(operator.clone());
+    env.storage().persistent().set(&key, node_key);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD, LEDGER_BUMP);
+}
+
+pub fn remove_operator(env: &Env, operator: &Address) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::Operator(operator.clone()));
+}
+
+// =============================================================================
+// Deposit Records
+// =============================================================================
+
+pub fn get_deposit(env: &Env, contract_id: &BytesN<32>) -> Option<DepositRecord> {
+    let key = DataKey::Deposit(contract_id.clone());
+    let record = env.storage().persistent().get(&key);
+    if record.is_some() {
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, LEDGER_THRESHOLD, LEDGER_BUMP);
+    }
+    record
+}
+
+pub fn set_deposit(env: &Env, contract_id: &BytesN<32>, record: &DepositRecord) {
+    let key = DataKey::Deposit(contract_id.clone());
+    env.storage().persistent().set(&key, record);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD, LEDGER_BUMP);
+}
+
+pub fn remove_deposit(env: &Env, contract_id: &BytesN<32>) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::Deposit(contract_id.clone()));
+}
+
+// =============================================================================
+// Depositor Index
+// =============================================================================
+
+/// Contract IDs with a live record created by `depositor`, oldest first.
+pub fn get_depositor_index(env: &Env, depositor: &Address) -> Vec<BytesN<32>> {
+    let key = DataKey::DepositorIndex(depositor.clone());
+    match env.storage().persistent().get(&key) {
+        Some(contract_ids) => {
+            env.storage()
+                .persistent()
+                .extend_ttl(&key, LEDGER_THRESHOLD, LEDGER_BUMP);
+            contract_ids
+        }
+        None => Vec::new(env),
+    }
+}
+
+pub fn index_deposit(env: &Env, depositor: &Address, contract_id: &BytesN<32>) {
+    let mut contract_ids = get_depositor_index(env, depositor);
+    contract_ids.push_back(contract_id.clone());
+    let key = DataKey::DepositorIndex(depositor.clone());
+    env.storage().persistent().set(&key, &contract_ids);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD, LEDGER_BUMP);
+}
+
+pub fn unindex_deposit(env: &Env, depositor: &Address, contract_id: &BytesN<32>) {
+    let mut contract_ids = get_depositor_index(env, depositor);
+    let Some(position) = contract_ids.first_index_of(contract_id) else {
+        return;
+    };
+    contract_ids.remove(position);
+    let key = DataKey::DepositorIndex(depositor.clone());
+    if contract_ids.is_empty() {
+        env.storage().persistent().remove(&key);
+    } else {
+        env.storage().persistent().set(&key, &contract_ids);
+    }
+}
+
+// =============================================================================
+// Deposit Receipts
+// =============================================================================
+
+pub fn get_receipt(env: &Env, contract_id: &BytesN<32>) -> Option<DepositReceipt> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Receipt(contract_id.clone()))
+}
+
+pub fn set_receipt(env: &Env, contract_id: &BytesN<32>, receipt: &DepositReceipt) {
+    let key = DataKey::Receipt(contract_id.clone());
+    env.storage().persistent().set(&key, receipt);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD, LEDGER_BUMP);
+}
+
+pub fn remove_receipt(env: &Env, contract_id: &BytesN<32>) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::Receipt(contract_id.clone()));
+}
+
+// =============================================================================
+// Refund Requests
+// =============================================================================
+
+pub fn get_refund(env: &Env, contract_id: &BytesN<32>) -> Option<RefundRequest> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Refund(contract_id.clone()))
+}
+
+pub fn set_refund(env: &Env, contract_id: &BytesN<32>, request: &RefundRequest) {
+    let key = DataKey::Refund(contract_id.clone());
+    env.storage().persistent().set(&key, request);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD, LEDGER_BUMP);
+}
+
+pub fn remove_refund(env: &Env, contract_id: &BytesN<32>) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::Refund(contract_id.clone()));
+}
+
+// =============================================================================
+// Clawback Proposals
+// =============================================================================
+
+pub fn get_clawback(env: &Env, contract_id: &BytesN<32>) -> Option<ClawbackProposal> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Clawback(contract_id.clone()))
+}
+
+pub fn set_clawback(env: &Env, contract_id: &BytesN<32>, proposal: &ClawbackProposal) {
+    let key = DataKey::Clawback(contract_id.clone());
+    env.storage().persistent().set(&key, proposal);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD, LEDGER_BUMP);
+}
+
+pub fn remove_clawback(env: &Env, contract_id: &BytesN<32>) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::Clawback(contract_id.clone()));
+}
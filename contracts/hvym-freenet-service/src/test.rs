@@ -1,13 +1,31 @@
+extern crate std;
+
 use soroban_sdk::{
-    testutils::Address as _,
+    contract, contractimpl,
+    testutils::{storage::Persistent as _, Address as _, Events as _, Ledger as _},
     token::{StellarAssetClient, TokenClient},
+    vec,
+    xdr::{ContractEventBody, ScVal, ToXdr},
     Address, BytesN, Env,
 };
 
-use crate::{FreenetService, FreenetServiceClient};
+use ed25519_dalek::{Signer, SigningKey};
+
+use crate::price_oracle::{Asset, PriceData, MAX_PRICE_AGE_SECS};
+use crate::types::{AccessMode, DataKey, HostingAttestation};
+use crate::{
+    FreenetService, FreenetServiceClient, CLAWBACK_DELAY_LEDGERS, EVENT_SCHEMA_VERSION,
+    REFUND_TIMELOCK_LEDGERS,
+};
+
+/// Ledger until which the test treasury's allowance to the contract lasts.
+const TREASURY_ALLOWANCE_LEDGER: u32 = 5_000_000;
 
 /// Set up the test environment with a native token, admin, and the FreenetService contract.
 ///
+/// The admin also holds the treasurer role. The treasury is a separate
+/// account that has approved the contract as a spender.
+///
 /// Returns (env, service_client, admin_address, token_address, token_admin_client).
 fn setup_env(
     burn_bps: u32,
@@ -30,8 +48,25 @@ fn setup_env(
     let token_address = token_contract.address();
     let token_admin_client = StellarAssetClient::new(&env, &token_address);
 
-    let contract_id = env.register(FreenetService, (&admin, burn_bps, &token_address));
+    let treasury = Address::generate(&env);
+    let contract_id = env.register(
+        FreenetService,
+        (
+            &admin,
+            &admin,
+            &treasury,
+            burn_bps,
+            &token_address,
+            None::<Address>,
+        ),
+    );
     let client = FreenetServiceClient::new(&env, &contract_id);
+    TokenClient::new(&env, &token_address).approve(
+        &treasury,
+        &contract_id,
+        &i128::MAX,
+        &TREASURY_ALLOWANCE_LEDGER,
+    );
 
     (env, client, admin, token_address, token_admin_client)
 }
@@ -44,6 +79,71 @@ fn token_balance(env: &Env, token_address: &Address, account: &Address) -> i128
     TokenClient::new(env, token_address).balance(account)
 }
 
+/// SEP-40 oracle stand-in quoting a fixed price with 14 decimals.
+#[contract]
+struct MockPriceOracle;
+
+#[contractimpl]
+impl MockPriceOracle {
+    pub fn lastprice(_env: Env, _asset: Asset) -> Option<PriceData> {
+        // 0.12 USD per XLM
+        Some(PriceData {
+            price: 12_000_000_000_000,
+            timestamp: 0,
+        })
+    }
+
+    pub fn decimals(_env: Env) -> u32 {
+        14
+    }
+}
+
+/// Sign a hosting attestation for `operator` claiming `contract_id` from
+/// `service` with `node_key`.
+fn make_attestation(
+    env: &Env,
+    service: &Address,
+    operator: &Address,
+    node_key: &SigningKey,
+    contract_id: &BytesN<32>,
+    expires_at: u32,
+) -> HostingAttestation {
+    let mut message = env.ledger().network_id().to_array().to_vec();
+    message.extend(service.clone().to_xdr(env).iter());
+    message.extend(operator.clone().to_xdr(env).iter());
+    message.extend_from_slice(&contract_id.to_array());
+    message.extend_from_slice(&expires_at.to_be_bytes());
+    HostingAttestation {
+        expires_at,
+        signature: BytesN::from_array(env, &node_key.sign(&message).to_bytes()),
+    }
+}
+
+/// Register a fresh operator with the given key seed, returning its address and key.
+fn register_operator(
+    env: &Env,
+    client: &FreenetServiceClient,
+    admin: &Address,
+    seed: u8,
+) -> (Address, SigningKey) {
+    let operator = Address::generate(env);
+    let node_key = SigningKey::from_bytes(&[seed; 32]);
+    let public = BytesN::from_array(env, &node_key.verifying_key().to_bytes());
+    client.register_operator(admin, &operator, &public);
+    (operator, node_key)
+}
+
+/// Topic symbol of the most recent event emitted by `contract`.
+fn last_event_name(env: &Env, contract: &Address) -> std::string::String {
+    let events = env.events().all().filter_by_contract(contract);
+    let event = events.events().last().expect("no events emitted");
+    let ContractEventBody::V0(body) = &event.body;
+    match &body.topics[0] {
+        ScVal::Symbol(sym) => sym.to_utf8_string_lossy(),
+        other => panic!("unexpected topic {other:?}"),
+    }
+}
+
 // =============================================================================
 // Constructor
 // =============================================================================
@@ -75,7 +175,17 @@ fn test_constructor_rejects_invalid_burn_bps() {
     let token_admin = Address::generate(&env);
     let token_contract = env.register_stellar_asset_contract_v2(token_admin);
     let token_address = token_contract.address();
-    env.register(FreenetService, (&admin, 10_001_u32, &token_address));
+    env.register(
+        FreenetService,
+        (
+            &admin,
+            &admin,
+            &admin,
+            10_001_u32,
+            &token_address,
+            None::<Address>,
+        ),
+    );
 }
 
 // =============================================================================
@@ -104,9 +214,11 @@ fn test_deposit_splits_burn_and_treasury() {
     // Depositor should have 0 (all spent: 7000 transferred + 3000 burned)
     assert_eq!(token_balance(&env, &token_address, &depositor), 0);
 
-    // Contract (treasury) should have 7000
-    let service_addr = client.address.clone();
-    assert_eq!(token_balance(&env, &token_address, &service_addr), 7_000);
+    // Treasury account should have 7000
+    assert_eq!(
+        token_balance(&env, &token_address, &client.treasury()),
+        7_000
+    );
 }
 
 #[test]
@@ -122,8 +234,10 @@ fn test_deposit_zero_burn() {
 
     // 0% burned, 100% treasury
     assert_eq!(token_balance(&env, &token_address, &depositor), 0);
-    let service_addr = client.address.clone();
-    assert_eq!(token_balance(&env, &token_address, &service_addr), 10_000);
+    assert_eq!(
+        token_balance(&env, &token_address, &client.treasury()),
+        10_000
+    );
 }
 
 #[test]
@@ -139,8 +253,7 @@ fn test_deposit_full_burn() {
 
     // 100% burned, 0% treasury
     assert_eq!(token_balance(&env, &token_address, &depositor), 0);
-    let service_addr = client.address.clone();
-    assert_eq!(token_balance(&env, &token_address, &service_addr), 0);
+    assert_eq!(token_balance(&env, &token_address, &client.treasury()), 0);
 }
 
 #[test]
@@ -166,94 +279,1630 @@ fn test_multiple_deposits_accumulate_treasury() {
     client.deposit(&depositor, &contract_id_b, &10_000);
 
     // 2 × 7000 = 14000 in treasury
-    let service_addr = client.address.clone();
-    assert_eq!(token_balance(&env, &token_address, &service_addr), 14_000);
+    assert_eq!(
+        token_balance(&env, &token_address, &client.treasury()),
+        14_000
+    );
     // 2 × 3000 = 6000 burned, depositor spent all 20000
     assert_eq!(token_balance(&env, &token_address, &depositor), 0);
 }
 
-// =============================================================================
-// Admin Withdraw
-// =============================================================================
+#[test]
+fn test_first_deposit_creates_record() {
+    let (env, client, _admin, _, token_admin_client) = setup_env(3000);
+
+    let depositor = Address::generate(&env);
+    let contract_id = make_contract_id(&env, 1);
+    token_admin_client.mint(&depositor, &10_000);
+
+    env.ledger().set_sequence_number(42);
+    client.deposit(&depositor, &contract_id, &10_000);
+    assert_eq!(last_event_name(&env, &client.address), "DEPOSIT");
+
+    let record = client.get_deposit(&contract_id).unwrap();
+    assert_eq!(record.depositor, depositor);
+    assert_eq!(record.amount, 10_000);
+    assert_eq!(record.burned, 3_000);
+    assert_eq!(record.created_at, 42);
+    assert_eq!(record.updated_at, 42);
+}
 
 #[test]
-fn test_admin_withdraw() {
-    let (env, client, admin, token_address, token_admin_client) = setup_env(3000);
+fn test_second_deposit_is_topup() {
+    let (env, client, _admin, _, token_admin_client) = setup_env(3000);
+
+    let depositor = Address::generate(&env);
+    let contract_id = make_contract_id(&env, 1);
+    token_admin_client.mint(&depositor, &15_000);
+
+    env.ledger().set_sequence_number(10);
+    client.deposit(&depositor, &contract_id, &10_000);
+    env.ledger().set_sequence_number(20);
+    client.deposit(&depositor, &contract_id, &5_000);
+    assert_eq!(last_event_name(&env, &client.address), "TOPUP");
+
+    let record = client.get_deposit(&contract_id).unwrap();
+    assert_eq!(record.amount, 15_000);
+    assert_eq!(record.burned, 4_500);
+    assert_eq!(record.created_at, 10);
+    assert_eq!(record.updated_at, 20);
+}
+
+#[test]
+fn test_topup_by_delegate_keeps_depositor() {
+    let (env, client, _admin, _, token_admin_client) = setup_env(3000);
 
     let depositor = Address::generate(&env);
+    let delegate = Address::generate(&env);
     let contract_id = make_contract_id(&env, 1);
     token_admin_client.mint(&depositor, &10_000);
+    token_admin_client.mint(&delegate, &10_000);
 
     client.deposit(&depositor, &contract_id, &10_000);
+    client.approve_topup(&depositor, &delegate, &contract_id);
+    assert_eq!(last_event_name(&env, &client.address), "DELEGATE");
+    client.deposit(&delegate, &contract_id, &10_000);
 
-    // Treasury has 7000. Admin withdraws 5000 to a recipient.
-    let recipient = Address::generate(&env);
-    client.admin_withdraw(&admin, &recipient, &5_000);
+    let record = client.get_deposit(&contract_id).unwrap();
+    assert_eq!(record.depositor, depositor);
+    assert_eq!(record.amount, 20_000);
+    assert_eq!(client.get_receipt(&contract_id).unwrap().caller, delegate);
+}
 
-    assert_eq!(token_balance(&env, &token_address, &recipient), 5_000);
-    let service_addr = client.address.clone();
-    assert_eq!(token_balance(&env, &token_address, &service_addr), 2_000);
+#[test]
+#[should_panic(expected = "caller cannot top up")]
+fn test_topup_by_unapproved_caller_rejected() {
+    let (env, client, _admin, _, token_admin_client) = setup_env(3000);
+    let depositor = Address::generate(&env);
+    let other = Address::generate(&env);
+    let contract_id = make_contract_id(&env, 1);
+    token_admin_client.mint(&depositor, &10_000);
+    token_admin_client.mint(&other, &10_000);
+
+    client.deposit(&depositor, &contract_id, &10_000);
+    client.deposit(&other, &contract_id, &10_000);
 }
 
 #[test]
-#[should_panic(expected = "only admin can withdraw")]
-fn test_non_admin_cannot_withdraw() {
+#[should_panic(expected = "caller cannot top up")]
+fn test_revoked_delegate_cannot_top_up() {
+    let (env, client, _admin, _, token_admin_client) = setup_env(3000);
+    let depositor = Address::generate(&env);
+    let delegate = Address::generate(&env);
+    let contract_id = make_contract_id(&env, 1);
+    token_admin_client.mint(&depositor, &10_000);
+    token_admin_client.mint(&delegate, &10_000);
+
+    client.deposit(&depositor, &contract_id, &10_000);
+    client.approve_topup(&depositor, &delegate, &contract_id);
+    client.revoke_topup(&depositor, &delegate, &contract_id);
+    assert!(!client.is_topup_delegate(&contract_id, &delegate));
+    client.deposit(&delegate, &contract_id, &10_000);
+}
+
+#[test]
+#[should_panic(expected = "only depositor can withdraw")]
+fn test_delegate_cannot_withdraw() {
     let (env, client, _admin, _, token_admin_client) = setup_env(3000);
+    let depositor = Address::generate(&env);
+    let delegate = Address::generate(&env);
+    let contract_id = make_contract_id(&env, 1);
+    token_admin_client.mint(&depositor, &10_000);
+
+    client.deposit(&depositor, &contract_id, &10_000);
+    client.approve_topup(&depositor, &delegate, &contract_id);
+    assert!(client.is_topup_delegate(&contract_id, &delegate));
+    client.withdraw(&delegate, &contract_id, &None);
+}
 
+#[test]
+#[should_panic(expected = "only depositor can approve top-ups")]
+fn test_approve_topup_requires_depositor() {
+    let (env, client, _admin, _, token_admin_client) = setup_env(3000);
     let depositor = Address::generate(&env);
+    let other = Address::generate(&env);
     let contract_id = make_contract_id(&env, 1);
     token_admin_client.mint(&depositor, &10_000);
+
     client.deposit(&depositor, &contract_id, &10_000);
+    client.approve_topup(&other, &other, &contract_id);
+}
 
-    let not_admin = Address::generate(&env);
-    let recipient = Address::generate(&env);
-    client.admin_withdraw(&not_admin, &recipient, &5_000);
+#[test]
+fn test_receipt_records_split_per_deposit() {
+    let (env, client, admin, _, token_admin_client) = setup_env(3000);
+    let depositor = Address::generate(&env);
+    let contract_id = make_contract_id(&env, 1);
+    token_admin_client.mint(&depositor, &20_000);
+
+    env.ledger().set_sequence_number(100);
+    client.deposit(&depositor, &contract_id, &10_000);
+    let receipt = client.get_receipt(&contract_id).unwrap();
+    assert_eq!(receipt.caller, depositor);
+    assert_eq!(receipt.gross, 10_000);
+    assert_eq!(receipt.burned, 3_000);
+    assert_eq!(receipt.treasury, 7_000);
+    assert_eq!(receipt.burn_bps, 3000);
+    assert_eq!(receipt.ledger_seq, 100);
+    assert_eq!(receipt.record_created_at, 100);
+
+    // A top-up under a new burn ratio gets its own receipt
+    client.set_burn_bps(&admin, &5000);
+    env.ledger().set_sequence_number(200);
+    client.deposit(&depositor, &contract_id, &1_000);
+    let receipt = client.get_receipt(&contract_id).unwrap();
+    assert_eq!(receipt.gross, 1_000);
+    assert_eq!(receipt.burned, 500);
+    assert_eq!(receipt.treasury, 500);
+    assert_eq!(receipt.burn_bps, 5000);
+    assert_eq!(receipt.ledger_seq, 200);
+    assert_eq!(receipt.record_created_at, 100);
 }
 
 #[test]
-#[should_panic(expected = "amount must be positive")]
-fn test_admin_withdraw_requires_positive_amount() {
-    let (env, client, admin, _, _) = setup_env(3000);
-    let recipient = Address::generate(&env);
-    client.admin_withdraw(&admin, &recipient, &0);
+fn test_get_deposit_missing() {
+    let (env, client, _, _, _) = setup_env(3000);
+    assert!(client.get_deposit(&make_contract_id(&env, 9)).is_none());
+    assert!(client.get_receipt(&make_contract_id(&env, 9)).is_none());
 }
 
 // =============================================================================
-// Set Burn BPS
+// Depositor Index
 // =============================================================================
 
 #[test]
-fn test_set_burn_bps() {
-    let (env, client, admin, token_address, token_admin_client) = setup_env(3000);
+fn test_deposits_by_depositor_paginates() {
+    let (env, client, _admin, _, token_admin_client) = setup_env(3000);
+    let depositor = Address::generate(&env);
+    let other = Address::generate(&env);
+    token_admin_client.mint(&depositor, &30_000);
+    token_admin_client.mint(&other, &10_000);
 
-    // Change burn to 50%
-    client.set_burn_bps(&admin, &5000_u32);
+    for seed in 1..=3 {
+        client.deposit(&depositor, &make_contract_id(&env, seed), &10_000);
+    }
+    client.deposit(&other, &make_contract_id(&env, 9), &10_000);
+
+    let first = client.get_deposits_by_depositor(&depositor, &0, &2);
+    assert_eq!(first.len(), 2);
+    assert_eq!(first.get(0).unwrap().0, make_contract_id(&env, 1));
+    assert_eq!(first.get(1).unwrap().0, make_contract_id(&env, 2));
+
+    let rest = client.get_deposits_by_depositor(&depositor, &2, &2);
+    assert_eq!(rest.len(), 1);
+    let (contract_id, record) = rest.get(0).unwrap();
+    assert_eq!(contract_id, make_contract_id(&env, 3));
+    assert_eq!(record.depositor, depositor);
+
+    assert_eq!(
+        client.get_deposits_by_depositor(&depositor, &5, &2).len(),
+        0
+    );
+    assert_eq!(client.get_deposits_by_depositor(&other, &0, &10).len(), 1);
+}
 
+#[test]
+fn test_topup_by_delegate_not_indexed() {
+    let (env, client, _admin, _, token_admin_client) = setup_env(3000);
     let depositor = Address::generate(&env);
+    let other = Address::generate(&env);
     let contract_id = make_contract_id(&env, 1);
     token_admin_client.mint(&depositor, &10_000);
+    token_admin_client.mint(&other, &10_000);
 
     client.deposit(&depositor, &contract_id, &10_000);
+    client.approve_topup(&depositor, &other, &contract_id);
+    client.deposit(&other, &contract_id, &10_000);
 
-    // 50% burned = 5000, 50% treasury = 5000
-    let service_addr = client.address.clone();
-    assert_eq!(token_balance(&env, &token_address, &service_addr), 5_000);
+    assert_eq!(
+        client.get_deposits_by_depositor(&depositor, &0, &10).len(),
+        1
+    );
+    assert_eq!(client.get_deposits_by_depositor(&other, &0, &10).len(), 0);
+}
+
+#[test]
+fn test_removed_record_leaves_depositor_index() {
+    let (env, client, _admin, _, token_admin_client) = setup_env(3000);
+    let depositor = Address::generate(&env);
+    token_admin_client.mint(&depositor, &20_000);
+    client.deposit(&depositor, &make_contract_id(&env, 1), &10_000);
+    client.deposit(&depositor, &make_contract_id(&env, 2), &10_000);
+
+    client.withdraw(&depositor, &make_contract_id(&env, 1), &None);
+
+    let page = client.get_deposits_by_depositor(&depositor, &0, &10);
+    assert_eq!(page.len(), 1);
+    assert_eq!(page.get(0).unwrap().0, make_contract_id(&env, 2));
+
+    client.withdraw(&depositor, &make_contract_id(&env, 2), &None);
+    assert!(!env.as_contract(&client.address, || {
+        env.storage()
+            .persistent()
+            .has(&DataKey::DepositorIndex(depositor.clone()))
+    }));
+}
+
+// =============================================================================
+// USD Valuation
+// =============================================================================
+
+#[test]
+fn test_deposit_records_usd_value() {
+    let (env, client, admin, _, token_admin_client) = setup_env(3000);
+    let oracle = env.register(MockPriceOracle, ());
+    client.set_price_oracle(&admin, &Some(oracle));
+
+    let depositor = Address::generate(&env);
+    let contract_id = make_contract_id(&env, 1);
+    token_admin_client.mint(&depositor, &200_000_000);
+
+    // 10 XLM at 0.12 USD = 1.2 USD
+    client.deposit(&depositor, &contract_id, &100_000_000);
+    assert_eq!(
+        client.get_receipt(&contract_id).unwrap().usd_value,
+        12_000_000
+    );
+    client.deposit(&depositor, &contract_id, &50_000_000);
+    assert_eq!(
+        client.get_receipt(&contract_id).unwrap().usd_value,
+        6_000_000
+    );
+    assert_eq!(
+        client.get_deposit(&contract_id).unwrap().usd_value,
+        18_000_000
+    );
+}
+
+#[test]
+fn test_deposit_ignores_stale_price() {
+    let (env, client, admin, _, token_admin_client) = setup_env(3000);
+    let oracle = env.register(MockPriceOracle, ());
+    client.set_price_oracle(&admin, &Some(oracle));
+
+    let depositor = Address::generate(&env);
+    let contract_id = make_contract_id(&env, 1);
+    token_admin_client.mint(&depositor, &200_000_000);
+
+    // The mock price is from timestamp 0
+    env.ledger().set_timestamp(MAX_PRICE_AGE_SECS);
+    client.deposit(&depositor, &contract_id, &100_000_000);
+    assert_eq!(
+        client.get_receipt(&contract_id).unwrap().usd_value,
+        12_000_000
+    );
+
+    env.ledger().set_timestamp(MAX_PRICE_AGE_SECS + 1);
+    client.deposit(&depositor, &contract_id, &100_000_000);
+    assert_eq!(client.get_receipt(&contract_id).unwrap().usd_value, 0);
+    assert_eq!(
+        client.get_deposit(&contract_id).unwrap().usd_value,
+        12_000_000
+    );
+}
+
+#[test]
+fn test_deposit_without_oracle_has_zero_usd_value() {
+    let (env, client, _, _, token_admin_client) = setup_env(3000);
+    let depositor = Address::generate(&env);
+    let contract_id = make_contract_id(&env, 1);
+    token_admin_client.mint(&depositor, &10_000);
+
+    client.deposit(&depositor, &contract_id, &10_000);
+    assert_eq!(client.get_deposit(&contract_id).unwrap().usd_value, 0);
+}
+
+// =============================================================================
+// TTL Maintenance
+// =============================================================================
+
+fn persistent_ttl(env: &Env, contract: &Address, key: &DataKey) -> u32 {
+    env.as_contract(contract, || env.storage().persistent().get_ttl(key))
+}
+
+#[test]
+fn test_bump_ttl_extends_deposit_and_config() {
+    let (env, client, _, _, token_admin_client) = setup_env(3000);
+    let depositor = Address::generate(&env);
+    let contract_id = make_contract_id(&env, 1);
+    token_admin_client.mint(&depositor, &10_000);
+
+    env.ledger().set_sequence_number(100);
+    client.deposit(&depositor, &contract_id, &10_000);
+
+    // Let the entries age below the bump threshold
+    env.ledger().set_sequence_number(300_100);
+    let deposit_key = DataKey::Deposit(contract_id.clone());
+    let aged = persistent_ttl(&env, &client.address, &deposit_key);
+
+    let missing = make_contract_id(&env, 2);
+    assert_eq!(
+        client.bump_ttl(&vec![&env, contract_id.clone(), missing]),
+        1
+    );
+    let bumped = persistent_ttl(&env, &client.address, &deposit_key);
+    assert!(bumped > aged);
+    assert_eq!(
+        persistent_ttl(&env, &client.address, &DataKey::Receipt(contract_id)),
+        bumped
+    );
+    assert_eq!(
+        persistent_ttl(&env, &client.address, &DataKey::BurnBps),
+        bumped
+    );
+}
+
+#[test]
+fn test_deposit_bumps_config_ttl() {
+    let (env, client, _, _, token_admin_client) = setup_env(3000);
+    let depositor = Address::generate(&env);
+    token_admin_client.mint(&depositor, &10_000);
+
+    env.ledger().set_sequence_number(300_100);
+    let aged = persistent_ttl(&env, &client.address, &DataKey::Admin);
+    client.deposit(&depositor, &make_contract_id(&env, 1), &10_000);
+    assert!(persistent_ttl(&env, &client.address, &DataKey::Admin) > aged);
+}
+
+// =============================================================================
+// Access Lists
+// =============================================================================
+
+#[test]
+fn test_open_mode_by_default() {
+    let (env, client, _, _, _) = setup_env(3000);
+    assert_eq!(client.access_mode(), AccessMode::Open);
+    assert!(client.is_fundable(&make_contract_id(&env, 1)));
+}
+
+#[test]
+fn test_allowlist_mode() {
+    let (env, client, admin, _, token_admin_client) = setup_env(3000);
+    let depositor = Address::generate(&env);
+    let allowed = make_contract_id(&env, 1);
+    let other = make_contract_id(&env, 2);
+    token_admin_client.mint(&depositor, &10_000);
+
+    client.set_access_mode(&admin, &AccessMode::Allowlist);
+    client.set_allowed(&admin, &allowed, &true);
+    assert!(client.is_fundable(&allowed));
+    assert!(!client.is_fundable(&other));
+
+    client.deposit(&depositor, &allowed, &10_000);
+    assert!(client.try_deposit(&depositor, &other, &10_000).is_err());
+
+    client.set_allowed(&admin, &allowed, &false);
+    assert!(!client.is_fundable(&allowed));
+}
+
+#[test]
+#[should_panic(expected = "contract_id not fundable")]
+fn test_denylist_mode_rejects_denied() {
+    let (env, client, admin, _, token_admin_client) = setup_env(3000);
+    let depositor = Address::generate(&env);
+    let denied = make_contract_id(&env, 1);
+    token_admin_client.mint(&depositor, &20_000);
+
+    client.set_access_mode(&admin, &AccessMode::Denylist);
+    client.set_denied(&admin, &denied, &true);
+    client.deposit(&depositor, &make_contract_id(&env, 2), &10_000);
+    client.deposit(&depositor, &denied, &10_000);
+}
+
+#[test]
+#[should_panic(expected = "only admin can edit allowlist")]
+fn test_non_admin_cannot_edit_allowlist() {
+    let (env, client, _, _, _) = setup_env(3000);
+    client.set_allowed(&Address::generate(&env), &make_contract_id(&env, 1), &true);
+}
+
+// =============================================================================
+// Sponsored Deposits
+// =============================================================================
+
+#[test]
+fn test_deposit_sponsored_records_depositor() {
+    let (env, client, admin, token_address, token_admin_client) = setup_env(3000);
+    let sponsor = Address::generate(&env);
+    let depositor = Address::generate(&env);
+    let contract_id = make_contract_id(&env, 1);
+    client.set_sponsor(&admin, &sponsor, &true);
+    assert!(client.is_sponsor(&sponsor));
+
+    token_admin_client.mint(&depositor, &10_000);
+    client.deposit_sponsored(&sponsor, &depositor, &contract_id, &10_000);
+    assert_eq!(last_event_name(&env, &client.address), "DEPOSIT");
+
+    // Both the sponsor and the depositor authorized the call
+    let auth_addresses: std::vec::Vec<Address> = env
+        .auths()
+        .into_iter()
+        .map(|(address, _)| address)
+        .collect();
+    assert!(auth_addresses.contains(&sponsor));
+    assert!(auth_addresses.contains(&depositor));
+
+    let record = client.get_deposit(&contract_id).unwrap();
+    assert_eq!(record.depositor, depositor);
+    assert_eq!(record.amount, 10_000);
     assert_eq!(token_balance(&env, &token_address, &depositor), 0);
+    assert_eq!(token_balance(&env, &token_address, &sponsor), 0);
 }
 
 #[test]
-#[should_panic(expected = "only admin can set burn ratio")]
-fn test_non_admin_cannot_set_burn_bps() {
-    let (env, client, _admin, _, _) = setup_env(3000);
-    let not_admin = Address::generate(&env);
-    client.set_burn_bps(&not_admin, &5000_u32);
+#[should_panic(expected = "sponsor not registered")]
+fn test_deposit_sponsored_requires_registered_sponsor() {
+    let (env, client, _admin, _, token_admin_client) = setup_env(3000);
+    let sponsor = Address::generate(&env);
+    let depositor = Address::generate(&env);
+    token_admin_client.mint(&depositor, &10_000);
+    client.deposit_sponsored(&sponsor, &depositor, &make_contract_id(&env, 1), &10_000);
 }
 
 #[test]
-#[should_panic(expected = "burn_bps must be <= 10000")]
-fn test_set_burn_bps_rejects_invalid() {
-    let (_env, client, admin, _, _) = setup_env(3000);
-    client.set_burn_bps(&admin, &10_001_u32);
+#[should_panic(expected = "sponsor not registered")]
+fn test_removed_sponsor_cannot_deposit() {
+    let (env, client, admin, _, token_admin_client) = setup_env(3000);
+    let sponsor = Address::generate(&env);
+    let depositor = Address::generate(&env);
+    client.set_sponsor(&admin, &sponsor, &true);
+    client.set_sponsor(&admin, &sponsor, &false);
+    assert!(!client.is_sponsor(&sponsor));
+    token_admin_client.mint(&depositor, &10_000);
+    client.deposit_sponsored(&sponsor, &depositor, &make_contract_id(&env, 1), &10_000);
+}
+
+#[test]
+#[should_panic(expected = "only admin can edit sponsors")]
+fn test_set_sponsor_requires_admin() {
+    let (env, client, _, _, _) = setup_env(3000);
+    let other = Address::generate(&env);
+    client.set_sponsor(&other, &other, &true);
+}
+
+// =============================================================================
+// Streaming Deposits
+// =============================================================================
+
+#[test]
+fn test_stream_drains_per_ledger() {
+    let (env, client, _admin, _, token_admin_client) = setup_env(3000);
+
+    let depositor = Address::generate(&env);
+    let contract_id = make_contract_id(&env, 1);
+    token_admin_client.mint(&depositor, &10_000);
+
+    env.ledger().set_sequence_number(100);
+    client.deposit_stream(&depositor, &contract_id, &10_000, &10);
+    assert_eq!(last_event_name(&env, &client.address), "STREAM");
+    assert_eq!(client.claimable(&contract_id), 0);
+    assert_eq!(client.live_amount(&contract_id), 10_000);
+
+    env.ledger().set_sequence_number(150);
+    assert_eq!(client.claimable(&contract_id), 500);
+    assert_eq!(client.live_amount(&contract_id), 9_500);
+}
+
+#[test]
+fn test_stream_drain_capped_at_non_burned_balance() {
+    let (env, client, _admin, _, token_admin_client) = setup_env(3000);
+
+    let depositor = Address::generate(&env);
+    let contract_id = make_contract_id(&env, 1);
+    token_admin_client.mint(&depositor, &10_000);
+
+    env.ledger().set_sequence_number(100);
+    client.deposit_stream(&depositor, &contract_id, &10_000, &100);
+
+    // 7000 treasury portion drains fully; the burned 3000 stays live
+    env.ledger().set_sequence_number(10_000);
+    assert_eq!(client.claimable(&contract_id), 7_000);
+    assert_eq!(client.live_amount(&contract_id), 3_000);
+}
+
+#[test]
+fn test_stream_topup_checkpoints_drain() {
+    let (env, client, _admin, _, token_admin_client) = setup_env(0);
+
+    let depositor = Address::generate(&env);
+    let contract_id = make_contract_id(&env, 1);
+    token_admin_client.mint(&depositor, &2_000);
+
+    env.ledger().set_sequence_number(100);
+    client.deposit_stream(&depositor, &contract_id, &1_000, &100);
+
+    // Fully drained by ledger 110; idle until the top-up at 200
+    env.ledger().set_sequence_number(200);
+    assert_eq!(client.claimable(&contract_id), 1_000);
+    client.deposit(&depositor, &contract_id, &1_000);
+    assert_eq!(last_event_name(&env, &client.address), "STREAM");
+    assert_eq!(client.live_amount(&contract_id), 1_000);
+
+    env.ledger().set_sequence_number(205);
+    assert_eq!(client.claimable(&contract_id), 1_500);
+    assert_eq!(client.live_amount(&contract_id), 500);
+}
+
+#[test]
+fn test_flat_deposit_never_drains() {
+    let (env, client, _admin, _, token_admin_client) = setup_env(3000);
+
+    let depositor = Address::generate(&env);
+    let contract_id = make_contract_id(&env, 1);
+    token_admin_client.mint(&depositor, &10_000);
+
+    client.deposit(&depositor, &contract_id, &10_000);
+    env.ledger().set_sequence_number(1_000_000);
+    assert_eq!(client.claimable(&contract_id), 0);
+    assert_eq!(client.live_amount(&contract_id), 10_000);
+}
+
+#[test]
+fn test_stream_rate_changed_by_depositor() {
+    let (env, client, _admin, _, token_admin_client) = setup_env(0);
+    let depositor = Address::generate(&env);
+    let contract_id = make_contract_id(&env, 1);
+    token_admin_client.mint(&depositor, &2_000);
+
+    client.deposit_stream(&depositor, &contract_id, &1_000, &10);
+    client.deposit_stream(&depositor, &contract_id, &1_000, &20);
+    assert_eq!(client.get_deposit(&contract_id).unwrap().drain_rate, 20);
+}
+
+#[test]
+#[should_panic(expected = "only depositor can set drain rate")]
+fn test_stream_rate_not_set_by_other_caller() {
+    let (env, client, _admin, _, token_admin_client) = setup_env(0);
+    let depositor = Address::generate(&env);
+    let other = Address::generate(&env);
+    let contract_id = make_contract_id(&env, 1);
+    token_admin_client.mint(&depositor, &1_000);
+    token_admin_client.mint(&other, &1_000);
+
+    client.deposit(&depositor, &contract_id, &1_000);
+    client.deposit_stream(&other, &contract_id, &1_000, &1_000_000);
+}
+
+#[test]
+#[should_panic(expected = "only depositor can set drain rate")]
+fn test_stream_rate_not_set_by_delegate() {
+    let (env, client, _admin, _, token_admin_client) = setup_env(0);
+    let depositor = Address::generate(&env);
+    let delegate = Address::generate(&env);
+    let contract_id = make_contract_id(&env, 1);
+    token_admin_client.mint(&depositor, &1_000);
+    token_admin_client.mint(&delegate, &2_000);
+
+    client.deposit_stream(&depositor, &contract_id, &1_000, &10);
+    client.approve_topup(&depositor, &delegate, &contract_id);
+    client.deposit(&delegate, &contract_id, &1_000);
+    client.deposit_stream(&delegate, &contract_id, &1_000, &1_000_000);
+}
+
+#[test]
+#[should_panic(expected = "drain_rate must be positive")]
+fn test_stream_requires_positive_rate() {
+    let (env, client, _, _, token_admin_client) = setup_env(3000);
+    let depositor = Address::generate(&env);
+    token_admin_client.mint(&depositor, &10_000);
+    client.deposit_stream(&depositor, &make_contract_id(&env, 1), &10_000, &0);
+}
+
+// =============================================================================
+// Operator Claims
+// =============================================================================
+
+#[test]
+fn test_claim_pays_drained_pool() {
+    let (env, client, admin, token_address, token_admin_client) = setup_env(0);
+    let (operator, node_key) = register_operator(&env, &client, &admin, 7);
+
+    let depositor = Address::generate(&env);
+    let contract_id = make_contract_id(&env, 1);
+    token_admin_client.mint(&depositor, &10_000);
+
+    env.ledger().set_sequence_number(100);
+    client.deposit_stream(&depositor, &contract_id, &10_000, &10);
+
+    env.ledger().set_sequence_number(150);
+    let attestation = make_attestation(
+        &env,
+        &client.address,
+        &operator,
+        &node_key,
+        &contract_id,
+        200,
+    );
+    assert_eq!(client.claim(&operator, &contract_id, &attestation), 500);
+    assert_eq!(last_event_name(&env, &client.address), "CLAIM");
+    assert_eq!(token_balance(&env, &token_address, &operator), 500);
+    assert_eq!(client.claimable(&contract_id), 0);
+
+    // Claims do not change the live commitment
+    assert_eq!(client.live_amount(&contract_id), 9_500);
+    assert_eq!(client.get_deposit(&contract_id).unwrap().claimed, 500);
+}
+
+#[test]
+fn test_claim_pays_reward_share() {
+    let (env, client, admin, token_address, token_admin_client) = setup_env(0);
+    let (operator, node_key) = register_operator(&env, &client, &admin, 7);
+    client.set_reward_bps(&admin, &4_000);
+
+    let depositor = Address::generate(&env);
+    let contract_id = make_contract_id(&env, 1);
+    token_admin_client.mint(&depositor, &10_000);
+
+    env.ledger().set_sequence_number(100);
+    client.deposit_stream(&depositor, &contract_id, &10_000, &10);
+
+    env.ledger().set_sequence_number(200);
+    let attestation = make_attestation(
+        &env,
+        &client.address,
+        &operator,
+        &node_key,
+        &contract_id,
+        200,
+    );
+    assert_eq!(client.claim(&operator, &contract_id, &attestation), 400);
+    assert_eq!(token_balance(&env, &token_address, &operator), 400);
+    // The whole pool is consumed; the remainder stays in the treasury
+    assert_eq!(
+        token_balance(&env, &token_address, &client.treasury()),
+        9_600
+    );
+    assert_eq!(client.claimable(&contract_id), 0);
+}
+
+#[test]
+#[should_panic(expected = "operator not registered")]
+fn test_claim_requires_registered_operator() {
+    let (env, client, _, _, token_admin_client) = setup_env(0);
+    let depositor = Address::generate(&env);
+    let contract_id = make_contract_id(&env, 1);
+    token_admin_client.mint(&depositor, &10_000);
+    client.deposit_stream(&depositor, &contract_id, &10_000, &10);
+
+    let operator = Address::generate(&env);
+    let node_key = SigningKey::from_bytes(&[7; 32]);
+    let attestation = make_attestation(
+        &env,
+        &client.address,
+        &operator,
+        &node_key,
+        &contract_id,
+        200,
+    );
+    client.claim(&operator, &contract_id, &attestation);
+}
+
+#[test]
+#[should_panic]
+fn test_claim_rejects_foreign_signature() {
+    let (env, client, admin, _, token_admin_client) = setup_env(0);
+    let (operator, _) = register_operator(&env, &client, &admin, 7);
+
+    let depositor = Address::generate(&env);
+    let contract_id = make_contract_id(&env, 1);
+    token_admin_client.mint(&depositor, &10_000);
+    env.ledger().set_sequence_number(100);
+    client.deposit_stream(&depositor, &contract_id, &10_000, &10);
+
+    env.ledger().set_sequence_number(150);
+    let other_key = SigningKey::from_bytes(&[8; 32]);
+    let attestation = make_attestation(
+        &env,
+        &client.address,
+        &operator,
+        &other_key,
+        &contract_id,
+        200,
+    );
+    client.claim(&operator, &contract_id, &attestation);
+}
+
+#[test]
+#[should_panic]
+fn test_claim_rejects_attestation_for_other_operator() {
+    let (env, client, admin, _, token_admin_client) = setup_env(0);
+    let (operator, node_key) = register_operator(&env, &client, &admin, 7);
+
+    let depositor = Address::generate(&env);
+    let contract_id = make_contract_id(&env, 1);
+    token_admin_client.mint(&depositor, &10_000);
+    env.ledger().set_sequence_number(100);
+    client.deposit_stream(&depositor, &contract_id, &10_000, &10);
+
+    env.ledger().set_sequence_number(150);
+    let other = Address::generate(&env);
+    let attestation = make_attestation(&env, &client.address, &other, &node_key, &contract_id, 200);
+    client.claim(&operator, &contract_id, &attestation);
+}
+
+#[test]
+#[should_panic]
+fn test_claim_rejects_attestation_for_other_deployment() {
+    let (env, client, admin, _, token_admin_client) = setup_env(0);
+    let (operator, node_key) = register_operator(&env, &client, &admin, 7);
+
+    let depositor = Address::generate(&env);
+    let contract_id = make_contract_id(&env, 1);
+    token_admin_client.mint(&depositor, &10_000);
+    env.ledger().set_sequence_number(100);
+    client.deposit_stream(&depositor, &contract_id, &10_000, &10);
+
+    env.ledger().set_sequence_number(150);
+    let other_service = Address::generate(&env);
+    let attestation = make_attestation(
+        &env,
+        &other_service,
+        &operator,
+        &node_key,
+        &contract_id,
+        200,
+    );
+    client.claim(&operator, &contract_id, &attestation);
+}
+
+#[test]
+#[should_panic(expected = "attestation expired")]
+fn test_claim_rejects_expired_attestation() {
+    let (env, client, admin, _, token_admin_client) = setup_env(0);
+    let (operator, node_key) = register_operator(&env, &client, &admin, 7);
+
+    let depositor = Address::generate(&env);
+    let contract_id = make_contract_id(&env, 1);
+    token_admin_client.mint(&depositor, &10_000);
+    env.ledger().set_sequence_number(100);
+    client.deposit_stream(&depositor, &contract_id, &10_000, &10);
+
+    env.ledger().set_sequence_number(150);
+    let attestation = make_attestation(
+        &env,
+        &client.address,
+        &operator,
+        &node_key,
+        &contract_id,
+        149,
+    );
+    client.claim(&operator, &contract_id, &attestation);
+}
+
+#[test]
+#[should_panic(expected = "nothing to claim")]
+fn test_claim_flat_deposit_has_nothing() {
+    let (env, client, admin, _, token_admin_client) = setup_env(0);
+    let (operator, node_key) = register_operator(&env, &client, &admin, 7);
+
+    let depositor = Address::generate(&env);
+    let contract_id = make_contract_id(&env, 1);
+    token_admin_client.mint(&depositor, &10_000);
+    client.deposit(&depositor, &contract_id, &10_000);
+
+    let attestation = make_attestation(
+        &env,
+        &client.address,
+        &operator,
+        &node_key,
+        &contract_id,
+        200,
+    );
+    client.claim(&operator, &contract_id, &attestation);
+}
+
+#[test]
+#[should_panic(expected = "operator not registered")]
+fn test_removed_operator_cannot_claim() {
+    let (env, client, admin, _, token_admin_client) = setup_env(0);
+    let (operator, node_key) = register_operator(&env, &client, &admin, 7);
+    client.remove_operator(&admin, &operator);
+
+    let depositor = Address::generate(&env);
+    let contract_id = make_contract_id(&env, 1);
+    token_admin_client.mint(&depositor, &10_000);
+    client.deposit_stream(&depositor, &contract_id, &10_000, &10);
+
+    let attestation = make_attestation(
+        &env,
+        &client.address,
+        &operator,
+        &node_key,
+        &contract_id,
+        200,
+    );
+    client.claim(&operator, &contract_id, &attestation);
+}
+
+// =============================================================================
+// Refunds
+// =============================================================================
+
+#[test]
+fn test_refund_after_timelock() {
+    let (env, client, admin, token_address, token_admin_client) = setup_env(3000);
+    let depositor = Address::generate(&env);
+    let contract_id = make_contract_id(&env, 1);
+    token_admin_client.mint(&depositor, &10_000);
+
+    env.ledger().set_sequence_number(100);
+    client.deposit(&depositor, &contract_id, &10_000);
+    client.request_refund(&depositor, &contract_id);
+    assert_eq!(last_event_name(&env, &client.address), "REFUNDREQ");
+    let request = client.get_refund(&contract_id).unwrap();
+    assert_eq!(request.unlocks_at, 100 + REFUND_TIMELOCK_LEDGERS);
+
+    env.ledger()
+        .set_sequence_number(100 + REFUND_TIMELOCK_LEDGERS);
+    // Burned share (3000) is gone; the treasury share comes back
+    assert_eq!(client.approve_refund(&admin, &contract_id), 7_000);
+    assert_eq!(last_event_name(&env, &client.address), "REFUND");
+    assert_eq!(token_balance(&env, &token_address, &depositor), 7_000);
+    assert!(client.get_deposit(&contract_id).is_none());
+    assert!(client.get_refund(&contract_id).is_none());
+}
+
+#[test]
+fn test_refund_excludes_drained_amount() {
+    let (env, client, admin, token_address, token_admin_client) = setup_env(0);
+    let depositor = Address::generate(&env);
+    let contract_id = make_contract_id(&env, 1);
+    token_admin_client.mint(&depositor, &100_000);
+
+    env.ledger().set_sequence_number(100);
+    client.deposit_stream(&depositor, &contract_id, &100_000, &1);
+    client.request_refund(&depositor, &contract_id);
+
+    // Drain continues during the timelock
+    env.ledger()
+        .set_sequence_number(100 + REFUND_TIMELOCK_LEDGERS);
+    let refunded = client.approve_refund(&admin, &contract_id);
+    assert_eq!(refunded, 100_000 - REFUND_TIMELOCK_LEDGERS as i128);
+    assert_eq!(token_balance(&env, &token_address, &depositor), refunded);
+}
+
+#[test]
+#[should_panic(expected = "refund timelock active")]
+fn test_refund_timelock_enforced() {
+    let (env, client, admin, _, token_admin_client) = setup_env(3000);
+    let depositor = Address::generate(&env);
+    let contract_id = make_contract_id(&env, 1);
+    token_admin_client.mint(&depositor, &10_000);
+
+    env.ledger().set_sequence_number(100);
+    client.deposit(&depositor, &contract_id, &10_000);
+    client.request_refund(&depositor, &contract_id);
+
+    env.ledger()
+        .set_sequence_number(99 + REFUND_TIMELOCK_LEDGERS);
+    client.approve_refund(&admin, &contract_id);
+}
+
+#[test]
+#[should_panic(expected = "only depositor can request refund")]
+fn test_only_depositor_can_request_refund() {
+    let (env, client, _, _, token_admin_client) = setup_env(3000);
+    let depositor = Address::generate(&env);
+    let contract_id = make_contract_id(&env, 1);
+    token_admin_client.mint(&depositor, &10_000);
+    client.deposit(&depositor, &contract_id, &10_000);
+
+    client.request_refund(&Address::generate(&env), &contract_id);
+}
+
+#[test]
+#[should_panic(expected = "only admin can approve refunds")]
+fn test_only_admin_can_approve_refund() {
+    let (env, client, _, _, token_admin_client) = setup_env(3000);
+    let depositor = Address::generate(&env);
+    let contract_id = make_contract_id(&env, 1);
+    token_admin_client.mint(&depositor, &10_000);
+    client.deposit(&depositor, &contract_id, &10_000);
+    client.request_refund(&depositor, &contract_id);
+
+    env.ledger().set_sequence_number(REFUND_TIMELOCK_LEDGERS);
+    client.approve_refund(&depositor, &contract_id);
+}
+
+#[test]
+fn test_rejected_refund_keeps_deposit() {
+    let (env, client, admin, _, token_admin_client) = setup_env(3000);
+    let depositor = Address::generate(&env);
+    let contract_id = make_contract_id(&env, 1);
+    token_admin_client.mint(&depositor, &10_000);
+    client.deposit(&depositor, &contract_id, &10_000);
+    client.request_refund(&depositor, &contract_id);
+
+    client.reject_refund(&admin, &contract_id);
+    assert_eq!(last_event_name(&env, &client.address), "REFUNDREJ");
+    assert!(client.get_refund(&contract_id).is_none());
+    assert_eq!(client.get_deposit(&contract_id).unwrap().amount, 10_000);
+
+    // A new request may be filed after rejection
+    client.request_refund(&depositor, &contract_id);
+}
+
+#[test]
+#[should_panic(expected = "refund already requested")]
+fn test_duplicate_refund_request_rejected() {
+    let (env, client, _, _, token_admin_client) = setup_env(3000);
+    let depositor = Address::generate(&env);
+    let contract_id = make_contract_id(&env, 1);
+    token_admin_client.mint(&depositor, &10_000);
+    client.deposit(&depositor, &contract_id, &10_000);
+
+    client.request_refund(&depositor, &contract_id);
+    client.request_refund(&depositor, &contract_id);
+}
+
+// =============================================================================
+// Expiry
+// =============================================================================
+
+#[test]
+fn test_sweep_after_expiry() {
+    let (env, client, admin, token_address, token_admin_client) = setup_env(3000);
+    let depositor = Address::generate(&env);
+    let contract_id = make_contract_id(&env, 1);
+    token_admin_client.mint(&depositor, &10_000);
+
+    env.ledger().set_sequence_number(100);
+    client.deposit(&depositor, &contract_id, &10_000);
+    client.set_expiry(&depositor, &contract_id, &1_000);
+    assert_eq!(last_event_name(&env, &client.address), "HORIZON");
+
+    env.ledger().set_sequence_number(1_100);
+    assert_eq!(client.sweep(&contract_id), 7_000);
+    assert_eq!(last_event_name(&env, &client.address), "EXPIRE");
+    assert!(client.get_deposit(&contract_id).is_none());
+    assert!(client.get_receipt(&contract_id).is_none());
+
+    // Swept funds are ordinary treasury balance
+    client.admin_withdraw(&admin, &admin, &7_000);
+    assert_eq!(token_balance(&env, &token_address, &admin), 7_000);
+}
+
+#[test]
+#[should_panic(expected = "deposit not expired")]
+fn test_topup_extends_expiry() {
+    let (env, client, _, _, token_admin_client) = setup_env(3000);
+    let depositor = Address::generate(&env);
+    let contract_id = make_contract_id(&env, 1);
+    token_admin_client.mint(&depositor, &20_000);
+
+    env.ledger().set_sequence_number(100);
+    client.deposit(&depositor, &contract_id, &10_000);
+    client.set_expiry(&depositor, &contract_id, &1_000);
+
+    env.ledger().set_sequence_number(1_000);
+    client.deposit(&depositor, &contract_id, &10_000);
+    env.ledger().set_sequence_number(1_100);
+    client.sweep(&contract_id);
+}
+
+#[test]
+#[should_panic(expected = "deposit does not expire")]
+fn test_sweep_requires_expiry_horizon() {
+    let (env, client, _, _, token_admin_client) = setup_env(3000);
+    let depositor = Address::generate(&env);
+    let contract_id = make_contract_id(&env, 1);
+    token_admin_client.mint(&depositor, &10_000);
+    client.deposit(&depositor, &contract_id, &10_000);
+
+    env.ledger().set_sequence_number(1_000_000);
+    client.sweep(&contract_id);
+}
+
+#[test]
+#[should_panic(expected = "only depositor can set expiry")]
+fn test_only_depositor_can_set_expiry() {
+    let (env, client, _, _, token_admin_client) = setup_env(3000);
+    let depositor = Address::generate(&env);
+    let contract_id = make_contract_id(&env, 1);
+    token_admin_client.mint(&depositor, &10_000);
+    client.deposit(&depositor, &contract_id, &10_000);
+
+    client.set_expiry(&Address::generate(&env), &contract_id, &1_000);
+}
+
+#[test]
+fn test_sweep_excludes_claimed_drain() {
+    let (env, client, admin, _, token_admin_client) = setup_env(0);
+    let (operator, node_key) = register_operator(&env, &client, &admin, 7);
+    let depositor = Address::generate(&env);
+    let contract_id = make_contract_id(&env, 1);
+    token_admin_client.mint(&depositor, &10_000);
+
+    env.ledger().set_sequence_number(100);
+    client.deposit_stream(&depositor, &contract_id, &10_000, &10);
+    client.set_expiry(&depositor, &contract_id, &100);
+
+    env.ledger().set_sequence_number(200);
+    let attestation = make_attestation(
+        &env,
+        &client.address,
+        &operator,
+        &node_key,
+        &contract_id,
+        200,
+    );
+    client.claim(&operator, &contract_id, &attestation);
+    assert_eq!(client.sweep(&contract_id), 9_000);
+}
+
+// =============================================================================
+// Emergency Clawback
+// =============================================================================
+
+#[test]
+fn test_clawback_after_delay() {
+    let (env, client, admin, token_address, token_admin_client) = setup_env(3000);
+    let depositor = Address::generate(&env);
+    let contract_id = make_contract_id(&env, 1);
+    let vault = Address::generate(&env);
+    token_admin_client.mint(&depositor, &10_000);
+
+    env.ledger().set_sequence_number(100);
+    client.deposit(&depositor, &contract_id, &10_000);
+    client.propose_clawback(&admin, &contract_id, &vault);
+    assert_eq!(last_event_name(&env, &client.address), "CLAWPROP");
+    let proposal = client.get_clawback(&contract_id).unwrap();
+    assert_eq!(proposal.executable_at, 100 + CLAWBACK_DELAY_LEDGERS);
+
+    env.ledger()
+        .set_sequence_number(100 + CLAWBACK_DELAY_LEDGERS);
+    assert_eq!(client.execute_clawback(&admin, &contract_id), 7_000);
+    assert_eq!(last_event_name(&env, &client.address), "CLAWBACK");
+    assert_eq!(token_balance(&env, &token_address, &vault), 7_000);
+    assert!(client.get_deposit(&contract_id).is_none());
+    assert!(client.get_clawback(&contract_id).is_none());
+}
+
+#[test]
+#[should_panic(expected = "clawback delay active")]
+fn test_clawback_delay_enforced() {
+    let (env, client, admin, _, token_admin_client) = setup_env(3000);
+    let depositor = Address::generate(&env);
+    let contract_id = make_contract_id(&env, 1);
+    token_admin_client.mint(&depositor, &10_000);
+
+    env.ledger().set_sequence_number(100);
+    client.deposit(&depositor, &contract_id, &10_000);
+    client.propose_clawback(&admin, &contract_id, &admin);
+
+    env.ledger()
+        .set_sequence_number(99 + CLAWBACK_DELAY_LEDGERS);
+    client.execute_clawback(&admin, &contract_id);
+}
+
+#[test]
+fn test_treasurer_can_cancel_clawback() {
+    let (env, client, admin, _, token_admin_client) = setup_env(3000);
+    let treasurer = Address::generate(&env);
+    client.set_treasurer(&admin, &treasurer);
+
+    let depositor = Address::generate(&env);
+    let contract_id = make_contract_id(&env, 1);
+    token_admin_client.mint(&depositor, &10_000);
+    client.deposit(&depositor, &contract_id, &10_000);
+    client.propose_clawback(&admin, &contract_id, &admin);
+
+    client.cancel_clawback(&treasurer, &contract_id);
+    assert_eq!(last_event_name(&env, &client.address), "CLAWCANCL");
+    assert!(client.get_clawback(&contract_id).is_none());
+
+    env.ledger().set_sequence_number(CLAWBACK_DELAY_LEDGERS);
+    assert!(client.try_execute_clawback(&admin, &contract_id).is_err());
+    assert_eq!(client.get_deposit(&contract_id).unwrap().amount, 10_000);
+}
+
+#[test]
+#[should_panic(expected = "only admin or treasurer can cancel clawback")]
+fn test_depositor_cannot_cancel_clawback() {
+    let (env, client, admin, _, token_admin_client) = setup_env(3000);
+    let depositor = Address::generate(&env);
+    let contract_id = make_contract_id(&env, 1);
+    token_admin_client.mint(&depositor, &10_000);
+    client.deposit(&depositor, &contract_id, &10_000);
+    client.propose_clawback(&admin, &contract_id, &admin);
+
+    client.cancel_clawback(&depositor, &contract_id);
+}
+
+#[test]
+#[should_panic(expected = "only admin can propose clawback")]
+fn test_non_admin_cannot_propose_clawback() {
+    let (env, client, _, _, token_admin_client) = setup_env(3000);
+    let depositor = Address::generate(&env);
+    let contract_id = make_contract_id(&env, 1);
+    token_admin_client.mint(&depositor, &10_000);
+    client.deposit(&depositor, &contract_id, &10_000);
+
+    client.propose_clawback(&depositor, &contract_id, &depositor);
+}
+
+// =============================================================================
+// Depositor Withdraw
+// =============================================================================
+
+#[test]
+fn test_withdraw_amount_charges_fee() {
+    let (env, client, admin, token_address, token_admin_client) = setup_env(3000);
+    client.set_withdraw_fee_bps(&admin, &100);
+    let depositor = Address::generate(&env);
+    let contract_id = make_contract_id(&env, 1);
+    token_admin_client.mint(&depositor, &10_000);
+    client.deposit(&depositor, &contract_id, &10_000);
+
+    assert_eq!(
+        client.withdraw_amount(&depositor, &contract_id, &5_000, &None),
+        4_950
+    );
+    assert_eq!(last_event_name(&env, &client.address), "WITHDRAW");
+    assert_eq!(token_balance(&env, &token_address, &depositor), 4_950);
+    assert_eq!(client.get_deposit(&contract_id).unwrap().amount, 5_000);
+    // The fee stays in the treasury
+    assert_eq!(
+        token_balance(&env, &token_address, &client.treasury()),
+        2_050
+    );
+}
+
+#[test]
+fn test_withdraw_full_removes_record() {
+    let (env, client, _, token_address, token_admin_client) = setup_env(3000);
+    let depositor = Address::generate(&env);
+    let contract_id = make_contract_id(&env, 1);
+    token_admin_client.mint(&depositor, &10_000);
+    client.deposit(&depositor, &contract_id, &10_000);
+
+    // Burned share is gone; no fee by default
+    assert_eq!(client.withdraw(&depositor, &contract_id, &None), 7_000);
+    assert_eq!(token_balance(&env, &token_address, &depositor), 7_000);
+    assert!(client.get_deposit(&contract_id).is_none());
+}
+
+#[test]
+fn test_withdraw_to_other_address() {
+    let (env, client, _, token_address, token_admin_client) = setup_env(3000);
+    let depositor = Address::generate(&env);
+    let new_wallet = Address::generate(&env);
+    let contract_id = make_contract_id(&env, 1);
+    token_admin_client.mint(&depositor, &10_000);
+    client.deposit(&depositor, &contract_id, &10_000);
+
+    client.withdraw(&depositor, &contract_id, &Some(new_wallet.clone()));
+
+    let events = env.events().all().filter_by_contract(&client.address);
+    let event = events.events().last().expect("no events emitted");
+    let ContractEventBody::V0(body) = &event.body;
+    let ScVal::Vec(Some(data)) = &body.data else {
+        panic!("unexpected data {:?}", body.data);
+    };
+    assert_eq!(data.len(), 6);
+    assert_eq!(data[5], ScVal::Address((&new_wallet).try_into().unwrap()));
+
+    assert_eq!(token_balance(&env, &token_address, &depositor), 0);
+    assert_eq!(token_balance(&env, &token_address, &new_wallet), 7_000);
+}
+
+#[test]
+fn test_withdraw_excludes_drained_amount() {
+    let (env, client, _, _, token_admin_client) = setup_env(0);
+    let depositor = Address::generate(&env);
+    let contract_id = make_contract_id(&env, 1);
+    token_admin_client.mint(&depositor, &10_000);
+
+    env.ledger().set_sequence_number(100);
+    client.deposit_stream(&depositor, &contract_id, &10_000, &10);
+    env.ledger().set_sequence_number(200);
+    assert!(client
+        .try_withdraw_amount(&depositor, &contract_id, &9_001, &None)
+        .is_err());
+    client.withdraw_amount(&depositor, &contract_id, &4_000, &None);
+    assert_eq!(last_event_name(&env, &client.address), "STREAM");
+    // Drained 1000 stays claimable; the rest keeps draining from the lower cap
+    assert_eq!(client.claimable(&contract_id), 1_000);
+    assert_eq!(client.live_amount(&contract_id), 5_000);
+}
+
+#[test]
+#[should_panic(expected = "only depositor can withdraw")]
+fn test_only_depositor_can_withdraw() {
+    let (env, client, _, _, token_admin_client) = setup_env(3000);
+    let depositor = Address::generate(&env);
+    let contract_id = make_contract_id(&env, 1);
+    token_admin_client.mint(&depositor, &10_000);
+    client.deposit(&depositor, &contract_id, &10_000);
+
+    client.withdraw(&Address::generate(&env), &contract_id, &None);
+}
+
+#[test]
+#[should_panic(expected = "clawback pending")]
+fn test_withdraw_blocked_by_pending_clawback() {
+    let (env, client, admin, _, token_admin_client) = setup_env(3000);
+    let depositor = Address::generate(&env);
+    let contract_id = make_contract_id(&env, 1);
+    token_admin_client.mint(&depositor, &10_000);
+    client.deposit(&depositor, &contract_id, &10_000);
+    client.propose_clawback(&admin, &contract_id, &admin);
+
+    client.withdraw(&depositor, &contract_id, &None);
+}
+
+#[test]
+#[should_panic(expected = "fee_bps must be <= 10000")]
+fn test_set_withdraw_fee_rejects_invalid() {
+    let (_env, client, admin, _, _) = setup_env(3000);
+    client.set_withdraw_fee_bps(&admin, &10_001);
+}
+
+// =============================================================================
+// Admin Withdraw
+// =============================================================================
+
+#[test]
+fn test_admin_withdraw() {
+    let (env, client, admin, token_address, token_admin_client) = setup_env(3000);
+
+    let depositor = Address::generate(&env);
+    let contract_id = make_contract_id(&env, 1);
+    token_admin_client.mint(&depositor, &10_000);
+
+    client.deposit(&depositor, &contract_id, &10_000);
+
+    // Treasury has 7000. Admin withdraws 5000 to a recipient.
+    let recipient = Address::generate(&env);
+    client.admin_withdraw(&admin, &recipient, &5_000);
+
+    assert_eq!(token_balance(&env, &token_address, &recipient), 5_000);
+    assert_eq!(
+        token_balance(&env, &token_address, &client.treasury()),
+        2_000
+    );
+}
+
+#[test]
+#[should_panic(expected = "only treasurer can withdraw")]
+fn test_non_admin_cannot_withdraw() {
+    let (env, client, _admin, _, token_admin_client) = setup_env(3000);
+
+    let depositor = Address::generate(&env);
+    let contract_id = make_contract_id(&env, 1);
+    token_admin_client.mint(&depositor, &10_000);
+    client.deposit(&depositor, &contract_id, &10_000);
+
+    let not_admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    client.admin_withdraw(&not_admin, &recipient, &5_000);
+}
+
+#[test]
+#[should_panic(expected = "amount must be positive")]
+fn test_admin_withdraw_requires_positive_amount() {
+    let (env, client, admin, _, _) = setup_env(3000);
+    let recipient = Address::generate(&env);
+    client.admin_withdraw(&admin, &recipient, &0);
+}
+
+#[test]
+fn test_treasurer_role_is_separate_from_admin() {
+    let (env, client, admin, token_address, token_admin_client) = setup_env(3000);
+    let treasurer = Address::generate(&env);
+    client.set_treasurer(&admin, &treasurer);
+    assert_eq!(last_event_name(&env, &client.address), "TREASURER");
+    assert_eq!(client.treasurer(), treasurer);
+    assert_eq!(client.admin(), admin);
+
+    let depositor = Address::generate(&env);
+    token_admin_client.mint(&depositor, &10_000);
+    client.deposit(&depositor, &make_contract_id(&env, 1), &10_000);
+
+    let recipient = Address::generate(&env);
+    assert!(client
+        .try_admin_withdraw(&admin, &recipient, &1_000)
+        .is_err());
+    client.admin_withdraw(&treasurer, &recipient, &1_000);
+    assert_eq!(token_balance(&env, &token_address, &recipient), 1_000);
+}
+
+#[test]
+#[should_panic(expected = "only treasurer can transfer treasurer")]
+fn test_admin_cannot_rotate_treasurer() {
+    let (env, client, admin, _, _) = setup_env(3000);
+    let treasurer = Address::generate(&env);
+    client.set_treasurer(&admin, &treasurer);
+    client.set_treasurer(&admin, &admin);
+}
+
+#[test]
+fn test_set_admin_emits_event() {
+    let (env, client, admin, _, _) = setup_env(3000);
+    let new_admin = Address::generate(&env);
+    client.set_admin(&admin, &new_admin);
+    assert_eq!(last_event_name(&env, &client.address), "ADMIN");
+    assert_eq!(client.admin(), new_admin);
+    // Rotating admin leaves the treasurer untouched
+    assert_eq!(client.treasurer(), admin);
+}
+
+#[test]
+#[should_panic(expected = "only admin can upgrade")]
+fn test_non_admin_cannot_upgrade() {
+    let (env, client, _, _, _) = setup_env(3000);
+    client.upgrade(
+        &Address::generate(&env),
+        &BytesN::from_array(&env, &[0; 32]),
+    );
+}
+
+// =============================================================================
+// Set Burn BPS
+// =============================================================================
+
+#[test]
+fn test_set_burn_bps() {
+    let (env, client, admin, token_address, token_admin_client) = setup_env(3000);
+
+    // Change burn to 50%
+    client.set_burn_bps(&admin, &5000_u32);
+
+    let depositor = Address::generate(&env);
+    let contract_id = make_contract_id(&env, 1);
+    token_admin_client.mint(&depositor, &10_000);
+
+    client.deposit(&depositor, &contract_id, &10_000);
+
+    // 50% burned = 5000, 50% treasury = 5000
+    assert_eq!(
+        token_balance(&env, &token_address, &client.treasury()),
+        5_000
+    );
+    assert_eq!(token_balance(&env, &token_address, &depositor), 0);
+}
+
+#[test]
+#[should_panic(expected = "only admin can set burn ratio")]
+fn test_non_admin_cannot_set_burn_bps() {
+    let (env, client, _admin, _, _) = setup_env(3000);
+    let not_admin = Address::generate(&env);
+    client.set_burn_bps(&not_admin, &5000_u32);
+}
+
+#[test]
+#[should_panic(expected = "burn_bps must be <= 10000")]
+fn test_set_burn_bps_rejects_invalid() {
+    let (_env, client, admin, _, _) = setup_env(3000);
+    client.set_burn_bps(&admin, &10_001_u32);
+}
+
+// =============================================================================
+// Pause
+// =============================================================================
+
+#[test]
+#[should_panic(expected = "deposits paused")]
+fn test_pause_blocks_deposits() {
+    let (env, client, admin, _, token_admin_client) = setup_env(3000);
+    let depositor = Address::generate(&env);
+    token_admin_client.mint(&depositor, &10_000);
+
+    client.pause(&admin);
+    assert_eq!(last_event_name(&env, &client.address), "PAUSE");
+    assert!(client.is_paused());
+    client.deposit(&depositor, &make_contract_id(&env, 1), &10_000);
+}
+
+#[test]
+fn test_pause_keeps_withdrawals_open() {
+    let (env, client, admin, token_address, token_admin_client) = setup_env(0);
+    let depositor = Address::generate(&env);
+    let contract_id = make_contract_id(&env, 1);
+    token_admin_client.mint(&depositor, &10_000);
+    client.deposit(&depositor, &contract_id, &10_000);
+
+    client.pause(&admin);
+    client.withdraw(&depositor, &contract_id, &None);
+    assert_eq!(token_balance(&env, &token_address, &depositor), 10_000);
+}
+
+#[test]
+fn test_unpause_resumes_deposits() {
+    let (env, client, admin, _, token_admin_client) = setup_env(3000);
+    let depositor = Address::generate(&env);
+    token_admin_client.mint(&depositor, &10_000);
+
+    client.pause(&admin);
+    client.unpause(&admin);
+    assert_eq!(last_event_name(&env, &client.address), "UNPAUSE");
+    assert!(!client.is_paused());
+    client.deposit(&depositor, &make_contract_id(&env, 1), &10_000);
+}
+
+#[test]
+#[should_panic(expected = "only admin can pause")]
+fn test_pause_requires_admin() {
+    let (env, client, _, _, _) = setup_env(3000);
+    client.pause(&Address::generate(&env));
+}
+
+// =============================================================================
+// Version and Config
+// =============================================================================
+
+#[test]
+fn test_version_matches_crate() {
+    let (env, client, _, _, _) = setup_env(3000);
+    assert_eq!(
+        client.version(),
+        soroban_sdk::String::from_str(&env, env!("CARGO_PKG_VERSION"))
+    );
+}
+
+#[test]
+fn test_config_reflects_settings() {
+    let (_env, client, admin, token_address, _) = setup_env(3000);
+    let config = client.config();
+    assert_eq!(config.burn_bps, 3000);
+    assert_eq!(config.token, token_address);
+    assert_eq!(config.min_deposit, 1);
+    assert!(!config.paused);
+
+    client.set_burn_bps(&admin, &1000);
+    client.set_min_deposit(&admin, &500);
+    client.pause(&admin);
+    let config = client.config();
+    assert_eq!(config.burn_bps, 1000);
+    assert_eq!(config.min_deposit, 500);
+    assert!(config.paused);
+}
+
+#[test]
+#[should_panic(expected = "amount below min deposit")]
+fn test_deposit_below_min_rejected() {
+    let (env, client, admin, _, token_admin_client) = setup_env(3000);
+    let depositor = Address::generate(&env);
+    token_admin_client.mint(&depositor, &10_000);
+    client.set_min_deposit(&admin, &1_000);
+    assert_eq!(last_event_name(&env, &client.address), "MINDEP");
+    client.deposit(&depositor, &make_contract_id(&env, 1), &999);
+}
+
+// =============================================================================
+// Admin Action Events
+// =============================================================================
+
+#[test]
+fn test_config_changes_emit_events() {
+    let (env, client, admin, _, _) = setup_env(3000);
+    let contract_id = make_contract_id(&env, 1);
+    let other = Address::generate(&env);
+
+    client.set_burn_bps(&admin, &1000);
+    assert_eq!(last_event_name(&env, &client.address), "BURNBPS");
+    client.set_withdraw_fee_bps(&admin, &100);
+    assert_eq!(last_event_name(&env, &client.address), "FEEBPS");
+    client.set_reward_bps(&admin, &5000);
+    assert_eq!(last_event_name(&env, &client.address), "REWARDBPS");
+    client.set_price_oracle(&admin, &None);
+    assert_eq!(last_event_name(&env, &client.address), "ORACLE");
+    client.set_access_mode(&admin, &AccessMode::Denylist);
+    assert_eq!(last_event_name(&env, &client.address), "ACCESS");
+    client.set_allowed(&admin, &contract_id, &true);
+    assert_eq!(last_event_name(&env, &client.address), "ALLOWED");
+    client.set_denied(&admin, &contract_id, &true);
+    assert_eq!(last_event_name(&env, &client.address), "DENIED");
+    client.set_sponsor(&admin, &other, &true);
+    assert_eq!(last_event_name(&env, &client.address), "SPONSOR");
+    client.register_operator(&admin, &other, &BytesN::from_array(&env, &[7u8; 32]));
+    assert_eq!(last_event_name(&env, &client.address), "OPERATOR");
+    client.remove_operator(&admin, &other);
+    assert_eq!(last_event_name(&env, &client.address), "OPREMOVE");
+}
+
+#[test]
+fn test_events_carry_schema_version_topic() {
+    let (env, client, admin, _, token_admin_client) = setup_env(3000);
+    let depositor = Address::generate(&env);
+    token_admin_client.mint(&depositor, &10_000);
+
+    client.deposit(&depositor, &make_contract_id(&env, 1), &10_000);
+    let events = env.events().all().filter_by_contract(&client.address);
+    let event = events.events().last().expect("no events emitted");
+    let ContractEventBody::V0(body) = &event.body;
+    assert_eq!(body.topics.len(), 3);
+    assert_eq!(body.topics[2], ScVal::U32(EVENT_SCHEMA_VERSION));
+
+    client.pause(&admin);
+    let events = env.events().all().filter_by_contract(&client.address);
+    let event = events.events().last().expect("no events emitted");
+    let ContractEventBody::V0(body) = &event.body;
+    assert_eq!(body.topics.len(), 2);
+    assert_eq!(body.topics[1], ScVal::U32(EVENT_SCHEMA_VERSION));
+}
+
+#[test]
+fn test_set_burn_bps_event_carries_previous_value() {
+    let (env, client, admin, _, _) = setup_env(3000);
+    client.set_burn_bps(&admin, &1000);
+
+    let events = env.events().all().filter_by_contract(&client.address);
+    let event = events.events().last().expect("no events emitted");
+    let ContractEventBody::V0(body) = &event.body;
+    let ScVal::Vec(Some(data)) = &body.data else {
+        panic!("unexpected data {:?}", body.data);
+    };
+    assert_eq!(data.as_slice(), &[ScVal::U32(3000), ScVal::U32(1000)]);
+}
+
+// =============================================================================
+// Treasury Account
+// =============================================================================
+
+#[test]
+fn test_deposit_funds_treasury_account_not_contract() {
+    let (env, client, _, token_address, token_admin_client) = setup_env(3000);
+    let depositor = Address::generate(&env);
+    token_admin_client.mint(&depositor, &10_000);
+    client.deposit(&depositor, &make_contract_id(&env, 1), &10_000);
+
+    assert_ne!(client.treasury(), client.address);
+    assert_eq!(
+        token_balance(&env, &token_address, &client.treasury()),
+        7_000
+    );
+    assert_eq!(token_balance(&env, &token_address, &client.address), 0);
+}
+
+#[test]
+fn test_set_treasury_redirects_deposits() {
+    let (env, client, admin, token_address, token_admin_client) = setup_env(3000);
+    let depositor = Address::generate(&env);
+    let old_treasury = client.treasury();
+    let new_treasury = Address::generate(&env);
+    token_admin_client.mint(&depositor, &20_000);
+    client.deposit(&depositor, &make_contract_id(&env, 1), &10_000);
+
+    client.set_treasury(&admin, &new_treasury);
+    assert_eq!(last_event_name(&env, &client.address), "TREASURY");
+    assert_eq!(client.treasury(), new_treasury);
+    client.deposit(&depositor, &make_contract_id(&env, 2), &10_000);
+
+    assert_eq!(token_balance(&env, &token_address, &old_treasury), 7_000);
+    assert_eq!(token_balance(&env, &token_address, &new_treasury), 7_000);
+}
+
+#[test]
+fn test_payout_requires_new_treasury_allowance() {
+    let (env, client, admin, token_address, token_admin_client) = setup_env(3000);
+    let depositor = Address::generate(&env);
+    let contract_id = make_contract_id(&env, 1);
+    let new_treasury = Address::generate(&env);
+    token_admin_client.mint(&depositor, &10_000);
+    client.set_treasury(&admin, &new_treasury);
+    client.deposit(&depositor, &contract_id, &10_000);
+
+    assert!(client
+        .try_withdraw(&depositor, &contract_id, &None)
+        .is_err());
+
+    TokenClient::new(&env, &token_address).approve(
+        &new_treasury,
+        &client.address,
+        &7_000,
+        &TREASURY_ALLOWANCE_LEDGER,
+    );
+    assert_eq!(client.withdraw(&depositor, &contract_id, &None), 7_000);
+    assert_eq!(token_balance(&env, &token_address, &new_treasury), 0);
+}
+
+#[test]
+#[should_panic(expected = "only admin can set treasury")]
+fn test_set_treasury_requires_admin() {
+    let (env, client, _, _, _) = setup_env(3000);
+    let other = Address::generate(&env);
+    client.set_treasury(&other, &other);
 }
 
 // =============================================================================
@@ -1,13 +1,215 @@
-use soroban_sdk::contracttype;
+use soroban_sdk::{contracttype, Address, BytesN};
 
 /// Storage keys for the contract.
 #[contracttype]
 #[derive(Clone)]
 pub enum DataKey {
-    /// Admin address (persistent storage).
+    /// Admin address: configuration and upgrades (persistent storage).
     Admin,
+    /// Treasurer address: treasury withdrawals (persistent storage).
+    Treasurer,
+    /// Account holding the treasury share of deposits (persistent storage).
+    Treasury,
     /// Burn ratio in basis points, e.g. 3000 = 30% (persistent storage).
     BurnBps,
     /// Native XLM SAC token address (persistent storage).
     TokenAddress,
+    /// Optional SEP-40 price-oracle contract address (persistent storage).
+    PriceOracle,
+    /// Deposit record keyed by Freenet contract ID hash (persistent storage).
+    Deposit(BytesN<32>),
+    /// Share of claimed drain paid to operators, in basis points (persistent storage).
+    RewardBps,
+    /// Registered node-operator ed25519 key, keyed by payout address (persistent storage).
+    Operator(Address),
+    /// Pending refund request keyed by Freenet contract ID hash (persistent storage).
+    Refund(BytesN<32>),
+    /// Enforcement mode for contract ID access lists (persistent storage).
+    AccessMode,
+    /// Allowlist membership keyed by Freenet contract ID hash (persistent storage).
+    Allowed(BytesN<32>),
+    /// Denylist membership keyed by Freenet contract ID hash (persistent storage).
+    Denied(BytesN<32>),
+    /// Split of the latest deposit keyed by Freenet contract ID hash (persistent storage).
+    Receipt(BytesN<32>),
+    /// Pending emergency clawback keyed by Freenet contract ID hash (persistent storage).
+    Clawback(BytesN<32>),
+    /// Fee on depositor withdrawals in basis points (persistent storage).
+    WithdrawFeeBps,
+    /// Registered fee sponsor for `deposit_sponsored` (persistent storage).
+    Sponsor(Address),
+    /// Contract IDs whose records list this address as depositor (persistent storage).
+    DepositorIndex(Address),
+    /// Whether new deposits are suspended (persistent storage).
+    Paused,
+    /// Smallest accepted deposit or top-up in stroops (persistent storage).
+    MinDeposit,
+    /// Top-up approval keyed by (contract ID, depositor, delegate) (persistent storage).
+    TopupDelegate(BytesN<32>, Address, Address),
+}
+
+/// Which contract IDs `deposit` accepts.
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum AccessMode {
+    /// Any contract ID may be funded (default).
+    Open = 0,
+    /// Only allowlisted contract IDs may be funded.
+    Allowlist = 1,
+    /// Every contract ID except denylisted ones may be funded.
+    Denylist = 2,
+}
+
+/// Snapshot of the contract's deposit settings, returned by `config()`.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContractConfig {
+    /// Burn ratio in basis points.
+    pub burn_bps: u32,
+    /// Native XLM SAC token address.
+    pub token: Address,
+    /// Smallest accepted deposit or top-up in stroops.
+    pub min_deposit: i128,
+    /// Whether new deposits are suspended.
+    pub paused: bool,
+}
+
+/// Per-contract deposit record.
+///
+/// Created by the first deposit for a Freenet contract ID; later deposits
+/// for the same ID are top-ups and accumulate into the same record.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct DepositRecord {
+    /// Address that made the first deposit for this contract ID.
+    pub depositor: Address,
+    /// Cumulative deposited amount in stroops (burned portion included).
+    pub amount: i128,
+    /// Cumulative amount burned via the SAC.
+    pub burned: i128,
+    /// Cumulative USD value of deposits at deposit time (7 decimals; 0 when
+    /// no price was available).
+    pub usd_value: i128,
+    /// Ledger sequence when the record was created.
+    pub created_at: u32,
+    /// Ledger sequence of the most recent deposit or top-up.
+    pub updated_at: u32,
+    /// Streaming drain rate in stroops per ledger (0 = flat deposit).
+    pub drain_rate: i128,
+    /// Amount drained as of `drain_checkpoint`.
+    pub drained: i128,
+    /// Ledger sequence from which `drain_rate` accrues on top of `drained`.
+    pub drain_checkpoint: u32,
+    /// Portion of the drained amount already consumed by operator claims.
+    pub claimed: i128,
+    /// Ledgers of inactivity after `updated_at` before the record may be
+    /// swept to the treasury (0 = never expires).
+    pub expiry_horizon: u32,
+}
+
+impl DepositRecord {
+    /// Maximum amount that can ever drain: the non-burned balance.
+    pub fn drain_cap(&self) -> i128 {
+        self.amount - self.burned
+    }
+
+    /// Total amount drained as of `ledger`.
+    pub fn drained_at(&self, ledger: u32) -> i128 {
+        let elapsed = ledger.saturating_sub(self.drain_checkpoint) as i128;
+        let accrued = self
+            .drained
+            .saturating_add(self.drain_rate.saturating_mul(elapsed));
+        accrued.min(self.drain_cap())
+    }
+
+    /// Deposited amount still counting as live commitment at `ledger`.
+    pub fn live_amount(&self, ledger: u32) -> i128 {
+        self.amount - self.drained_at(ledger)
+    }
+
+    /// Non-burned balance that has not drained as of `ledger`.
+    pub fn refundable_at(&self, ledger: u32) -> i128 {
+        self.drain_cap() - self.drained_at(ledger)
+    }
+
+    /// Ledger sequence from which `sweep` is allowed, if the record expires.
+    pub fn expires_at(&self) -> Option<u32> {
+        if self.expiry_horizon == 0 {
+            None
+        } else {
+            Some(self.updated_at.saturating_add(self.expiry_horizon))
+        }
+    }
+
+    /// Drained amount not yet consumed by operator claims at `ledger`.
+    pub fn unclaimed_at(&self, ledger: u32) -> i128 {
+        self.drained_at(ledger) - self.claimed
+    }
+
+    /// Fold accrued drain into `drained` and restart accrual at `ledger`.
+    pub fn checkpoint(&mut self, ledger: u32) {
+        self.drained = self.drained_at(ledger);
+        self.drain_checkpoint = ledger;
+    }
+}
+
+/// How the most recent deposit or top-up for a contract ID was split.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct DepositReceipt {
+    /// Address that made this deposit.
+    pub caller: Address,
+    /// Gross amount deposited in stroops.
+    pub gross: i128,
+    /// Portion burned via the SAC.
+    pub burned: i128,
+    /// Portion transferred to the contract treasury.
+    pub treasury: i128,
+    /// Burn ratio in effect for this deposit, in basis points.
+    pub burn_bps: u32,
+    /// USD value of `gross` at deposit time (7 decimals; 0 when unpriced).
+    pub usd_value: i128,
+    /// Ledger sequence of this deposit.
+    pub ledger_seq: u32,
+    /// Ledger sequence when the contract ID's record was created.
+    pub record_created_at: u32,
+}
+
+/// A depositor's request to reverse a deposit, awaiting admin approval.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct RefundRequest {
+    /// Ledger sequence when the refund was requested.
+    pub requested_at: u32,
+    /// First ledger sequence at which the admin may approve the refund.
+    pub unlocks_at: u32,
+}
+
+/// An admin proposal to claw back a deposit, executable after a delay.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClawbackProposal {
+    /// Recipient of the clawed-back funds.
+    pub to: Address,
+    /// Ledger sequence when the clawback was proposed.
+    pub proposed_at: u32,
+    /// First ledger sequence at which the clawback may be executed.
+    pub executable_at: u32,
+}
+
+/// Proof from a registered node operator that it hosts a Freenet contract.
+///
+/// `signature` is the operator's ed25519 signature over
+/// `network_id || service || operator || contract_id || expires_at`: the
+/// 32-byte network ID (SHA-256 of the network passphrase), the service
+/// contract's and the operator's addresses as `ScVal` XDR, the 32-byte
+/// contract ID and a big-endian `u32`.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct HostingAttestation {
+    /// Last ledger sequence at which the attestation is accepted.
+    pub expires_at: u32,
+    /// ed25519 signature by the operator's registered node key.
+    pub signature: BytesN<64>,
 }
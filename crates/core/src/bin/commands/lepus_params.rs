@@ -0,0 +1,111 @@
+//! Generate deposit-index parameters from the Stellar Tier-1 topology.
+
+use anyhow::{Context, Result};
+use clap::Args;
+use freenet::lepus::{
+    deposit_index_params, fetch_stellarbeat, tier_one_from_quorum_config, topology_changes,
+    DepositIndexParams, StellarNetwork,
+};
+use std::path::PathBuf;
+
+#[derive(Args, Debug, Clone)]
+pub struct LepusParamsCommand {
+    /// Soroban contract indexed by the deposit-index (C... address)
+    #[arg(long)]
+    pub hvym_contract: String,
+
+    /// Stellar network the deposit-index follows
+    #[arg(long, value_enum, default_value_t = StellarNetwork::Mainnet)]
+    pub network: StellarNetwork,
+
+    /// Stellarbeat API to fetch Tier-1 organizations from, instead of the
+    /// network's
+    #[arg(long, conflicts_with = "quorum_config")]
+    pub stellarbeat: Option<String>,
+
+    /// Read Tier-1 organizations from a stellar-core quorum configuration
+    /// (HOME_DOMAINS / VALIDATORS) instead of stellarbeat
+    #[arg(long)]
+    pub quorum_config: Option<PathBuf>,
+
+    /// Stellar network passphrase, instead of the network's
+    #[arg(long)]
+    pub network_passphrase: Option<String>,
+
+    /// Organizations required for quorum (0 = two-thirds plus one)
+    #[arg(long, default_value_t = 0)]
+    pub quorum_org_threshold: usize,
+
+    /// Write the parameters to this file, listing topology changes against
+    /// its current contents; prints to stdout otherwise
+    #[arg(long, short)]
+    pub output: Option<PathBuf>,
+}
+
+impl LepusParamsCommand {
+    pub fn run(&self) -> Result<()> {
+        let rt = tokio::runtime::Runtime::new()?;
+        rt.block_on(self.run_async())
+    }
+
+    async fn run_async(&self) -> Result<()> {
+        let organizations = match &self.quorum_config {
+            Some(path) => {
+                let config = std::fs::read_to_string(path)
+                    .with_context(|| format!("reading {}", path.display()))?;
+                tier_one_from_quorum_config(&config)?
+            }
+            None => {
+                let url = self
+                    .stellarbeat
+                    .as_deref()
+                    .or(self.network.stellarbeat_url())
+                    .with_context(|| {
+                        format!(
+                            "stellarbeat doesn't follow {:?}; pass --quorum-config",
+                            self.network
+                        )
+                    })?;
+                fetch_stellarbeat(&reqwest::Client::new(), url).await?
+            }
+        };
+        let passphrase = self
+            .network_passphrase
+            .as_deref()
+            .unwrap_or(self.network.passphrase());
+        let params = deposit_index_params(
+            passphrase,
+            organizations,
+            self.quorum_org_threshold,
+            &self.hvym_contract,
+        )?;
+        let json = serde_json::to_string_pretty(&params)?;
+
+        let Some(output) = &self.output else {
+            println!("{json}");
+            return Ok(());
+        };
+        if let Ok(existing) = std::fs::read_to_string(output) {
+            let previous: DepositIndexParams = serde_json::from_str(&existing)
+                .with_context(|| format!("parsing existing {}", output.display()))?;
+            if previous == params {
+                eprintln!("{} is up to date", output.display());
+                return Ok(());
+            }
+            for change in topology_changes(&previous, &params) {
+                eprintln!("{change}");
+            }
+            eprintln!(
+                "Parameters changed: the deposit-index gets a new contract key and must be republished"
+            );
+        }
+        std::fs::write(output, json + "\n")
+            .with_context(|| format!("writing {}", output.display()))?;
+        eprintln!(
+            "Wrote {} Tier-1 organizations to {}",
+            params.organizations.len(),
+            output.display()
+        );
+        Ok(())
+    }
+}
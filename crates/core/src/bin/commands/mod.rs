@@ -1,4 +1,6 @@
 pub mod auto_update;
+#[cfg(feature = "lepus")]
+pub mod lepus_params;
 pub mod report;
 pub mod service;
 pub mod update;
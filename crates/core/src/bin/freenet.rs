@@ -40,6 +40,9 @@ enum Command {
     Service(ServiceCommand),
     /// Update Freenet to the latest version
     Update(UpdateCommand),
+    /// Generate Lepus deposit-index parameters from the Stellar Tier-1 validators
+    #[cfg(feature = "lepus")]
+    LepusParams(commands::lepus_params::LepusParamsCommand),
 }
 
 /// Build metadata embedded at compile time
@@ -275,6 +278,8 @@ fn freenet_main() -> anyhow::Result<()> {
             )
         }
         Some(Command::Update(cmd)) => cmd.run(build_info::VERSION),
+        #[cfg(feature = "lepus")]
+        Some(Command::LepusParams(cmd)) => cmd.run(),
         Some(Command::Network { mut config }) => {
             config.mode = Some(OperationMode::Network);
             run_node(config)
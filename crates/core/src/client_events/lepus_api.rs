@@ -0,0 +1,84 @@
+//! Lepus queries served over the client WebSocket.
+//!
+//! `ClientRequest` belongs to freenet-stdlib, so Lepus queries travel beside
+//! it as JSON objects under a `lepus` field, whatever encoding protocol the
+//! connection negotiated. They are answered from the local ring without
+//! going through the node's event loop, as a JSON text frame wrapped the
+//! same way:
+//!
+//! ```json
+//! {"lepus": {"deposit_info": {"instance_id": "<base58 instance id>"}}}
+//! {"lepus": {"deposit_info": {"instance_id": "...", "entry": {...}, "live_stroops": 0,
+//!   "sync_version": 12, "sync_ledger": 51234567}}}
+//! {"lepus": {"error": "invalid instance id"}}
+//! ```
+
+use freenet_stdlib::prelude::ContractInstanceId;
+use serde::{Deserialize, Serialize};
+
+use crate::ring::{diagnostics, DepositInfo};
+
+#[derive(Debug, Serialize, Deserialize)]
+struct LepusFrame<T> {
+    lepus: T,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum LepusRequest {
+    /// The locally known deposit of a contract and the deposit-index
+    /// version it was synced at.
+    DepositInfo { instance_id: String },
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum LepusResponse {
+    DepositInfo(DepositInfo),
+    Error(String),
+}
+
+/// The reply to `msg` if it is a Lepus query, `None` if it is anything else.
+pub(super) fn handle(msg: &[u8]) -> Option<String> {
+    let frame = serde_json::from_slice::<LepusFrame<serde_json::Value>>(msg).ok()?;
+    let response = match serde_json::from_value::<LepusRequest>(frame.lepus) {
+        Ok(request) => respond(request),
+        Err(e) => LepusResponse::Error(format!("invalid lepus request: {e}")),
+    };
+    serde_json::to_string(&LepusFrame { lepus: response }).ok()
+}
+
+fn respond(request: LepusRequest) -> LepusResponse {
+    match request {
+        LepusRequest::DepositInfo { instance_id } => {
+            let Ok(instance_id) = ContractInstanceId::from_bytes(&instance_id) else {
+                return LepusResponse::Error("invalid instance id".to_string());
+            };
+            match diagnostics::local_ring() {
+                Some(ring) => LepusResponse::DepositInfo(ring.deposit_info(&instance_id)),
+                None => LepusResponse::Error("node not running".to_string()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use freenet_stdlib::client_api::ClientRequest;
+
+    #[test]
+    fn test_only_lepus_frames_are_handled() {
+        let request = serde_json::to_vec(&ClientRequest::Disconnect { cause: None }).unwrap();
+        assert_eq!(handle(&request), None);
+        assert_eq!(handle(&[0x10, 0x00, 0x00, 0x00]), None);
+
+        let reply = handle(br#"{"lepus": {"deposit_info": {"instance_id": "not-base58!"}}}"#);
+        assert_eq!(
+            reply.as_deref(),
+            Some(r#"{"lepus":{"error":"invalid instance id"}}"#)
+        );
+        let reply = handle(br#"{"lepus": {"withdrawals": {}}}"#).unwrap();
+        assert!(reply.starts_with(r#"{"lepus":{"error":"invalid lepus request"#));
+    }
+}
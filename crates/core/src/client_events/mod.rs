@@ -34,6 +34,8 @@ use crate::{
 pub(crate) mod combinator;
 #[cfg(test)]
 mod integration_verification;
+#[cfg(all(feature = "websocket", feature = "lepus"))]
+mod lepus_api;
 pub(crate) mod result_router;
 pub(crate) mod session_actor;
 #[cfg(test)]
@@ -676,6 +676,11 @@ async fn process_client_request(
         Err(err) => return Err(Some(err.into())),
     };
 
+    #[cfg(feature = "lepus")]
+    if let Some(reply) = super::lepus_api::handle(&msg) {
+        return Ok(Some(Message::Text(reply.into())));
+    }
+
     // Try to deserialize the ClientRequest message
     let req = {
         match encoding_protoc {
@@ -97,6 +97,11 @@ pub struct ConfigArgs {
 
     #[command(flatten)]
     pub telemetry: TelemetryArgs,
+
+    /// Built-in Lepus hosting profile, replacing `profile` in `[lepus]`.
+    #[cfg(feature = "lepus")]
+    #[arg(long, value_enum, env = "LEPUS_PROFILE")]
+    pub lepus_profile: Option<HostingProfile>,
 }
 
 impl Default for ConfigArgs {
@@ -119,6 +124,8 @@ impl Default for ConfigArgs {
                 blocked_addresses: None,
                 transient_budget: Some(DEFAULT_TRANSIENT_BUDGET),
                 transient_ttl_secs: Some(DEFAULT_TRANSIENT_TTL_SECS),
+                hosting_budget: None,
+                hosting_min_ttl_secs: None,
                 min_connections: None,
                 max_connections: None,
                 streaming_enabled: None,   // Default: enabled
@@ -140,6 +147,8 @@ impl Default for ConfigArgs {
             version: false,
             max_blocking_threads: None,
             telemetry: Default::default(),
+            #[cfg(feature = "lepus")]
+            lepus_profile: None,
         }
     }
 }
@@ -264,6 +273,21 @@ impl ConfigArgs {
 
         let should_persist = cfg.is_none();
 
+        // Lepus settings are read from the configuration file, except the profile
+        #[cfg(feature = "lepus")]
+        let lepus = {
+            let lepus = cfg
+                .as_ref()
+                .map(|cfg| cfg.lepus.clone())
+                .unwrap_or_default();
+            let lepus = match self.lepus_profile {
+                Some(profile) => lepus.with_profile(profile)?,
+                None => lepus,
+            };
+            lepus.validate()?;
+            lepus
+        };
+
         // merge the configuration from the file with the command line arguments
         if let Some(cfg) = cfg {
             self.secrets.merge(cfg.secrets);
@@ -302,6 +326,12 @@ impl ConfigArgs {
             self.network_api
                 .transient_ttl_secs
                 .get_or_insert(cfg.network_api.transient_ttl_secs);
+            if let Some(budget) = cfg.network_api.hosting_budget {
+                self.network_api.hosting_budget.get_or_insert(budget);
+            }
+            if let Some(ttl) = cfg.network_api.hosting_min_ttl_secs {
+                self.network_api.hosting_min_ttl_secs.get_or_insert(ttl);
+            }
             self.network_api
                 .min_connections
                 .get_or_insert(cfg.network_api.min_connections);
@@ -506,6 +536,8 @@ impl ConfigArgs {
                     .network_api
                     .transient_ttl_secs
                     .unwrap_or(DEFAULT_TRANSIENT_TTL_SECS),
+                hosting_budget: self.network_api.hosting_budget,
+                hosting_min_ttl_secs: self.network_api.hosting_min_ttl_secs,
                 min_connections: self
                     .network_api
                     .min_connections
@@ -584,7 +616,10 @@ impl ConfigArgs {
                 // environments to avoid flooding the collector with test data.
                 is_test_environment: self.id.is_some(),
             },
+            #[cfg(feature = "lepus")]
+            lepus,
         };
+        this.network_api.validate()?;
 
         fs::create_dir_all(this.config_dir())?;
         gateways.save_to_file(&gateways_file)?;
@@ -673,6 +708,10 @@ pub struct Config {
     /// Telemetry configuration
     #[serde(flatten)]
     pub telemetry: TelemetryConfig,
+    /// Lepus configuration (`[lepus]` section)
+    #[cfg(feature = "lepus")]
+    #[serde(default)]
+    pub lepus: LepusConfig,
 }
 
 /// Default max blocking threads: 2x CPU cores, clamped to 4-32.
@@ -690,6 +729,29 @@ impl Config {
     pub fn paths(&self) -> Arc<ConfigPaths> {
         self.config_paths.clone()
     }
+
+    /// Hosting cache byte budget: `hosting-budget`, else the `[lepus]`
+    /// budget when built with Lepus, else the default.
+    pub(crate) fn hosting_budget_bytes(&self) -> u64 {
+        #[cfg(feature = "lepus")]
+        let default = self.lepus.budget_bytes();
+        #[cfg(not(feature = "lepus"))]
+        let default = crate::ring::DEFAULT_HOSTING_BUDGET_BYTES;
+        self.network_api.hosting_budget.unwrap_or(default)
+    }
+
+    /// Eviction protection for newly hosted contracts: `hosting-min-ttl-secs`,
+    /// else the `[lepus]` TTL when built with Lepus, else the default.
+    pub(crate) fn hosting_min_ttl(&self) -> Duration {
+        #[cfg(feature = "lepus")]
+        let default = self.lepus.min_ttl();
+        #[cfg(not(feature = "lepus"))]
+        let default = crate::ring::DEFAULT_MIN_TTL;
+        self.network_api
+            .hosting_min_ttl_secs
+            .map(Duration::from_secs)
+            .unwrap_or(default)
+    }
 }
 
 #[derive(clap::Parser, Debug, Default, Clone, Serialize, Deserialize)]
@@ -786,6 +848,22 @@ pub struct NetworkArgs {
     #[serde(rename = "transient-ttl-secs", skip_serializing_if = "Option::is_none")]
     pub transient_ttl_secs: Option<u64>,
 
+    /// Byte budget of the hosting cache. Default: 100 MiB, or the `[lepus]`
+    /// profile's budget when built with Lepus.
+    #[arg(long, env = "HOSTING_BUDGET")]
+    #[serde(rename = "hosting-budget", skip_serializing_if = "Option::is_none")]
+    pub hosting_budget: Option<u64>,
+
+    /// Time (in seconds) newly hosted contracts are protected from eviction.
+    /// Default: 480 (8 minutes), or the `[lepus]` profile's TTL when built
+    /// with Lepus.
+    #[arg(long, env = "HOSTING_MIN_TTL_SECS")]
+    #[serde(
+        rename = "hosting-min-ttl-secs",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub hosting_min_ttl_secs: Option<u64>,
+
     /// Minimum desired connections for the ring topology. Defaults to 10.
     #[arg(long = "min-number-of-connections", env = "MIN_NUMBER_OF_CONNECTIONS")]
     #[serde(
@@ -957,6 +1035,21 @@ pub struct NetworkApiConfig {
     #[serde(default = "default_transient_ttl_secs", rename = "transient-ttl-secs")]
     pub transient_ttl_secs: u64,
 
+    /// Byte budget of the hosting cache.
+    ///
+    /// Default: None (100 MiB, or the `[lepus]` budget when built with Lepus)
+    #[serde(rename = "hosting-budget", skip_serializing_if = "Option::is_none")]
+    pub hosting_budget: Option<u64>,
+
+    /// Time (in seconds) newly hosted contracts are protected from eviction.
+    ///
+    /// Default: None (8 minutes, or the `[lepus]` TTL when built with Lepus)
+    #[serde(
+        rename = "hosting-min-ttl-secs",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub hosting_min_ttl_secs: Option<u64>,
+
     /// Minimum desired connections for the ring topology.
     #[serde(
         default = "default_min_connections",
@@ -1022,6 +1115,13 @@ pub struct NetworkApiConfig {
 }
 
 impl NetworkApiConfig {
+    pub(crate) fn validate(&self) -> anyhow::Result<()> {
+        if self.hosting_budget == Some(0) {
+            anyhow::bail!("hosting-budget must be positive");
+        }
+        Ok(())
+    }
+
     /// Build a `CongestionControlConfig` from the current network API configuration.
     ///
     /// This parses the `congestion_control` string to determine the algorithm
@@ -1226,6 +1326,838 @@ impl Default for TelemetryConfig {
     }
 }
 
+/// Lepus-specific node settings, read from the `[lepus]` section of the
+/// configuration file.
+///
+/// With a `profile`, the hosting settings start from that preset instead of
+/// the defaults; any `[lepus.cwp]` field, `hosting-budget-bytes` or
+/// `hosting-min-ttl-secs` set explicitly still overrides it.
+///
+/// Written back with only the `[lepus.cwp]` fields that were set explicitly
+/// or differ from the profile, so changing the profile later still changes
+/// the rest.
+#[cfg(feature = "lepus")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "LepusConfigFile", into = "LepusConfigFile")]
+pub struct LepusConfig {
+    /// Built-in hosting preset the settings start from.
+    pub profile: Option<HostingProfile>,
+    /// Commitment-weighted persistence scoring (`[lepus.cwp]`), with the
+    /// profile applied.
+    pub cwp: crate::ring::CWPConfig,
+    /// Compressed on-disk archive for evicted contracts (`[lepus.cold-tier]`).
+    pub cold_tier: ColdTierConfig,
+    /// On-disk audit log of hosting evictions (`[lepus.eviction-log]`).
+    pub eviction_log: EvictionLogConfig,
+    /// Deposit-index subscriber and Stellar relayer (`[lepus.oracle]`).
+    pub oracle: LepusOracleConfig,
+    /// Number of independently locked shards the hosting cache is split
+    /// into, each with an equal share of the byte budget.
+    pub hosting_shards: usize,
+    /// Hosting cache byte budget, overriding the profile's. The network-level
+    /// `hosting-budget` takes precedence over it.
+    pub hosting_budget_bytes: Option<u64>,
+    /// Seconds a newly hosted contract is protected from eviction,
+    /// overriding the profile's. The network-level `hosting-min-ttl-secs`
+    /// takes precedence over it.
+    pub hosting_min_ttl_secs: Option<u64>,
+    /// Hex 32-byte `ContractInstanceId` of the contract listing revoked
+    /// creator keys. Envelopes signed by a listed key don't verify.
+    pub revocation_key: Option<String>,
+    /// `[lepus.cwp]` fields as written, re-applied when the profile changes.
+    cwp_overrides: toml::Table,
+}
+
+/// Default number of hosting cache shards: one cache for the whole budget.
+#[cfg(feature = "lepus")]
+pub const DEFAULT_HOSTING_SHARDS: usize = 1;
+
+#[cfg(feature = "lepus")]
+fn default_hosting_shards() -> usize {
+    DEFAULT_HOSTING_SHARDS
+}
+
+#[cfg(feature = "lepus")]
+impl Default for LepusConfig {
+    fn default() -> Self {
+        Self {
+            profile: None,
+            cwp: Default::default(),
+            cold_tier: Default::default(),
+            eviction_log: Default::default(),
+            oracle: Default::default(),
+            hosting_shards: DEFAULT_HOSTING_SHARDS,
+            hosting_budget_bytes: None,
+            hosting_min_ttl_secs: None,
+            revocation_key: None,
+            cwp_overrides: toml::Table::new(),
+        }
+    }
+}
+
+/// `[lepus]` as written in the configuration file, before the profile is
+/// applied.
+#[cfg(feature = "lepus")]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct LepusConfigFile {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    profile: Option<HostingProfile>,
+    #[serde(default, skip_serializing_if = "toml::Table::is_empty")]
+    cwp: toml::Table,
+    #[serde(default)]
+    cold_tier: ColdTierConfig,
+    #[serde(default)]
+    eviction_log: EvictionLogConfig,
+    #[serde(default)]
+    oracle: LepusOracleConfig,
+    #[serde(default = "default_hosting_shards")]
+    hosting_shards: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    hosting_budget_bytes: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    hosting_min_ttl_secs: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    revocation_key: Option<String>,
+}
+
+#[cfg(feature = "lepus")]
+impl From<LepusConfig> for LepusConfigFile {
+    fn from(config: LepusConfig) -> Self {
+        Self {
+            profile: config.profile,
+            cwp: config.written_cwp(),
+            cold_tier: config.cold_tier,
+            eviction_log: config.eviction_log,
+            oracle: config.oracle,
+            hosting_shards: config.hosting_shards,
+            hosting_budget_bytes: config.hosting_budget_bytes,
+            hosting_min_ttl_secs: config.hosting_min_ttl_secs,
+            revocation_key: config.revocation_key,
+        }
+    }
+}
+
+#[cfg(feature = "lepus")]
+impl TryFrom<LepusConfigFile> for LepusConfig {
+    type Error = String;
+
+    fn try_from(file: LepusConfigFile) -> Result<Self, Self::Error> {
+        let mut config = Self {
+            profile: file.profile,
+            cwp: Default::default(),
+            cold_tier: file.cold_tier,
+            eviction_log: file.eviction_log,
+            oracle: file.oracle,
+            hosting_shards: file.hosting_shards,
+            hosting_budget_bytes: file.hosting_budget_bytes,
+            hosting_min_ttl_secs: file.hosting_min_ttl_secs,
+            revocation_key: file.revocation_key,
+            cwp_overrides: file.cwp,
+        };
+        config.cwp = config.resolve_cwp()?;
+        Ok(config)
+    }
+}
+
+#[cfg(feature = "lepus")]
+impl LepusConfig {
+    pub(crate) fn validate(&self) -> anyhow::Result<()> {
+        if self.hosting_shards == 0 {
+            anyhow::bail!("lepus.hosting-shards must be at least 1");
+        }
+        if self.budget_bytes() == 0 {
+            anyhow::bail!("lepus.hosting-budget-bytes must be positive");
+        }
+        if let Some(key) = &self.revocation_key {
+            if hex::decode(key.trim()).map_or(true, |bytes| bytes.len() != 32) {
+                anyhow::bail!("lepus.revocation-key must be 32 bytes of hex (64 characters)");
+            }
+        }
+        self.cwp.validate()?;
+        self.cold_tier.validate()?;
+        self.eviction_log.validate()?;
+        self.oracle.validate()
+    }
+
+    /// Switch to `profile`, keeping the fields set explicitly in the
+    /// configuration file. Used for `--lepus-profile`.
+    pub(crate) fn with_profile(mut self, profile: HostingProfile) -> anyhow::Result<Self> {
+        self.profile = Some(profile);
+        self.cwp = self.resolve_cwp().map_err(anyhow::Error::msg)?;
+        Ok(self)
+    }
+
+    /// Hosting cache byte budget: `hosting-budget-bytes`, else the
+    /// profile's, else the default.
+    pub fn budget_bytes(&self) -> u64 {
+        self.hosting_budget_bytes
+            .or(self.profile.map(HostingProfile::budget_bytes))
+            .unwrap_or(crate::ring::DEFAULT_HOSTING_BUDGET_BYTES)
+    }
+
+    /// Eviction protection for newly hosted contracts:
+    /// `hosting-min-ttl-secs`, else the profile's, else the default.
+    pub fn min_ttl(&self) -> Duration {
+        self.hosting_min_ttl_secs
+            .map(Duration::from_secs)
+            .or(self.profile.map(HostingProfile::min_ttl))
+            .unwrap_or(crate::ring::DEFAULT_MIN_TTL)
+    }
+
+    /// The profile's CWP configuration with the explicit `[lepus.cwp]`
+    /// fields laid over it.
+    fn resolve_cwp(&self) -> Result<crate::ring::CWPConfig, String> {
+        let base = self
+            .profile
+            .map(HostingProfile::cwp_config)
+            .unwrap_or_default();
+        let base = toml::Value::try_from(base).map_err(|e| format!("lepus.cwp: {e}"))?;
+        let mut fields = base.as_table().cloned().unwrap_or_default();
+        fields.extend(self.cwp_overrides.clone());
+        toml::Value::Table(fields)
+            .try_into()
+            .map_err(|e| format!("lepus.cwp: {e}"))
+    }
+
+    /// The `[lepus.cwp]` fields to write back: those set explicitly and
+    /// those that differ from the profile's.
+    fn written_cwp(&self) -> toml::Table {
+        let table = |cwp: crate::ring::CWPConfig| match toml::Value::try_from(cwp) {
+            Ok(toml::Value::Table(fields)) => fields,
+            _ => toml::Table::new(),
+        };
+        let base = table(
+            self.profile
+                .map(HostingProfile::cwp_config)
+                .unwrap_or_default(),
+        );
+        table(self.cwp.clone())
+            .into_iter()
+            .filter(|(name, value)| {
+                self.cwp_overrides.contains_key(name) || base.get(name) != Some(value)
+            })
+            .collect()
+    }
+}
+
+/// Built-in hosting presets, selected with `profile` in `[lepus]` or
+/// `--lepus-profile`. Each sets the whole CWP configuration, the hosting
+/// byte budget and the minimum TTL.
+#[cfg(feature = "lepus")]
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum HostingProfile {
+    /// Long-term storage of committed datapods: commitment-heavy scoring,
+    /// slow decay, a 10 GiB budget and a day of eviction protection.
+    ArchiveNode,
+    /// Short-term cache of popular content: recency- and traffic-heavy
+    /// scoring that favors small contracts, a 256 MiB budget and four
+    /// minutes of eviction protection.
+    EdgeCache,
+}
+
+#[cfg(feature = "lepus")]
+impl HostingProfile {
+    pub fn cwp_config(self) -> crate::ring::CWPConfig {
+        let defaults = crate::ring::CWPConfig::default();
+        match self {
+            Self::ArchiveNode => crate::ring::CWPConfig {
+                commitment_weight: 0.70,
+                identity_weight: 0.20,
+                contribution_weight: 0.05,
+                recency_weight: 0.05,
+                recency_halflife_secs: 2_592_000.0, // 30 days
+                commitment_staleness_halflife_secs: 7_776_000.0, // 90 days
+                commitment_warmup_secs: 3_600.0,
+                eviction_high_watermark: 0.95,
+                eviction_low_watermark: 0.90,
+                ..defaults
+            },
+            Self::EdgeCache => crate::ring::CWPConfig {
+                commitment_weight: 0.15,
+                identity_weight: 0.10,
+                contribution_weight: 0.25,
+                recency_weight: 0.50,
+                recency_halflife_secs: 86_400.0,     // 1 day
+                contribution_window_secs: 604_800.0, // 7 days
+                state_refresh_cooldown_secs: 300.0,
+                size_penalty_weight: 0.5,
+                ..defaults
+            },
+        }
+    }
+
+    pub fn budget_bytes(self) -> u64 {
+        match self {
+            Self::ArchiveNode => 10 * 1024 * 1024 * 1024,
+            Self::EdgeCache => 256 * 1024 * 1024,
+        }
+    }
+
+    pub fn min_ttl(self) -> Duration {
+        match self {
+            Self::ArchiveNode => Duration::from_secs(86_400),
+            Self::EdgeCache => Duration::from_secs(240),
+        }
+    }
+}
+
+/// Default cold tier budget: 1 GiB of compressed archives.
+#[cfg(feature = "lepus")]
+pub const DEFAULT_COLD_TIER_BUDGET_BYTES: u64 = 1024 * 1024 * 1024;
+
+/// Cold-tier spillover for the hosting cache.
+///
+/// When enabled, contracts evicted from the hosting cache are archived
+/// compressed on disk instead of being forgotten, and re-hosted on the next
+/// GET. Requires the `redb` storage backend.
+#[cfg(feature = "lepus")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct ColdTierConfig {
+    /// Archive evicted contracts instead of dropping them.
+    pub enabled: bool,
+    /// Byte budget for compressed archives; the oldest are dropped when full.
+    pub budget_bytes: u64,
+}
+
+#[cfg(feature = "lepus")]
+impl Default for ColdTierConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            budget_bytes: DEFAULT_COLD_TIER_BUDGET_BYTES,
+        }
+    }
+}
+
+#[cfg(feature = "lepus")]
+impl ColdTierConfig {
+    fn validate(&self) -> anyhow::Result<()> {
+        if self.enabled && self.budget_bytes == 0 {
+            anyhow::bail!(
+                "lepus.cold-tier.budget-bytes must be positive when the cold tier is enabled"
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Default number of eviction records kept on disk.
+#[cfg(feature = "lepus")]
+pub const DEFAULT_EVICTION_LOG_MAX_RECORDS: usize = 10_000;
+
+/// Audit log of contracts evicted from the hosting cache.
+///
+/// Each eviction is appended to `hosting-evictions.jsonl` in the database
+/// directory with its CWP score breakdown and the budget pressure at the time.
+#[cfg(feature = "lepus")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct EvictionLogConfig {
+    /// Record evictions on disk.
+    pub enabled: bool,
+    /// Most recent evictions to keep; older records are rotated out.
+    pub max_records: usize,
+}
+
+#[cfg(feature = "lepus")]
+impl Default for EvictionLogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_records: DEFAULT_EVICTION_LOG_MAX_RECORDS,
+        }
+    }
+}
+
+#[cfg(feature = "lepus")]
+impl EvictionLogConfig {
+    fn validate(&self) -> anyhow::Result<()> {
+        if self.enabled && self.max_records == 0 {
+            anyhow::bail!(
+                "lepus.eviction-log.max-records must be positive when the eviction log is enabled"
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Built-in Stellar networks, selected with `network` in `[lepus.oracle]`,
+/// `LEPUS_NETWORK` or `freenet lepus-params --network`. Each supplies the
+/// network passphrase and public endpoints, so the oracle and the
+/// deposit-index parameters agree on the network.
+#[cfg(feature = "lepus")]
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum StellarNetwork {
+    /// The public network. SDF runs no public Soroban RPC for it, so RPC
+    /// endpoints must still be configured.
+    #[serde(alias = "public")]
+    #[value(alias = "public")]
+    Mainnet,
+    Testnet,
+    Futurenet,
+}
+
+#[cfg(feature = "lepus")]
+impl StellarNetwork {
+    pub fn passphrase(self) -> &'static str {
+        match self {
+            Self::Mainnet => "Public Global Stellar Network ; September 2015",
+            Self::Testnet => "Test SDF Network ; September 2015",
+            Self::Futurenet => "Test SDF Future Network ; October 2022",
+        }
+    }
+
+    /// Public Soroban RPC endpoints.
+    pub fn rpc_urls(self) -> &'static [&'static str] {
+        match self {
+            Self::Mainnet => &[],
+            Self::Testnet => &["https://soroban-testnet.stellar.org"],
+            Self::Futurenet => &["https://rpc-futurenet.stellar.org"],
+        }
+    }
+
+    pub fn horizon_url(self) -> &'static str {
+        match self {
+            Self::Mainnet => "https://horizon.stellar.org",
+            Self::Testnet => "https://horizon-testnet.stellar.org",
+            Self::Futurenet => "https://horizon-futurenet.stellar.org",
+        }
+    }
+
+    /// SDF history archive.
+    pub fn history_archive_url(self) -> &'static str {
+        match self {
+            Self::Mainnet => "https://history.stellar.org/prd/core-live/core_live_001",
+            Self::Testnet => "https://history.stellar.org/prd/core-testnet/core_testnet_001",
+            Self::Futurenet => "http://history-futurenet.stellar.org",
+        }
+    }
+
+    /// Stellarbeat API listing the network's Tier-1 organizations, if
+    /// stellarbeat follows it.
+    pub fn stellarbeat_url(self) -> Option<&'static str> {
+        match self {
+            Self::Mainnet => Some("https://api.stellarbeat.io"),
+            Self::Testnet | Self::Futurenet => None,
+        }
+    }
+}
+
+/// Default seconds between relayer polls of Stellar RPC.
+#[cfg(feature = "lepus")]
+pub const DEFAULT_ORACLE_POLL_INTERVAL_SECS: u64 = 60;
+
+/// Default reach of the relayer's startup backfill: about a week of ledgers
+/// at five seconds each, the usual Soroban RPC event retention.
+#[cfg(feature = "lepus")]
+pub const DEFAULT_ORACLE_BACKFILL_MAX_LEDGERS: u32 = 120_960;
+
+/// Default reach of the relayer behind the tip at any time, matching the
+/// startup backfill. Older ledgers are skipped as coverage gaps.
+#[cfg(feature = "lepus")]
+pub const DEFAULT_ORACLE_MAX_CATCH_UP_LEDGERS: u32 = 120_960;
+
+/// Default minimum milliseconds between proofs fetched while backfilling.
+#[cfg(feature = "lepus")]
+pub const DEFAULT_ORACLE_BACKFILL_PROOF_INTERVAL_MS: u64 = 1_000;
+
+/// Default proofs the relayer fetches at once while catching up.
+#[cfg(feature = "lepus")]
+pub const DEFAULT_ORACLE_FETCH_CONCURRENCY: u32 = 4;
+
+/// Default ledgers per relayed UPDATE. Batches need a deposit-index that
+/// accepts them, so proofs go out one at a time unless configured.
+#[cfg(feature = "lepus")]
+pub const DEFAULT_ORACLE_SUBMIT_BATCH_MAX_LEDGERS: u32 = 1;
+
+/// Default size limit of a relayed UPDATE, in bytes of proof JSON. Larger
+/// proofs are split into parts.
+#[cfg(feature = "lepus")]
+pub const DEFAULT_ORACLE_SUBMIT_MAX_UPDATE_BYTES: u32 = 1_048_576;
+
+/// Default sustained rate of relayed UPDATEs per minute.
+#[cfg(feature = "lepus")]
+pub const DEFAULT_ORACLE_SUBMIT_RATE_PER_MIN: u32 = 12;
+
+/// Default UPDATEs the relayer may send back to back before the rate
+/// applies.
+#[cfg(feature = "lepus")]
+pub const DEFAULT_ORACLE_SUBMIT_BURST: u32 = 5;
+
+/// Default seconds over which relayers' turns at a ledger are spread.
+#[cfg(feature = "lepus")]
+pub const DEFAULT_ORACLE_TAKEOVER_SECS: u64 = 30;
+
+/// Default failed relayer polls in a row that raise an alert.
+#[cfg(feature = "lepus")]
+pub const DEFAULT_ORACLE_ALERT_FAILURE_THRESHOLD: u32 = 5;
+
+/// Default relayer lag, in ledgers, that raises an alert: about an hour at
+/// five seconds a ledger.
+#[cfg(feature = "lepus")]
+pub const DEFAULT_ORACLE_ALERT_LAG_LEDGERS: u32 = 720;
+
+/// Default delay before the oracle retries a failed subscription or poll.
+#[cfg(feature = "lepus")]
+pub const DEFAULT_ORACLE_RETRY_BASE_MS: u64 = 1_000;
+
+/// Default factor the retry delay grows by with each failure in a row.
+#[cfg(feature = "lepus")]
+pub const DEFAULT_ORACLE_RETRY_MULTIPLIER: f64 = 2.0;
+
+/// Default longest retry delay, 5 minutes.
+#[cfg(feature = "lepus")]
+pub const DEFAULT_ORACLE_RETRY_MAX_MS: u64 = 300_000;
+
+/// Default share of the retry delay added at random, so nodes that failed
+/// together don't retry together.
+#[cfg(feature = "lepus")]
+pub const DEFAULT_ORACLE_RETRY_JITTER: f64 = 0.25;
+
+/// Deposit-index oracle: every node with a deposit-index key subscribes to
+/// it, and nodes with Stellar RPC access also relay deposit proofs to it.
+///
+/// The `LEPUS_*` environment variables override these settings when set.
+#[cfg(feature = "lepus")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct LepusOracleConfig {
+    /// Built-in Stellar network supplying the RPC endpoints, Horizon server
+    /// and history archive that aren't set here.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network: Option<StellarNetwork>,
+    /// Stellar Soroban RPC endpoints, tried in order.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub rpc_urls: Vec<String>,
+    /// Credentials for RPC endpoints that require them, such as paid
+    /// providers.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub rpc_auth: Vec<LepusRpcAuth>,
+    /// Hex 32-byte deposit-index `ContractInstanceId`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deposit_index_key: Option<String>,
+    /// Hex 32-byte deposit-index `CodeHash`, needed to submit proofs.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deposit_index_code_hash: Option<String>,
+    /// StrKey of the hvym-freenet-service Soroban contract.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub soroban_contract_id: Option<String>,
+    /// Stellar history archive root for SCP envelopes and transaction sets.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub history_archive_url: Option<String>,
+    /// Seconds between relayer polls.
+    pub poll_interval_secs: u64,
+    /// Shortest the poll interval narrows to while polls keep finding
+    /// deposits. Defaults to `poll-interval-secs`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub poll_interval_min_secs: Option<u64>,
+    /// Longest the poll interval widens to while polls find nothing.
+    /// Defaults to `poll-interval-secs`, so the interval stays fixed unless
+    /// a bound is set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub poll_interval_max_secs: Option<u64>,
+    /// Poll as soon as a ledger closes, keeping `poll-interval-secs` as a
+    /// fallback.
+    pub stream: bool,
+    /// Horizon server whose ledger stream wakes the relayer in stream mode.
+    /// Without it the relayer checks the RPC tip every ledger close.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub horizon_url: Option<String>,
+    /// stellar-core ledger close meta stream to take proofs from, instead
+    /// of Soroban RPC: a file or named pipe, or `unix:<path>` for a Unix
+    /// socket. RPC endpoints, if any, fill in what the stream missed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub close_meta_stream: Option<String>,
+    /// Relay proofs when the relayer settings are complete. Off for nodes
+    /// that should only subscribe.
+    pub relayer: bool,
+    /// How far behind the tip, in ledgers, the relayer catches up on
+    /// deposits missed while it was down. 0 disables the backfill.
+    pub backfill_max_ledgers: u32,
+    /// Minimum milliseconds between proofs fetched while backfilling.
+    pub backfill_proof_interval_ms: u64,
+    /// Most ledgers the relayer trails the tip by before giving up on the
+    /// oldest ones, which are skipped and reported as coverage gaps in the
+    /// oracle status. 0 catches up on every ledger.
+    pub max_catch_up_ledgers: u32,
+    /// Most proofs fetched at once when a poll finds several ledgers. They
+    /// are still submitted in ledger order.
+    pub fetch_concurrency: u32,
+    /// Most ledgers whose proofs are combined into one UPDATE. Only raise
+    /// it for a deposit-index that accepts proof batches.
+    pub submit_batch_max_ledgers: u32,
+    /// Most bytes of proof JSON per UPDATE. A batch is sent before it
+    /// grows past this, and a single proof bigger than this is split into
+    /// parts the deposit-index applies one after another.
+    pub submit_max_update_bytes: u32,
+    /// Sustained UPDATEs per minute the relayer sends. 0 disables the limit.
+    pub submit_rate_per_min: u32,
+    /// UPDATEs the relayer may send back to back before the rate applies.
+    pub submit_burst: u32,
+    /// Seconds over which relayers take turns at each ledger, nearest to
+    /// the ledger's ring location first, so one of them usually submits
+    /// it. 0 lets every relayer submit at once.
+    pub takeover_secs: u64,
+    /// Sign submitted proofs with the node's Stellar key
+    /// (`LEPUS_STELLAR_SECRET`), so the deposit-index records which relayer
+    /// submitted each ledger.
+    pub attribute_proofs: bool,
+    /// Hooks told when the relayer keeps failing or falls behind
+    /// (`[lepus.oracle.alerts]`).
+    pub alerts: LepusRelayAlerts,
+    /// How the subscriber and relayer retry after failing
+    /// (`[lepus.oracle.retry]`).
+    pub retry: LepusRetryPolicy,
+}
+
+#[cfg(feature = "lepus")]
+impl Default for LepusOracleConfig {
+    fn default() -> Self {
+        Self {
+            network: None,
+            rpc_urls: Vec::new(),
+            rpc_auth: Vec::new(),
+            deposit_index_key: None,
+            deposit_index_code_hash: None,
+            soroban_contract_id: None,
+            history_archive_url: None,
+            poll_interval_secs: DEFAULT_ORACLE_POLL_INTERVAL_SECS,
+            poll_interval_min_secs: None,
+            poll_interval_max_secs: None,
+            stream: false,
+            horizon_url: None,
+            close_meta_stream: None,
+            relayer: true,
+            backfill_max_ledgers: DEFAULT_ORACLE_BACKFILL_MAX_LEDGERS,
+            backfill_proof_interval_ms: DEFAULT_ORACLE_BACKFILL_PROOF_INTERVAL_MS,
+            max_catch_up_ledgers: DEFAULT_ORACLE_MAX_CATCH_UP_LEDGERS,
+            fetch_concurrency: DEFAULT_ORACLE_FETCH_CONCURRENCY,
+            submit_batch_max_ledgers: DEFAULT_ORACLE_SUBMIT_BATCH_MAX_LEDGERS,
+            submit_max_update_bytes: DEFAULT_ORACLE_SUBMIT_MAX_UPDATE_BYTES,
+            submit_rate_per_min: DEFAULT_ORACLE_SUBMIT_RATE_PER_MIN,
+            submit_burst: DEFAULT_ORACLE_SUBMIT_BURST,
+            takeover_secs: DEFAULT_ORACLE_TAKEOVER_SECS,
+            attribute_proofs: false,
+            alerts: LepusRelayAlerts::default(),
+            retry: LepusRetryPolicy::default(),
+        }
+    }
+}
+
+#[cfg(feature = "lepus")]
+impl LepusOracleConfig {
+    fn validate(&self) -> anyhow::Result<()> {
+        if self.poll_interval_secs == 0 {
+            anyhow::bail!("lepus.oracle.poll-interval-secs must be positive");
+        }
+        if self.poll_interval_min_secs == Some(0) {
+            anyhow::bail!("lepus.oracle.poll-interval-min-secs must be positive");
+        }
+        let min = self
+            .poll_interval_min_secs
+            .unwrap_or(self.poll_interval_secs);
+        let max = self
+            .poll_interval_max_secs
+            .unwrap_or(self.poll_interval_secs);
+        if min > self.poll_interval_secs || max < self.poll_interval_secs {
+            anyhow::bail!(
+                "lepus.oracle.poll-interval-secs must lie between poll-interval-min-secs and \
+                 poll-interval-max-secs"
+            );
+        }
+        if self.fetch_concurrency == 0 {
+            anyhow::bail!("lepus.oracle.fetch-concurrency must be positive");
+        }
+        if self.submit_batch_max_ledgers == 0 {
+            anyhow::bail!("lepus.oracle.submit-batch-max-ledgers must be positive");
+        }
+        if self.submit_max_update_bytes == 0 {
+            anyhow::bail!("lepus.oracle.submit-max-update-bytes must be positive");
+        }
+        for auth in &self.rpc_auth {
+            auth.validate()?;
+        }
+        self.alerts.validate()?;
+        self.retry.validate()?;
+        if self.submit_rate_per_min > 0 && self.submit_burst == 0 {
+            anyhow::bail!(
+                "lepus.oracle.submit-burst must be positive when submissions are rate-limited"
+            );
+        }
+        for (name, value) in [
+            ("deposit-index-key", &self.deposit_index_key),
+            ("deposit-index-code-hash", &self.deposit_index_code_hash),
+        ] {
+            if let Some(value) = value {
+                if hex::decode(value.trim()).map_or(true, |bytes| bytes.len() != 32) {
+                    anyhow::bail!("lepus.oracle.{name} must be 32 bytes of hex (64 characters)");
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Credentials sent to the Stellar RPC endpoints starting with `url`, as
+/// `[[lepus.oracle.rpc-auth]]` entries. The first matching entry applies.
+#[cfg(feature = "lepus")]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct LepusRpcAuth {
+    /// Endpoint URL, or a prefix of the endpoints the entry covers.
+    pub url: String,
+    /// Extra request headers, e.g. `{ "X-API-Key" = "..." }`.
+    #[serde(default, skip_serializing_if = "std::collections::BTreeMap::is_empty")]
+    pub headers: std::collections::BTreeMap<String, String>,
+    /// HTTP basic authentication user.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub basic_auth_user: Option<String>,
+    /// HTTP basic authentication password, used with `basic-auth-user`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub basic_auth_password: Option<String>,
+}
+
+#[cfg(feature = "lepus")]
+impl LepusRpcAuth {
+    /// Whether the entry applies to the endpoint at `rpc_url`: the same
+    /// URL, or a path below it on the same host.
+    pub fn covers(&self, rpc_url: &str) -> bool {
+        rpc_url
+            .strip_prefix(self.url.trim().trim_end_matches('/'))
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with(['/', '?']))
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        if self.url.trim().is_empty() {
+            anyhow::bail!("lepus.oracle.rpc-auth entries need a url");
+        }
+        if self.basic_auth_password.is_some() && self.basic_auth_user.is_none() {
+            anyhow::bail!(
+                "lepus.oracle.rpc-auth for {} sets basic-auth-password without basic-auth-user",
+                self.url
+            );
+        }
+        Ok(())
+    }
+}
+
+// Credentials stay out of logs
+#[cfg(feature = "lepus")]
+impl std::fmt::Debug for LepusRpcAuth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LepusRpcAuth")
+            .field("url", &self.url)
+            .field("headers", &self.headers.keys().collect::<Vec<_>>())
+            .field("basic_auth_user", &self.basic_auth_user)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Hooks fired when the relayer fails `failure-threshold` polls in a row or
+/// trails the network by more than `lag-ledgers`, and again when it
+/// recovers. Alerts are off unless `webhook-url` or `command` is set.
+#[cfg(feature = "lepus")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct LepusRelayAlerts {
+    /// URL each alert is POSTed to as JSON.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub webhook_url: Option<String>,
+    /// Shell command run for each alert, with the alert JSON on its
+    /// standard input.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    /// Failed polls in a row that raise an alert. 0 disables it.
+    pub failure_threshold: u32,
+    /// Ledgers behind the network that raise an alert. 0 disables it.
+    pub lag_ledgers: u32,
+}
+
+#[cfg(feature = "lepus")]
+impl Default for LepusRelayAlerts {
+    fn default() -> Self {
+        Self {
+            webhook_url: None,
+            command: None,
+            failure_threshold: DEFAULT_ORACLE_ALERT_FAILURE_THRESHOLD,
+            lag_ledgers: DEFAULT_ORACLE_ALERT_LAG_LEDGERS,
+        }
+    }
+}
+
+#[cfg(feature = "lepus")]
+impl LepusRelayAlerts {
+    /// Whether any hook is set.
+    pub fn enabled(&self) -> bool {
+        self.webhook_url.is_some() || self.command.is_some()
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        if let Some(url) = &self.webhook_url {
+            if !url.starts_with("http://") && !url.starts_with("https://") {
+                anyhow::bail!("lepus.oracle.alerts.webhook-url must be an http(s) URL");
+            }
+        }
+        if self.command.as_deref().is_some_and(|c| c.trim().is_empty()) {
+            anyhow::bail!("lepus.oracle.alerts.command must not be empty");
+        }
+        Ok(())
+    }
+}
+
+/// Delays between the attempts of the deposit-index subscription and of
+/// relayer polls that keep failing: `base-ms` after the first failure,
+/// growing `multiplier` times with each one after up to `max-ms`, plus up
+/// to a `jitter` share of it at random.
+#[cfg(feature = "lepus")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct LepusRetryPolicy {
+    pub base_ms: u64,
+    pub multiplier: f64,
+    pub max_ms: u64,
+    pub jitter: f64,
+    /// Failures in a row after which the loop gives up until the node
+    /// restarts. 0 retries forever.
+    pub max_attempts: u32,
+}
+
+#[cfg(feature = "lepus")]
+impl Default for LepusRetryPolicy {
+    fn default() -> Self {
+        Self {
+            base_ms: DEFAULT_ORACLE_RETRY_BASE_MS,
+            multiplier: DEFAULT_ORACLE_RETRY_MULTIPLIER,
+            max_ms: DEFAULT_ORACLE_RETRY_MAX_MS,
+            jitter: DEFAULT_ORACLE_RETRY_JITTER,
+            max_attempts: 0,
+        }
+    }
+}
+
+#[cfg(feature = "lepus")]
+impl LepusRetryPolicy {
+    fn validate(&self) -> anyhow::Result<()> {
+        if self.base_ms == 0 {
+            anyhow::bail!("lepus.oracle.retry.base-ms must be positive");
+        }
+        if !self.multiplier.is_finite() || self.multiplier < 1.0 {
+            anyhow::bail!("lepus.oracle.retry.multiplier must be at least 1");
+        }
+        if self.max_ms < self.base_ms {
+            anyhow::bail!("lepus.oracle.retry.max-ms must be at least base-ms");
+        }
+        if !(0.0..=1.0).contains(&self.jitter) {
+            anyhow::bail!("lepus.oracle.retry.jitter must lie between 0 and 1");
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Copy, Clone, Serialize, Deserialize)]
 pub struct WebsocketApiConfig {
     /// Address to bind to
@@ -2386,6 +3318,512 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "lepus")]
+    #[test]
+    fn test_lepus_cwp_config_serde() {
+        let config_str = r#"
+            [cwp]
+            commitment-weight = 0.40
+            identity-weight = 0.20
+            contribution-weight = 0.20
+            recency-weight = 0.20
+        "#;
+
+        let config: LepusConfig = toml::from_str(config_str).unwrap();
+        assert_eq!(config.cwp.commitment_weight, 0.40);
+        assert_eq!(config.cwp.recency_weight, 0.20);
+        // Omitted fields keep their defaults
+        assert_eq!(
+            config.cwp.recency_halflife_secs,
+            crate::ring::CWPConfig::default().recency_halflife_secs
+        );
+        assert_eq!(config.cwp.max_bytes_per_creator, None);
+        config.validate().unwrap();
+
+        let config: LepusConfig = toml::from_str(
+            r#"
+            [cwp]
+            max-bytes-per-creator = 10485760
+            max-contracts-per-creator = 500
+            max-peer-contribution-share = 0.25
+            eviction-high-watermark = 0.95
+            eviction-low-watermark = 0.85
+            penalty-weight = 0.3
+            penalty-saturation = 10.0
+            subscription-weight = 0.1
+            aggregate-creator-commitment = true
+            contribution-window-secs = 604800.0
+            committed-budget-share = 0.7
+            commitment-warmup-secs = 900.0
+            state-refresh-cooldown-secs = 3600.0
+            commitment-decrease-confirmation-secs = 120.0
+            replication-hint-score = 0.4
+            replication-hint-attempts = 5
+            known-contracts-capacity = 500
+        "#,
+        )
+        .unwrap();
+        assert_eq!(config.cwp.max_bytes_per_creator, Some(10 * 1024 * 1024));
+        assert_eq!(config.cwp.max_contracts_per_creator, Some(500));
+        assert_eq!(config.cwp.max_peer_contribution_share, 0.25);
+        assert_eq!(config.cwp.eviction_high_watermark, 0.95);
+        assert_eq!(config.cwp.eviction_low_watermark, 0.85);
+        assert_eq!(config.cwp.penalty_weight, 0.3);
+        assert_eq!(config.cwp.penalty_saturation, 10.0);
+        assert_eq!(config.cwp.subscription_weight, 0.1);
+        assert!(config.cwp.aggregate_creator_commitment);
+        assert_eq!(config.cwp.contribution_window_secs, 604_800.0);
+        assert_eq!(config.cwp.committed_budget_share, Some(0.7));
+        assert_eq!(config.cwp.commitment_warmup_secs, 900.0);
+        assert_eq!(config.cwp.state_refresh_cooldown_secs, 3600.0);
+        assert_eq!(config.cwp.commitment_decrease_confirmation_secs, 120.0);
+        assert_eq!(config.cwp.replication_hint_score, Some(0.4));
+        assert_eq!(config.cwp.replication_hint_attempts, 5);
+        assert_eq!(config.cwp.known_contracts_capacity, 500);
+        config.validate().unwrap();
+
+        let config: LepusConfig = toml::from_str("").unwrap();
+        assert_eq!(config, LepusConfig::default());
+    }
+
+    #[cfg(feature = "lepus")]
+    #[test]
+    fn test_lepus_cold_tier_config_serde() {
+        let config: LepusConfig = toml::from_str("").unwrap();
+        assert!(!config.cold_tier.enabled);
+        assert_eq!(
+            config.cold_tier.budget_bytes,
+            DEFAULT_COLD_TIER_BUDGET_BYTES
+        );
+
+        let config: LepusConfig = toml::from_str(
+            r#"
+            [cold-tier]
+            enabled = true
+            budget-bytes = 5368709120
+        "#,
+        )
+        .unwrap();
+        assert!(config.cold_tier.enabled);
+        assert_eq!(config.cold_tier.budget_bytes, 5 * 1024 * 1024 * 1024);
+        config.validate().unwrap();
+
+        let config: LepusConfig = toml::from_str(
+            r#"
+            [cold-tier]
+            enabled = true
+            budget-bytes = 0
+        "#,
+        )
+        .unwrap();
+        let err = config.validate().unwrap_err();
+        assert!(err.to_string().contains("budget-bytes"), "{err}");
+    }
+
+    #[cfg(feature = "lepus")]
+    #[test]
+    fn test_lepus_oracle_config_serde() {
+        let config: LepusConfig = toml::from_str("").unwrap();
+        assert_eq!(config.oracle, LepusOracleConfig::default());
+        assert!(config.oracle.relayer);
+
+        let config: LepusConfig = toml::from_str(
+            r#"
+            [oracle]
+            network = "testnet"
+            rpc-urls = ["https://soroban-testnet.stellar.org", "https://rpc.example.com"]
+            rpc-auth = [
+                { url = "https://rpc.example.com", headers = { "X-API-Key" = "secret" } },
+                { url = "https://soroban-testnet.stellar.org", basic-auth-user = "relayer", basic-auth-password = "hunter2" },
+            ]
+            deposit-index-key = "0102030405060708091011121314151617181920212223242526272829303132"
+            deposit-index-code-hash = "3132333435363738394041424344454647484950515253545556575859606162"
+            soroban-contract-id = "CD3KT3NS3GMAQTTNVS5HIMV7Q6ISZNRIFXF7LIOMUOC5JC5VMG4UVOHQ"
+            poll-interval-secs = 30
+            poll-interval-min-secs = 10
+            poll-interval-max-secs = 300
+            stream = true
+            horizon-url = "https://horizon.stellar.org"
+            close-meta-stream = "unix:/run/stellar-core/meta.sock"
+            relayer = false
+            backfill-max-ledgers = 17280
+            max-catch-up-ledgers = 34560
+            fetch-concurrency = 8
+            submit-batch-max-ledgers = 10
+            submit-max-update-bytes = 262144
+            submit-rate-per-min = 0
+            takeover-secs = 0
+            attribute-proofs = true
+
+            [oracle.alerts]
+            webhook-url = "https://alerts.example.com/hook"
+            lag-ledgers = 120
+
+            [oracle.retry]
+            base-ms = 500
+            multiplier = 1.5
+            max-attempts = 10
+        "#,
+        )
+        .unwrap();
+        assert_eq!(config.oracle.network, Some(StellarNetwork::Testnet));
+        assert_eq!(config.oracle.rpc_urls.len(), 2);
+        assert_eq!(config.oracle.rpc_auth.len(), 2);
+        assert!(config.oracle.rpc_auth[0].covers("https://rpc.example.com/soroban"));
+        assert!(!config.oracle.rpc_auth[0].covers("https://soroban-testnet.stellar.org"));
+        assert!(!config.oracle.rpc_auth[0].covers("https://rpc.example.com.evil.org"));
+        assert_eq!(
+            config.oracle.rpc_auth[0]
+                .headers
+                .get("X-API-Key")
+                .map(String::as_str),
+            Some("secret")
+        );
+        assert_eq!(
+            config.oracle.rpc_auth[1].basic_auth_user.as_deref(),
+            Some("relayer")
+        );
+        assert!(!format!("{:?}", config.oracle.rpc_auth).contains("secret"));
+        assert_eq!(config.oracle.poll_interval_secs, 30);
+        assert_eq!(config.oracle.poll_interval_min_secs, Some(10));
+        assert_eq!(config.oracle.poll_interval_max_secs, Some(300));
+        assert!(config.oracle.stream);
+        assert_eq!(
+            config.oracle.horizon_url.as_deref(),
+            Some("https://horizon.stellar.org")
+        );
+        assert_eq!(
+            config.oracle.close_meta_stream.as_deref(),
+            Some("unix:/run/stellar-core/meta.sock")
+        );
+        assert!(!config.oracle.relayer);
+        assert_eq!(config.oracle.backfill_max_ledgers, 17_280);
+        assert_eq!(config.oracle.max_catch_up_ledgers, 34_560);
+        assert_eq!(
+            config.oracle.backfill_proof_interval_ms,
+            DEFAULT_ORACLE_BACKFILL_PROOF_INTERVAL_MS
+        );
+        assert_eq!(config.oracle.fetch_concurrency, 8);
+        assert_eq!(config.oracle.submit_batch_max_ledgers, 10);
+        assert_eq!(config.oracle.submit_max_update_bytes, 262_144);
+        assert_eq!(config.oracle.submit_rate_per_min, 0);
+        assert_eq!(config.oracle.submit_burst, DEFAULT_ORACLE_SUBMIT_BURST);
+        assert_eq!(config.oracle.takeover_secs, 0);
+        assert!(config.oracle.attribute_proofs);
+        assert!(config.oracle.alerts.enabled());
+        assert_eq!(config.oracle.alerts.command, None);
+        assert_eq!(
+            config.oracle.alerts.failure_threshold,
+            DEFAULT_ORACLE_ALERT_FAILURE_THRESHOLD
+        );
+        assert_eq!(config.oracle.alerts.lag_ledgers, 120);
+        assert_eq!(config.oracle.retry.base_ms, 500);
+        assert_eq!(config.oracle.retry.multiplier, 1.5);
+        assert_eq!(config.oracle.retry.max_ms, DEFAULT_ORACLE_RETRY_MAX_MS);
+        assert_eq!(config.oracle.retry.jitter, DEFAULT_ORACLE_RETRY_JITTER);
+        assert_eq!(config.oracle.retry.max_attempts, 10);
+        config.validate().unwrap();
+        let round_trip: LepusOracleConfig =
+            toml::from_str(&toml::to_string(&config.oracle).unwrap()).unwrap();
+        assert_eq!(round_trip, config.oracle);
+
+        let oracle: LepusOracleConfig = toml::from_str(r#"network = "public""#).unwrap();
+        assert_eq!(oracle.network, Some(StellarNetwork::Mainnet));
+
+        let config: LepusConfig = toml::from_str(
+            r#"
+            [oracle]
+            deposit-index-key = "abc"
+        "#,
+        )
+        .unwrap();
+        let err = config.validate().unwrap_err();
+        assert!(err.to_string().contains("deposit-index-key"), "{err}");
+
+        let config: LepusConfig = toml::from_str(
+            r#"
+            [oracle]
+            poll-interval-max-secs = 30
+        "#,
+        )
+        .unwrap();
+        let err = config.validate().unwrap_err();
+        assert!(err.to_string().contains("poll-interval-max-secs"), "{err}");
+
+        let config: LepusConfig = toml::from_str(
+            r#"
+            [oracle.retry]
+            multiplier = 0.5
+        "#,
+        )
+        .unwrap();
+        let err = config.validate().unwrap_err();
+        assert!(err.to_string().contains("retry.multiplier"), "{err}");
+    }
+
+    #[cfg(feature = "lepus")]
+    #[test]
+    fn test_lepus_eviction_log_config_serde() {
+        let config: LepusConfig = toml::from_str("").unwrap();
+        assert!(config.eviction_log.enabled);
+        assert_eq!(
+            config.eviction_log.max_records,
+            DEFAULT_EVICTION_LOG_MAX_RECORDS
+        );
+
+        let config: LepusConfig = toml::from_str(
+            r#"
+            [eviction-log]
+            max-records = 0
+        "#,
+        )
+        .unwrap();
+        let err = config.validate().unwrap_err();
+        assert!(err.to_string().contains("max-records"), "{err}");
+    }
+
+    #[cfg(feature = "lepus")]
+    #[test]
+    fn test_lepus_hosting_shards_config_serde() {
+        let config: LepusConfig = toml::from_str("").unwrap();
+        assert_eq!(config.hosting_shards, DEFAULT_HOSTING_SHARDS);
+
+        let config: LepusConfig = toml::from_str("hosting-shards = 8").unwrap();
+        assert_eq!(config.hosting_shards, 8);
+        config.validate().unwrap();
+
+        let config: LepusConfig = toml::from_str("hosting-shards = 0").unwrap();
+        let err = config.validate().unwrap_err();
+        assert!(err.to_string().contains("hosting-shards"), "{err}");
+    }
+
+    #[cfg(feature = "lepus")]
+    #[test]
+    fn test_lepus_revocation_key_config_serde() {
+        let config: LepusConfig = toml::from_str("").unwrap();
+        assert_eq!(config.revocation_key, None);
+
+        let key = "07".repeat(32);
+        let config: LepusConfig = toml::from_str(&format!("revocation-key = \"{key}\"")).unwrap();
+        assert_eq!(config.revocation_key.as_deref(), Some(key.as_str()));
+        config.validate().unwrap();
+
+        let config: LepusConfig = toml::from_str("revocation-key = \"abc\"").unwrap();
+        let err = config.validate().unwrap_err();
+        assert!(err.to_string().contains("revocation-key"), "{err}");
+    }
+
+    #[cfg(feature = "lepus")]
+    #[test]
+    fn test_lepus_hosting_profile_config_serde() {
+        for profile in [HostingProfile::ArchiveNode, HostingProfile::EdgeCache] {
+            profile.cwp_config().validate().unwrap();
+        }
+
+        let config: LepusConfig = toml::from_str(
+            r#"
+            profile = "archive-node"
+            hosting-min-ttl-secs = 600
+            [cwp]
+            recency-halflife-secs = 3600.0
+        "#,
+        )
+        .unwrap();
+        let archive = HostingProfile::ArchiveNode.cwp_config();
+        assert_eq!(config.cwp.commitment_weight, archive.commitment_weight);
+        assert_eq!(config.cwp.recency_halflife_secs, 3600.0);
+        assert_eq!(
+            config.budget_bytes(),
+            HostingProfile::ArchiveNode.budget_bytes()
+        );
+        assert_eq!(config.min_ttl(), Duration::from_secs(600));
+        config.validate().unwrap();
+
+        // A profile from the command line keeps the fields set in the file
+        let config = config.with_profile(HostingProfile::EdgeCache).unwrap();
+        let edge = HostingProfile::EdgeCache.cwp_config();
+        assert_eq!(config.cwp.commitment_weight, edge.commitment_weight);
+        assert_eq!(config.cwp.recency_halflife_secs, 3600.0);
+        assert_eq!(
+            config.budget_bytes(),
+            HostingProfile::EdgeCache.budget_bytes()
+        );
+        assert_eq!(config.min_ttl(), Duration::from_secs(600));
+
+        let config: LepusConfig = toml::from_str(r#"profile = "edge-cache""#).unwrap();
+        assert_eq!(config.cwp, edge);
+        assert_eq!(config.min_ttl(), HostingProfile::EdgeCache.min_ttl());
+
+        let config: LepusConfig = toml::from_str("").unwrap();
+        assert_eq!(
+            config.budget_bytes(),
+            crate::ring::DEFAULT_HOSTING_BUDGET_BYTES
+        );
+        assert_eq!(config.min_ttl(), crate::ring::DEFAULT_MIN_TTL);
+    }
+
+    #[cfg(feature = "lepus")]
+    #[test]
+    fn test_lepus_saved_config_follows_profile_change() {
+        let config: LepusConfig = toml::from_str(
+            r#"
+            profile = "archive-node"
+            [cwp]
+            recency-halflife-secs = 3600.0
+        "#,
+        )
+        .unwrap();
+        let saved = toml::to_string(&config).unwrap();
+        assert!(!saved.contains("commitment-weight"), "{saved}");
+
+        // Switching the profile in the saved file changes everything but
+        // the explicit override
+        let edited = saved.replace("archive-node", "edge-cache");
+        let reloaded: LepusConfig = toml::from_str(&edited).unwrap();
+        let edge = HostingProfile::EdgeCache.cwp_config();
+        assert_eq!(
+            reloaded.cwp,
+            crate::ring::CWPConfig {
+                recency_halflife_secs: 3600.0,
+                ..edge
+            }
+        );
+
+        let saved = toml::to_string(&LepusConfig::default()).unwrap();
+        assert!(!saved.contains("cwp"), "{saved}");
+    }
+
+    #[cfg(feature = "lepus")]
+    #[test]
+    fn test_lepus_cwp_config_rejects_bad_weights() {
+        let config: LepusConfig = toml::from_str(
+            r#"
+            [cwp]
+            commitment-weight = 0.90
+        "#,
+        )
+        .unwrap();
+        let err = config.validate().unwrap_err();
+        assert!(err.to_string().contains("sum to 1.0"), "{err}");
+
+        let config: LepusConfig = toml::from_str(
+            r#"
+            [cwp]
+            commitment-weight = 0.60
+            identity-weight = -0.10
+            contribution-weight = 0.30
+            recency-weight = 0.20
+        "#,
+        )
+        .unwrap();
+        let err = config.validate().unwrap_err();
+        assert!(err.to_string().contains("identity-weight"), "{err}");
+
+        let config: LepusConfig = toml::from_str(
+            r#"
+            [cwp]
+            recency-halflife-secs = 0.0
+        "#,
+        )
+        .unwrap();
+        let err = config.validate().unwrap_err();
+        assert!(err.to_string().contains("recency-halflife-secs"), "{err}");
+
+        let config: LepusConfig = toml::from_str(
+            r#"
+            [cwp]
+            max-contracts-per-creator = 0
+        "#,
+        )
+        .unwrap();
+        let err = config.validate().unwrap_err();
+        assert!(
+            err.to_string().contains("max-contracts-per-creator"),
+            "{err}"
+        );
+
+        let config: LepusConfig = toml::from_str(
+            r#"
+            [cwp]
+            committed-budget-share = 1.0
+        "#,
+        )
+        .unwrap();
+        let err = config.validate().unwrap_err();
+        assert!(err.to_string().contains("committed-budget-share"), "{err}");
+
+        let config: LepusConfig = toml::from_str(
+            r#"
+            [cwp]
+            max-peer-contribution-share = 1.5
+        "#,
+        )
+        .unwrap();
+        let err = config.validate().unwrap_err();
+        assert!(
+            err.to_string().contains("max-peer-contribution-share"),
+            "{err}"
+        );
+
+        let config: LepusConfig = toml::from_str(
+            r#"
+            [cwp]
+            size-penalty-weight = 2.0
+        "#,
+        )
+        .unwrap();
+        let err = config.validate().unwrap_err();
+        assert!(err.to_string().contains("size-penalty-weight"), "{err}");
+
+        let config: LepusConfig = toml::from_str(
+            r#"
+            [cwp]
+            penalty-weight = -0.1
+        "#,
+        )
+        .unwrap();
+        let err = config.validate().unwrap_err();
+        assert!(err.to_string().contains("penalty-weight"), "{err}");
+
+        let config: LepusConfig = toml::from_str(
+            r#"
+            [cwp]
+            contribution-bucket-secs = 86400.0
+            contribution-window-secs = 3600.0
+        "#,
+        )
+        .unwrap();
+        let err = config.validate().unwrap_err();
+        assert!(
+            err.to_string().contains("contribution-bucket-secs"),
+            "{err}"
+        );
+
+        let config: LepusConfig = toml::from_str(
+            r#"
+            [cwp]
+            eviction-high-watermark = 0.8
+            eviction-low-watermark = 0.9
+        "#,
+        )
+        .unwrap();
+        let err = config.validate().unwrap_err();
+        assert!(err.to_string().contains("eviction-low-watermark"), "{err}");
+
+        let config: LepusConfig = toml::from_str(
+            r#"
+            [cwp]
+            replication-hint-attempts = 0
+        "#,
+        )
+        .unwrap();
+        let err = config.validate().unwrap_err();
+        assert!(
+            err.to_string().contains("replication-hint-attempts"),
+            "{err}"
+        );
+    }
+
     #[test]
     fn test_streaming_config_defaults_via_serde() {
         // Verify streaming is enabled by default when deserializing empty config
@@ -2426,6 +3864,32 @@ mod tests {
         assert!(serialized.contains("streaming-threshold = 131072"));
     }
 
+    #[test]
+    fn test_hosting_limits_config_serde() {
+        let config: NetworkApiConfig = toml::from_str(
+            r#"
+            hosting-budget = 1048576
+            hosting-min-ttl-secs = 60
+        "#,
+        )
+        .unwrap();
+        assert_eq!(config.hosting_budget, Some(1024 * 1024));
+        assert_eq!(config.hosting_min_ttl_secs, Some(60));
+        config.validate().unwrap();
+
+        let serialized = toml::to_string(&config).unwrap();
+        assert!(serialized.contains("hosting-budget = 1048576"));
+        assert!(serialized.contains("hosting-min-ttl-secs = 60"));
+
+        let config: NetworkApiConfig = toml::from_str("").unwrap();
+        assert_eq!(config.hosting_budget, None);
+        assert_eq!(config.hosting_min_ttl_secs, None);
+
+        let config: NetworkApiConfig = toml::from_str("hosting-budget = 0").unwrap();
+        let err = config.validate().unwrap_err();
+        assert!(err.to_string().contains("hosting-budget"), "{err}");
+    }
+
     #[test]
     fn test_network_args_streaming_defaults() {
         // Verify NetworkArgs streaming fields are None by default (disabled)
@@ -14,6 +14,8 @@ pub type Storage = SqlitePool;
 pub mod redb;
 #[cfg(feature = "redb")]
 pub use self::redb::HostingMetadata;
+#[cfg(all(feature = "redb", feature = "lepus"))]
+pub use self::redb::LepusHostingMetadata;
 #[cfg(feature = "redb")]
 pub use self::redb::ReDb;
 
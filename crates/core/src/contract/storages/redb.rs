@@ -12,7 +12,8 @@ const STATE_TABLE: TableDefinition<&[u8], &[u8]> = TableDefinition::new("state")
 
 /// Table for persisting hosting metadata across restarts.
 /// Key: ContractKey bytes
-/// Value: HostingMetadata serialized (last_access_ms, access_type, size_bytes)
+/// Value: HostingMetadata serialized (last_access_ms, access_type, size_bytes,
+/// code_hash, and the optional Lepus scoring state)
 const HOSTING_METADATA_TABLE: TableDefinition<&[u8], &[u8]> =
     TableDefinition::new("hosting_metadata");
 
@@ -48,8 +49,14 @@ pub struct HostingMetadata {
     pub size_bytes: u64,
     /// Code hash of the contract (needed to reconstruct ContractKey)
     pub code_hash: [u8; 32],
+    /// CWP scoring state, appended after the base fields when present
+    #[cfg(feature = "lepus")]
+    pub lepus: Option<LepusHostingMetadata>,
 }
 
+/// Size of the base `HostingMetadata` encoding shared by all builds.
+const HOSTING_METADATA_BASE_LEN: usize = 49;
+
 impl HostingMetadata {
     pub fn new(last_access_ms: u64, access_type: u8, size_bytes: u64, code_hash: [u8; 32]) -> Self {
         Self {
@@ -57,22 +64,40 @@ impl HostingMetadata {
             access_type,
             size_bytes,
             code_hash,
+            #[cfg(feature = "lepus")]
+            lepus: None,
         }
     }
 
-    /// Serialize to bytes: [last_access_ms: 8][access_type: 1][size_bytes: 8][code_hash: 32] = 49 bytes
-    pub fn to_bytes(&self) -> [u8; 49] {
-        let mut buf = [0u8; 49];
+    /// Attach CWP scoring state to be persisted with this entry.
+    #[cfg(feature = "lepus")]
+    pub fn with_lepus(mut self, lepus: LepusHostingMetadata) -> Self {
+        self.lepus = Some(lepus);
+        self
+    }
+
+    /// Serialize to bytes: [last_access_ms: 8][access_type: 1][size_bytes: 8][code_hash: 32] = 49 bytes,
+    /// followed by the Lepus state (if any).
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = vec![0u8; HOSTING_METADATA_BASE_LEN];
         buf[0..8].copy_from_slice(&self.last_access_ms.to_le_bytes());
         buf[8] = self.access_type;
         buf[9..17].copy_from_slice(&self.size_bytes.to_le_bytes());
         buf[17..49].copy_from_slice(&self.code_hash);
+        #[cfg(feature = "lepus")]
+        if let Some(lepus) = &self.lepus {
+            buf.extend_from_slice(&lepus.to_bytes());
+        }
         buf
     }
 
-    /// Deserialize from bytes
+    /// Deserialize from bytes.
+    ///
+    /// Trailing bytes beyond the base encoding are ignored unless they hold
+    /// Lepus state, so entries written by Lepus and non-Lepus builds can be
+    /// read by either.
     pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
-        if bytes.len() < 49 {
+        if bytes.len() < HOSTING_METADATA_BASE_LEN {
             return None;
         }
         let last_access_ms = u64::from_le_bytes(bytes[0..8].try_into().ok()?);
@@ -84,6 +109,108 @@ impl HostingMetadata {
             access_type,
             size_bytes,
             code_hash,
+            #[cfg(feature = "lepus")]
+            lepus: LepusHostingMetadata::from_bytes(&bytes[HOSTING_METADATA_BASE_LEN..]),
+        })
+    }
+}
+
+/// CWP scoring state of a hosted contract, persisted so eviction scores
+/// survive restarts instead of waiting for the oracle and identity checks.
+#[cfg(feature = "lepus")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LepusHostingMetadata {
+    /// Deposited XLM last reported by the oracle
+    pub deposited_xlm: u64,
+    /// Milliseconds since UNIX epoch of the last oracle check (`None` = never checked)
+    pub last_oracle_check_ms: Option<u64>,
+    /// Creator's Ed25519 public key, if known
+    pub creator_pubkey: Option<[u8; 32]>,
+    /// Whether the creator identity was verified
+    pub creator_verified: bool,
+    /// Subscriber's Ed25519 public key, if known
+    pub subscriber_pubkey: Option<[u8; 32]>,
+    /// Whether the subscriber identity was verified
+    pub subscriber_verified: bool,
+    /// Recipient public key from the identity envelope, if any
+    pub recipient_pubkey: Option<[u8; 32]>,
+    /// Bytes served to other peers
+    pub bytes_served: u64,
+    /// Bytes consumed from other peers
+    pub bytes_consumed: u64,
+}
+
+#[cfg(feature = "lepus")]
+impl LepusHostingMetadata {
+    /// Format version written as the first byte of the encoding.
+    const VERSION: u8 = 1;
+    /// Encoded length: version, deposit, oracle check, flags, three keys, two counters.
+    const LEN: usize = 1 + 8 + 8 + 1 + 3 * 32 + 8 + 8;
+
+    const FLAG_ORACLE_CHECKED: u8 = 1 << 0;
+    const FLAG_CREATOR_KEY: u8 = 1 << 1;
+    const FLAG_CREATOR_VERIFIED: u8 = 1 << 2;
+    const FLAG_SUBSCRIBER_KEY: u8 = 1 << 3;
+    const FLAG_SUBSCRIBER_VERIFIED: u8 = 1 << 4;
+    const FLAG_RECIPIENT_KEY: u8 = 1 << 5;
+
+    /// Serialize to bytes: [version: 1][deposited_xlm: 8][last_oracle_check_ms: 8][flags: 1]
+    /// [creator: 32][subscriber: 32][recipient: 32][bytes_served: 8][bytes_consumed: 8] = 130 bytes
+    fn to_bytes(self) -> [u8; Self::LEN] {
+        let mut buf = [0u8; Self::LEN];
+        let mut flags = 0u8;
+        let mut set_flag = |flag: u8, on: bool| {
+            if on {
+                flags |= flag;
+            }
+        };
+        set_flag(
+            Self::FLAG_ORACLE_CHECKED,
+            self.last_oracle_check_ms.is_some(),
+        );
+        set_flag(Self::FLAG_CREATOR_KEY, self.creator_pubkey.is_some());
+        set_flag(Self::FLAG_CREATOR_VERIFIED, self.creator_verified);
+        set_flag(Self::FLAG_SUBSCRIBER_KEY, self.subscriber_pubkey.is_some());
+        set_flag(Self::FLAG_SUBSCRIBER_VERIFIED, self.subscriber_verified);
+        set_flag(Self::FLAG_RECIPIENT_KEY, self.recipient_pubkey.is_some());
+
+        buf[0] = Self::VERSION;
+        buf[1..9].copy_from_slice(&self.deposited_xlm.to_le_bytes());
+        buf[9..17].copy_from_slice(&self.last_oracle_check_ms.unwrap_or(0).to_le_bytes());
+        buf[17] = flags;
+        buf[18..50].copy_from_slice(&self.creator_pubkey.unwrap_or_default());
+        buf[50..82].copy_from_slice(&self.subscriber_pubkey.unwrap_or_default());
+        buf[82..114].copy_from_slice(&self.recipient_pubkey.unwrap_or_default());
+        buf[114..122].copy_from_slice(&self.bytes_served.to_le_bytes());
+        buf[122..130].copy_from_slice(&self.bytes_consumed.to_le_bytes());
+        buf
+    }
+
+    /// Deserialize from bytes; `None` if absent, truncated, or of an unknown version.
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < Self::LEN || bytes[0] != Self::VERSION {
+            return None;
+        }
+        let flags = bytes[17];
+        let last_oracle_check_ms = u64::from_le_bytes(bytes[9..17].try_into().ok()?);
+        let key_if = |flag: u8, range: std::ops::Range<usize>| -> Option<[u8; 32]> {
+            if flags & flag != 0 {
+                bytes[range].try_into().ok()
+            } else {
+                None
+            }
+        };
+        Some(Self {
+            deposited_xlm: u64::from_le_bytes(bytes[1..9].try_into().ok()?),
+            last_oracle_check_ms: (flags & Self::FLAG_ORACLE_CHECKED != 0)
+                .then_some(last_oracle_check_ms),
+            creator_pubkey: key_if(Self::FLAG_CREATOR_KEY, 18..50),
+            creator_verified: flags & Self::FLAG_CREATOR_VERIFIED != 0,
+            subscriber_pubkey: key_if(Self::FLAG_SUBSCRIBER_KEY, 50..82),
+            subscriber_verified: flags & Self::FLAG_SUBSCRIBER_VERIFIED != 0,
+            recipient_pubkey: key_if(Self::FLAG_RECIPIENT_KEY, 82..114),
+            bytes_served: u64::from_le_bytes(bytes[114..122].try_into().ok()?),
+            bytes_consumed: u64::from_le_bytes(bytes[122..130].try_into().ok()?),
         })
     }
 }
@@ -355,6 +482,34 @@ impl ReDb {
         }
     }
 
+    /// Write a contract's state synchronously, leaving its hosting metadata
+    /// untouched.
+    ///
+    /// Used to restore state rehydrated from the hosting cold tier, whose
+    /// metadata is written separately by the hosting manager.
+    #[cfg(feature = "lepus")]
+    pub fn store_state_sync(&self, key: &ContractKey, state: &[u8]) -> Result<(), redb::Error> {
+        let txn = self.0.begin_write()?;
+        {
+            let mut tbl = txn.open_table(STATE_TABLE)?;
+            tbl.insert(key.as_bytes(), state)?;
+        }
+        txn.commit().map_err(Into::into)
+    }
+
+    /// Delete a contract's state synchronously, leaving its hosting metadata
+    /// untouched.
+    ///
+    /// Used by the hosting manager to drop the state of evicted contracts.
+    pub fn remove_state_sync(&self, key: &ContractKey) -> Result<(), redb::Error> {
+        let txn = self.0.begin_write()?;
+        {
+            let mut tbl = txn.open_table(STATE_TABLE)?;
+            tbl.remove(key.as_bytes())?;
+        }
+        txn.commit().map_err(Into::into)
+    }
+
     /// Iterate all contract keys that have stored state.
     /// Returns the raw key bytes - caller must reconstruct ContractKey.
     pub fn iter_all_state_keys(&self) -> Result<Vec<Vec<u8>>, redb::Error> {
@@ -669,6 +824,15 @@ impl StateStorage for ReDb {
             // Store the code hash so we can reconstruct ContractKey on load
             let code_hash: [u8; 32] = **key.code_hash();
             let metadata = HostingMetadata::new(now_ms, 1, state_size, code_hash);
+            // Keep the Lepus scoring state already persisted for this contract
+            #[cfg(feature = "lepus")]
+            let metadata = HostingMetadata {
+                lepus: tbl
+                    .get(key.as_bytes())?
+                    .and_then(|v| HostingMetadata::from_bytes(v.value()))
+                    .and_then(|existing| existing.lepus),
+                ..metadata
+            };
             tbl.insert(key.as_bytes(), metadata.to_bytes().as_slice())?;
         }
 
@@ -792,6 +956,82 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_hosting_metadata_roundtrip() {
+        let metadata = HostingMetadata::new(1_700_000_000_000, 2, 4096, [7u8; 32]);
+        let bytes = metadata.to_bytes();
+        assert_eq!(bytes.len(), 49);
+
+        let decoded = HostingMetadata::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.last_access_ms, 1_700_000_000_000);
+        assert_eq!(decoded.access_type, 2);
+        assert_eq!(decoded.size_bytes, 4096);
+        assert_eq!(decoded.code_hash, [7u8; 32]);
+
+        assert!(HostingMetadata::from_bytes(&bytes[..48]).is_none());
+    }
+
+    #[cfg(feature = "lepus")]
+    #[test]
+    fn test_hosting_metadata_lepus_roundtrip() {
+        let lepus = LepusHostingMetadata {
+            deposited_xlm: 10,
+            last_oracle_check_ms: Some(1_700_000_000_500),
+            creator_pubkey: Some([1u8; 32]),
+            creator_verified: true,
+            subscriber_pubkey: None,
+            subscriber_verified: false,
+            recipient_pubkey: Some([3u8; 32]),
+            bytes_served: 5000,
+            bytes_consumed: 2000,
+        };
+        let metadata =
+            HostingMetadata::new(1_700_000_000_000, 1, 2048, [9u8; 32]).with_lepus(lepus);
+        let bytes = metadata.to_bytes();
+        assert_eq!(bytes.len(), 49 + 130);
+
+        let decoded = HostingMetadata::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.size_bytes, 2048);
+        assert_eq!(decoded.lepus, Some(lepus));
+
+        // Entries written without Lepus state load with none
+        let base = HostingMetadata::from_bytes(&bytes[..49]).unwrap();
+        assert_eq!(base.lepus, None);
+        // An unknown trailer version is ignored rather than misread
+        let mut future = bytes.clone();
+        future[49] = 2;
+        assert_eq!(HostingMetadata::from_bytes(&future).unwrap().lepus, None);
+    }
+
+    #[cfg(feature = "lepus")]
+    #[tokio::test]
+    async fn test_state_store_keeps_lepus_metadata() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = ReDb::new(temp_dir.path()).await.unwrap();
+        let key = ContractKey::from_id_and_code(
+            ContractInstanceId::new([4u8; 32]),
+            CodeHash::new([5u8; 32]),
+        );
+        let lepus = LepusHostingMetadata {
+            deposited_xlm: 42,
+            bytes_served: 100,
+            ..Default::default()
+        };
+        db.store_hosting_metadata(
+            &key,
+            HostingMetadata::new(0, 0, 10, [5u8; 32]).with_lepus(lepus),
+        )
+        .unwrap();
+
+        db.store(key, WrappedState::new(vec![0u8; 64]))
+            .await
+            .unwrap();
+
+        let stored = db.get_hosting_metadata(&key).unwrap().unwrap();
+        assert_eq!(stored.size_bytes, 64);
+        assert_eq!(stored.lepus, Some(lepus));
+    }
+
     #[tokio::test]
     async fn test_migration_with_fresh_database() {
         let temp_dir = TempDir::new().unwrap();
@@ -52,6 +52,16 @@ mod wasm_runtime;
 /// Deterministic simulation testing framework.
 pub mod simulation;
 
+/// Tools for operating the Lepus deposit-index.
+#[cfg(feature = "lepus")]
+pub mod lepus {
+    pub use crate::config::StellarNetwork;
+    pub use crate::ring::validator_set::{
+        deposit_index_params, fetch_stellarbeat, tier_one_from_quorum_config, topology_changes,
+    };
+    pub use crate::ring::{DepositIndexParams, ValidatorOrg};
+}
+
 /// Exports to build a running local node.
 pub mod local_node {
     use super::*;
@@ -86,6 +96,11 @@ pub mod dev_tool {
         InitPeerNode, NetworkStats, NodeConfig, PeerId,
     };
     pub use ring::Location;
+    #[cfg(all(feature = "lepus", feature = "testing"))]
+    pub use ring::{
+        set_test_proof_source, CWPConfig, ClassReport, ContractClass, CwpSimulation,
+        DepositIndexParams, DepositProof, MockStellarProofSource, ProofFixtures, SimulationReport,
+    };
     pub use transport::{TransportKeypair, TransportPublicKey};
 
     // Re-export topology registry for subscription validation in tests
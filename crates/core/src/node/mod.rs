@@ -515,6 +515,11 @@ async fn report_result(
 
     match op_result {
         Ok(Some(op_res)) => {
+            #[cfg(feature = "lepus")]
+            if let Some(transaction) = tx {
+                op_manager.notify_result(transaction, || op_res.to_host_result().is_ok());
+            }
+
             // Log specifically for UPDATE operations
             if let crate::operations::OpEnum::Update(ref update_op) = op_res {
                 tracing::debug!(
@@ -606,6 +611,8 @@ async fn report_result(
         Err(err) => {
             // just mark the operation as completed so no redundant messages are processed for this transaction anymore
             if let Some(tx) = tx {
+                #[cfg(feature = "lepus")]
+                op_manager.notify_result(tx, || false);
                 op_manager.completed(tx);
             }
             #[cfg(any(debug_assertions, test))]
@@ -1357,8 +1364,11 @@ async fn handle_interest_sync_message(
                 .await
             {
                 Ok(ContractHandlerEvent::UpdateResponse {
-                    new_value: Ok(_), ..
+                    new_value: Ok(new_state),
+                    ..
                 }) => {
+                    #[cfg(not(feature = "lepus"))]
+                    let _ = new_state;
                     tracing::info!(
                         from = %source,
                         contract = %key,
@@ -1366,6 +1376,16 @@ async fn handle_interest_sync_message(
                         changed = true,
                         "ResyncResponse state applied successfully"
                     );
+                    // Lepus: re-verify the identity envelope of the resynced state
+                    #[cfg(feature = "lepus")]
+                    {
+                        op_manager
+                            .ring
+                            .verify_and_update_identity(&key, new_state.as_ref());
+                        op_manager
+                            .ring
+                            .check_deposit_index_update(&key, new_state.as_ref());
+                    }
                 }
                 Ok(ContractHandlerEvent::UpdateNoChange { .. }) => {
                     tracing::info!(
@@ -2024,6 +2024,14 @@ impl P2pConnManager {
                                             "Failed to send state change broadcast"
                                         );
                                     } else {
+                                        // CWP: record bytes served to the subscriber
+                                        #[cfg(feature = "lepus")]
+                                        op_manager.ring.record_bytes_served(
+                                            &key,
+                                            Some(&peer_key),
+                                            payload_size as u64,
+                                        );
+
                                         // Track delta vs full state sends for testing (PR #2763)
                                         if sent_delta {
                                             op_manager
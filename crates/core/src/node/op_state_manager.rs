@@ -200,6 +200,10 @@ struct Ops {
     update: DashMap<Transaction, UpdateOp>,
     completed: DashSet<Transaction>,
     under_progress: DashSet<Transaction>,
+    /// Senders for [`OpManager::wait_for_result`], dropped when the
+    /// transaction times out.
+    #[cfg(feature = "lepus")]
+    result_waiters: DashMap<Transaction, oneshot::Sender<bool>>,
 }
 
 /// Thread safe and friendly data structure to maintain state of the different operations
@@ -753,6 +757,28 @@ impl OpManager {
         }
     }
 
+    /// Register to learn whether the operation `id` succeeded once it
+    /// finishes. Register before starting the operation.
+    ///
+    /// The receiver gets `true` for a successful result and `false` for an
+    /// error; it is dropped without a value if the operation times out.
+    #[cfg(feature = "lepus")]
+    pub fn wait_for_result(&self, id: Transaction) -> oneshot::Receiver<bool> {
+        let (tx, rx) = oneshot::channel();
+        self.ops.result_waiters.insert(id, tx);
+        rx
+    }
+
+    /// Resolve the waiter registered for `id`, if any. `succeeded` is only
+    /// evaluated when there is one.
+    #[cfg(feature = "lepus")]
+    pub(crate) fn notify_result(&self, id: Transaction, succeeded: impl FnOnce() -> bool) {
+        if let Some((_, waiter)) = self.ops.result_waiters.remove(&id) {
+            // Ignore errors if the receiver was dropped
+            let _ = waiter.send(succeeded());
+        }
+    }
+
     /// Atomically registers both expected count and parent-child relationship.
     /// This prevents race conditions where children complete before registration.
     pub fn expect_and_register_sub_operation(&self, parent: Transaction, child: Transaction) {
@@ -1005,6 +1031,8 @@ async fn garbage_cleanup_task<ER: NetEventRegister>(
                 let mut old_missing = std::mem::replace(&mut delayed, Vec::with_capacity(200));
                 for tx in old_missing.drain(..) {
                     if let Some(tx) = ops.completed.remove(&tx) {
+                        #[cfg(feature = "lepus")]
+                        ops.result_waiters.remove(&tx);
                         if cfg!(feature = "trace-ot") {
                             event_register.notify_of_time_out(tx).await;
                         } else {
@@ -1036,6 +1064,8 @@ async fn garbage_cleanup_task<ER: NetEventRegister>(
                     } else {
                         ops.under_progress.remove(&tx);
                         ops.completed.remove(&tx);
+                        #[cfg(feature = "lepus")]
+                        ops.result_waiters.remove(&tx);
                         tracing::info!(
                             tx = %tx,
                             tx_type = ?tx.transaction_type(),
@@ -1084,6 +1114,8 @@ async fn garbage_cleanup_task<ER: NetEventRegister>(
                         delayed.push(tx);
                         continue;
                     }
+                    #[cfg(feature = "lepus")]
+                    ops.result_waiters.remove(&tx);
                     if let Some(tx) = ops.completed.remove(&tx) {
                         tracing::debug!("Clean up timed out: {tx}");
                         if cfg!(feature = "trace-ot") {
@@ -201,6 +201,10 @@ impl NodeP2P {
             handle.abort();
         }
 
+        // Let the Lepus relayer save its progress before the process exits
+        #[cfg(feature = "lepus")]
+        self.op_manager.ring.shutdown_oracle().await;
+
         // Emit peer shutdown event
         let (graceful, reason) = match &result {
             Ok(_) => (true, None),
@@ -12,7 +12,7 @@ use crate::{
     message::{InnerMessage, NetMessage, Transaction},
     node::{NetworkBridge, OpManager},
     operations::{OpInitialization, Operation},
-    ring::{Location, PeerKeyLocation, RingError},
+    ring::{AccessType, Location, PeerKeyLocation, RingError},
     tracing::{state_hash_full, NetEventLog, OperationFailure},
 };
 use either::Either;
@@ -118,6 +118,9 @@ pub(crate) async fn request_get(
             "GET: Checking local storage for fallback before network query"
         );
 
+        #[cfg(all(feature = "lepus", feature = "redb"))]
+        op_manager.ring.rehydrate_from_cold_tier(instance_id);
+
         let get_result = op_manager
             .notify_contract_handler(ContractHandlerEvent::GetQuery {
                 instance_id: *instance_id,
@@ -921,6 +924,9 @@ impl Operation for GetOp {
                         }
 
                         // First check if we have the contract locally before forwarding
+                        #[cfg(all(feature = "lepus", feature = "redb"))]
+                        op_manager.ring.rehydrate_from_cold_tier(&instance_id);
+
                         let get_result = op_manager
                             .notify_contract_handler(ContractHandlerEvent::GetQuery {
                                 instance_id,
@@ -965,9 +971,11 @@ impl Operation for GetOp {
 
                             // CWP: record bytes served to requester
                             #[cfg(feature = "lepus")]
-                            op_manager
-                                .ring
-                                .record_bytes_served(&key, state.size() as u64);
+                            op_manager.ring.record_bytes_served_to(
+                                &key,
+                                self.upstream_addr,
+                                state.size() as u64,
+                            );
 
                             // Register the GET requester's interest in this contract so that
                             // update broadcasts include them as a target. This is critical for
@@ -1688,10 +1696,13 @@ impl Operation for GetOp {
                             // which meant re-GETs on cached contracts wouldn't refresh the
                             // hosting cache's TTL/LRU position, causing subscriptions to expire.
                             //
-                            // record_get_access now returns is_new atomically, eliminating the
+                            // host_contract_state now returns is_new atomically, eliminating the
                             // TOCTOU race that existed when checking is_hosting_contract() first.
-                            let access_result =
-                                op_manager.ring.record_get_access(key, value.size() as u64);
+                            let access_result = op_manager.ring.host_contract_state(
+                                key,
+                                value.as_ref(),
+                                AccessType::Get,
+                            );
 
                             // CWP: record bytes consumed from remote peer
                             #[cfg(feature = "lepus")]
@@ -1743,7 +1754,7 @@ impl Operation for GetOp {
                             }
 
                             // Auto-subscribe to receive updates for this contract
-                            // record_get_access already refreshed the hosting cache above
+                            // host_contract_state already refreshed the hosting cache above
                             if crate::ring::AUTO_SUBSCRIBE_ON_GET {
                                 // Only start new subscription if not already subscribed
                                 if access_result.is_new || !op_manager.ring.is_subscribed(&key) {
@@ -1769,18 +1780,25 @@ impl Operation for GetOp {
                                     .await?;
 
                                 match res {
-                                    ContractHandlerEvent::PutResponse { new_value: Ok(_), .. } => {
+                                    ContractHandlerEvent::PutResponse { new_value: Ok(_stored), .. } => {
                                         tracing::debug!(tx = %id, %key, "Contract put at executor");
 
+                                        // Lepus: deposit-index state fetched by GET, including the
+                                        // one a new subscription fetches, feeds commitments like an UPDATE
+                                        #[cfg(feature = "lepus")]
+                                        op_manager
+                                            .ring
+                                            .check_deposit_index_update(&key, _stored.as_ref());
+
                                         // BUG FIX (2026-01): ALWAYS refresh hosting status on GET.
                                         // This ensures re-GETs keep the hosting cache's TTL/LRU fresh.
                                         //
-                                        // record_get_access now returns is_new atomically, eliminating the
+                                        // host_contract_state now returns is_new atomically, eliminating the
                                         // TOCTOU race that existed when checking is_hosting_contract() first.
                                         tracing::debug!(tx = %id, %key, peer = ?op_manager.ring.connection_manager.get_own_addr(), "Recording contract access in hosting cache");
                                         let access_result = op_manager
                                             .ring
-                                            .record_get_access(key, value.size() as u64);
+                                            .host_contract_state(key, value.as_ref(), AccessType::Get);
 
                                         // Clean up interest tracking for evicted contracts (always, even if already hosting)
                                         let mut removed_contracts = Vec::new();
@@ -1827,7 +1845,7 @@ impl Operation for GetOp {
                                         }
 
                                         // Auto-subscribe to receive updates for this contract
-                                        // record_get_access already refreshed the hosting cache above
+                                        // host_contract_state already refreshed the hosting cache above
                                         if crate::ring::AUTO_SUBSCRIBE_ON_GET {
                                             // Only start new subscription if not already subscribed
                                             if access_result.is_new || !op_manager.ring.is_subscribed(&key) {
@@ -1924,6 +1942,13 @@ impl Operation for GetOp {
                     } else {
                         // Forward response to upstream
                         tracing::info!(tx = %id, contract = %key, phase = "response", "Get response received for contract at hop peer");
+                        // CWP: the state we just cached is served on to the requester
+                        #[cfg(feature = "lepus")]
+                        op_manager.ring.record_bytes_served_to(
+                            &key,
+                            self.upstream_addr,
+                            value.size() as u64,
+                        );
                         new_state = None;
                         return_msg = Some(GetMsg::Response {
                             id,
@@ -2196,7 +2221,7 @@ impl Operation for GetOp {
 
                         if !already_hosting {
                             // Use put_query to cache the contract
-                            let _ = op_manager
+                            let _put_result = op_manager
                                 .notify_contract_handler(ContractHandlerEvent::PutQuery {
                                     key,
                                     state: state.clone(),
@@ -2204,11 +2229,25 @@ impl Operation for GetOp {
                                     contract: contract_to_cache,
                                 })
                                 .await;
+
+                            // Lepus: deposit-index state fetched by GET feeds commitments
+                            #[cfg(feature = "lepus")]
+                            if let Ok(ContractHandlerEvent::PutResponse {
+                                new_value: Ok(stored),
+                                ..
+                            }) = &_put_result
+                            {
+                                op_manager
+                                    .ring
+                                    .check_deposit_index_update(&key, stored.as_ref());
+                            }
                         }
 
                         // BUG FIX (2026-01): ALWAYS refresh hosting status on GET.
                         // This keeps the TTL/LRU position fresh for already-hosted contracts.
-                        op_manager.ring.record_get_access(key, state.size() as u64);
+                        op_manager
+                            .ring
+                            .host_contract_state(key, state.as_ref(), AccessType::Get);
                     }
 
                     // Step 6: Emit telemetry
@@ -2257,6 +2296,15 @@ impl Operation for GetOp {
                         new_state = Some(GetState::Finished { key });
                         return_msg = None;
                     } else if piping_started {
+                        // CWP: record bytes served to requester
+                        #[cfg(feature = "lepus")]
+                        if let Some(state) = &value.state {
+                            op_manager.ring.record_bytes_served_to(
+                                &key,
+                                self.upstream_addr,
+                                state.size() as u64,
+                            );
+                        }
                         // Piping is already underway - no need to send return_msg
                         tracing::debug!(
                             tx = %id,
@@ -2275,6 +2323,15 @@ impl Operation for GetOp {
                             phase = "forward",
                             "Forwarding GET response as non-streaming to upstream"
                         );
+                        // CWP: record bytes served to requester
+                        #[cfg(feature = "lepus")]
+                        if let Some(state) = &value.state {
+                            op_manager.ring.record_bytes_served_to(
+                                &key,
+                                self.upstream_addr,
+                                state.size() as u64,
+                            );
+                        }
 
                         return_msg = Some(GetMsg::Response {
                             id,
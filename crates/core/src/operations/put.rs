@@ -24,7 +24,7 @@ use crate::{
     contract::ContractHandlerEvent,
     message::{InnerMessage, NetMessage, Transaction},
     node::{NetworkBridge, OpManager},
-    ring::{KnownPeerKeyLocation, Location, PeerKeyLocation},
+    ring::{AccessType, KnownPeerKeyLocation, Location, PeerKeyLocation},
     tracing::{state_hash_full, NetEventLog, OperationFailure},
 };
 use either::Either;
@@ -317,6 +317,11 @@ impl Operation for PutOp {
 
                     // Check if we're already subscribed to this contract BEFORE storing
                     let was_seeding = op_manager.ring.is_seeding_contract(&key);
+                    // Lepus: pushing out an evicted contract must not host it again
+                    #[cfg(feature = "lepus")]
+                    let replicating = is_originator && op_manager.ring.is_replicating(&key);
+                    #[cfg(not(feature = "lepus"))]
+                    let replicating = false;
 
                     // Step 1: Store contract locally (all nodes cache)
                     // put_contract returns (merged_value, state_changed) where state_changed
@@ -331,8 +336,11 @@ impl Operation for PutOp {
                     .await?;
 
                     // Mark as seeding if not already
-                    if !was_seeding {
-                        let evicted = op_manager.ring.seed_contract(key, value.size() as u64);
+                    if !was_seeding && !replicating {
+                        let evicted = op_manager
+                            .ring
+                            .host_contract_state(key, value.as_ref(), AccessType::Put)
+                            .evicted;
                         super::announce_contract_cached(op_manager, &key).await;
 
                         // Clean up interest tracking for evicted contracts
@@ -360,7 +368,7 @@ impl Operation for PutOp {
 
                     // Invariant: after storing and seeding, the contract MUST be in the seed list.
                     debug_assert!(
-                        op_manager.ring.is_seeding_contract(&key),
+                        replicating || op_manager.ring.is_seeding_contract(&key),
                         "PUT Request: contract {key} must be in seed list after put_contract + seed_contract"
                     );
 
@@ -370,6 +378,10 @@ impl Operation for PutOp {
                         op_manager
                             .ring
                             .verify_and_update_identity(&key, value.as_ref());
+                        // CWP: record bytes consumed from the PUT
+                        op_manager
+                            .ring
+                            .record_bytes_consumed(&key, value.size() as u64);
                     }
 
                     // Network peer notification is now automatic via BroadcastStateChange
@@ -877,7 +889,10 @@ impl Operation for PutOp {
 
                     // Mark as seeding if not already
                     if !was_seeding {
-                        let evicted = op_manager.ring.seed_contract(key, value.size() as u64);
+                        let evicted = op_manager
+                            .ring
+                            .host_contract_state(key, value.as_ref(), AccessType::Put)
+                            .evicted;
                         super::announce_contract_cached(op_manager, &key).await;
 
                         let mut removed_contracts = Vec::new();
@@ -911,6 +926,10 @@ impl Operation for PutOp {
                         op_manager
                             .ring
                             .verify_and_update_identity(&key, value.as_ref());
+                        // CWP: record bytes consumed from the PUT
+                        op_manager
+                            .ring
+                            .record_bytes_consumed(&key, value.size() as u64);
                     }
 
                     // Step 5: Handle forwarding or final destination
@@ -1391,6 +1410,83 @@ pub(crate) async fn request_put(op_manager: &OpManager, put_op: PutOp) -> Result
     Ok(())
 }
 
+/// Push a contract evicted from the hosting cache towards the peers closest
+/// to its location, so dropping the local copy doesn't remove the last
+/// nearby replica. Each attempt is a PUT originated here, which the local
+/// node doesn't re-host (see `Ring::is_replicating`), and counts as done once
+/// the operation reports success. Returns whether an attempt succeeded.
+#[cfg(feature = "lepus")]
+pub(crate) async fn replicate_before_eviction(
+    op_manager: &OpManager,
+    key: ContractKey,
+    attempts: usize,
+) -> bool {
+    let (contract, state) = match op_manager
+        .notify_contract_handler(ContractHandlerEvent::GetQuery {
+            instance_id: *key.id(),
+            return_contract_code: true,
+        })
+        .await
+    {
+        Ok(ContractHandlerEvent::GetResponse {
+            response:
+                Ok(crate::contract::StoreResponse {
+                    state: Some(state),
+                    contract: Some(contract),
+                }),
+            ..
+        }) => (contract, state),
+        _ => {
+            tracing::debug!(contract = %key, "Evicted contract not stored locally, nothing to replicate");
+            return false;
+        }
+    };
+
+    let mut own_addr = HashSet::new();
+    if let Some(addr) = op_manager.ring.connection_manager.get_own_addr() {
+        own_addr.insert(addr);
+    }
+    for attempt in 1..=attempts {
+        // Without a peer to forward to, the PUT would complete locally
+        if op_manager
+            .ring
+            .closest_potentially_caching(&key, &own_addr)
+            .is_none()
+        {
+            tracing::debug!(contract = %key, "No peer to replicate evicted contract to");
+            return false;
+        }
+        let op = start_op(
+            contract.clone(),
+            RelatedContracts::default(),
+            state.clone(),
+            op_manager.ring.max_hops_to_live,
+            false,
+            false,
+        );
+        let id = op.id;
+        let result = op_manager.wait_for_result(id);
+        if let Err(e) = request_put(op_manager, op).await {
+            tracing::debug!(contract = %key, attempt, error = %e, "Failed to start replication PUT");
+            op_manager.notify_result(id, || false);
+            continue;
+        }
+        match result.await {
+            Ok(true) => {
+                tracing::debug!(contract = %key, attempt, tx = %id, "Replicated evicted contract");
+                return true;
+            }
+            Ok(false) => {
+                tracing::debug!(contract = %key, attempt, tx = %id, "Replication PUT failed");
+            }
+            Err(_) => {
+                tracing::debug!(contract = %key, attempt, tx = %id, "Replication PUT timed out");
+            }
+        }
+    }
+    false
+}
+
 /// Stores the contract state and returns (new_state, state_changed).
 /// `state_changed` is true if the stored state was actually modified
 /// (old state != new state), which is needed to trigger UPDATE propagation.
@@ -1430,6 +1526,11 @@ async fn put_contract(
             ..
         }) => {
             tracing::error!(contract = %key, error = %err, phase = "error", "Failed to update contract value");
+            // Lepus: a rejected state counts against the contract's persistence score
+            #[cfg(feature = "lepus")]
+            if err.is_request() {
+                op_manager.ring.record_validation_failure(&key);
+            }
             Err(OpError::from(err))
             // TODO: not a valid value update, notify back to requester
         }
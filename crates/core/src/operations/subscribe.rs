@@ -31,15 +31,16 @@ const MAX_GHOST_SUBSCRIPTIONS: usize = 50;
 
 /// Stellar identity payload carried in subscription handshake (FREENET_LEPUS.md §5).
 ///
-/// Proves the subscriber owns the Stellar Ed25519 key that matches
-/// the datapod's `recipient_public_key`.
+/// Sent in [`SubscribeMsg::IdentityProof`] to answer the node-issued nonce from
+/// [`SubscribeMsg::IdentityChallenge`], proving the subscriber owns the Stellar
+/// Ed25519 key that matches the datapod's `recipient_public_key`.
 #[cfg(feature = "lepus")]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct StellarIdentityPayload {
     /// Subscriber's Ed25519 public key (32 bytes).
     pub stellar_pubkey: [u8; 32],
-    /// Ed25519 signature over the contract instance_id bytes.
-    /// Proves ownership of the key at subscription time.
+    /// Ed25519 signature over `nonce || instance_id` (see [`identity_challenge_message`]).
+    /// A fresh nonce per handshake keeps the proof from being replayed.
     #[serde(with = "serde_big_array")]
     pub signature: [u8; 64],
 }
@@ -65,29 +66,70 @@ mod serde_big_array {
     }
 }
 
-/// Build a Stellar identity payload for subscription handshake.
+/// The Stellar public key this node declares in outgoing subscribe requests.
 ///
-/// Reads `LEPUS_STELLAR_SECRET` env var and signs the `instance_id` to prove key ownership.
+/// Only declared when a node signer is configured, so the node can always
+/// answer the identity challenge that follows.
 #[cfg(feature = "lepus")]
-fn build_stellar_identity(instance_id: &ContractInstanceId) -> Option<StellarIdentityPayload> {
-    use ed25519_dalek::{Signer, SigningKey};
+fn declared_stellar_pubkey() -> Option<[u8; 32]> {
+    crate::ring::node_signer().map(|signer| signer.pubkey())
+}
 
-    let secret_hex = std::env::var("LEPUS_STELLAR_SECRET").ok()?;
-    let secret_bytes = hex::decode(secret_hex.trim()).ok()?;
-    if secret_bytes.len() != 32 {
-        return None;
-    }
-    let mut arr = [0u8; 32];
-    arr.copy_from_slice(&secret_bytes);
-    let signing_key = SigningKey::from_bytes(&arr);
-    let pubkey = signing_key.verifying_key().to_bytes();
-    let sig = signing_key.sign(instance_id.as_bytes());
+/// Message signed by the subscriber to answer an identity challenge.
+#[cfg(feature = "lepus")]
+fn identity_challenge_message(nonce: &[u8; 32], instance_id: &ContractInstanceId) -> Vec<u8> {
+    let mut message = Vec::with_capacity(32 + instance_id.as_bytes().len());
+    message.extend_from_slice(nonce);
+    message.extend_from_slice(instance_id.as_bytes());
+    message
+}
+
+/// Build a Stellar identity payload answering the node-issued `nonce`.
+///
+/// Signing runs on a blocking thread, since an external signer may wait on
+/// a device. Returns `None` if no node signer is configured or signing fails.
+#[cfg(feature = "lepus")]
+async fn build_stellar_identity(
+    nonce: &[u8; 32],
+    instance_id: &ContractInstanceId,
+) -> Option<StellarIdentityPayload> {
+    let signer = crate::ring::node_signer()?;
+    let message = identity_challenge_message(nonce, instance_id);
+    let signature = match crate::ring::sign_blocking(signer, message).await {
+        Ok(signature) => signature,
+        Err(e) => {
+            tracing::warn!(%instance_id, error = %e, "Failed to sign identity challenge");
+            return None;
+        }
+    };
     Some(StellarIdentityPayload {
-        stellar_pubkey: pubkey,
-        signature: sig.to_bytes(),
+        stellar_pubkey: signer.pubkey(),
+        signature,
     })
 }
 
+/// Check an identity proof against the key the subscriber declared and the
+/// nonce this node issued for it.
+#[cfg(feature = "lepus")]
+fn verify_stellar_identity(
+    identity: &StellarIdentityPayload,
+    declared_pubkey: &[u8; 32],
+    nonce: &[u8; 32],
+    instance_id: &ContractInstanceId,
+) -> bool {
+    use ed25519_dalek::Verifier;
+
+    if identity.stellar_pubkey != *declared_pubkey {
+        return false;
+    }
+    let Ok(vk) = ed25519_dalek::VerifyingKey::from_bytes(&identity.stellar_pubkey) else {
+        return false;
+    };
+    let sig = ed25519_dalek::Signature::from_bytes(&identity.signature);
+    vk.verify(&identity_challenge_message(nonce, instance_id), &sig)
+        .is_ok()
+}
+
 /// Wait for a contract to become available, using channel-based notification.
 ///
 /// This handles the race condition where a subscription arrives before the contract
@@ -158,6 +200,16 @@ enum SubscribeState {
         /// The contract being subscribed to (needed for error notification on abort)
         instance_id: ContractInstanceId,
     },
+    /// Holding the contract and waiting for the requester to answer an identity
+    /// challenge before confirming the subscription (Lepus §5).
+    #[cfg(feature = "lepus")]
+    AwaitingIdentityProof {
+        key: ContractKey,
+        /// Stellar key the requester declared in its `Request`.
+        declared_pubkey: [u8; 32],
+        /// Nonce issued in the `IdentityChallenge`.
+        nonce: [u8; 32],
+    },
     /// Subscription completed.
     Completed { key: ContractKey },
 }
@@ -358,7 +410,7 @@ pub(crate) async fn request_subscribe(
         visited,
         is_renewal,
         #[cfg(feature = "lepus")]
-        stellar_identity: build_stellar_identity(instance_id),
+        stellar_pubkey: declared_stellar_pubkey(),
     };
 
     // Emit telemetry for subscribe request initiation
@@ -453,6 +505,8 @@ impl SubscribeOp {
         match &self.state {
             Some(SubscribeState::PrepareRequest { instance_id, .. }) => Some(*instance_id),
             Some(SubscribeState::AwaitingResponse { instance_id, .. }) => Some(*instance_id),
+            #[cfg(feature = "lepus")]
+            Some(SubscribeState::AwaitingIdentityProof { key, .. }) => Some(*key.id()),
             _ => None,
         }
     }
@@ -495,6 +549,71 @@ impl SubscribeOp {
         }
     }
 
+    /// Resolve the requesting peer for interest registration.
+    ///
+    /// Uses `requester_pub_key` (resolved at init time) when available, falling
+    /// back to addr lookup. The pub_key path avoids failures during NAT traversal
+    /// timing windows. (#2886)
+    fn requester_peer_key(
+        &self,
+        op_manager: &OpManager,
+        requester_addr: std::net::SocketAddr,
+        source_addr: Option<std::net::SocketAddr>,
+    ) -> Option<crate::ring::interest::PeerKey> {
+        self.requester_pub_key
+            .as_ref()
+            .map(|pk| crate::ring::interest::PeerKey::from(pk.clone()))
+            .or_else(|| {
+                op_manager
+                    .ring
+                    .connection_manager
+                    .get_peer_by_addr(requester_addr)
+                    .or_else(|| {
+                        source_addr
+                            .and_then(|sa| op_manager.ring.connection_manager.get_peer_by_addr(sa))
+                    })
+                    .map(|pkl| crate::ring::interest::PeerKey::from(pkl.pub_key.clone()))
+            })
+    }
+
+    /// Challenge a requester that declared a Stellar key to sign a fresh nonce.
+    ///
+    /// The subscription is only confirmed once the matching `IdentityProof`
+    /// comes back, so `subscriber_verified` is never set from a bare claim.
+    #[cfg(feature = "lepus")]
+    fn issue_identity_challenge(
+        self,
+        key: ContractKey,
+        declared_pubkey: [u8; 32],
+        requester_addr: std::net::SocketAddr,
+    ) -> OperationResult {
+        let mut nonce = [0u8; 32];
+        crate::config::GlobalRng::fill_bytes(&mut nonce);
+        tracing::debug!(
+            tx = %self.id,
+            contract = %key,
+            pubkey = hex::encode(declared_pubkey),
+            "Issuing Stellar identity challenge to subscriber"
+        );
+        OperationResult {
+            return_msg: Some(NetMessage::from(SubscribeMsg::IdentityChallenge {
+                id: self.id,
+                instance_id: *key.id(),
+                nonce,
+            })),
+            next_hop: Some(requester_addr),
+            state: Some(OpEnum::Subscribe(SubscribeOp {
+                state: Some(SubscribeState::AwaitingIdentityProof {
+                    key,
+                    declared_pubkey,
+                    nonce,
+                }),
+                ..self
+            })),
+            stream_data: None,
+        }
+    }
+
     /// Handle aborted connections by failing the operation immediately.
     ///
     /// Unlike Get operations, Subscribe doesn't have alternative routes to try.
@@ -573,10 +692,13 @@ impl Operation for SubscribeOp {
         source_addr: Option<std::net::SocketAddr>,
     ) -> Result<OpInitialization<Self>, OpError> {
         let id = *msg.id();
-        let msg_type = if matches!(msg, SubscribeMsg::Request { .. }) {
-            "Request"
-        } else {
-            "Response"
+        let msg_type = match msg {
+            SubscribeMsg::Request { .. } => "Request",
+            SubscribeMsg::Response { .. } => "Response",
+            #[cfg(feature = "lepus")]
+            SubscribeMsg::IdentityChallenge { .. } => "IdentityChallenge",
+            #[cfg(feature = "lepus")]
+            SubscribeMsg::IdentityProof { .. } => "IdentityProof",
         };
         tracing::debug!(
             tx = %id,
@@ -603,9 +725,10 @@ impl Operation for SubscribeOp {
                 Err(OpError::OpNotPresent(id))
             }
             Ok(None) => {
-                // Check if this is a response message - if so, the operation was likely
-                // cleaned up due to timeout and we should not create a new operation
-                if matches!(msg, SubscribeMsg::Response { .. }) {
+                // Anything but a request belongs to an existing operation - if it's
+                // missing, it was likely cleaned up due to timeout and we should not
+                // create a new operation
+                if !matches!(msg, SubscribeMsg::Request { .. }) {
                     tracing::debug!(
                         tx = %id,
                         phase = "load_or_init",
@@ -622,7 +745,7 @@ impl Operation for SubscribeOp {
                         instance_id,
                         ..
                     } => (*is_renewal, *instance_id),
-                    _ => unreachable!("Response case handled above"),
+                    _ => unreachable!("non-Request cases handled above"),
                 };
                 // Resolve requester's public key at init time, when the connection
                 // is freshest. This avoids addr->pubkey lookup failures during NAT
@@ -674,7 +797,7 @@ impl Operation for SubscribeOp {
                     visited,
                     is_renewal,
                     #[cfg(feature = "lepus")]
-                    stellar_identity,
+                    stellar_pubkey,
                 } => {
                     tracing::debug!(
                         tx = %id,
@@ -687,87 +810,26 @@ impl Operation for SubscribeOp {
 
                     // Check if we have the contract
                     if let Some(key) = super::has_contract(op_manager, *instance_id).await? {
-                        // Lepus: verify subscriber identity + enforce ghost subscription cap
-                        #[cfg(feature = "lepus")]
-                        if let Some(ref identity) = stellar_identity {
-                            // Verify signature over instance_id (proves key ownership)
-                            use ed25519_dalek::Verifier;
-                            if let Ok(vk) =
-                                ed25519_dalek::VerifyingKey::from_bytes(&identity.stellar_pubkey)
-                            {
-                                let sig = ed25519_dalek::Signature::from_bytes(&identity.signature);
-                                let msg = instance_id.as_bytes();
-                                if vk.verify(msg, &sig).is_ok() {
-                                    // Ghost subscription cap (FREENET_LEPUS.md D7)
-                                    let active_count = op_manager
-                                        .ring
-                                        .count_subscriptions_for_identity(&identity.stellar_pubkey);
-                                    let is_funded = op_manager
-                                        .ring
-                                        .is_identity_funded(&identity.stellar_pubkey);
-                                    if !is_funded && active_count >= MAX_GHOST_SUBSCRIPTIONS {
-                                        tracing::warn!(
-                                            pubkey = hex::encode(identity.stellar_pubkey),
-                                            active = active_count,
-                                            "Ghost subscription cap reached — unfunded identity \
-                                             exceeded {MAX_GHOST_SUBSCRIPTIONS} subscriptions"
-                                        );
-                                        return Ok(OperationResult {
-                                            return_msg: Some(NetMessage::from(
-                                                SubscribeMsg::Response {
-                                                    id: *id,
-                                                    instance_id: *instance_id,
-                                                    result: SubscribeMsgResult::NotFound,
-                                                },
-                                            )),
-                                            next_hop: self.requester_addr,
-                                            state: None,
-                                            stream_data: None,
-                                        });
-                                    }
-
-                                    // Valid subscriber identity — update CWP subscriber verification
-                                    op_manager
-                                        .ring
-                                        .update_subscriber_identity(&key, &identity.stellar_pubkey);
-                                }
-                            }
-                        }
-
                         // We have the contract - respond to confirm subscription
                         // State is NOT sent here - requester gets state via GET, not SUBSCRIBE
                         // In the lease-based model (2026-01), we just confirm we have the contract.
                         // Updates propagate via proximity cache, not explicit tree.
                         if let Some(requester_addr) = self.requester_addr {
+                            // Lepus: a declared Stellar key must answer a fresh nonce
+                            // before the subscription is confirmed.
+                            #[cfg(feature = "lepus")]
+                            if let Some(declared_pubkey) = stellar_pubkey {
+                                return Ok(self.issue_identity_challenge(
+                                    key,
+                                    *declared_pubkey,
+                                    requester_addr,
+                                ));
+                            }
                             // Register the subscribing peer in the interest manager so that
                             // update broadcasts include them as a target immediately.
-                            // Use requester_pub_key (resolved at init time) when available,
-                            // falling back to addr lookup. The pub_key path avoids failures
-                            // during NAT traversal timing windows. (#2886)
-                            let peer_key = self
-                                .requester_pub_key
-                                .as_ref()
-                                .map(|pk| crate::ring::interest::PeerKey::from(pk.clone()))
-                                .or_else(|| {
-                                    op_manager
-                                        .ring
-                                        .connection_manager
-                                        .get_peer_by_addr(requester_addr)
-                                        .or_else(|| {
-                                            source_addr.and_then(|sa| {
-                                                op_manager
-                                                    .ring
-                                                    .connection_manager
-                                                    .get_peer_by_addr(sa)
-                                            })
-                                        })
-                                        .map(|pkl| {
-                                            crate::ring::interest::PeerKey::from(
-                                                pkl.pub_key.clone(),
-                                            )
-                                        })
-                                });
-                            if let Some(peer_key) = peer_key {
+                            if let Some(peer_key) =
+                                self.requester_peer_key(op_manager, requester_addr, source_addr)
+                            {
                                 op_manager
                                     .interest_manager
                                     .register_peer_interest(&key, peer_key, None, false);
@@ -820,33 +882,18 @@ impl Operation for SubscribeOp {
                         // Contract arrived - respond to confirm subscription
                         // State is NOT sent here - requester gets state via GET, not SUBSCRIBE
                         if let Some(requester_addr) = self.requester_addr {
+                            #[cfg(feature = "lepus")]
+                            if let Some(declared_pubkey) = stellar_pubkey {
+                                return Ok(self.issue_identity_challenge(
+                                    key,
+                                    *declared_pubkey,
+                                    requester_addr,
+                                ));
+                            }
                             // Register the subscribing peer in the interest manager.
-                            // Use requester_pub_key (resolved at init time) when available,
-                            // falling back to addr lookup. (#2886)
-                            let peer_key = self
-                                .requester_pub_key
-                                .as_ref()
-                                .map(|pk| crate::ring::interest::PeerKey::from(pk.clone()))
-                                .or_else(|| {
-                                    op_manager
-                                        .ring
-                                        .connection_manager
-                                        .get_peer_by_addr(requester_addr)
-                                        .or_else(|| {
-                                            source_addr.and_then(|sa| {
-                                                op_manager
-                                                    .ring
-                                                    .connection_manager
-                                                    .get_peer_by_addr(sa)
-                                            })
-                                        })
-                                        .map(|pkl| {
-                                            crate::ring::interest::PeerKey::from(
-                                                pkl.pub_key.clone(),
-                                            )
-                                        })
-                                });
-                            if let Some(peer_key) = peer_key {
+                            if let Some(peer_key) =
+                                self.requester_peer_key(op_manager, requester_addr, source_addr)
+                            {
                                 op_manager
                                     .interest_manager
                                     .register_peer_interest(&key, peer_key, None, false);
@@ -859,23 +906,6 @@ impl Operation for SubscribeOp {
                                     "Subscribe: could not find peer to register interest (after contract wait)"
                                 );
                             }
-                            // Lepus: verify subscriber identity (after contract wait path)
-                            #[cfg(feature = "lepus")]
-                            if let Some(ref identity) = stellar_identity {
-                                use ed25519_dalek::Verifier;
-                                if let Ok(vk) = ed25519_dalek::VerifyingKey::from_bytes(
-                                    &identity.stellar_pubkey,
-                                ) {
-                                    let sig =
-                                        ed25519_dalek::Signature::from_bytes(&identity.signature);
-                                    if vk.verify(instance_id.as_bytes(), &sig).is_ok() {
-                                        op_manager.ring.update_subscriber_identity(
-                                            &key,
-                                            &identity.stellar_pubkey,
-                                        );
-                                    }
-                                }
-                            }
                             return Ok(OperationResult {
                                 return_msg: Some(NetMessage::from(SubscribeMsg::Response {
                                     id: *id,
@@ -952,7 +982,7 @@ impl Operation for SubscribeOp {
                             visited: new_visited,
                             is_renewal: *is_renewal,
                             #[cfg(feature = "lepus")]
-                            stellar_identity: stellar_identity.clone(),
+                            stellar_pubkey: *stellar_pubkey,
                         })),
                         next_hop: Some(next_addr),
                         state: Some(OpEnum::Subscribe(SubscribeOp {
@@ -1245,6 +1275,164 @@ impl Operation for SubscribeOp {
                         }
                     }
                 }
+
+                #[cfg(feature = "lepus")]
+                SubscribeMsg::IdentityChallenge {
+                    id: msg_id,
+                    instance_id,
+                    nonce,
+                } => {
+                    let Some(SubscribeState::AwaitingResponse { next_hop, .. }) = self.state else {
+                        return Err(OpError::UnexpectedOpState);
+                    };
+                    // Proofs travel back the way the challenge came.
+                    let upstream = source_addr.or(next_hop);
+                    let requester_addr = self.requester_addr;
+                    let state = Some(OpEnum::Subscribe(SubscribeOp {
+                        state: Some(SubscribeState::AwaitingResponse {
+                            next_hop: upstream,
+                            instance_id: *instance_id,
+                        }),
+                        ..self
+                    }));
+
+                    if let Some(requester_addr) = requester_addr {
+                        tracing::debug!(tx = %msg_id, %instance_id, requester = %requester_addr, "Forwarding identity challenge to requester");
+                        return Ok(OperationResult {
+                            return_msg: Some(NetMessage::from(SubscribeMsg::IdentityChallenge {
+                                id: *msg_id,
+                                instance_id: *instance_id,
+                                nonce: *nonce,
+                            })),
+                            next_hop: Some(requester_addr),
+                            state,
+                            stream_data: None,
+                        });
+                    }
+
+                    // We're the originator - answer with our Stellar key
+                    let identity = build_stellar_identity(nonce, instance_id).await;
+                    if identity.is_none() {
+                        tracing::warn!(
+                            tx = %msg_id,
+                            %instance_id,
+                            "Identity challenge received but the node signer is not usable; \
+                             subscribing without verification"
+                        );
+                    }
+                    Ok(OperationResult {
+                        return_msg: Some(NetMessage::from(SubscribeMsg::IdentityProof {
+                            id: *msg_id,
+                            instance_id: *instance_id,
+                            identity,
+                        })),
+                        next_hop: upstream,
+                        state,
+                        stream_data: None,
+                    })
+                }
+
+                #[cfg(feature = "lepus")]
+                SubscribeMsg::IdentityProof {
+                    id: msg_id,
+                    instance_id,
+                    identity,
+                } => match self.state {
+                    Some(SubscribeState::AwaitingResponse {
+                        next_hop: Some(upstream),
+                        ..
+                    }) if self.requester_addr.is_some() => {
+                        tracing::debug!(tx = %msg_id, %instance_id, upstream = %upstream, "Forwarding identity proof to challenger");
+                        Ok(OperationResult {
+                            return_msg: Some(NetMessage::from(SubscribeMsg::IdentityProof {
+                                id: *msg_id,
+                                instance_id: *instance_id,
+                                identity: identity.clone(),
+                            })),
+                            next_hop: Some(upstream),
+                            state: Some(OpEnum::Subscribe(self)),
+                            stream_data: None,
+                        })
+                    }
+                    Some(SubscribeState::AwaitingIdentityProof {
+                        key,
+                        declared_pubkey,
+                        nonce,
+                    }) => {
+                        let Some(requester_addr) = self.requester_addr else {
+                            return Err(OpError::UnexpectedOpState);
+                        };
+
+                        let verified = identity.as_ref().is_some_and(|identity| {
+                            verify_stellar_identity(identity, &declared_pubkey, &nonce, key.id())
+                        });
+                        if verified {
+                            // Ghost subscription cap (FREENET_LEPUS.md D7)
+                            let active_count = op_manager
+                                .ring
+                                .count_subscriptions_for_identity(&declared_pubkey);
+                            let is_funded = op_manager.ring.is_identity_funded(&declared_pubkey);
+                            if !is_funded && active_count >= MAX_GHOST_SUBSCRIPTIONS {
+                                tracing::warn!(
+                                    pubkey = hex::encode(declared_pubkey),
+                                    active = active_count,
+                                    "Ghost subscription cap reached — unfunded identity \
+                                     exceeded {MAX_GHOST_SUBSCRIPTIONS} subscriptions"
+                                );
+                                return Ok(OperationResult {
+                                    return_msg: Some(NetMessage::from(SubscribeMsg::Response {
+                                        id: *msg_id,
+                                        instance_id: *instance_id,
+                                        result: SubscribeMsgResult::NotFound,
+                                    })),
+                                    next_hop: Some(requester_addr),
+                                    state: None,
+                                    stream_data: None,
+                                });
+                            }
+
+                            // Proven subscriber identity — update CWP subscriber verification
+                            op_manager
+                                .ring
+                                .update_subscriber_identity(&key, &declared_pubkey);
+                        } else {
+                            tracing::warn!(
+                                tx = %msg_id,
+                                contract = %key,
+                                pubkey = hex::encode(declared_pubkey),
+                                "Stellar identity proof missing or invalid; subscribing without verification"
+                            );
+                        }
+
+                        if let Some(peer_key) =
+                            self.requester_peer_key(op_manager, requester_addr, source_addr)
+                        {
+                            op_manager
+                                .interest_manager
+                                .register_peer_interest(&key, peer_key, None, false);
+                        } else {
+                            tracing::warn!(
+                                tx = %msg_id,
+                                contract = %key,
+                                requester_addr = %requester_addr,
+                                source_addr = ?source_addr,
+                                "Subscribe: could not find peer to register interest (after identity proof)"
+                            );
+                        }
+                        tracing::info!(tx = %msg_id, contract = %key, verified, phase = "response", "Subscription fulfilled after identity handshake, sending Response");
+                        Ok(OperationResult {
+                            return_msg: Some(NetMessage::from(SubscribeMsg::Response {
+                                id: *msg_id,
+                                instance_id: *instance_id,
+                                result: SubscribeMsgResult::Subscribed { key },
+                            })),
+                            next_hop: Some(requester_addr),
+                            state: None,
+                            stream_data: None,
+                        })
+                    }
+                    _ => Err(OpError::UnexpectedOpState),
+                },
             }
         })
     }
@@ -1294,9 +1482,10 @@ mod messages {
             /// Whether this is a renewal (requester already has contract state).
             /// If true, responder skips sending state to save bandwidth.
             is_renewal: bool,
-            /// Optional Stellar identity proving subscriber key ownership (Lepus §5).
+            /// Stellar key the subscriber claims (Lepus §5). Ownership is proven
+            /// through `IdentityChallenge`/`IdentityProof` before it is trusted.
             #[cfg(feature = "lepus")]
-            stellar_identity: Option<super::StellarIdentityPayload>,
+            stellar_pubkey: Option<[u8; 32]>,
         },
         /// Response for a SUBSCRIBE operation. Routed hop-by-hop back to originator.
         /// Uses instance_id for routing (always available from the request).
@@ -1306,12 +1495,30 @@ mod messages {
             instance_id: ContractInstanceId,
             result: SubscribeMsgResult,
         },
+        /// Nonce issued by the node holding the contract for a requester that
+        /// declared a Stellar key. Routed hop-by-hop back to the originator.
+        #[cfg(feature = "lepus")]
+        IdentityChallenge {
+            id: Transaction,
+            instance_id: ContractInstanceId,
+            nonce: [u8; 32],
+        },
+        /// Originator's answer to an `IdentityChallenge`, routed hop-by-hop back
+        /// to the challenging node. `None` when the originator cannot sign.
+        #[cfg(feature = "lepus")]
+        IdentityProof {
+            id: Transaction,
+            instance_id: ContractInstanceId,
+            identity: Option<super::StellarIdentityPayload>,
+        },
     }
 
     impl InnerMessage for SubscribeMsg {
         fn id(&self) -> &Transaction {
             match self {
                 Self::Request { id, .. } | Self::Response { id, .. } => id,
+                #[cfg(feature = "lepus")]
+                Self::IdentityChallenge { id, .. } | Self::IdentityProof { id, .. } => id,
             }
         }
 
@@ -1320,6 +1527,9 @@ mod messages {
                 Self::Request { instance_id, .. } | Self::Response { instance_id, .. } => {
                     Some(Location::from(instance_id))
                 }
+                #[cfg(feature = "lepus")]
+                Self::IdentityChallenge { instance_id, .. }
+                | Self::IdentityProof { instance_id, .. } => Some(Location::from(instance_id)),
             }
         }
     }
@@ -1345,6 +1555,25 @@ mod messages {
                         "Subscribe::Response(id: {id}, instance_id: {instance_id}, result: {result_str})"
                     )
                 }
+                #[cfg(feature = "lepus")]
+                Self::IdentityChallenge { instance_id, .. } => {
+                    write!(
+                        f,
+                        "Subscribe::IdentityChallenge(id: {id}, instance_id: {instance_id})"
+                    )
+                }
+                #[cfg(feature = "lepus")]
+                Self::IdentityProof {
+                    instance_id,
+                    identity,
+                    ..
+                } => {
+                    write!(
+                        f,
+                        "Subscribe::IdentityProof(id: {id}, instance_id: {instance_id}, signed: {})",
+                        identity.is_some()
+                    )
+                }
             }
         }
     }
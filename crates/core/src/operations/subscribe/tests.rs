@@ -759,3 +759,68 @@ fn test_op_enum_is_subscription_renewal() {
     });
     assert!(!non_renewal.is_subscription_renewal());
 }
+
+#[cfg(feature = "lepus")]
+#[test]
+fn test_identity_proof_bound_to_issued_nonce() {
+    use ed25519_dalek::{Signer, SigningKey};
+
+    let instance_id = ContractInstanceId::new([24u8; 32]);
+    let sk = SigningKey::from_bytes(&[42u8; 32]);
+    let pubkey = sk.verifying_key().to_bytes();
+    let nonce = [7u8; 32];
+    let proof = StellarIdentityPayload {
+        stellar_pubkey: pubkey,
+        signature: sk
+            .sign(&identity_challenge_message(&nonce, &instance_id))
+            .to_bytes(),
+    };
+
+    assert!(verify_stellar_identity(
+        &proof,
+        &pubkey,
+        &nonce,
+        &instance_id
+    ));
+    // A proof for one nonce cannot be replayed against another challenge
+    assert!(!verify_stellar_identity(
+        &proof,
+        &pubkey,
+        &[8u8; 32],
+        &instance_id
+    ));
+    // Nor against a different contract
+    assert!(!verify_stellar_identity(
+        &proof,
+        &pubkey,
+        &nonce,
+        &ContractInstanceId::new([25u8; 32])
+    ));
+    // The proving key must be the one declared in the request
+    assert!(!verify_stellar_identity(
+        &proof,
+        &[9u8; 32],
+        &nonce,
+        &instance_id
+    ));
+}
+
+#[cfg(feature = "lepus")]
+#[test]
+fn test_awaiting_identity_proof_reports_instance_id() {
+    let instance_id = ContractInstanceId::new([26u8; 32]);
+    let contract_key = ContractKey::from_id_and_code(instance_id, CodeHash::new([27u8; 32]));
+    let op = SubscribeOp {
+        id: Transaction::new::<SubscribeMsg>(),
+        state: Some(SubscribeState::AwaitingIdentityProof {
+            key: contract_key,
+            declared_pubkey: [1u8; 32],
+            nonce: [2u8; 32],
+        }),
+        requester_addr: Some("127.0.0.1:9000".parse().unwrap()),
+        requester_pub_key: None,
+        is_renewal: false,
+    };
+    assert_eq!(op.instance_id(), Some(instance_id));
+    assert!(!op.finalized());
+}
@@ -249,6 +249,12 @@ impl Operation for UpdateOp {
                             // Compute before hash for telemetry
                             let hash_before = state_before.as_ref().map(state_hash_full);
 
+                            // CWP: record bytes consumed from the update request
+                            #[cfg(feature = "lepus")]
+                            op_manager
+                                .ring
+                                .record_bytes_consumed(key, value.size() as u64);
+
                             // Update contract locally
                             // Note: RequestUpdate sender should NOT be excluded from broadcast.
                             // Unlike BroadcastTo (where sender has the state), RequestUpdate sender
@@ -720,6 +726,12 @@ impl Operation for UpdateOp {
                         "UPDATE RequestUpdateStreaming: applying update"
                     );
 
+                    // CWP: record bytes consumed from the streamed update request
+                    #[cfg(feature = "lepus")]
+                    op_manager
+                        .ring
+                        .record_bytes_consumed(key, value.size() as u64);
+
                     // Update contract locally
                     let UpdateExecution {
                         value: updated_value,
@@ -1232,6 +1244,11 @@ async fn update_contract(
             ..
         }) => {
             tracing::error!(contract = %key, error = %err, phase = "error", "Failed to update contract value");
+            // Lepus: a rejected state counts against the contract's persistence score
+            #[cfg(feature = "lepus")]
+            if err.is_request() {
+                op_manager.ring.record_validation_failure(&key);
+            }
             Err(err.into())
         }
         Ok(ContractHandlerEvent::UpdateNoChange { .. }) => {
@@ -0,0 +1,24 @@
+//! Process-wide handle to the local node's ring for admin diagnostics.
+//!
+//! The HTTP gateway is served before the node is built, so its admin
+//! endpoints can't be handed the ring directly. `Ring::new` registers the
+//! ring here instead, and handlers look it up per request. When several
+//! nodes run in one process (simulations), the most recently created wins.
+
+use std::sync::{Arc, RwLock, Weak};
+
+use super::Ring;
+
+static LOCAL_RING: RwLock<Option<Weak<Ring>>> = RwLock::new(None);
+
+/// Make `ring` the one served by admin diagnostics.
+pub(crate) fn register(ring: &Arc<Ring>) {
+    if let Ok(mut local) = LOCAL_RING.write() {
+        *local = Some(Arc::downgrade(ring));
+    }
+}
+
+/// The registered ring, if it is still alive.
+pub(crate) fn local_ring() -> Option<Arc<Ring>> {
+    LOCAL_RING.read().ok()?.as_ref()?.upgrade()
+}
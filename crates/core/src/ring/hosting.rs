@@ -29,19 +29,58 @@
 
 mod cache;
 #[cfg(feature = "lepus")]
+mod close_meta;
+#[cfg(all(feature = "lepus", feature = "redb"))]
+pub(crate) mod cold_tier;
+#[cfg(feature = "lepus")]
 pub(crate) mod deposit_index;
 #[cfg(feature = "lepus")]
+pub(crate) mod eviction_log;
+#[cfg(feature = "lepus")]
+mod history_archive;
+#[cfg(feature = "lepus")]
 pub(crate) mod identity;
 #[cfg(feature = "lepus")]
+mod known;
+#[cfg(feature = "lepus")]
+mod ledger_stream;
+pub(crate) mod metrics;
+#[cfg(feature = "lepus")]
 pub(crate) mod oracle;
+#[cfg(all(feature = "lepus", any(test, feature = "testing")))]
+#[cfg_attr(not(feature = "testing"), allow(dead_code))]
+pub(crate) mod proof_fixtures;
+#[cfg(feature = "lepus")]
+mod relay_alerts;
+#[cfg(feature = "lepus")]
+pub(crate) mod relayer_metrics;
+#[cfg(feature = "lepus")]
+pub(crate) mod revocation;
+mod sharded;
+#[cfg(feature = "lepus")]
+pub(crate) mod signer;
+#[cfg(all(feature = "lepus", any(test, feature = "testing")))]
+#[cfg_attr(not(feature = "testing"), allow(dead_code))]
+pub(crate) mod simulator;
+#[cfg(feature = "lepus")]
+pub(crate) mod validator_set;
 
 use crate::util::backoff::{ExponentialBackoff, TrackedBackoff};
 use crate::util::time_source::InstantTimeSrc;
-pub use cache::{AccessType, RecordAccessResult};
-use cache::{HostingCache, DEFAULT_HOSTING_BUDGET_BYTES, DEFAULT_MIN_TTL};
+pub use cache::{AccessType, EvictionCandidate, RecordAccessResult};
+#[cfg(feature = "lepus")]
+pub use cache::{CWPConfig, HostedContractPage, HostedContractSort, ScoreReport};
+use cache::{EvictionNotice, HostingCache};
+pub(crate) use cache::{DEFAULT_HOSTING_BUDGET_BYTES, DEFAULT_MIN_TTL};
 use dashmap::{DashMap, DashSet};
+#[cfg(feature = "lepus")]
+pub use eviction_log::{EvictionLogQuery, EvictionRecord};
 use freenet_stdlib::prelude::{ContractInstanceId, ContractKey};
-use parking_lot::RwLock;
+use metrics::HOSTING_METRICS;
+#[cfg(feature = "lepus")]
+pub use oracle::OracleStatus;
+use parking_lot::{Mutex, RwLock};
+use sharded::ShardedHostingCache;
 use std::collections::HashSet;
 use std::time::Duration;
 use tokio::time::Instant;
@@ -132,9 +171,10 @@ pub(crate) struct HostingManager {
     /// Prevents hosting cache eviction while client subscriptions exist.
     client_subscriptions: DashMap<ContractInstanceId, HashSet<crate::client_events::ClientId>>,
 
-    /// Unified hosting cache with byte-budget LRU and TTL protection.
+    /// Unified hosting cache with byte-budget LRU and TTL protection,
+    /// sharded by contract key.
     /// This is the single source of truth for which contracts we're hosting.
-    hosting_cache: RwLock<HostingCache<InstantTimeSrc>>,
+    hosting_cache: ShardedHostingCache<InstantTimeSrc>,
 
     /// Contracts with subscription requests currently in-flight.
     pending_subscription_requests: DashSet<ContractKey>,
@@ -142,6 +182,47 @@ pub(crate) struct HostingManager {
     /// Exponential backoff state for subscription retries.
     subscription_backoff: RwLock<TrackedBackoff<ContractKey>>,
 
+    /// Evictions of contracts with client subscriptions, waiting for `Ring`
+    /// to notify those clients.
+    client_evictions: Mutex<Vec<EvictionNotice>>,
+
+    /// Evictions of contracts with a deposit, waiting for `Ring` to report
+    /// them through the event register.
+    #[cfg(feature = "lepus")]
+    committed_evictions: Mutex<Vec<EvictionNotice>>,
+
+    /// Contracts evicted with at least `CWPConfig::replication_hint_score`,
+    /// waiting for `Ring` to push them to the closest peers.
+    #[cfg(feature = "lepus")]
+    replication_hints: Mutex<Vec<ContractKey>>,
+
+    /// Evicted contracts whose stored state is kept until their push to the
+    /// closest peers finishes (see `finish_replication`).
+    #[cfg(feature = "lepus")]
+    replicating: DashSet<ContractKey>,
+
+    /// Every contract's entry in the latest deposit-index state, cached by
+    /// the subscriber hook (see `set_deposit_snapshot`).
+    #[cfg(feature = "lepus")]
+    deposit_snapshot: RwLock<deposit_index::DepositSnapshot>,
+
+    /// Identities and commitments of contracts that aren't hosted, and the
+    /// contracts each identity pubkey has been seen creating or subscribing
+    /// to, hosted here or not. Bounded by
+    /// `CWPConfig::known_contracts_capacity`.
+    #[cfg(feature = "lepus")]
+    known: Mutex<known::KnownContracts>,
+
+    /// Compressed archive that evicted contracts spill into, if enabled.
+    /// Set at startup via `set_cold_tier()`.
+    #[cfg(all(feature = "lepus", feature = "redb"))]
+    cold_tier: Mutex<Option<cold_tier::ColdTier>>,
+
+    /// On-disk audit log of evictions, if enabled.
+    /// Set at startup via `set_eviction_log()`.
+    #[cfg(feature = "lepus")]
+    eviction_log: Mutex<Option<eviction_log::EvictionLog>>,
+
     /// Storage reference for persisting/removing hosting metadata.
     /// Set after executor creation via `set_storage()`.
     #[cfg(feature = "redb")]
@@ -152,21 +233,79 @@ pub(crate) struct HostingManager {
 
 impl HostingManager {
     pub fn new() -> Self {
+        Self::with_limits(DEFAULT_HOSTING_BUDGET_BYTES, DEFAULT_MIN_TTL)
+    }
+
+    /// Create a hosting manager with a `budget_bytes` cache protecting
+    /// contracts for `min_ttl`.
+    pub fn with_limits(budget_bytes: u64, min_ttl: Duration) -> Self {
+        Self::with_cache(HostingCache::new(
+            budget_bytes,
+            min_ttl,
+            InstantTimeSrc::new(),
+        ))
+    }
+
+    /// Create a hosting manager with a `budget_bytes` cache split into
+    /// `shard_count` shards, each scoring evictions with `cwp_config` and
+    /// protecting contracts for `min_ttl`.
+    #[cfg(feature = "lepus")]
+    pub fn with_cwp_config(
+        cwp_config: CWPConfig,
+        budget_bytes: u64,
+        min_ttl: Duration,
+        shard_count: usize,
+    ) -> Self {
+        Self::with_sharded_cache(ShardedHostingCache::new(
+            shard_count,
+            budget_bytes,
+            |budget_bytes| {
+                HostingCache::new_with_cwp(
+                    budget_bytes,
+                    min_ttl,
+                    InstantTimeSrc::new(),
+                    cwp_config.clone(),
+                )
+            },
+        ))
+    }
+
+    fn with_cache(hosting_cache: HostingCache<InstantTimeSrc>) -> Self {
+        Self::with_sharded_cache(ShardedHostingCache::single(hosting_cache))
+    }
+
+    fn with_sharded_cache(hosting_cache: ShardedHostingCache<InstantTimeSrc>) -> Self {
         let backoff_config =
             ExponentialBackoff::new(INITIAL_SUBSCRIPTION_BACKOFF, MAX_SUBSCRIPTION_BACKOFF);
+        #[cfg(feature = "lepus")]
+        let known_capacity = hosting_cache.shards()[0]
+            .read()
+            .cwp_config()
+            .known_contracts_capacity;
         Self {
             active_subscriptions: DashMap::new(),
             client_subscriptions: DashMap::new(),
-            hosting_cache: RwLock::new(HostingCache::new(
-                DEFAULT_HOSTING_BUDGET_BYTES,
-                DEFAULT_MIN_TTL,
-                InstantTimeSrc::new(),
-            )),
+            hosting_cache,
             pending_subscription_requests: DashSet::new(),
             subscription_backoff: RwLock::new(TrackedBackoff::new(
                 backoff_config,
                 MAX_SUBSCRIPTION_BACKOFF_ENTRIES,
             )),
+            client_evictions: Mutex::new(Vec::new()),
+            #[cfg(feature = "lepus")]
+            committed_evictions: Mutex::new(Vec::new()),
+            #[cfg(feature = "lepus")]
+            replication_hints: Mutex::new(Vec::new()),
+            #[cfg(feature = "lepus")]
+            replicating: DashSet::new(),
+            #[cfg(feature = "lepus")]
+            deposit_snapshot: RwLock::new(deposit_index::DepositSnapshot::default()),
+            #[cfg(feature = "lepus")]
+            known: Mutex::new(known::KnownContracts::new(known_capacity)),
+            #[cfg(all(feature = "lepus", feature = "redb"))]
+            cold_tier: Mutex::new(None),
+            #[cfg(feature = "lepus")]
+            eviction_log: Mutex::new(None),
             storage: RwLock::new(None),
         }
     }
@@ -177,6 +316,18 @@ impl HostingManager {
         *self.storage.write() = Some(storage);
     }
 
+    /// Spill evicted contracts into `tier` instead of dropping them.
+    #[cfg(all(feature = "lepus", feature = "redb"))]
+    pub fn set_cold_tier(&self, tier: cold_tier::ColdTier) {
+        *self.cold_tier.lock() = Some(tier);
+    }
+
+    /// Append a record of every eviction to `log`.
+    #[cfg(feature = "lepus")]
+    pub(crate) fn set_eviction_log(&self, log: eviction_log::EvictionLog) {
+        *self.eviction_log.lock() = Some(log);
+    }
+
     // =========================================================================
     // Subscription Management (Lease-Based)
     // =========================================================================
@@ -302,6 +453,9 @@ impl HostingManager {
         let mut entry = self.client_subscriptions.entry(*instance_id).or_default();
         let is_first_client = entry.is_empty();
         entry.insert(client_id);
+        drop(entry);
+        #[cfg(feature = "lepus")]
+        self.sync_client_subscribers(instance_id);
         debug!(
             contract = %instance_id,
             %client_id,
@@ -330,6 +484,8 @@ impl HostingManager {
         if no_more_subscriptions {
             self.client_subscriptions.remove(instance_id);
         }
+        #[cfg(feature = "lepus")]
+        self.sync_client_subscribers(instance_id);
 
         debug!(
             contract = %instance_id,
@@ -349,6 +505,24 @@ impl HostingManager {
             .unwrap_or(false)
     }
 
+    /// Copy a contract's client subscription count into the hosting cache,
+    /// where it feeds the CWP subscription sub-score.
+    #[cfg(feature = "lepus")]
+    fn sync_client_subscribers(&self, instance_id: &ContractInstanceId) {
+        let count = self.client_subscriber_count(instance_id);
+        let mut cache = self.hosting_cache.shard(instance_id).write();
+        if let Some(key) = cache.find_key(instance_id) {
+            cache.set_client_subscribers(&key, count);
+        }
+    }
+
+    #[cfg(feature = "lepus")]
+    fn client_subscriber_count(&self, instance_id: &ContractInstanceId) -> u32 {
+        self.client_subscriptions
+            .get(instance_id)
+            .map_or(0, |clients| clients.len() as u32)
+    }
+
     /// Remove a client from ALL its subscriptions (used when client disconnects).
     pub fn remove_client_from_all_subscriptions(
         &self,
@@ -403,17 +577,46 @@ impl HostingManager {
     /// - `evicted`: Contracts that were evicted to make room
     ///
     /// Automatically persists hosting metadata for the accessed contract and
-    /// removes persisted metadata for evicted contracts.
+    /// releases evicted contracts (see `release_evicted`).
     pub fn record_contract_access(
         &self,
         key: ContractKey,
         size_bytes: u64,
         access_type: AccessType,
     ) -> RecordAccessResult {
-        let result = self
-            .hosting_cache
-            .write()
-            .record_access(key, size_bytes, access_type);
+        let (result, evictions) = {
+            let mut cache = self.hosting_cache.shard(key.id()).write();
+            let result = cache.record_access(key, size_bytes, access_type);
+            (result, cache.take_evictions())
+        };
+        HOSTING_METRICS.set_current_bytes(self.hosting_cache.current_bytes());
+        self.collect_evictions(evictions);
+        // Clients may have subscribed before the contract was hosted
+        #[cfg(feature = "lepus")]
+        if result.is_new {
+            let count = self.client_subscriber_count(key.id());
+            let mut cache = self.hosting_cache.shard(key.id()).write();
+            cache.set_client_subscribers(&key, count);
+            // Pick up what was learned while it wasn't hosted
+            let (identity, commitment) = self.known.lock().take(&key);
+            if let Some(identity) = identity {
+                cache.update_identity(
+                    &key,
+                    identity.creator_pubkey,
+                    identity.creator_verified,
+                    identity.subscriber_pubkey,
+                    identity.subscriber_verified,
+                    identity.recipient_pubkey,
+                );
+            }
+            if let Some(commitment) = commitment {
+                cache.update_commitment(
+                    &key,
+                    commitment.deposited_xlm,
+                    commitment.last_oracle_check,
+                );
+            }
+        }
 
         // Persist hosting metadata for the accessed contract
         if let Some(storage) = self.storage.read().as_ref() {
@@ -431,6 +634,11 @@ impl HostingManager {
                 };
                 let code_hash: [u8; 32] = **key.code_hash();
                 let metadata = HostingMetadata::new(now_ms, access_type_u8, size_bytes, code_hash);
+                #[cfg(feature = "lepus")]
+                let metadata = match self.lepus_metadata(&key, now_ms) {
+                    Some(lepus) => metadata.with_lepus(lepus),
+                    None => metadata,
+                };
                 if let Err(e) = storage.store_hosting_metadata(&key, metadata) {
                     tracing::warn!(
                         contract = %key,
@@ -448,40 +656,106 @@ impl HostingManager {
                     "Sqlite hosting metadata update deferred to state store"
                 );
             }
-
-            // Clean up persisted metadata for evicted contracts
-            for evicted_key in &result.evicted {
-                #[cfg(feature = "redb")]
-                {
-                    if let Err(e) = storage.remove_hosting_metadata(evicted_key) {
-                        tracing::warn!(
-                            contract = %evicted_key,
-                            error = %e,
-                            "Failed to remove persisted hosting metadata for evicted contract"
-                        );
-                    }
-                }
-                #[cfg(all(feature = "sqlite", not(feature = "redb")))]
-                {
-                    tracing::debug!(
-                        contract = %evicted_key,
-                        "Evicted contract - sqlite metadata cleanup deferred"
-                    );
-                }
-            }
         }
+        self.release_evicted(&result.evicted);
 
         result
     }
 
+    /// Record a contract access carrying its current state.
+    ///
+    /// Like `record_contract_access`, except that a state the contract was
+    /// already accessed with within `state_refresh_cooldown_secs` leaves it
+    /// untouched: it isn't refreshed, persisted or counted as new.
+    #[cfg(feature = "lepus")]
+    pub fn record_state_access(
+        &self,
+        key: ContractKey,
+        state: &[u8],
+        access_type: AccessType,
+    ) -> RecordAccessResult {
+        let state_hash = *blake3::hash(state).as_bytes();
+        if self
+            .hosting_cache
+            .shard(key.id())
+            .read()
+            .state_seen_recently(&key, &state_hash)
+        {
+            debug!(contract = %key, "Repeated state within cooldown, not refreshing hosting");
+            return RecordAccessResult {
+                is_new: false,
+                evicted: Vec::new(),
+            };
+        }
+        let result = self.record_contract_access(key, state.len() as u64, access_type);
+        self.hosting_cache
+            .shard(key.id())
+            .write()
+            .record_state_hash(&key, state_hash);
+        result
+    }
+
     /// Check if a contract is in the hosting cache.
     pub fn is_hosting_contract(&self, key: &ContractKey) -> bool {
-        self.hosting_cache.read().contains(key)
+        self.hosting_cache.contains(key)
     }
 
     /// Get the number of contracts in the hosting cache.
     pub fn hosting_contracts_count(&self) -> usize {
-        self.hosting_cache.read().len()
+        self.hosting_cache.len()
+    }
+
+    /// Contracts that would be evicted if `size_needed` more bytes were
+    /// hosted now, lowest priority first. Doesn't modify the cache.
+    pub fn simulate_eviction(&self, size_needed: u64) -> Vec<EvictionCandidate> {
+        self.hosting_cache.simulate_eviction(size_needed)
+    }
+
+    /// Sub-scores and inputs behind a hosted contract's persistence score.
+    #[cfg(feature = "lepus")]
+    pub fn score_report(&self, instance_id: &ContractInstanceId) -> Option<ScoreReport> {
+        let cache = self.hosting_cache.shard(instance_id).read();
+        cache.score_report(&cache.find_key(instance_id)?)
+    }
+
+    /// Score reports for a page of hosted contracts in `sort` order.
+    #[cfg(feature = "lepus")]
+    pub fn list_hosted_contracts(
+        &self,
+        sort: HostedContractSort,
+        descending: bool,
+        offset: usize,
+        limit: usize,
+    ) -> HostedContractPage {
+        self.hosting_cache
+            .list_score_reports(sort, descending, offset, limit)
+    }
+
+    /// The CWP configuration currently scoring evictions.
+    #[cfg(feature = "lepus")]
+    pub fn cwp_config(&self) -> CWPConfig {
+        self.hosting_cache.shards()[0].read().cwp_config().clone()
+    }
+
+    /// Swap in a new CWP configuration without restarting the node.
+    ///
+    /// Leaves the current configuration in place if `cwp_config` doesn't
+    /// validate. All shards switch together. Returns the previous
+    /// configuration.
+    #[cfg(feature = "lepus")]
+    pub fn reload_cwp_config(&self, cwp_config: CWPConfig) -> anyhow::Result<CWPConfig> {
+        cwp_config.validate()?;
+        let previous = self.hosting_cache.set_cwp_config(cwp_config.clone());
+        self.known
+            .lock()
+            .resize(cwp_config.known_contracts_capacity);
+        info!(
+            old_weights = %format_cwp_weights(&previous),
+            new_weights = %format_cwp_weights(&cwp_config),
+            "Reloaded CWP configuration"
+        );
+        debug!(?previous, new = ?cwp_config, "Full CWP configuration change");
+        Ok(previous)
     }
 
     /// Check if we should continue hosting a contract.
@@ -500,72 +774,427 @@ impl HostingManager {
     ///
     /// Called when GET or SUBSCRIBE refreshes a hosted contract's TTL.
     pub fn touch_hosting(&self, key: &ContractKey) {
-        self.hosting_cache.write().touch(key);
+        self.hosting_cache.shard(key.id()).write().touch(key);
     }
 
     /// Sweep for expired entries in the hosting cache.
     ///
     /// Contracts with client subscriptions are protected from eviction.
-    /// Automatically removes persisted metadata for expired contracts.
+    /// Automatically releases expired contracts (see `release_evicted`).
     pub fn sweep_expired_hosting(&self) -> Vec<ContractKey> {
-        let expired = self.hosting_cache.write().sweep_expired(|key| {
-            // Retain contracts with client subscriptions - they need updates
-            self.has_client_subscriptions(key.id())
-        });
+        let mut expired = Vec::new();
+        let mut evictions = Vec::new();
+        for shard in self.hosting_cache.shards() {
+            let mut cache = shard.write();
+            expired.extend(cache.sweep_expired(|key| {
+                // Retain contracts with client subscriptions - they need updates
+                self.has_client_subscriptions(key.id())
+            }));
+            evictions.extend(cache.take_evictions());
+        }
+        HOSTING_METRICS.publish(&self.hosting_cache.occupancy());
+        self.collect_evictions(evictions);
+        self.release_evicted(&expired);
 
-        // Clean up persisted metadata for expired contracts
-        if !expired.is_empty() {
-            if let Some(storage) = self.storage.read().as_ref() {
-                for expired_key in &expired {
-                    #[cfg(feature = "redb")]
-                    {
-                        if let Err(e) = storage.remove_hosting_metadata(expired_key) {
-                            tracing::warn!(
-                                contract = %expired_key,
-                                error = %e,
-                                "Failed to remove persisted hosting metadata for expired contract"
-                            );
-                        }
-                    }
-                    #[cfg(all(feature = "sqlite", not(feature = "redb")))]
-                    {
-                        tracing::debug!(
-                            contract = %expired_key,
-                            "Expired contract - sqlite metadata cleanup deferred"
-                        );
-                    }
+        expired
+    }
+
+    /// Finish evicting contracts that left the hosting cache, so eviction
+    /// doesn't leave zombies behind: stop renewing their network
+    /// subscriptions and delete their stored state and hosting metadata,
+    /// archiving them to the cold tier first if it is enabled.
+    ///
+    /// The executor's in-memory state cache may still serve an evicted
+    /// state until it ages out. With Lepus, contracts being pushed to the
+    /// closest peers keep their stored state until `finish_replication`.
+    fn release_evicted(&self, evicted: &[ContractKey]) {
+        if evicted.is_empty() {
+            return;
+        }
+        for key in evicted {
+            self.unsubscribe(key);
+        }
+        let storage = self.storage.read();
+        let Some(storage) = storage.as_ref() else {
+            return;
+        };
+        for key in evicted {
+            #[cfg(feature = "lepus")]
+            if self.replicating.contains(key) {
+                debug!(contract = %key, "Keeping evicted contract state until it is replicated");
+                continue;
+            }
+            self.remove_evicted_state(storage, key);
+        }
+    }
+
+    /// Delete an evicted contract's stored state and hosting metadata,
+    /// archiving them to the cold tier first if it is enabled.
+    fn remove_evicted_state(
+        &self,
+        storage: &crate::contract::storages::Storage,
+        key: &ContractKey,
+    ) {
+        #[cfg(feature = "redb")]
+        {
+            #[cfg(feature = "lepus")]
+            self.archive_to_cold_tier(storage, key);
+            if let Err(e) = storage.remove_hosting_metadata(key) {
+                tracing::warn!(
+                    contract = %key,
+                    error = %e,
+                    "Failed to remove persisted hosting metadata for evicted contract"
+                );
+            }
+            if let Err(e) = storage.remove_state_sync(key) {
+                tracing::warn!(
+                    contract = %key,
+                    error = %e,
+                    "Failed to remove stored state for evicted contract"
+                );
+            }
+        }
+        #[cfg(all(feature = "sqlite", not(feature = "redb")))]
+        {
+            let _ = storage;
+            tracing::debug!(
+                contract = %key,
+                "Evicted contract - sqlite state and metadata cleanup deferred"
+            );
+        }
+    }
+
+    /// Drain contracts evicted with a high enough score to be pushed to the
+    /// closest peers before their stored state is dropped.
+    #[cfg(feature = "lepus")]
+    pub fn take_replication_hints(&self) -> Vec<ContractKey> {
+        std::mem::take(&mut *self.replication_hints.lock())
+    }
+
+    /// Whether an evicted contract is being pushed to the closest peers.
+    #[cfg(feature = "lepus")]
+    pub fn is_replicating(&self, key: &ContractKey) -> bool {
+        self.replicating.contains(key)
+    }
+
+    /// Finish evicting a contract from `take_replication_hints` once its
+    /// push is over, whether or not it reached a peer. Its stored state is
+    /// left alone if it was hosted again in the meantime.
+    #[cfg(feature = "lepus")]
+    pub fn finish_replication(&self, key: &ContractKey) {
+        if self.replicating.remove(key).is_none() || self.hosting_cache.contains(key) {
+            return;
+        }
+        if let Some(storage) = self.storage.read().as_ref() {
+            self.remove_evicted_state(storage, key);
+        }
+    }
+
+    /// Count evictions in metrics, append them to the eviction log and queue
+    /// those of client-subscribed contracts for `take_client_evictions`.
+    fn collect_evictions(&self, evictions: Vec<EvictionNotice>) {
+        #[cfg(feature = "lepus")]
+        self.log_evictions(&evictions);
+        #[cfg(feature = "lepus")]
+        if !evictions.is_empty() {
+            let mut known = self.known.lock();
+            for eviction in &evictions {
+                known.remember_evicted(eviction);
+            }
+        }
+        #[cfg(feature = "lepus")]
+        self.alert_committed_evictions(&evictions);
+        #[cfg(feature = "lepus")]
+        self.hint_replication(&evictions);
+        let mut client_evictions = Vec::new();
+        for eviction in evictions {
+            HOSTING_METRICS.record_evictions(eviction.reason, 1);
+            if self.has_client_subscriptions(eviction.key.id()) {
+                debug!(contract = %eviction.key, reason = %eviction.reason, score = ?eviction.score,
+                    "Evicted contract with client subscriptions");
+                client_evictions.push(eviction);
+            }
+        }
+        if !client_evictions.is_empty() {
+            self.client_evictions.lock().extend(client_evictions);
+        }
+    }
+
+    /// Drain evictions of contracts that local clients are subscribed to.
+    pub fn take_client_evictions(&self) -> Vec<EvictionNotice> {
+        std::mem::take(&mut *self.client_evictions.lock())
+    }
+
+    /// Warn about evicted contracts that had a deposit and queue them for
+    /// `take_committed_evictions`.
+    #[cfg(feature = "lepus")]
+    fn alert_committed_evictions(&self, evictions: &[EvictionNotice]) {
+        let committed: Vec<_> = evictions
+            .iter()
+            .filter(|eviction| eviction.commitment.deposited_xlm > 0)
+            .cloned()
+            .collect();
+        if committed.is_empty() {
+            return;
+        }
+        for eviction in &committed {
+            tracing::warn!(
+                contract = %eviction.key,
+                reason = %eviction.reason,
+                deposited_xlm = eviction.commitment.deposited_xlm,
+                score = eviction.breakdown.score,
+                used_bytes = eviction.used_bytes,
+                budget_bytes = eviction.budget_bytes,
+                "Evicted committed contract"
+            );
+        }
+        self.committed_evictions.lock().extend(committed);
+    }
+
+    /// Drain evictions of contracts that had a deposit.
+    #[cfg(feature = "lepus")]
+    pub fn take_committed_evictions(&self) -> Vec<EvictionNotice> {
+        std::mem::take(&mut *self.committed_evictions.lock())
+    }
+
+    /// Queue evicted contracts scoring at least
+    /// `CWPConfig::replication_hint_score` for `take_replication_hints`,
+    /// holding back their storage cleanup until `finish_replication`.
+    #[cfg(feature = "lepus")]
+    fn hint_replication(&self, evictions: &[EvictionNotice]) {
+        if evictions.is_empty() || self.storage.read().is_none() {
+            return;
+        }
+        let Some(threshold) = self.cwp_config().replication_hint_score else {
+            return;
+        };
+        let mut hints = Vec::new();
+        for eviction in evictions {
+            let score = eviction.score.unwrap_or(f64::NEG_INFINITY);
+            if score >= threshold && self.replicating.insert(eviction.key) {
+                debug!(contract = %eviction.key, score, "Replicating evicted contract before dropping it");
+                hints.push(eviction.key);
+            }
+        }
+        if !hints.is_empty() {
+            self.replication_hints.lock().extend(hints);
+        }
+    }
+
+    // =========================================================================
+    // Eviction Log (Lepus)
+    // =========================================================================
+
+    #[cfg(feature = "lepus")]
+    fn log_evictions(&self, evictions: &[EvictionNotice]) {
+        if evictions.is_empty() {
+            return;
+        }
+        let mut guard = self.eviction_log.lock();
+        let Some(log) = guard.as_mut() else {
+            return;
+        };
+        let now_ms = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+        let records: Vec<_> = evictions
+            .iter()
+            .map(|eviction| EvictionRecord::new(eviction, now_ms))
+            .collect();
+        if let Err(e) = log.append(&records) {
+            tracing::warn!(
+                error = %e,
+                evictions = records.len(),
+                "Failed to append to hosting eviction log"
+            );
+        }
+    }
+
+    /// Logged evictions matching `query`, oldest first. Empty if the
+    /// eviction log is disabled.
+    #[cfg(feature = "lepus")]
+    pub fn query_eviction_log(&self, query: &EvictionLogQuery) -> Vec<EvictionRecord> {
+        let guard = self.eviction_log.lock();
+        let Some(log) = guard.as_ref() else {
+            return Vec::new();
+        };
+        log.query(query).unwrap_or_else(|e| {
+            tracing::warn!(error = %e, "Failed to read hosting eviction log");
+            Vec::new()
+        })
+    }
+
+    // =========================================================================
+    // Cold Tier (Lepus)
+    // =========================================================================
+
+    /// Archive an evicted contract's state and persisted hosting metadata in
+    /// the cold tier, if one is set. Call before removing the metadata.
+    ///
+    /// The hot state store keeps its copy; eviction only stops hosting.
+    #[cfg(all(feature = "lepus", feature = "redb"))]
+    fn archive_to_cold_tier(
+        &self,
+        storage: &crate::contract::storages::Storage,
+        key: &ContractKey,
+    ) {
+        let mut guard = self.cold_tier.lock();
+        let Some(tier) = guard.as_mut() else {
+            return;
+        };
+        let state = match storage.get_state_sync(key) {
+            Ok(Some(state)) => state,
+            Ok(None) => return,
+            Err(e) => {
+                tracing::warn!(
+                    contract = %key,
+                    error = %e,
+                    "Failed to read evicted contract state for cold tier"
+                );
+                return;
+            }
+        };
+        let metadata = storage.get_hosting_metadata(key).ok().flatten();
+        match tier.archive(key, state.as_ref(), metadata.as_ref()) {
+            Ok(dropped) => {
+                debug!(
+                    contract = %key,
+                    dropped = dropped.len(),
+                    "Archived evicted contract in cold tier"
+                );
+            }
+            Err(e) => {
+                tracing::warn!(
+                    contract = %key,
+                    error = %e,
+                    "Failed to archive evicted contract in cold tier"
+                );
+            }
+        }
+    }
+
+    /// Re-host a contract archived in the cold tier.
+    ///
+    /// Takes the archive, writes the state back to the state store if it is no
+    /// longer there, records a GET access and restores the archived CWP
+    /// scoring state. Returns the key if the contract was rehydrated.
+    #[cfg(all(feature = "lepus", feature = "redb"))]
+    pub fn rehydrate_from_cold_tier(
+        &self,
+        instance_id: &ContractInstanceId,
+    ) -> Option<ContractKey> {
+        let storage = self.storage.read().clone()?;
+        let entry = {
+            let mut guard = self.cold_tier.lock();
+            let tier = guard.as_mut()?;
+            if !tier.contains(instance_id) {
+                return None;
+            }
+            match tier.take(instance_id) {
+                Ok(entry) => entry?,
+                Err(e) => {
+                    tracing::warn!(
+                        contract = %instance_id,
+                        error = %e,
+                        "Failed to read cold tier archive"
+                    );
+                    return None;
                 }
             }
+        };
+        let key = entry.key;
+
+        match storage.get_state_size(&key) {
+            Ok(Some(_)) => {}
+            Ok(None) => {
+                if let Err(e) = storage.store_state_sync(&key, &entry.state) {
+                    tracing::warn!(
+                        contract = %key,
+                        error = %e,
+                        "Failed to restore cold tier state"
+                    );
+                    return None;
+                }
+            }
+            Err(e) => {
+                tracing::warn!(
+                    contract = %key,
+                    error = %e,
+                    "Failed to check state store for rehydrated contract"
+                );
+            }
         }
 
-        expired
+        self.record_contract_access(key, entry.state.len() as u64, AccessType::Get);
+        if let Some(lepus) = entry.metadata.and_then(|metadata| metadata.lepus) {
+            let now_ms = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64;
+            restore_persisted_lepus(
+                &mut self.hosting_cache.shard(key.id()).write(),
+                &key,
+                lepus,
+                now_ms,
+            );
+            self.persist_lepus_state(std::slice::from_ref(&key));
+        }
+        info!(
+            contract = %key,
+            size_bytes = entry.state.len(),
+            "Rehydrated contract from cold tier"
+        );
+        Some(key)
     }
 
     // =========================================================================
     // CWP Contribution Tracking (Lepus)
     // =========================================================================
 
-    /// Record bytes served to other peers for a contract.
+    /// Record bytes served to other peers for a contract, attributed to
+    /// `peer` when the recipient is known.
     #[cfg(feature = "lepus")]
-    pub fn record_bytes_served(&self, key: &ContractKey, bytes: u64) {
-        self.hosting_cache.write().record_bytes_served(key, bytes);
+    pub fn record_bytes_served(
+        &self,
+        key: &ContractKey,
+        peer: Option<&super::PeerKey>,
+        bytes: u64,
+    ) {
+        let mut cache = self.hosting_cache.shard(key.id()).write();
+        match peer {
+            Some(peer) => cache.record_peer_bytes_served(key, peer, bytes),
+            None => cache.record_bytes_served(key, bytes),
+        }
     }
 
     /// Record bytes consumed (received) from other peers for a contract.
     #[cfg(feature = "lepus")]
     pub fn record_bytes_consumed(&self, key: &ContractKey, bytes: u64) {
-        self.hosting_cache.write().record_bytes_consumed(key, bytes);
+        self.hosting_cache
+            .shard(key.id())
+            .write()
+            .record_bytes_consumed(key, bytes);
+    }
+
+    /// Record a PUT or UPDATE of a contract that failed validation.
+    #[cfg(feature = "lepus")]
+    pub fn record_validation_failure(&self, key: &ContractKey) {
+        self.hosting_cache
+            .shard(key.id())
+            .write()
+            .record_validation_failure(key);
     }
 
     /// Get all hosted contract keys.
     #[cfg(feature = "lepus")]
     pub fn hosted_contract_keys(&self) -> Vec<ContractKey> {
-        self.hosting_cache.read().contract_keys()
+        self.hosting_cache.keys()
     }
 
     /// Batch-update commitment deposits for hosted contracts.
     ///
-    /// Acquires a single write lock and applies all updates.
+    /// Applies the updates under one write lock per shard.
     /// Returns the number of contracts that were found and updated.
     #[cfg(feature = "lepus")]
     pub fn update_commitments_batch(
@@ -573,14 +1202,23 @@ impl HostingManager {
         updates: &[(ContractKey, u64)],
         check_time: Instant,
     ) -> usize {
-        let mut cache = self.hosting_cache.write();
-        let mut count = 0;
+        let mut updated = Vec::with_capacity(updates.len());
         for (key, deposited_xlm) in updates {
-            if cache.update_commitment(key, *deposited_xlm, check_time) {
-                count += 1;
+            if self
+                .hosting_cache
+                .shard(key.id())
+                .write()
+                .update_commitment(key, *deposited_xlm, check_time)
+            {
+                updated.push(*key);
+            } else {
+                self.known
+                    .lock()
+                    .record_commitment(*key, *deposited_xlm, check_time);
             }
         }
-        count
+        self.persist_lepus_state(&updated);
+        updated.len()
     }
 
     /// Verify identity envelope in contract state and update the hosting cache.
@@ -588,17 +1226,99 @@ impl HostingManager {
     /// Parses the Lepus identity envelope from `state_bytes`, verifies the creator
     /// signature, checks subscriber matching, and stores results on the hosted contract.
     /// Returns `true` if the contract was found in cache and updated.
+    ///
+    /// Call on every accepted state change: the result replaces what was
+    /// verified before, so a state that drops or breaks its envelope loses
+    /// the identity bonus.
+    ///
+    /// An envelope whose creator signature fails verification counts as a
+    /// forgery against the contract's persistence score.
     #[cfg(feature = "lepus")]
     pub fn verify_and_update_identity(&self, key: &ContractKey, state_bytes: &[u8]) -> bool {
+        if let Some(newly_revoked) = revocation::check_revocation_update(key, state_bytes) {
+            self.revoke_creators(&newly_revoked);
+        }
         let result = identity::verify_identity(state_bytes);
-        self.hosting_cache.write().update_identity(
+        {
+            let mut known = self.known.lock();
+            if result.creator_verified {
+                if let Some(pubkey) = &result.creator_pubkey {
+                    known.record_identity_contract(pubkey, key);
+                }
+            }
+            if result.subscriber_verified {
+                if let Some(pubkey) = &result.subscriber_pubkey {
+                    known.record_identity_contract(pubkey, key);
+                }
+            }
+        }
+        let mut cache = self.hosting_cache.shard(key.id()).write();
+        if result.creator_pubkey.is_some() && !result.creator_verified && !result.creator_revoked {
+            tracing::debug!(
+                contract = %key,
+                "Identity envelope failed creator signature verification"
+            );
+            cache.record_envelope_forgery(key);
+        }
+        let was_verified = cache
+            .get(key)
+            .is_some_and(|contract| contract.identity.creator_verified);
+        if was_verified && !result.creator_verified {
+            info!(
+                contract = %key,
+                envelope_present = result.creator_pubkey.is_some(),
+                "New state no longer carries a valid identity envelope; dropping creator verification"
+            );
+        }
+        let updated = cache.update_identity(
             key,
             result.creator_pubkey,
             result.creator_verified,
             result.subscriber_pubkey,
             result.subscriber_verified,
             result.recipient_pubkey,
-        )
+        );
+        drop(cache);
+        if updated {
+            self.persist_lepus_state(std::slice::from_ref(key));
+        } else {
+            self.known.lock().record_identity(
+                *key,
+                cache::IdentityState {
+                    creator_pubkey: result.creator_pubkey,
+                    creator_verified: result.creator_verified,
+                    subscriber_pubkey: result.subscriber_pubkey,
+                    subscriber_verified: result.subscriber_verified,
+                    recipient_pubkey: result.recipient_pubkey,
+                },
+            );
+        }
+        updated
+    }
+
+    /// Drop creator verification from hosted contracts signed by a key that
+    /// was just added to the revocation list.
+    ///
+    /// Envelopes verified later are checked against the list as they arrive.
+    #[cfg(feature = "lepus")]
+    pub fn revoke_creators(&self, creators: &[[u8; 32]]) {
+        if creators.is_empty() {
+            return;
+        }
+        let mut revoked = Vec::new();
+        for shard in self.hosting_cache.shards() {
+            let mut cache = shard.write();
+            for creator in creators {
+                revoked.extend(cache.revoke_creator(creator));
+            }
+        }
+        if !revoked.is_empty() {
+            info!(
+                contracts = revoked.len(),
+                "Dropped creator verification for contracts signed by revoked keys"
+            );
+            self.persist_lepus_state(&revoked);
+        }
     }
 
     /// Update subscriber identity from subscription handshake.
@@ -608,23 +1328,142 @@ impl HostingManager {
         key: &ContractKey,
         subscriber_pubkey: &[u8; 32],
     ) -> bool {
-        self.hosting_cache
+        self.known
+            .lock()
+            .record_identity_contract(subscriber_pubkey, key);
+        let updated = self
+            .hosting_cache
+            .shard(key.id())
             .write()
-            .update_subscriber_identity(key, subscriber_pubkey)
+            .update_subscriber_identity(key, subscriber_pubkey);
+        if updated {
+            self.persist_lepus_state(std::slice::from_ref(key));
+        } else {
+            self.known.lock().record_subscriber(*key, subscriber_pubkey);
+        }
+        updated
+    }
+
+    /// Persist the current CWP scoring state of hosted contracts, keeping the
+    /// rest of their stored hosting metadata.
+    ///
+    /// Contribution counters change on every transfer, so they are written
+    /// along with the next access or commitment/identity update rather than
+    /// on each increment.
+    #[cfg(feature = "lepus")]
+    fn persist_lepus_state(&self, keys: &[ContractKey]) {
+        if keys.is_empty() {
+            return;
+        }
+        #[cfg(feature = "redb")]
+        if let Some(storage) = self.storage.read().as_ref() {
+            let now_ms = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64;
+            for key in keys {
+                let Some(lepus) = self.lepus_metadata(key, now_ms) else {
+                    continue;
+                };
+                let stored = match storage.get_hosting_metadata(key) {
+                    Ok(Some(stored)) => stored,
+                    Ok(None) => continue,
+                    Err(e) => {
+                        tracing::warn!(
+                            contract = %key,
+                            error = %e,
+                            "Failed to read hosting metadata to persist Lepus state"
+                        );
+                        continue;
+                    }
+                };
+                if let Err(e) = storage.store_hosting_metadata(key, stored.with_lepus(lepus)) {
+                    tracing::warn!(
+                        contract = %key,
+                        error = %e,
+                        "Failed to persist Lepus hosting state"
+                    );
+                }
+            }
+        }
+        #[cfg(all(feature = "sqlite", not(feature = "redb")))]
+        {
+            tracing::trace!(
+                contracts = keys.len(),
+                "Sqlite does not persist Lepus hosting state"
+            );
+        }
+    }
+
+    /// Snapshot a hosted contract's CWP scoring state for persistence.
+    #[cfg(all(feature = "lepus", feature = "redb"))]
+    fn lepus_metadata(
+        &self,
+        key: &ContractKey,
+        now_ms: u64,
+    ) -> Option<crate::contract::storages::LepusHostingMetadata> {
+        let cache = self.hosting_cache.shard(key.id()).read();
+        let contract = cache.get(key)?;
+        let now = Instant::now();
+        Some(crate::contract::storages::LepusHostingMetadata {
+            deposited_xlm: contract.commitment.deposited_xlm,
+            last_oracle_check_ms: contract.commitment.last_oracle_check.map(|checked| {
+                now_ms.saturating_sub(now.saturating_duration_since(checked).as_millis() as u64)
+            }),
+            creator_pubkey: contract.identity.creator_pubkey,
+            creator_verified: contract.identity.creator_verified,
+            subscriber_pubkey: contract.identity.subscriber_pubkey,
+            subscriber_verified: contract.identity.subscriber_verified,
+            recipient_pubkey: contract.identity.recipient_pubkey,
+            bytes_served: contract.bytes_served,
+            bytes_consumed: contract.bytes_consumed,
+        })
     }
 
     /// Count subscriptions for a given identity pubkey.
     #[cfg(feature = "lepus")]
     pub fn count_subscriptions_for_identity(&self, pubkey: &[u8; 32]) -> usize {
         self.hosting_cache
-            .read()
-            .count_subscriptions_for_identity(pubkey)
+            .shards()
+            .iter()
+            .map(|shard| shard.read().count_subscriptions_for_identity(pubkey))
+            .sum()
     }
 
     /// Check if a subscriber identity has any funded contract.
+    ///
+    /// Hosted contracts are checked first; contracts hosted elsewhere count
+    /// if the latest deposit-index state shows a live deposit for them.
     #[cfg(feature = "lepus")]
     pub fn is_identity_funded(&self, pubkey: &[u8; 32]) -> bool {
-        self.hosting_cache.read().is_identity_funded(pubkey)
+        if self
+            .hosting_cache
+            .shards()
+            .iter()
+            .any(|shard| shard.read().is_identity_funded(pubkey))
+        {
+            return true;
+        }
+        let known = self.known.lock();
+        let Some(contracts) = known.identity_contracts(pubkey) else {
+            return false;
+        };
+        let deposits = self.deposit_snapshot.read();
+        contracts
+            .iter()
+            .any(|id| deposits.live_deposit(id).is_some_and(|xlm| xlm > 0))
+    }
+
+    /// Replace the cached deposit-index state with `deposits`.
+    #[cfg(feature = "lepus")]
+    pub fn set_deposit_snapshot(&self, deposits: deposit_index::DepositSnapshot) {
+        *self.deposit_snapshot.write() = deposits;
+    }
+
+    /// `instance_id`'s deposit in the cached deposit-index state.
+    #[cfg(feature = "lepus")]
+    pub fn deposit_info(&self, instance_id: &ContractInstanceId) -> deposit_index::DepositInfo {
+        self.deposit_snapshot.read().deposit_info(instance_id)
     }
 
     // =========================================================================
@@ -733,6 +1572,7 @@ impl HostingManager {
                 // Need to find the ContractKey - check hosting cache
                 if let Some(contract) = self
                     .hosting_cache
+                    .shard(&instance_id)
                     .read()
                     .iter()
                     .find(|k| k.id() == &instance_id)
@@ -745,7 +1585,7 @@ impl HostingManager {
         // 3. THE FIX: All hosted contracts should have subscriptions renewed
         // This ensures GET-triggered subscriptions don't expire without renewal
         // Collect and sort for deterministic iteration order
-        let mut hosted_contracts: Vec<_> = self.hosting_cache.read().iter().collect();
+        let mut hosted_contracts = self.hosting_cache.keys();
         hosted_contracts.sort_by(|a, b| a.id().as_bytes().cmp(b.id().as_bytes()));
 
         for contract in hosted_contracts {
@@ -768,6 +1608,22 @@ impl HostingManager {
         result
     }
 
+    /// Order contracts by persistence score, highest first, so that when
+    /// renewals are rationed the most valuable contracts are renewed first.
+    /// Contracts no longer hosted go last. Equal scores keep their order.
+    #[cfg(feature = "lepus")]
+    pub fn sort_by_persistence_score(&self, contracts: &mut [ContractKey]) {
+        contracts.sort_by_cached_key(|key| {
+            let score = self
+                .hosting_cache
+                .shard(key.id())
+                .read()
+                .persistence_score(key)
+                .unwrap_or(f64::NEG_INFINITY);
+            std::cmp::Reverse(ordered_float::OrderedFloat(score))
+        });
+    }
+
     // =========================================================================
     // Topology Snapshot (for telemetry/visualization)
     // =========================================================================
@@ -790,8 +1646,7 @@ impl HostingManager {
 
         // Add all hosted contracts
         // Collect and sort for deterministic iteration order
-        let hosting_cache = self.hosting_cache.read();
-        let mut hosted_contracts: Vec<_> = hosting_cache.iter().collect();
+        let mut hosted_contracts = self.hosting_cache.keys();
         hosted_contracts.sort_by(|a, b| a.id().as_bytes().cmp(b.id().as_bytes()));
 
         for contract_key in hosted_contracts {
@@ -820,7 +1675,7 @@ impl HostingManager {
         active_subs.sort_by(|(a, _), (b, _)| a.id().as_bytes().cmp(b.id().as_bytes()));
 
         for (contract_key, expires_at) in active_subs {
-            if expires_at > now && !hosting_cache.contains(&contract_key) {
+            if expires_at > now && !self.hosting_cache.contains(&contract_key) {
                 let has_client_subscriptions =
                     self.client_subscriptions.contains_key(contract_key.id());
 
@@ -855,9 +1710,15 @@ impl HostingManager {
     /// This restores the hosting cache from persisted data, allowing the peer
     /// to continue hosting contracts after a restart without losing LRU state.
     ///
-    /// Also migrates legacy contracts that have state but no hosting metadata.
-    /// This is critical for network upgrades - without migration, all peers would
-    /// "forget" legacy contracts after upgrading.
+    /// Also reconciles the cache with the contract store, which can drift
+    /// apart after a crash:
+    /// - Contracts with state but no hosting metadata (including legacy
+    ///   contracts from before hosting metadata existed) are imported as the
+    ///   lowest-priority entries, so they are evicted first unless accessed.
+    ///   Without this, all peers would "forget" them after upgrading.
+    /// - Metadata for contracts whose state is missing is dropped instead of
+    ///   counting bytes that aren't stored.
+    /// - Entries take their size from the stored state.
     ///
     /// # Arguments
     /// * `storage` - The storage backend (ReDb or SqlitePool)
@@ -865,7 +1726,7 @@ impl HostingManager {
     ///   Uses ContractStore which has the id->code_hash mapping.
     ///
     /// # Returns
-    /// The number of contracts loaded from storage (including migrated legacy contracts).
+    /// The number of contracts loaded from storage (including imported orphans).
     #[cfg(feature = "redb")]
     pub fn load_from_storage<F>(
         &self,
@@ -884,10 +1745,10 @@ impl HostingManager {
             .unwrap_or_default()
             .as_millis() as u64;
 
-        let mut cache = self.hosting_cache.write();
         let mut loaded = 0;
+        let mut pruned = 0;
 
-        // Track which instance IDs we've loaded (for legacy detection)
+        // Track which instance IDs we've loaded (for orphan detection)
         let mut loaded_instance_ids: HashSet<[u8; 32]> = HashSet::new();
 
         for (key_bytes, metadata) in metadata_entries {
@@ -908,20 +1769,53 @@ impl HostingManager {
                     _ => cache::AccessType::Get,
                 };
 
+                // Drop metadata whose state is gone; the state, if any, is
+                // the authority on size
+                let size_bytes = match storage.get_state_size(&key) {
+                    Ok(Some(size_bytes)) => size_bytes,
+                    Ok(None) => {
+                        if let Err(e) = storage.remove_hosting_metadata(&key) {
+                            tracing::warn!(
+                                contract = %key,
+                                error = %e,
+                                "Failed to remove hosting metadata for contract without state"
+                            );
+                        }
+                        pruned += 1;
+                        continue;
+                    }
+                    Err(e) => {
+                        tracing::warn!(
+                            contract = %key,
+                            error = %e,
+                            "Failed to read stored state size; trusting hosting metadata"
+                        );
+                        metadata.size_bytes
+                    }
+                };
+
                 // Calculate age from persisted timestamp
                 let age_ms = now_ms.saturating_sub(metadata.last_access_ms);
                 let age = std::time::Duration::from_millis(age_ms);
 
-                cache.load_persisted_entry(key, metadata.size_bytes, access_type, age);
+                let mut cache = self.hosting_cache.shard(key.id()).write();
+                cache.load_persisted_entry(key, size_bytes, access_type, age);
+
+                // Restore CWP scoring state so scores don't flatten until the
+                // oracle and identity checks catch up
+                #[cfg(feature = "lepus")]
+                if let Some(lepus) = metadata.lepus {
+                    restore_persisted_lepus(&mut cache, &key, lepus, now_ms);
+                }
                 loaded += 1;
             }
         }
 
-        // Migrate legacy contracts: contracts in states table but without hosting metadata
-        // This ensures the network doesn't "forget" contracts after upgrading
+        // Import orphaned contracts: contracts in states table but without hosting metadata
+        // This ensures the network doesn't "forget" contracts after upgrading or a crash
         let all_state_keys = storage.iter_all_state_keys().unwrap_or_default();
-        let mut migrated = 0;
-        let mut migration_failures = 0;
+        let mut imported = 0;
+        let mut import_failures = 0;
 
         for key_bytes in all_state_keys {
             if key_bytes.len() != 32 {
@@ -936,7 +1830,7 @@ impl HostingManager {
                 continue;
             }
 
-            // Legacy contract: has state but no hosting metadata
+            // Orphaned contract: has state but no hosting metadata
             let instance_id = ContractInstanceId::new(instance_id_bytes);
 
             // Look up code_hash from ContractStore
@@ -946,19 +1840,18 @@ impl HostingManager {
                 // Get state size for the hosting cache
                 let size_bytes = storage.get_state_size(&key).unwrap_or(Some(0)).unwrap_or(0);
 
-                // Add to hosting cache as if it was just accessed via GET
-                // Use current time as last_access since we don't have historical data
-                cache.load_persisted_entry(
-                    key,
-                    size_bytes,
-                    cache::AccessType::Get,
-                    std::time::Duration::ZERO,
-                );
+                // Add to hosting cache behind everything else: nothing says
+                // anyone still wants it
+                let age = self
+                    .hosting_cache
+                    .shard(key.id())
+                    .write()
+                    .load_orphaned_entry(key, size_bytes);
 
-                // Persist hosting metadata so future restarts don't need migration
+                // Persist hosting metadata so future restarts don't need to import it again
                 let code_hash_bytes: [u8; 32] = *code_hash;
                 let metadata = crate::contract::storages::HostingMetadata::new(
-                    now_ms,
+                    now_ms.saturating_sub(age.as_millis() as u64),
                     0, // GET access type
                     size_bytes,
                     code_hash_bytes,
@@ -967,40 +1860,44 @@ impl HostingManager {
                     tracing::warn!(
                         contract = %key,
                         error = %e,
-                        "Failed to persist hosting metadata for migrated legacy contract"
+                        "Failed to persist hosting metadata for orphaned contract"
                     );
                 }
 
-                migrated += 1;
+                imported += 1;
             } else {
                 // ContractStore doesn't know about this contract
                 // This shouldn't happen normally - means WASM code is missing
-                migration_failures += 1;
+                import_failures += 1;
                 tracing::warn!(
                     instance_id = %instance_id,
-                    "Legacy contract has state but no WASM code - cannot migrate"
+                    "Orphaned contract has state but no WASM code - cannot import"
                 );
             }
         }
 
         // Sort LRU order by last_accessed time
-        cache.finalize_loading();
+        for shard in self.hosting_cache.shards() {
+            shard.write().finalize_loading();
+        }
+        HOSTING_METRICS.publish(&self.hosting_cache.occupancy());
 
-        let total_loaded = loaded + migrated;
+        let total_loaded = loaded + imported;
 
-        if migrated > 0 || migration_failures > 0 {
+        if imported > 0 || import_failures > 0 || pruned > 0 {
             tracing::info!(
                 loaded_with_metadata = loaded,
-                migrated_legacy = migrated,
-                migration_failures = migration_failures,
+                imported_orphans = imported,
+                import_failures,
+                pruned_without_state = pruned,
                 total_contracts = total_loaded,
-                total_bytes = cache.current_bytes(),
-                "Loaded hosting cache from storage (with legacy migration)"
+                total_bytes = self.hosting_cache.current_bytes(),
+                "Loaded hosting cache from storage (reconciled with contract store)"
             );
         } else {
             tracing::info!(
                 loaded_contracts = total_loaded,
-                total_bytes = cache.current_bytes(),
+                total_bytes = self.hosting_cache.current_bytes(),
                 "Loaded hosting cache from storage"
             );
         }
@@ -1010,7 +1907,7 @@ impl HostingManager {
 
     /// Load hosting metadata from storage during startup (sqlite version).
     ///
-    /// Also migrates legacy contracts that have state but no hosting metadata.
+    /// Also reconciles the cache with the contract store, as in the redb version.
     #[cfg(all(feature = "sqlite", not(feature = "redb")))]
     pub async fn load_from_storage<F>(
         &self,
@@ -1029,10 +1926,10 @@ impl HostingManager {
             .unwrap_or_default()
             .as_millis() as u64;
 
-        let mut cache = self.hosting_cache.write();
         let mut loaded = 0;
+        let mut pruned = 0;
 
-        // Track which instance IDs we've loaded (for legacy detection)
+        // Track which instance IDs we've loaded (for orphan detection)
         let mut loaded_instance_ids: HashSet<[u8; 32]> = HashSet::new();
 
         for (key_bytes, metadata) in metadata_entries {
@@ -1053,19 +1950,47 @@ impl HostingManager {
                     _ => cache::AccessType::Get,
                 };
 
+                // Drop metadata whose state is gone; the state, if any, is
+                // the authority on size
+                let size_bytes = match storage.get_state_size(&key).await {
+                    Ok(Some(size_bytes)) => size_bytes,
+                    Ok(None) => {
+                        if let Err(e) = storage.remove_hosting_metadata(&key).await {
+                            tracing::warn!(
+                                contract = %key,
+                                error = %e,
+                                "Failed to remove hosting metadata for contract without state"
+                            );
+                        }
+                        pruned += 1;
+                        continue;
+                    }
+                    Err(e) => {
+                        tracing::warn!(
+                            contract = %key,
+                            error = %e,
+                            "Failed to read stored state size; trusting hosting metadata"
+                        );
+                        metadata.size_bytes
+                    }
+                };
+
                 // Calculate age from persisted timestamp
                 let age_ms = now_ms.saturating_sub(metadata.last_access_ms);
                 let age = std::time::Duration::from_millis(age_ms);
 
-                cache.load_persisted_entry(key, metadata.size_bytes, access_type, age);
+                self.hosting_cache
+                    .shard(key.id())
+                    .write()
+                    .load_persisted_entry(key, size_bytes, access_type, age);
                 loaded += 1;
             }
         }
 
-        // Migrate legacy contracts: contracts in states table but without hosting metadata
+        // Import orphaned contracts: contracts in states table but without hosting metadata
         let all_state_keys = storage.iter_all_state_keys().await.unwrap_or_default();
-        let mut migrated = 0;
-        let mut migration_failures = 0;
+        let mut imported = 0;
+        let mut import_failures = 0;
 
         for key_bytes in all_state_keys {
             if key_bytes.len() != 32 {
@@ -1080,7 +2005,7 @@ impl HostingManager {
                 continue;
             }
 
-            // Legacy contract: has state but no hosting metadata
+            // Orphaned contract: has state but no hosting metadata
             let instance_id = ContractInstanceId::new(instance_id_bytes);
 
             // Look up code_hash from ContractStore
@@ -1094,18 +2019,17 @@ impl HostingManager {
                     .unwrap_or(Some(0))
                     .unwrap_or(0);
 
-                // Add to hosting cache as if it was just accessed via GET
-                cache.load_persisted_entry(
-                    key,
-                    size_bytes,
-                    cache::AccessType::Get,
-                    std::time::Duration::ZERO,
-                );
+                // Add to hosting cache behind everything else
+                let age = self
+                    .hosting_cache
+                    .shard(key.id())
+                    .write()
+                    .load_orphaned_entry(key, size_bytes);
 
-                // Persist hosting metadata so future restarts don't need migration
+                // Persist hosting metadata so future restarts don't need to import it again
                 let code_hash_bytes: [u8; 32] = *code_hash;
                 let metadata = crate::contract::storages::sqlite::HostingMetadata::new(
-                    now_ms,
+                    now_ms.saturating_sub(age.as_millis() as u64),
                     0, // GET access type
                     size_bytes,
                     code_hash_bytes,
@@ -1114,38 +2038,42 @@ impl HostingManager {
                     tracing::warn!(
                         contract = %key,
                         error = %e,
-                        "Failed to persist hosting metadata for migrated legacy contract"
+                        "Failed to persist hosting metadata for orphaned contract"
                     );
                 }
 
-                migrated += 1;
+                imported += 1;
             } else {
-                migration_failures += 1;
+                import_failures += 1;
                 tracing::warn!(
                     instance_id = %instance_id,
-                    "Legacy contract has state but no WASM code - cannot migrate"
+                    "Orphaned contract has state but no WASM code - cannot import"
                 );
             }
         }
 
         // Sort LRU order by last_accessed time
-        cache.finalize_loading();
+        for shard in self.hosting_cache.shards() {
+            shard.write().finalize_loading();
+        }
+        HOSTING_METRICS.publish(&self.hosting_cache.occupancy());
 
-        let total_loaded = loaded + migrated;
+        let total_loaded = loaded + imported;
 
-        if migrated > 0 || migration_failures > 0 {
+        if imported > 0 || import_failures > 0 || pruned > 0 {
             tracing::info!(
                 loaded_with_metadata = loaded,
-                migrated_legacy = migrated,
-                migration_failures = migration_failures,
+                imported_orphans = imported,
+                import_failures,
+                pruned_without_state = pruned,
                 total_contracts = total_loaded,
-                total_bytes = cache.current_bytes(),
-                "Loaded hosting cache from storage (with legacy migration)"
+                total_bytes = self.hosting_cache.current_bytes(),
+                "Loaded hosting cache from storage (reconciled with contract store)"
             );
         } else {
             tracing::info!(
                 loaded_contracts = total_loaded,
-                total_bytes = cache.current_bytes(),
+                total_bytes = self.hosting_cache.current_bytes(),
                 "Loaded hosting cache from storage"
             );
         }
@@ -1154,12 +2082,53 @@ impl HostingManager {
     }
 }
 
+/// Restore persisted CWP scoring state onto a contract already in `cache`.
+#[cfg(all(feature = "lepus", feature = "redb"))]
+fn restore_persisted_lepus(
+    cache: &mut HostingCache<InstantTimeSrc>,
+    key: &ContractKey,
+    lepus: crate::contract::storages::LepusHostingMetadata,
+    now_ms: u64,
+) {
+    cache.restore_lepus_state(
+        key,
+        lepus.deposited_xlm,
+        lepus
+            .last_oracle_check_ms
+            .map(|checked_ms| Duration::from_millis(now_ms.saturating_sub(checked_ms))),
+        cache::IdentityState {
+            creator_pubkey: lepus.creator_pubkey,
+            creator_verified: lepus.creator_verified,
+            subscriber_pubkey: lepus.subscriber_pubkey,
+            subscriber_verified: lepus.subscriber_verified,
+            recipient_pubkey: lepus.recipient_pubkey,
+        },
+        lepus.bytes_served,
+        lepus.bytes_consumed,
+    );
+}
+
 impl Default for HostingManager {
     fn default() -> Self {
         Self::new()
     }
 }
 
+/// The score weights of a CWP configuration, for logging.
+#[cfg(feature = "lepus")]
+fn format_cwp_weights(config: &CWPConfig) -> String {
+    format!(
+        "commitment={} identity={} contribution={} recency={} subscription={} penalty={} size_penalty={}",
+        config.commitment_weight,
+        config.identity_weight,
+        config.contribution_weight,
+        config.recency_weight,
+        config.subscription_weight,
+        config.penalty_weight,
+        config.size_penalty_weight
+    )
+}
+
 // =============================================================================
 // Tests
 // =============================================================================
@@ -1293,6 +2262,51 @@ mod tests {
         assert!(is_last2);
     }
 
+    #[cfg(feature = "lepus")]
+    #[test]
+    fn test_client_subscriptions_feed_cwp_score() {
+        let manager = HostingManager::new();
+        let key = make_contract_key(1);
+        let client1 = crate::client_events::ClientId::next();
+        let client2 = crate::client_events::ClientId::next();
+        let subscribers =
+            |manager: &HostingManager| manager.score_report(key.id()).unwrap().client_subscribers;
+
+        // Subscribed before the contract is hosted
+        manager.add_client_subscription(key.id(), client1);
+        manager.record_contract_access(key, 100, AccessType::Get);
+        assert_eq!(subscribers(&manager), 1);
+
+        manager.add_client_subscription(key.id(), client2);
+        assert_eq!(subscribers(&manager), 2);
+        manager.remove_client_from_all_subscriptions(client1);
+        assert_eq!(subscribers(&manager), 1);
+        manager.remove_client_subscription(key.id(), client2);
+        assert_eq!(subscribers(&manager), 0);
+    }
+
+    #[test]
+    fn test_client_subscribed_evictions_are_queued() {
+        let manager = HostingManager::with_cache(HostingCache::new(
+            200,
+            Duration::ZERO,
+            InstantTimeSrc::new(),
+        ));
+        let watched = make_contract_key(1);
+        let unwatched = make_contract_key(2);
+        manager.add_client_subscription(watched.id(), crate::client_events::ClientId::next());
+
+        manager.record_contract_access(watched, 100, AccessType::Get);
+        manager.record_contract_access(unwatched, 100, AccessType::Get);
+        let result = manager.record_contract_access(make_contract_key(3), 200, AccessType::Get);
+        assert_eq!(result.evicted.len(), 2);
+
+        let evictions = manager.take_client_evictions();
+        assert_eq!(evictions.len(), 1);
+        assert_eq!(evictions[0].key, watched);
+        assert!(manager.take_client_evictions().is_empty());
+    }
+
     #[test]
     fn test_hosting_cache_basic() {
         let manager = HostingManager::new();
@@ -1307,6 +2321,84 @@ mod tests {
         assert_eq!(manager.hosting_contracts_count(), 1);
     }
 
+    #[cfg(feature = "redb")]
+    #[tokio::test]
+    async fn test_eviction_releases_subscription_and_state() {
+        use crate::wasm_runtime::StateStorage;
+        use freenet_stdlib::prelude::WrappedState;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let storage = crate::contract::storages::ReDb::new(temp_dir.path())
+            .await
+            .unwrap();
+        let evicted = make_contract_key(1);
+        let newcomer = make_contract_key(2);
+
+        // Room for one 150-byte contract, no TTL protection
+        let manager = HostingManager::with_cache(HostingCache::new(
+            200,
+            Duration::ZERO,
+            InstantTimeSrc::new(),
+        ));
+        manager.set_storage(storage.clone());
+        storage
+            .store(evicted, WrappedState::new(vec![1u8; 150]))
+            .await
+            .unwrap();
+        manager.record_contract_access(evicted, 150, AccessType::Put);
+        manager.subscribe(evicted);
+
+        let result = manager.record_contract_access(newcomer, 150, AccessType::Put);
+        assert_eq!(result.evicted, vec![evicted]);
+        assert!(!manager.is_subscribed(&evicted));
+        assert!(!manager.contracts_needing_renewal().contains(&evicted));
+        assert_eq!(storage.get_state_size(&evicted).unwrap(), None);
+        assert!(storage.get_hosting_metadata(&evicted).unwrap().is_none());
+    }
+
+    #[cfg(all(feature = "lepus", feature = "redb"))]
+    #[tokio::test]
+    async fn test_high_score_eviction_keeps_state_until_replicated() {
+        use crate::wasm_runtime::StateStorage;
+        use freenet_stdlib::prelude::WrappedState;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let storage = crate::contract::storages::ReDb::new(temp_dir.path())
+            .await
+            .unwrap();
+        let evicted = make_contract_key(1);
+        let newcomer = make_contract_key(2);
+
+        // Room for one 150-byte contract, every eviction hinted
+        let manager = HostingManager::with_cache(HostingCache::new_with_cwp(
+            200,
+            Duration::ZERO,
+            InstantTimeSrc::new(),
+            CWPConfig {
+                replication_hint_score: Some(0.0),
+                ..CWPConfig::default()
+            },
+        ));
+        manager.set_storage(storage.clone());
+        storage
+            .store(evicted, WrappedState::new(vec![1u8; 150]))
+            .await
+            .unwrap();
+        manager.record_contract_access(evicted, 150, AccessType::Put);
+
+        let result = manager.record_contract_access(newcomer, 150, AccessType::Put);
+        assert_eq!(result.evicted, vec![evicted]);
+        assert!(manager.is_replicating(&evicted));
+        assert_eq!(storage.get_state_size(&evicted).unwrap(), Some(150));
+        assert_eq!(manager.take_replication_hints(), vec![evicted]);
+        assert!(manager.take_replication_hints().is_empty());
+
+        manager.finish_replication(&evicted);
+        assert!(!manager.is_replicating(&evicted));
+        assert_eq!(storage.get_state_size(&evicted).unwrap(), None);
+        assert!(storage.get_hosting_metadata(&evicted).unwrap().is_none());
+    }
+
     #[test]
     fn test_subscription_backoff() {
         let manager = HostingManager::new();
@@ -1358,4 +2450,374 @@ mod tests {
             "Hosted contracts should need subscription renewal"
         );
     }
+
+    #[cfg(feature = "lepus")]
+    #[test]
+    fn test_renewals_sorted_by_persistence_score() {
+        let manager = HostingManager::new();
+        let funded = make_contract_key(1);
+        let unfunded = make_contract_key(2);
+        let not_hosted = make_contract_key(3);
+        manager.record_contract_access(funded, 1000, AccessType::Get);
+        manager.record_contract_access(unfunded, 1000, AccessType::Get);
+        manager.update_commitments_batch(&[(funded, 10)], Instant::now());
+
+        let mut contracts = vec![not_hosted, unfunded, funded];
+        manager.sort_by_persistence_score(&mut contracts);
+        assert_eq!(contracts, vec![funded, unfunded, not_hosted]);
+    }
+
+    #[cfg(feature = "lepus")]
+    #[test]
+    fn test_known_identity_and_commitment_outlive_hosting() {
+        // Room for one 150-byte contract
+        let manager = HostingManager::with_cache(HostingCache::new_with_cwp(
+            200,
+            Duration::ZERO,
+            InstantTimeSrc::new(),
+            CWPConfig::default(),
+        ));
+        let funded = make_contract_key(1);
+        let newcomer = make_contract_key(2);
+        manager.record_contract_access(funded, 150, AccessType::Put);
+        manager.update_commitments_batch(&[(funded, 10)], Instant::now());
+
+        // Learned while the newcomer isn't hosted yet
+        assert_eq!(
+            manager.update_commitments_batch(&[(newcomer, 20)], Instant::now()),
+            0
+        );
+        assert!(!manager.update_subscriber_identity(&newcomer, &[3u8; 32]));
+
+        let result = manager.record_contract_access(newcomer, 150, AccessType::Put);
+        assert_eq!(result.evicted, vec![funded]);
+        {
+            let cache = manager.hosting_cache.shard(newcomer.id()).read();
+            let contract = cache.get(&newcomer).unwrap();
+            assert_eq!(contract.commitment.deposited_xlm, 20);
+            assert_eq!(contract.identity.subscriber_pubkey, Some([3u8; 32]));
+        }
+
+        // The evicted contract gets its deposit back when hosted again
+        manager.record_contract_access(funded, 150, AccessType::Put);
+        let cache = manager.hosting_cache.shard(funded.id()).read();
+        assert_eq!(cache.get(&funded).unwrap().commitment.deposited_xlm, 10);
+    }
+
+    #[cfg(feature = "lepus")]
+    #[test]
+    fn test_committed_evictions_queued_for_alerts() {
+        // Room for two 150-byte contracts
+        let manager = HostingManager::with_cache(HostingCache::new_with_cwp(
+            300,
+            Duration::ZERO,
+            InstantTimeSrc::new(),
+            CWPConfig::default(),
+        ));
+        let funded = make_contract_key(1);
+        let unfunded = make_contract_key(2);
+        manager.record_contract_access(funded, 150, AccessType::Put);
+        manager.record_contract_access(unfunded, 150, AccessType::Put);
+        manager.update_commitments_batch(&[(funded, 10)], Instant::now());
+
+        let result = manager.record_contract_access(make_contract_key(3), 300, AccessType::Put);
+        assert_eq!(result.evicted.len(), 2);
+
+        let alerts = manager.take_committed_evictions();
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].key, funded);
+        assert_eq!(alerts[0].commitment.deposited_xlm, 10);
+        assert!(manager.take_committed_evictions().is_empty());
+    }
+
+    #[cfg(feature = "lepus")]
+    #[test]
+    fn test_identity_funded_by_contract_hosted_elsewhere() {
+        let manager = HostingManager::new();
+        let pubkey = [7u8; 32];
+        let remote = make_contract_key(1);
+        assert!(!manager.update_subscriber_identity(&remote, &pubkey));
+        assert!(!manager.is_identity_funded(&pubkey));
+
+        let snapshot = |deposited| {
+            deposit_index::DepositSnapshot::new(deposit_index::DepositMap {
+                version: 1,
+                last_ledger_seq: 100,
+                deposits: vec![deposit_index::DepositEntry {
+                    contract_id: hex::encode(remote.id().as_bytes()),
+                    total_deposited: deposited,
+                    total_usd: 0,
+                    last_ledger: 100,
+                    stream: None,
+                }],
+            })
+        };
+        manager.set_deposit_snapshot(snapshot(0));
+        assert!(!manager.is_identity_funded(&pubkey));
+
+        manager.set_deposit_snapshot(snapshot(500));
+        assert!(manager.is_identity_funded(&pubkey));
+        assert!(!manager.is_identity_funded(&[8u8; 32]));
+    }
+
+    #[cfg(all(feature = "lepus", feature = "redb"))]
+    #[tokio::test]
+    async fn test_lepus_state_survives_reload() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let storage = crate::contract::storages::ReDb::new(temp_dir.path())
+            .await
+            .unwrap();
+        let contract = make_contract_key(1);
+
+        storage.store_state_sync(&contract, &[0u8; 2048]).unwrap();
+
+        let manager = HostingManager::new();
+        manager.set_storage(storage.clone());
+        manager.record_contract_access(contract, 2048, AccessType::Put);
+        manager.update_commitments_batch(&[(contract, 10)], Instant::now());
+        manager.update_subscriber_identity(&contract, &[2u8; 32]);
+        // Counters are written with the next access
+        manager.record_bytes_served(&contract, None, 5000);
+        manager.record_bytes_consumed(&contract, 2000);
+        manager.record_contract_access(contract, 2048, AccessType::Get);
+
+        let restarted = HostingManager::new();
+        let loaded = restarted.load_from_storage(&storage, |_| None).unwrap();
+        assert_eq!(loaded, 1);
+
+        let cache = restarted.hosting_cache.shard(contract.id()).read();
+        let hosted = cache.get(&contract).unwrap();
+        assert_eq!(hosted.commitment.deposited_xlm, 10);
+        assert!(hosted.commitment.last_oracle_check.is_some());
+        assert_eq!(hosted.identity.subscriber_pubkey, Some([2u8; 32]));
+        assert_eq!(hosted.bytes_served, 5000);
+        assert_eq!(hosted.bytes_consumed, 2000);
+    }
+
+    #[cfg(feature = "redb")]
+    #[tokio::test]
+    async fn test_load_reconciles_with_contract_store() {
+        use crate::wasm_runtime::StateStorage;
+        use freenet_stdlib::prelude::WrappedState;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let storage = crate::contract::storages::ReDb::new(temp_dir.path())
+            .await
+            .unwrap();
+        let hosted = make_contract_key(1);
+        let orphan = make_contract_key(2);
+        let stateless = make_contract_key(3);
+
+        // Metadata recorded 1000 bytes, but only 600 made it to the store
+        storage
+            .store(hosted, WrappedState::new(vec![0u8; 600]))
+            .await
+            .unwrap();
+        storage
+            .store(orphan, WrappedState::new(vec![0u8; 300]))
+            .await
+            .unwrap();
+        storage.remove_hosting_metadata(&orphan).unwrap();
+        let manager = HostingManager::new();
+        manager.set_storage(storage.clone());
+        manager.record_contract_access(hosted, 1000, AccessType::Put);
+        manager.record_contract_access(stateless, 500, AccessType::Put);
+
+        let restarted = HostingManager::new();
+        let loaded = restarted
+            .load_from_storage(&storage, |id| {
+                (id == orphan.id()).then(|| CodeHash::new([3u8; 32]))
+            })
+            .unwrap();
+        assert_eq!(loaded, 2);
+
+        assert!(!restarted.is_hosting_contract(&stateless));
+        assert!(storage.get_hosting_metadata(&stateless).unwrap().is_none());
+        assert!(storage.get_hosting_metadata(&orphan).unwrap().is_some());
+
+        let cache = restarted.hosting_cache.shard(orphan.id()).read();
+        assert_eq!(cache.current_bytes(), 900);
+        // The orphan is first in line for eviction
+        assert_eq!(cache.keys_lru_order(), vec![orphan, hosted]);
+    }
+
+    #[cfg(all(feature = "lepus", feature = "redb"))]
+    #[tokio::test]
+    async fn test_evicted_contract_rehydrates_from_cold_tier() {
+        use crate::contract::storages::ReDb;
+        use crate::wasm_runtime::StateStorage;
+        use freenet_stdlib::prelude::WrappedState;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let storage = ReDb::new(temp_dir.path()).await.unwrap();
+        let evicted = make_contract_key(1);
+        let newcomer = make_contract_key(2);
+        let state = vec![1u8; 150];
+
+        // Room for one 150-byte contract, no TTL protection
+        let manager = HostingManager::with_cache(HostingCache::new(
+            200,
+            Duration::ZERO,
+            InstantTimeSrc::new(),
+        ));
+        manager.set_storage(storage.clone());
+        manager.set_cold_tier(
+            cold_tier::ColdTier::open(temp_dir.path().join(cold_tier::COLD_TIER_DIR), 1024 * 1024)
+                .unwrap(),
+        );
+
+        storage
+            .store(evicted, WrappedState::new(state.clone()))
+            .await
+            .unwrap();
+        manager.record_contract_access(evicted, 150, AccessType::Put);
+        manager.update_commitments_batch(&[(evicted, 10)], Instant::now());
+
+        let result = manager.record_contract_access(newcomer, 150, AccessType::Put);
+        assert_eq!(result.evicted, vec![evicted]);
+        assert!(storage.get_hosting_metadata(&evicted).unwrap().is_none());
+        // Nothing is archived for a contract without stored state
+        assert_eq!(manager.rehydrate_from_cold_tier(newcomer.id()), None);
+
+        // Rehydrate into a store that has lost the hot copy
+        let fresh_dir = tempfile::tempdir().unwrap();
+        let fresh = ReDb::new(fresh_dir.path()).await.unwrap();
+        manager.set_storage(fresh.clone());
+        assert_eq!(
+            manager.rehydrate_from_cold_tier(evicted.id()),
+            Some(evicted)
+        );
+
+        assert_eq!(
+            fresh.get_state_sync(&evicted).unwrap().unwrap().as_ref(),
+            state.as_slice()
+        );
+        assert!(manager.is_hosting_contract(&evicted));
+        assert!(!manager.is_hosting_contract(&newcomer));
+        let cache = manager.hosting_cache.shard(evicted.id()).read();
+        assert_eq!(cache.get(&evicted).unwrap().commitment.deposited_xlm, 10);
+        drop(cache);
+        let metadata = fresh.get_hosting_metadata(&evicted).unwrap().unwrap();
+        assert_eq!(metadata.lepus.unwrap().deposited_xlm, 10);
+        // The archive is consumed
+        assert_eq!(manager.rehydrate_from_cold_tier(evicted.id()), None);
+    }
+
+    #[cfg(feature = "lepus")]
+    #[test]
+    fn test_reload_cwp_config_rejects_invalid() {
+        let manager = HostingManager::new();
+        let tuned = CWPConfig {
+            commitment_weight: 0.40,
+            recency_weight: 0.20,
+            ..Default::default()
+        };
+        let previous = manager.reload_cwp_config(tuned.clone()).unwrap();
+        assert_eq!(previous, CWPConfig::default());
+        assert_eq!(manager.cwp_config(), tuned);
+
+        let invalid = CWPConfig {
+            commitment_weight: 0.90,
+            ..Default::default()
+        };
+        assert!(manager.reload_cwp_config(invalid).is_err());
+        assert_eq!(manager.cwp_config(), tuned);
+    }
+
+    #[cfg(feature = "lepus")]
+    #[test]
+    fn test_revoke_creators_drops_verification() {
+        let manager = HostingManager::new();
+        let key = make_contract_key(1);
+        let other = make_contract_key(2);
+        manager.record_contract_access(key, 100, AccessType::Put);
+        manager.record_contract_access(other, 100, AccessType::Put);
+
+        let revoked_key = ed25519_dalek::SigningKey::from_bytes(&[44u8; 32]);
+        let kept_key = ed25519_dalek::SigningKey::from_bytes(&[45u8; 32]);
+        let signed = identity::make_test_state(&revoked_key, [0u8; 32], b"revoked");
+        assert!(manager.verify_and_update_identity(&key, &signed));
+        let signed = identity::make_test_state(&kept_key, [0u8; 32], b"kept");
+        assert!(manager.verify_and_update_identity(&other, &signed));
+
+        manager.revoke_creators(&[revoked_key.verifying_key().to_bytes()]);
+        let report = manager.score_report(key.id()).unwrap();
+        assert!(!report.creator_verified);
+        assert!(report.creator_pubkey.is_some());
+        assert_eq!(report.envelope_forgeries, 0);
+        assert!(manager.score_report(other.id()).unwrap().creator_verified);
+    }
+
+    #[cfg(feature = "lepus")]
+    #[test]
+    fn test_reverification_downgrades_identity() {
+        let manager = HostingManager::new();
+        let key = make_contract_key(1);
+        manager.record_contract_access(key, 100, AccessType::Put);
+        let creator_verified =
+            |manager: &HostingManager| manager.score_report(key.id()).unwrap().creator_verified;
+
+        let signing_key = ed25519_dalek::SigningKey::from_bytes(&[42u8; 32]);
+        let signed = identity::make_test_state(&signing_key, [0u8; 32], b"v1");
+        assert!(manager.verify_and_update_identity(&key, &signed));
+        assert!(creator_verified(&manager));
+
+        // An update whose payload no longer matches the signature
+        let mut tampered = identity::make_test_state(&signing_key, [0u8; 32], b"v2");
+        *tampered.last_mut().unwrap() ^= 0xff;
+        manager.verify_and_update_identity(&key, &tampered);
+        let report = manager.score_report(key.id()).unwrap();
+        assert!(!report.creator_verified);
+        assert!(report.creator_pubkey.is_some());
+        assert_eq!(report.envelope_forgeries, 1);
+
+        assert!(manager.verify_and_update_identity(&key, &signed));
+        assert!(creator_verified(&manager));
+
+        // An update that drops the envelope altogether
+        manager.verify_and_update_identity(&key, b"plain state");
+        let report = manager.score_report(key.id()).unwrap();
+        assert!(!report.creator_verified);
+        assert_eq!(report.creator_pubkey, None);
+    }
+
+    #[cfg(feature = "lepus")]
+    #[test]
+    fn test_evictions_are_logged() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let evicted = make_contract_key(1);
+        let newcomer = make_contract_key(2);
+
+        let manager = HostingManager::with_cache(HostingCache::new(
+            200,
+            Duration::ZERO,
+            InstantTimeSrc::new(),
+        ));
+        manager.set_eviction_log(
+            eviction_log::EvictionLog::open(
+                temp_dir.path().join(eviction_log::EVICTION_LOG_FILE),
+                100,
+            )
+            .unwrap(),
+        );
+        manager.record_contract_access(evicted, 150, AccessType::Put);
+        manager.record_contract_access(newcomer, 150, AccessType::Put);
+
+        let records = manager.query_eviction_log(&EvictionLogQuery::default());
+        assert_eq!(records.len(), 1);
+        let record = &records[0];
+        assert_eq!(record.key, evicted.to_string());
+        assert_eq!(record.reason, "capacity");
+        assert_eq!(record.size_bytes, 150);
+        assert_eq!(record.used_bytes, 150);
+        assert_eq!(record.budget_bytes, 200);
+        assert!(record.breakdown.recency > 0.0);
+        assert!(record.timestamp_ms > 0);
+
+        let query = EvictionLogQuery {
+            key: Some(newcomer.to_string()),
+            ..Default::default()
+        };
+        assert!(manager.query_eviction_log(&query).is_empty());
+    }
 }
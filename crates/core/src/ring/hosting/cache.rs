@@ -11,19 +11,31 @@
 //! 3. **Subscription renewal**: All hosted contracts get subscription renewal
 //! 4. **Access type tracking**: Records how contract was accessed (GET/PUT/SUBSCRIBE)
 
+#[cfg(feature = "lepus")]
+use freenet_stdlib::prelude::ContractInstanceId;
 use freenet_stdlib::prelude::ContractKey;
-use std::collections::{HashMap, VecDeque};
+use std::collections::HashMap;
 use std::time::Duration;
 use tokio::time::Instant;
 
 use crate::util::time_source::TimeSource;
 
+use super::metrics::HostingOccupancy;
+
+#[cfg(feature = "lepus")]
+use crate::ring::PeerKey;
 #[cfg(feature = "lepus")]
 use ordered_float::OrderedFloat;
+#[cfg(feature = "lepus")]
+use std::collections::{BTreeMap, HashSet, VecDeque};
 
 /// Default hosting cache budget: 100MB
 pub const DEFAULT_HOSTING_BUDGET_BYTES: u64 = 100 * 1024 * 1024;
 
+/// Default high and low eviction watermark, as a fraction of the byte budget:
+/// evict exactly down to budget when it would be exceeded.
+pub const DEFAULT_EVICTION_WATERMARK: f64 = 1.0;
+
 /// Multiplier for TTL relative to subscription renewal interval.
 /// Gives this many renewal attempts before eviction if renewals keep failing.
 pub const TTL_RENEWAL_MULTIPLIER: u32 = 4;
@@ -34,6 +46,98 @@ pub const DEFAULT_MIN_TTL: Duration = Duration::from_secs(
     super::SUBSCRIPTION_RENEWAL_INTERVAL.as_secs() * TTL_RENEWAL_MULTIPLIER as u64,
 );
 
+/// Neighbours of a contract in the LRU list.
+#[derive(Debug, Clone, Copy)]
+struct LruLink {
+    prev: Option<ContractKey>,
+    next: Option<ContractKey>,
+}
+
+/// Doubly linked LRU list threaded through a hash map, so moving a contract
+/// to the back or removing it from the middle is O(1).
+#[derive(Debug, Default)]
+struct LruList {
+    head: Option<ContractKey>,
+    tail: Option<ContractKey>,
+    links: HashMap<ContractKey, LruLink>,
+}
+
+impl LruList {
+    // Only the LRU eviction path walks the list from the front
+    #[cfg_attr(feature = "lepus", allow(dead_code))]
+    fn is_empty(&self) -> bool {
+        self.links.is_empty()
+    }
+
+    /// Least recently used contract.
+    #[cfg_attr(feature = "lepus", allow(dead_code))]
+    fn front(&self) -> Option<ContractKey> {
+        self.head
+    }
+
+    #[cfg_attr(feature = "lepus", allow(dead_code))]
+    fn pop_front(&mut self) -> Option<ContractKey> {
+        let key = self.head?;
+        self.remove(&key);
+        Some(key)
+    }
+
+    /// Append `key` as most recently used, moving it if already present.
+    fn push_back(&mut self, key: ContractKey) {
+        self.remove(&key);
+        let link = LruLink {
+            prev: self.tail,
+            next: None,
+        };
+        match self.tail {
+            Some(tail) => {
+                if let Some(tail_link) = self.links.get_mut(&tail) {
+                    tail_link.next = Some(key);
+                }
+            }
+            None => self.head = Some(key),
+        }
+        self.tail = Some(key);
+        self.links.insert(key, link);
+    }
+
+    /// Unlink `key`. Returns `true` if it was in the list.
+    fn remove(&mut self, key: &ContractKey) -> bool {
+        let Some(link) = self.links.remove(key) else {
+            return false;
+        };
+        match link.prev {
+            Some(prev) => {
+                if let Some(prev_link) = self.links.get_mut(&prev) {
+                    prev_link.next = link.next;
+                }
+            }
+            None => self.head = link.next,
+        }
+        match link.next {
+            Some(next) => {
+                if let Some(next_link) = self.links.get_mut(&next) {
+                    next_link.prev = link.prev;
+                }
+            }
+            None => self.tail = link.prev,
+        }
+        true
+    }
+
+    fn clear(&mut self) {
+        self.head = None;
+        self.tail = None;
+        self.links.clear();
+    }
+
+    /// Iterate from least to most recently used.
+    #[cfg_attr(feature = "lepus", allow(dead_code))]
+    fn iter(&self) -> impl Iterator<Item = ContractKey> + '_ {
+        std::iter::successors(self.head, |key| self.links.get(key)?.next)
+    }
+}
+
 // =============================================================================
 // CWP (Commitment-Weighted Persistence) — Lepus Feature
 // =============================================================================
@@ -41,11 +145,16 @@ pub const DEFAULT_MIN_TTL: Duration = Duration::from_secs(
 /// Scoring weights and normalization targets for CWP eviction.
 ///
 /// CWP replaces LRU eviction with a weighted persistence score:
-///   score = w_c * commitment + w_i * identity + w_n * contribution + w_r * recency
+///   score = (w_c * commitment + w_i * identity + w_n * contribution + w_r * recency
+///            + w_s * subscription - w_p * penalty) * size_factor
 ///
 /// Higher scores survive eviction longer.
+///
+/// Read from the `[lepus.cwp]` section of the node configuration; omitted
+/// fields keep their defaults.
 #[cfg(feature = "lepus")]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
 pub struct CWPConfig {
     /// Weight for commitment (Soroban deposit) factor.
     pub commitment_weight: f64,
@@ -59,8 +168,83 @@ pub struct CWPConfig {
     pub commitment_density_target: f64,
     /// Target contribution ratio at which contribution score saturates.
     pub contribution_target: f64,
+    /// Length in seconds of the buckets that bytes served and consumed are
+    /// counted in for the contribution sub-score.
+    pub contribution_bucket_secs: f64,
+    /// Seconds of traffic the contribution sub-score looks back over. Each
+    /// bucket counts less as it ages and drops out once this old.
+    pub contribution_window_secs: f64,
     /// Half-life in seconds for recency decay. Score = 0.5 after this many seconds.
     pub recency_halflife_secs: f64,
+    /// Half-life in seconds for commitment staleness: the commitment sub-score
+    /// is halved once the oracle hasn't re-confirmed the deposit for this long.
+    pub commitment_staleness_halflife_secs: f64,
+    /// Maximum hosted bytes attributed to one creator identity. Unlimited when unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_bytes_per_creator: Option<u64>,
+    /// Maximum hosted contracts attributed to one creator identity. Unlimited when unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_contracts_per_creator: Option<usize>,
+    /// Seconds a contract stays protected from budget eviction after its
+    /// creator envelope verifies, while the oracle has yet to confirm a
+    /// deposit. 0.0 disables the warm-up.
+    pub commitment_warmup_secs: f64,
+    /// Seconds during which an access carrying a state the contract was
+    /// already accessed with doesn't refresh its TTL or LRU position, so
+    /// re-sending the same few states can't keep it hosted. 0.0 disables the
+    /// cooldown.
+    pub state_refresh_cooldown_secs: f64,
+    /// Seconds a lower deposit reported by the oracle must persist before it
+    /// replaces the current one, so a deposit briefly reported as lower
+    /// (e.g. during a withdrawal race) doesn't crater the score. Increases
+    /// apply immediately. 0.0 applies decreases immediately too.
+    pub commitment_decrease_confirmation_secs: f64,
+    /// Fraction of the byte budget reserved for committed contracts (those
+    /// with a deposit), the rest going to uncommitted ones. Eviction takes
+    /// victims from whichever class is over its share. One shared pool when
+    /// unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub committed_budget_share: Option<f64>,
+    /// Largest fraction of a contract's bytes served that may come from a
+    /// single remote peer; bytes served to one peer beyond it don't count
+    /// towards contribution. 1.0 disables the cap.
+    pub max_peer_contribution_share: f64,
+    /// Weight of the live client subscription bonus. Like the penalty, kept
+    /// outside the four weights that sum to 1.0.
+    pub subscription_weight: f64,
+    /// Client subscriptions at which the subscription sub-score saturates.
+    pub subscription_target: f64,
+    /// Weight of the misbehavior penalty subtracted from the score. Kept
+    /// outside the four weights that sum to 1.0.
+    pub penalty_weight: f64,
+    /// Penalty events (validation failures, with forgeries counting
+    /// `FORGERY_PENALTY_EVENTS` each) at which the penalty saturates.
+    pub penalty_saturation: f64,
+    /// Score each verified contract of a creator on the creator's total
+    /// verified deposits, spread across its hosted contracts by size, instead
+    /// of on the contract's own deposit.
+    pub aggregate_creator_commitment: bool,
+    /// How strongly large contracts are penalized: 0.0 ignores size, 1.0
+    /// scales the score by `1 - size / (size + size_reference_bytes)`.
+    pub size_penalty_weight: f64,
+    /// Contract size at which the size penalty reaches half its weight.
+    pub size_reference_bytes: f64,
+    /// Fraction of the byte budget at which eviction starts.
+    pub eviction_high_watermark: f64,
+    /// Fraction of the byte budget that eviction frees space down to once
+    /// started. Below `eviction_high_watermark`, evictions happen in batches
+    /// instead of one per insert when hovering at capacity.
+    pub eviction_low_watermark: f64,
+    /// Contracts evicted with at least this persistence score are first
+    /// pushed towards the peers closest to their location, and their stored
+    /// state is only dropped once that push finishes. Disabled when unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replication_hint_score: Option<f64>,
+    /// PUTs tried per contract when pushing it before eviction.
+    pub replication_hint_attempts: usize,
+    /// Contracts whose identity and commitment are remembered while they
+    /// aren't hosted, least recently updated dropped first.
+    pub known_contracts_capacity: usize,
 }
 
 #[cfg(feature = "lepus")]
@@ -73,25 +257,369 @@ impl Default for CWPConfig {
             recency_weight: 0.10,
             commitment_density_target: 0.001,
             contribution_target: 1.5,
-            recency_halflife_secs: 604_800.0, // 7 days
+            contribution_bucket_secs: 86_400.0,    // 1 day
+            contribution_window_secs: 2_592_000.0, // 30 days
+            recency_halflife_secs: 604_800.0,      // 7 days
+            commitment_staleness_halflife_secs: 2_592_000.0, // 30 days
+            max_bytes_per_creator: None,
+            max_contracts_per_creator: None,
+            commitment_warmup_secs: 0.0,
+            state_refresh_cooldown_secs: 0.0,
+            commitment_decrease_confirmation_secs: 600.0,
+            committed_budget_share: None,
+            max_peer_contribution_share: 1.0,
+            aggregate_creator_commitment: false,
+            subscription_weight: 0.05,
+            subscription_target: 10.0,
+            penalty_weight: 0.15,
+            penalty_saturation: 5.0,
+            size_penalty_weight: 0.0,
+            size_reference_bytes: 1_048_576.0, // 1 MiB
+            eviction_high_watermark: DEFAULT_EVICTION_WATERMARK,
+            eviction_low_watermark: DEFAULT_EVICTION_WATERMARK,
+            replication_hint_score: None,
+            replication_hint_attempts: 3,
+            known_contracts_capacity: 10_000,
+        }
+    }
+}
+
+/// How far the four CWP weights may sum away from 1.0.
+#[cfg(feature = "lepus")]
+pub const CWP_WEIGHT_SUM_TOLERANCE: f64 = 0.01;
+
+#[cfg(feature = "lepus")]
+impl CWPConfig {
+    /// Check that every setting is in range:
+    ///
+    /// - the four main weights are non-negative and sum to ~1.0
+    /// - normalization targets, half-lives, the contribution window and any
+    ///   creator quotas are positive
+    /// - contribution buckets fit in the contribution window
+    /// - the subscription and penalty weights, commitment warm-up, state
+    ///   refresh cooldown and commitment decrease confirmation window are
+    ///   non-negative
+    /// - the size penalty weight is in [0, 1]
+    /// - the per-peer contribution share is in (0, 1]
+    /// - any committed budget share is in (0, 1)
+    /// - `0 < eviction_low_watermark <= eviction_high_watermark <= 1`
+    /// - any replication hint score is finite, and the replication hint
+    ///   attempts and known contracts capacity are positive
+    pub fn validate(&self) -> anyhow::Result<()> {
+        let weights = [
+            ("commitment-weight", self.commitment_weight),
+            ("identity-weight", self.identity_weight),
+            ("contribution-weight", self.contribution_weight),
+            ("recency-weight", self.recency_weight),
+        ];
+        for (name, weight) in weights {
+            if !weight.is_finite() || weight < 0.0 {
+                anyhow::bail!("lepus.cwp.{name} must be a non-negative number, got {weight}");
+            }
+        }
+        let sum: f64 = weights.iter().map(|(_, weight)| weight).sum();
+        if (sum - 1.0).abs() > CWP_WEIGHT_SUM_TOLERANCE {
+            anyhow::bail!("lepus.cwp weights must sum to 1.0, got {sum}");
+        }
+        let targets = [
+            ("commitment-density-target", self.commitment_density_target),
+            ("contribution-target", self.contribution_target),
+            ("contribution-bucket-secs", self.contribution_bucket_secs),
+            ("contribution-window-secs", self.contribution_window_secs),
+            ("recency-halflife-secs", self.recency_halflife_secs),
+            (
+                "commitment-staleness-halflife-secs",
+                self.commitment_staleness_halflife_secs,
+            ),
+            ("size-reference-bytes", self.size_reference_bytes),
+            ("penalty-saturation", self.penalty_saturation),
+            ("subscription-target", self.subscription_target),
+        ];
+        for (name, value) in targets {
+            if !value.is_finite() || value <= 0.0 {
+                anyhow::bail!("lepus.cwp.{name} must be positive, got {value}");
+            }
+        }
+        if self.contribution_bucket_secs > self.contribution_window_secs {
+            anyhow::bail!(
+                "lepus.cwp.contribution-bucket-secs must not exceed contribution-window-secs, got {}",
+                self.contribution_bucket_secs
+            );
+        }
+        if self.max_bytes_per_creator == Some(0) {
+            anyhow::bail!("lepus.cwp.max-bytes-per-creator must be positive when set");
+        }
+        if self.max_contracts_per_creator == Some(0) {
+            anyhow::bail!("lepus.cwp.max-contracts-per-creator must be positive when set");
+        }
+        if let Some(share) = self.committed_budget_share {
+            if !share.is_finite() || share <= 0.0 || share >= 1.0 {
+                anyhow::bail!("lepus.cwp.committed-budget-share must be in (0, 1), got {share}");
+            }
+        }
+        for (name, value) in [
+            ("subscription-weight", self.subscription_weight),
+            ("penalty-weight", self.penalty_weight),
+            ("commitment-warmup-secs", self.commitment_warmup_secs),
+            (
+                "state-refresh-cooldown-secs",
+                self.state_refresh_cooldown_secs,
+            ),
+            (
+                "commitment-decrease-confirmation-secs",
+                self.commitment_decrease_confirmation_secs,
+            ),
+        ] {
+            if !value.is_finite() || value < 0.0 {
+                anyhow::bail!("lepus.cwp.{name} must be a non-negative number, got {value}");
+            }
+        }
+        let size_penalty = self.size_penalty_weight;
+        if !size_penalty.is_finite() || !(0.0..=1.0).contains(&size_penalty) {
+            anyhow::bail!("lepus.cwp.size-penalty-weight must be in [0, 1], got {size_penalty}");
+        }
+        let share = self.max_peer_contribution_share;
+        if !share.is_finite() || share <= 0.0 || share > 1.0 {
+            anyhow::bail!("lepus.cwp.max-peer-contribution-share must be in (0, 1], got {share}");
+        }
+        let (high, low) = (self.eviction_high_watermark, self.eviction_low_watermark);
+        if !high.is_finite() || high <= 0.0 || high > 1.0 {
+            anyhow::bail!("lepus.cwp.eviction-high-watermark must be in (0, 1], got {high}");
+        }
+        if !low.is_finite() || low <= 0.0 || low > high {
+            anyhow::bail!(
+                "lepus.cwp.eviction-low-watermark must be in (0, eviction-high-watermark], got {low}"
+            );
+        }
+        if let Some(score) = self.replication_hint_score {
+            if !score.is_finite() {
+                anyhow::bail!("lepus.cwp.replication-hint-score must be a number, got {score}");
+            }
+        }
+        if self.replication_hint_attempts == 0 {
+            anyhow::bail!("lepus.cwp.replication-hint-attempts must be positive");
+        }
+        if self.known_contracts_capacity == 0 {
+            anyhow::bail!("lepus.cwp.known-contracts-capacity must be positive");
+        }
+        Ok(())
+    }
+}
+
+/// Fraction of the shortest of `recency_halflife_secs`,
+/// `commitment_staleness_halflife_secs` and `contribution_window_secs` after
+/// which the CWP score index is fully rescored. Recency, commitment staleness
+/// and contribution decay are the only inputs that change without a
+/// mutation, so between rebuilds the indexed order can differ from a full
+/// scan only through drift of about
+/// `(recency_weight + commitment_weight + contribution_weight) * SCORE_INDEX_REFRESH_FRACTION`.
+#[cfg(feature = "lepus")]
+const SCORE_INDEX_REFRESH_FRACTION: f64 = 0.01;
+
+/// Eviction order of the score index: lowest score, then oldest access,
+/// then key bytes (the same tie-breaking as a full scan).
+#[cfg(feature = "lepus")]
+type ScoreIndexKey = (OrderedFloat<f64>, Instant, [u8; 32]);
+
+/// Priority index over CWP persistence scores.
+///
+/// Keeps hosted contracts sorted by score so eviction picks the lowest-scoring
+/// eligible contract without scanning the whole cache. Entries are rescored
+/// lazily: mutations that affect a contract's score mark it dirty, and dirty
+/// entries are reindexed (O(log n) each) before the next victim lookup. All
+/// entries are scored as of the same reference instant so they stay
+/// comparable; the whole index is rebuilt at a new reference instant once
+/// `SCORE_INDEX_REFRESH_FRACTION` of the half-life has passed.
+#[cfg(feature = "lepus")]
+#[derive(Default)]
+struct ScoreIndex {
+    order: BTreeMap<ScoreIndexKey, ContractKey>,
+    positions: HashMap<ContractKey, ScoreIndexKey>,
+    dirty: HashSet<ContractKey>,
+    /// Instant all indexed scores are computed at; `None` forces a full rebuild.
+    rebuilt_at: Option<Instant>,
+}
+
+#[cfg(feature = "lepus")]
+impl ScoreIndex {
+    fn mark_dirty(&mut self, key: ContractKey) {
+        self.dirty.insert(key);
+    }
+
+    /// Rescore every contract on the next refresh.
+    fn invalidate(&mut self) {
+        self.rebuilt_at = None;
+    }
+
+    fn remove(&mut self, key: &ContractKey) {
+        if let Some(position) = self.positions.remove(key) {
+            self.order.remove(&position);
+        }
+        self.dirty.remove(key);
+    }
+
+    fn insert(
+        &mut self,
+        key: ContractKey,
+        contract: &HostedContract,
+        scored_at: Instant,
+        config: &CWPConfig,
+    ) {
+        let position = (
+            OrderedFloat(contract.persistence_score(scored_at, config)),
+            contract.last_accessed,
+            <[u8; 32]>::try_from(key.id().as_bytes()).unwrap_or_default(),
+        );
+        if let Some(previous) = self.positions.insert(key, position) {
+            self.order.remove(&previous);
+        }
+        self.order.insert(position, key);
+    }
+
+    /// Bring the index up to date with `contracts` as of `now`.
+    fn refresh(
+        &mut self,
+        contracts: &HashMap<ContractKey, HostedContract>,
+        now: Instant,
+        config: &CWPConfig,
+    ) {
+        let max_age = config
+            .recency_halflife_secs
+            .min(config.commitment_staleness_halflife_secs)
+            .min(config.contribution_window_secs)
+            * SCORE_INDEX_REFRESH_FRACTION;
+        let reference = match self.rebuilt_at {
+            Some(rebuilt_at)
+                if now.saturating_duration_since(rebuilt_at).as_secs_f64() < max_age =>
+            {
+                rebuilt_at
+            }
+            _ => {
+                self.order.clear();
+                self.positions.clear();
+                self.dirty.clear();
+                for (key, contract) in contracts {
+                    self.insert(*key, contract, now, config);
+                }
+                self.rebuilt_at = Some(now);
+                return;
+            }
+        };
+        for key in std::mem::take(&mut self.dirty) {
+            match contracts.get(&key) {
+                Some(contract) => self.insert(key, contract, reference, config),
+                None => self.remove(&key),
+            }
         }
     }
 }
 
+/// Penalty events one envelope forgery counts as. A forged signature is
+/// deliberate, unlike an update that merely fails validation.
+#[cfg(feature = "lepus")]
+pub const FORGERY_PENALTY_EVENTS: u32 = 3;
+
+/// Remote peers tracked per contract for `max_peer_contribution_share`.
+/// Bytes served to further peers are counted without a cap, since reaching
+/// them already takes this many distinct peers.
+#[cfg(feature = "lepus")]
+pub const MAX_TRACKED_PEERS_PER_CONTRACT: usize = 32;
+
+/// Distinct state hashes remembered per hosted contract for the state
+/// refresh cooldown.
+#[cfg(feature = "lepus")]
+pub const STATE_HISTORY_LEN: usize = 8;
+
+/// Hosted bytes and contracts attributed to one creator identity, used to
+/// enforce `max_bytes_per_creator` / `max_contracts_per_creator`.
+#[cfg(feature = "lepus")]
+#[derive(Debug, Default)]
+struct CreatorUsage {
+    bytes: u64,
+    contracts: HashSet<ContractKey>,
+}
+
+/// Hosted bytes per budget partition while an eviction is in progress, with
+/// `committed_budget_share` set.
+#[cfg(feature = "lepus")]
+#[derive(Debug, Clone, Copy)]
+struct PartitionUsage {
+    committed: u64,
+    uncommitted: u64,
+    /// Bytes reserved for committed contracts.
+    committed_limit: u64,
+    /// Bytes reserved for uncommitted contracts.
+    uncommitted_limit: u64,
+}
+
+#[cfg(feature = "lepus")]
+impl PartitionUsage {
+    /// The class the next victim must come from: uncommitted if over its
+    /// share, else committed if over its share. `None` when neither is, and
+    /// eviction falls back to the lowest score overall.
+    fn over_share(&self) -> Option<bool> {
+        if self.uncommitted > self.uncommitted_limit {
+            Some(false)
+        } else if self.committed > self.committed_limit {
+            Some(true)
+        } else {
+            None
+        }
+    }
+
+    fn release(&mut self, committed: bool, size_bytes: u64) {
+        let bytes = if committed {
+            &mut self.committed
+        } else {
+            &mut self.uncommitted
+        };
+        *bytes = bytes.saturating_sub(size_bytes);
+    }
+}
+
 /// Placeholder for Soroban commitment state (Phase 2).
 #[cfg(feature = "lepus")]
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq)]
 #[allow(dead_code)] // Phase 2 placeholder — fields populated by Oracle
 pub struct CommitmentState {
     /// Deposited XLM (in stroops or smallest unit).
     pub deposited_xlm: u64,
     /// Last time the Oracle verified this deposit.
     pub last_oracle_check: Option<Instant>,
+    /// This contract's share of its creator's deposits, scored instead of
+    /// its own deposit when `aggregate_creator_commitment` is set.
+    pub pooled: Option<PooledCommitment>,
+    /// When the creator envelope verified before any oracle check, starting
+    /// the `commitment_warmup_secs` window. Cleared once the oracle reports.
+    pub warmup_started: Option<Instant>,
+    /// A lower deposit waiting out `commitment_decrease_confirmation_secs`
+    /// before it replaces `deposited_xlm`.
+    pub pending_decrease: Option<PendingDecrease>,
+}
+
+/// A lower deposit reported by the oracle and not yet confirmed.
+#[cfg(feature = "lepus")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PendingDecrease {
+    /// Most recently reported deposit.
+    pub deposited_xlm: u64,
+    /// When the oracle first reported a deposit below `deposited_xlm`.
+    pub since: Instant,
+}
+
+/// A creator's verified deposits attributed to one of its contracts, in
+/// proportion to the contract's share of the creator's hosted bytes.
+#[cfg(feature = "lepus")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PooledCommitment {
+    pub deposited_xlm: u64,
+    /// Most recent oracle check of any of the creator's funded contracts.
+    pub last_oracle_check: Option<Instant>,
 }
 
 /// Placeholder for identity verification state (Phase 3).
 #[cfg(feature = "lepus")]
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq)]
 #[allow(dead_code)] // Phase 3 placeholder — fields populated by identity verifier
 pub struct IdentityState {
     /// Creator's Ed25519 public key, if known.
@@ -107,6 +635,105 @@ pub struct IdentityState {
     pub recipient_pubkey: Option<[u8; 32]>,
 }
 
+#[cfg(feature = "lepus")]
+impl IdentityState {
+    /// Record a subscriber from the subscription handshake. It verifies if
+    /// it is the envelope's recipient or the content is public.
+    pub fn set_subscriber(&mut self, subscriber_pubkey: &[u8; 32]) {
+        self.subscriber_pubkey = Some(*subscriber_pubkey);
+        self.subscriber_verified = match &self.recipient_pubkey {
+            Some(recipient) => {
+                // Public content ([0u8;32]) verifies any subscriber
+                *recipient == [0u8; 32] || recipient == subscriber_pubkey
+            }
+            None => false, // No envelope parsed yet
+        };
+    }
+}
+
+/// Misbehavior observed for a hosted contract, feeding the CWP penalty.
+///
+/// Kept in memory only; counts start over when the node restarts.
+#[cfg(feature = "lepus")]
+#[derive(Debug, Clone, Default)]
+pub struct MisbehaviorState {
+    /// PUTs and UPDATEs of this contract rejected by its validation.
+    pub validation_failures: u32,
+    /// States whose identity envelope carried an invalid creator signature.
+    pub envelope_forgeries: u32,
+}
+
+/// Bytes served and consumed for a hosted contract, bucketed by time so the
+/// contribution sub-score reflects recent traffic rather than the contract's
+/// whole lifetime.
+///
+/// Holds at most `contribution_window_secs / contribution_bucket_secs`
+/// buckets (plus one); older buckets are dropped as new traffic arrives.
+#[cfg(feature = "lepus")]
+#[derive(Debug, Clone, Default)]
+pub struct ContributionWindow {
+    /// Oldest first.
+    buckets: VecDeque<ContributionBucket>,
+}
+
+#[cfg(feature = "lepus")]
+#[derive(Debug, Clone, Copy)]
+struct ContributionBucket {
+    started_at: Instant,
+    served: u64,
+    consumed: u64,
+}
+
+#[cfg(feature = "lepus")]
+impl ContributionWindow {
+    /// Add traffic at `now`, opening a new bucket once the newest one is
+    /// `contribution_bucket_secs` old.
+    pub fn record(&mut self, now: Instant, served: u64, consumed: u64, config: &CWPConfig) {
+        let bucket_secs = config.contribution_bucket_secs;
+        match self.buckets.back_mut() {
+            Some(bucket)
+                if now
+                    .saturating_duration_since(bucket.started_at)
+                    .as_secs_f64()
+                    < bucket_secs =>
+            {
+                bucket.served = bucket.served.saturating_add(served);
+                bucket.consumed = bucket.consumed.saturating_add(consumed);
+            }
+            _ => self.buckets.push_back(ContributionBucket {
+                started_at: now,
+                served,
+                consumed,
+            }),
+        }
+        while self.buckets.front().is_some_and(|bucket| {
+            now.saturating_duration_since(bucket.started_at)
+                .as_secs_f64()
+                >= config.contribution_window_secs
+        }) {
+            self.buckets.pop_front();
+        }
+    }
+
+    /// Decayed `(served, consumed)` as of `now`: each bucket weighted by
+    /// `1.0 - age_secs / contribution_window_secs`, so traffic fades out
+    /// linearly over the window.
+    pub fn decayed(&self, now: Instant, config: &CWPConfig) -> (f64, f64) {
+        self.buckets
+            .iter()
+            .fold((0.0, 0.0), |(served, consumed), bucket| {
+                let age = now
+                    .saturating_duration_since(bucket.started_at)
+                    .as_secs_f64();
+                let weight = (1.0 - age / config.contribution_window_secs).max(0.0);
+                (
+                    served + bucket.served as f64 * weight,
+                    consumed + bucket.consumed as f64 * weight,
+                )
+            })
+    }
+}
+
 /// Type of access that adds/refreshes a contract in the hosting cache.
 ///
 /// Only certain operations should refresh the LRU position to prevent manipulation:
@@ -125,6 +752,180 @@ pub enum AccessType {
     Subscribe,
 }
 
+impl AccessType {
+    #[cfg_attr(not(feature = "lepus"), allow(dead_code))]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AccessType::Get => "get",
+            AccessType::Put => "put",
+            AccessType::Subscribe => "subscribe",
+        }
+    }
+}
+
+/// Why a contract left the hosting cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EvictionReason {
+    /// Evicted to make room for a newly hosted contract.
+    Capacity,
+    /// Evicted by the periodic over-budget sweep.
+    Sweep,
+    /// Evicted because its creator exceeded a per-creator quota.
+    #[cfg_attr(not(feature = "lepus"), allow(dead_code))]
+    Quota,
+}
+
+impl EvictionReason {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            EvictionReason::Capacity => "capacity",
+            EvictionReason::Sweep => "sweep",
+            EvictionReason::Quota => "quota",
+        }
+    }
+}
+
+impl std::fmt::Display for EvictionReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A contract evicted from the hosting cache, reported via `take_evictions`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EvictionNotice {
+    pub key: ContractKey,
+    pub reason: EvictionReason,
+    /// CWP persistence score at eviction time (Lepus only).
+    pub score: Option<f64>,
+    pub size_bytes: u64,
+    /// Bytes hosted just before the eviction, against `budget_bytes`.
+    pub used_bytes: u64,
+    pub budget_bytes: u64,
+    /// Sub-scores behind `score`.
+    #[cfg(feature = "lepus")]
+    pub breakdown: ScoreBreakdown,
+    /// Identity known for the contract when it was evicted.
+    #[cfg(feature = "lepus")]
+    pub identity: IdentityState,
+    /// Commitment known for the contract when it was evicted.
+    #[cfg(feature = "lepus")]
+    pub commitment: CommitmentState,
+}
+
+impl std::fmt::Display for EvictionNotice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Contract {} evicted from hosting cache (reason: {}",
+            self.key, self.reason
+        )?;
+        if let Some(score) = self.score {
+            write!(f, ", score: {score:.4}")?;
+        }
+        f.write_str(")")
+    }
+}
+
+/// The CWP sub-scores behind a persistence score, each in [0.0, 1.0].
+#[cfg(feature = "lepus")]
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ScoreBreakdown {
+    pub commitment: f64,
+    pub identity: f64,
+    pub contribution: f64,
+    pub recency: f64,
+    pub subscription: f64,
+    pub penalty: f64,
+    pub size_factor: f64,
+    /// The resulting persistence score.
+    pub score: f64,
+}
+
+/// Why a hosted contract scores what it does, reported by `score_report`.
+#[cfg(feature = "lepus")]
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct ScoreReport {
+    pub key: String,
+    pub breakdown: ScoreBreakdown,
+    pub size_bytes: u64,
+    pub access_type: &'static str,
+    pub last_accessed_secs_ago: f64,
+    /// Whether `min_ttl` still protects the contract from eviction.
+    pub ttl_protected: bool,
+    /// Whether the commitment warm-up still protects the contract from
+    /// budget eviction.
+    pub warmup_protected: bool,
+    pub deposited_xlm: u64,
+    /// Share of the creator's deposits scored instead, with
+    /// `aggregate_creator_commitment`.
+    pub pooled_xlm: Option<u64>,
+    /// Time since the oracle last confirmed the deposit, if it ever has.
+    pub oracle_check_secs_ago: Option<f64>,
+    /// Hex-encoded Ed25519 public key.
+    pub creator_pubkey: Option<String>,
+    pub creator_verified: bool,
+    /// Hex-encoded Ed25519 public key.
+    pub subscriber_pubkey: Option<String>,
+    pub subscriber_verified: bool,
+    pub bytes_served: u64,
+    /// `bytes_served` after the per-peer contribution cap.
+    pub effective_bytes_served: u64,
+    pub bytes_consumed: u64,
+    pub client_subscribers: u32,
+    pub validation_failures: u32,
+    pub envelope_forgeries: u32,
+}
+
+/// Order of `list_score_reports`, ascending unless asked otherwise.
+#[cfg(feature = "lepus")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum HostedContractSort {
+    /// Persistence score, so ascending lists the next eviction victims first.
+    #[default]
+    Score,
+    Size,
+    /// Least recently accessed first.
+    LastAccessed,
+}
+
+#[cfg(feature = "lepus")]
+impl HostedContractSort {
+    /// Order two reports, breaking ties by key so pages are stable.
+    pub fn compare(self, a: &ScoreReport, b: &ScoreReport, descending: bool) -> std::cmp::Ordering {
+        let order = match self {
+            Self::Score => a.breakdown.score.total_cmp(&b.breakdown.score),
+            Self::Size => a.size_bytes.cmp(&b.size_bytes),
+            // Least recently accessed first
+            Self::LastAccessed => b
+                .last_accessed_secs_ago
+                .total_cmp(&a.last_accessed_secs_ago),
+        };
+        let order = if descending { order.reverse() } else { order };
+        order.then_with(|| a.key.cmp(&b.key))
+    }
+}
+
+/// A page of hosted contracts, reported by `list_score_reports`.
+#[cfg(feature = "lepus")]
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct HostedContractPage {
+    /// Contracts hosted in all.
+    pub total: usize,
+    pub offset: usize,
+    pub contracts: Vec<ScoreReport>,
+}
+
+/// A contract that would be evicted, reported by `simulate_eviction`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EvictionCandidate {
+    pub key: ContractKey,
+    pub size_bytes: u64,
+    /// CWP persistence score as of the simulation (Lepus only).
+    pub score: Option<f64>,
+}
+
 /// Result of recording a contract access in the hosting cache.
 #[derive(Debug)]
 pub struct RecordAccessResult {
@@ -155,6 +956,26 @@ pub struct HostedContract {
     /// Total bytes consumed (received) from other peers for this contract.
     #[cfg(feature = "lepus")]
     pub bytes_consumed: u64,
+    /// Recent bytes served and consumed, which the contribution sub-score
+    /// is computed from.
+    #[cfg(feature = "lepus")]
+    pub contribution: ContributionWindow,
+    /// Share of `bytes_served` attributed to each remote peer, up to
+    /// `MAX_TRACKED_PEERS_PER_CONTRACT` peers. Not persisted: bytes restored
+    /// at startup are unattributed.
+    #[cfg(feature = "lepus")]
+    pub served_by_peer: HashMap<PeerKey, u64>,
+    /// Validation failures and envelope forgeries (Lepus only).
+    #[cfg(feature = "lepus")]
+    pub misbehavior: MisbehaviorState,
+    /// Local clients currently subscribed to the contract (Lepus only).
+    #[cfg(feature = "lepus")]
+    pub client_subscribers: u32,
+    /// Hashes of the last `STATE_HISTORY_LEN` distinct states the contract
+    /// was accessed with and when each last refreshed it, oldest first
+    /// (Lepus only).
+    #[cfg(feature = "lepus")]
+    pub state_history: VecDeque<([u8; 32], Instant)>,
 }
 
 #[cfg(feature = "lepus")]
@@ -162,30 +983,124 @@ impl HostedContract {
     /// Compute the CWP persistence score for this contract.
     ///
     /// Higher scores indicate higher priority to keep in cache.
-    /// Score is in [0.0, 1.0] — a weighted sum of four sub-scores.
+    /// Score is in [0.0, 1.0] — a weighted sum of four sub-scores plus the
+    /// subscription bonus minus the misbehavior penalty, scaled by
+    /// `size_factor`.
     pub fn persistence_score(&self, now: Instant, config: &CWPConfig) -> f64 {
-        let c = self.commitment_score(config);
+        self.score_breakdown(now, config).score
+    }
+
+    /// Compute the persistence score along with the sub-scores behind it.
+    pub fn score_breakdown(&self, now: Instant, config: &CWPConfig) -> ScoreBreakdown {
+        let c = self.commitment_score(now, config);
         let i = self.identity_score();
-        let n = self.contribution_score(config);
+        let n = self.contribution_score(now, config);
         let r = self.recency_score(now, config);
+        let s = self.subscription_score(config);
+        let p = self.penalty_score(config);
+        let size_factor = self.size_factor(config);
 
         let score = config.commitment_weight * c
             + config.identity_weight * i
             + config.contribution_weight * n
-            + config.recency_weight * r;
+            + config.recency_weight * r
+            + config.subscription_weight * s
+            - config.penalty_weight * p;
+
+        ScoreBreakdown {
+            commitment: c,
+            identity: i,
+            contribution: n,
+            recency: r,
+            subscription: s,
+            penalty: p,
+            size_factor,
+            score: (score * size_factor).clamp(0.0, 1.0),
+        }
+    }
 
-        score.clamp(0.0, 1.0)
+    /// Subscription sub-score: `min(1.0, client_subscribers / subscription_target)`.
+    ///
+    /// Ranks contracts that local clients are watching above otherwise equal
+    /// contracts nobody is.
+    pub fn subscription_score(&self, config: &CWPConfig) -> f64 {
+        (self.client_subscribers as f64 / config.subscription_target).min(1.0)
+    }
+
+    /// Penalty sub-score:
+    /// `min(1.0, (validation_failures + FORGERY_PENALTY_EVENTS * envelope_forgeries) / saturation)`.
+    ///
+    /// With the default weight, a contract at full penalty ranks below a
+    /// freshly accessed stranger with no commitment, identity or contribution.
+    pub fn penalty_score(&self, config: &CWPConfig) -> f64 {
+        let events = self.misbehavior.validation_failures as f64
+            + FORGERY_PENALTY_EVENTS as f64 * self.misbehavior.envelope_forgeries as f64;
+        (events / config.penalty_saturation).min(1.0)
     }
 
-    /// Commitment sub-score: `min(1.0, deposited_xlm / (size_bytes * density_target))`.
+    /// Size factor: `1.0 - size_penalty_weight * size_bytes / (size_bytes + size_reference_bytes)`.
     ///
-    /// Returns 0.0 when no deposit exists (Phase 1 default).
-    pub fn commitment_score(&self, config: &CWPConfig) -> f64 {
+    /// Makes eviction prefer large contracts over small ones with the same
+    /// sub-scores, so the budget retains more value per byte. 1.0 when the
+    /// penalty is disabled.
+    pub fn size_factor(&self, config: &CWPConfig) -> f64 {
+        let size = self.size_bytes as f64;
+        1.0 - config.size_penalty_weight * size / (size + config.size_reference_bytes)
+    }
+
+    /// Commitment sub-score: `min(1.0, deposited_xlm / (size_bytes * density_target))`,
+    /// scaled by `commitment_freshness`.
+    ///
+    /// Uses the pooled deposit instead of the contract's own when creator
+    /// aggregation applies. Returns 0.0 when no deposit exists (Phase 1 default).
+    pub fn commitment_score(&self, now: Instant, config: &CWPConfig) -> f64 {
         let denominator = self.size_bytes as f64 * config.commitment_density_target;
         if denominator <= 0.0 {
             return 0.0;
         }
-        (self.commitment.deposited_xlm as f64 / denominator).min(1.0)
+        let (deposited_xlm, _) = self.scored_commitment();
+        (deposited_xlm as f64 / denominator).min(1.0) * self.commitment_freshness(now, config)
+    }
+
+    /// Whether the contract is within its commitment warm-up: its creator
+    /// envelope verified less than `commitment_warmup_secs` ago and the
+    /// oracle hasn't reported on it since.
+    pub fn in_warmup(&self, now: Instant, config: &CWPConfig) -> bool {
+        self.commitment.warmup_started.is_some_and(|started| {
+            now.saturating_duration_since(started).as_secs_f64() < config.commitment_warmup_secs
+        })
+    }
+
+    /// Whether the contract counts against the committed budget partition:
+    /// it has a deposit of its own or, with creator aggregation, a share of
+    /// its creator's.
+    pub fn is_committed(&self) -> bool {
+        self.scored_commitment().0 > 0
+    }
+
+    /// The deposit and oracle check time that `commitment_score` scores.
+    fn scored_commitment(&self) -> (u64, Option<Instant>) {
+        match self.commitment.pooled {
+            Some(pooled) => (pooled.deposited_xlm, pooled.last_oracle_check),
+            None => (
+                self.commitment.deposited_xlm,
+                self.commitment.last_oracle_check,
+            ),
+        }
+    }
+
+    /// Staleness factor: `1.0 / (1.0 + check_age_secs / staleness_halflife_secs)`.
+    ///
+    /// Decays like recency from the oracle's last confirmation of the deposit,
+    /// and resets when the oracle checks again. A commitment with no recorded
+    /// check is not decayed.
+    pub fn commitment_freshness(&self, now: Instant, config: &CWPConfig) -> f64 {
+        let (_, last_oracle_check) = self.scored_commitment();
+        let Some(checked_at) = last_oracle_check else {
+            return 1.0;
+        };
+        let age = now.saturating_duration_since(checked_at).as_secs_f64();
+        1.0 / (1.0 + age / config.commitment_staleness_halflife_secs)
     }
 
     /// Identity sub-score: `(creator_verified * 0.6) + (subscriber_verified * 0.4)`.
@@ -205,15 +1120,42 @@ impl HostedContract {
         creator + subscriber
     }
 
-    /// Contribution sub-score: `min(1.0, (bytes_served / max(bytes_consumed, 1)) / target)`.
+    /// Contribution sub-score:
+    /// `min(1.0, (served / max(consumed, 1)) / target)`, over the decayed
+    /// bytes in the contribution window.
     ///
-    /// Rewards contracts that serve more data than they consume.
-    pub fn contribution_score(&self, config: &CWPConfig) -> f64 {
-        let consumed = self.bytes_consumed.max(1) as f64;
-        let ratio = self.bytes_served as f64 / consumed;
+    /// Rewards contracts that serve more data than they consume, recently.
+    /// Served bytes are reduced by the same fraction the per-peer cap takes
+    /// off `bytes_served`.
+    pub fn contribution_score(&self, now: Instant, config: &CWPConfig) -> f64 {
+        let (served, consumed) = self.contribution.decayed(now, config);
+        let capped = if self.bytes_served == 0 {
+            1.0
+        } else {
+            self.effective_bytes_served(config) as f64 / self.bytes_served as f64
+        };
+        let ratio = served * capped / consumed.max(1.0);
         (ratio / config.contribution_target).min(1.0)
     }
 
+    /// `bytes_served` minus whatever any single peer received beyond
+    /// `max_peer_contribution_share` of it.
+    ///
+    /// Keeps a creator from inflating contribution by fetching their own
+    /// contract over and over from a second node.
+    pub fn effective_bytes_served(&self, config: &CWPConfig) -> u64 {
+        if config.max_peer_contribution_share >= 1.0 {
+            return self.bytes_served;
+        }
+        let cap = (self.bytes_served as f64 * config.max_peer_contribution_share) as u64;
+        let excess: u64 = self
+            .served_by_peer
+            .values()
+            .map(|bytes| bytes.saturating_sub(cap))
+            .sum();
+        self.bytes_served.saturating_sub(excess)
+    }
+
     /// Recency sub-score: `1.0 / (1.0 + elapsed_secs / halflife_secs)`.
     ///
     /// Exponential-ish decay: returns 1.0 for just-accessed, 0.5 at halflife.
@@ -244,15 +1186,30 @@ pub struct HostingCache<T: TimeSource> {
     current_bytes: u64,
     /// Minimum time since last access before eviction is allowed
     min_ttl: Duration,
+    /// Fraction of `budget_bytes` above which eviction starts
+    high_watermark: f64,
+    /// Fraction of `budget_bytes` that eviction frees space down to
+    low_watermark: f64,
     /// LRU order - front is oldest, back is newest
-    lru_order: VecDeque<ContractKey>,
+    lru_order: LruList,
     /// Contract metadata indexed by key
     contracts: HashMap<ContractKey, HostedContract>,
     /// Time source for testability
     time_source: T,
+    /// Evictions not yet collected by `take_evictions`.
+    pending_evictions: Vec<EvictionNotice>,
     /// CWP scoring configuration (Lepus only).
     #[cfg(feature = "lepus")]
     cwp_config: CWPConfig,
+    /// Score-ordered index used to pick CWP eviction victims (Lepus only).
+    #[cfg(feature = "lepus")]
+    score_index: ScoreIndex,
+    /// Hosted usage per creator pubkey (Lepus only).
+    #[cfg(feature = "lepus")]
+    creator_usage: HashMap<[u8; 32], CreatorUsage>,
+    /// Creators whose usage exceeded a quota since the last enforcement (Lepus only).
+    #[cfg(feature = "lepus")]
+    over_quota_creators: HashSet<[u8; 32]>,
 }
 
 impl<T: TimeSource> HostingCache<T> {
@@ -262,17 +1219,25 @@ impl<T: TimeSource> HostingCache<T> {
             budget_bytes,
             current_bytes: 0,
             min_ttl,
-            lru_order: VecDeque::new(),
+            high_watermark: DEFAULT_EVICTION_WATERMARK,
+            low_watermark: DEFAULT_EVICTION_WATERMARK,
+            lru_order: LruList::default(),
             contracts: HashMap::new(),
             time_source,
+            pending_evictions: Vec::new(),
             #[cfg(feature = "lepus")]
             cwp_config: CWPConfig::default(),
+            #[cfg(feature = "lepus")]
+            score_index: ScoreIndex::default(),
+            #[cfg(feature = "lepus")]
+            creator_usage: HashMap::new(),
+            #[cfg(feature = "lepus")]
+            over_quota_creators: HashSet::new(),
         }
     }
 
     /// Create a new hosting cache with explicit CWP configuration.
     #[cfg(feature = "lepus")]
-    #[allow(dead_code)] // Public API for custom CWP config
     pub fn new_with_cwp(
         budget_bytes: u64,
         min_ttl: Duration,
@@ -283,10 +1248,16 @@ impl<T: TimeSource> HostingCache<T> {
             budget_bytes,
             current_bytes: 0,
             min_ttl,
-            lru_order: VecDeque::new(),
+            high_watermark: cwp_config.eviction_high_watermark,
+            low_watermark: cwp_config.eviction_low_watermark,
+            lru_order: LruList::default(),
             contracts: HashMap::new(),
             time_source,
+            pending_evictions: Vec::new(),
             cwp_config,
+            score_index: ScoreIndex::default(),
+            creator_usage: HashMap::new(),
+            over_quota_creators: HashSet::new(),
         }
     }
 
@@ -300,6 +1271,13 @@ impl<T: TimeSource> HostingCache<T> {
     /// - `evicted`: Contracts that were evicted to make room (if any)
     ///
     /// Eviction respects TTL: contracts won't be evicted until min_ttl has passed.
+    /// With Lepus, nor during their commitment warm-up.
+    /// It starts once adding the contract would exceed the high watermark and
+    /// then frees space down to the low watermark.
+    ///
+    /// With Lepus, creators over their configured quota then lose their own
+    /// lowest-scoring contracts until back under it. Quota eviction ignores
+    /// TTL, since it caps what one identity may hold, but never evicts `key`.
     pub fn record_access(
         &mut self,
         key: ContractKey,
@@ -311,6 +1289,8 @@ impl<T: TimeSource> HostingCache<T> {
 
         if let Some(existing) = self.contracts.get_mut(&key) {
             // Already cached - update size if changed and refresh position
+            #[cfg(feature = "lepus")]
+            let previous = (existing.identity.creator_pubkey, existing.size_bytes);
             if existing.size_bytes != size_bytes {
                 // Adjust byte accounting: add new size, subtract old size
                 self.current_bytes = self
@@ -321,9 +1301,16 @@ impl<T: TimeSource> HostingCache<T> {
             }
             existing.last_accessed = now;
             existing.access_type = access_type;
+            #[cfg(feature = "lepus")]
+            {
+                self.score_index.mark_dirty(key);
+                let (creator, previous_size) = previous;
+                self.detach_creator(&key, creator, previous_size);
+                self.attach_creator(key, creator, size_bytes);
+                evicted.extend(self.enforce_creator_quotas(now, &key));
+            }
 
             // Move to back of LRU (most recently used)
-            self.lru_order.retain(|k| k != &key);
             self.lru_order.push_back(key);
 
             RecordAccessResult {
@@ -333,16 +1320,27 @@ impl<T: TimeSource> HostingCache<T> {
         } else {
             // Not cached - need to add it
             // First, evict until we have room (respecting TTL)
+            let evict_to = if self.current_bytes + size_bytes > self.high_watermark_bytes() {
+                self.low_watermark_bytes()
+            } else {
+                u64::MAX
+            };
             #[cfg(not(feature = "lepus"))]
             {
-                while self.current_bytes + size_bytes > self.budget_bytes
-                    && !self.lru_order.is_empty()
-                {
-                    if let Some(oldest_key) = self.lru_order.front().cloned() {
+                while self.current_bytes + size_bytes > evict_to && !self.lru_order.is_empty() {
+                    if let Some(oldest_key) = self.lru_order.front() {
                         if let Some(oldest) = self.contracts.get(&oldest_key) {
                             let age = now.saturating_duration_since(oldest.last_accessed);
                             if age >= self.min_ttl {
                                 if let Some(removed) = self.contracts.remove(&oldest_key) {
+                                    self.pending_evictions.push(EvictionNotice {
+                                        key: oldest_key,
+                                        reason: EvictionReason::Capacity,
+                                        score: None,
+                                        size_bytes: removed.size_bytes,
+                                        used_bytes: self.current_bytes,
+                                        budget_bytes: self.budget_bytes,
+                                    });
                                     self.current_bytes =
                                         self.current_bytes.saturating_sub(removed.size_bytes);
                                     self.lru_order.pop_front();
@@ -361,18 +1359,25 @@ impl<T: TimeSource> HostingCache<T> {
             }
 
             // CWP eviction: evict the contract with the lowest persistence score
-            // among those past min_ttl. O(n) scan — acceptable for ~50K contracts.
+            // among those past min_ttl, taken from the score index. With budget
+            // partitions, from the class over its share (a new contract is
+            // uncommitted).
             #[cfg(feature = "lepus")]
-            {
-                while self.current_bytes + size_bytes > self.budget_bytes
-                    && !self.contracts.is_empty()
-                {
-                    let victim = self.find_lowest_score_victim(now);
+            if self.current_bytes + size_bytes > evict_to {
+                let mut partition = self.partition_usage();
+                if let Some(usage) = partition.as_mut() {
+                    usage.uncommitted = usage.uncommitted.saturating_add(size_bytes);
+                }
+                while self.current_bytes + size_bytes > evict_to && !self.contracts.is_empty() {
+                    let class = partition.as_ref().and_then(PartitionUsage::over_share);
+                    let victim = self.find_lowest_score_victim(now, class);
                     if let Some(victim_key) = victim {
-                        if let Some(removed) = self.contracts.remove(&victim_key) {
-                            self.current_bytes =
-                                self.current_bytes.saturating_sub(removed.size_bytes);
-                            self.lru_order.retain(|k| k != &victim_key);
+                        if self.evict_partitioned(
+                            &victim_key,
+                            EvictionReason::Capacity,
+                            now,
+                            partition.as_mut(),
+                        ) {
                             evicted.push(victim_key);
                         }
                     } else {
@@ -395,10 +1400,27 @@ impl<T: TimeSource> HostingCache<T> {
                 bytes_served: 0,
                 #[cfg(feature = "lepus")]
                 bytes_consumed: 0,
+                #[cfg(feature = "lepus")]
+                contribution: ContributionWindow::default(),
+                #[cfg(feature = "lepus")]
+                served_by_peer: HashMap::new(),
+                #[cfg(feature = "lepus")]
+                misbehavior: MisbehaviorState::default(),
+                #[cfg(feature = "lepus")]
+                client_subscribers: 0,
+                #[cfg(feature = "lepus")]
+                state_history: VecDeque::new(),
             };
             self.contracts.insert(key, contract);
             self.lru_order.push_back(key);
             self.current_bytes = self.current_bytes.saturating_add(size_bytes);
+            #[cfg(feature = "lepus")]
+            {
+                self.score_index.mark_dirty(key);
+                // A new contract has no creator yet, but quotas exceeded since
+                // the last access (e.g. by identity verification) apply now.
+                evicted.extend(self.enforce_creator_quotas(now, &key));
+            }
 
             RecordAccessResult {
                 is_new: true,
@@ -416,8 +1438,9 @@ impl<T: TimeSource> HostingCache<T> {
         if let Some(existing) = self.contracts.get_mut(key) {
             existing.last_accessed = self.time_source.now();
             // Move to back of LRU
-            self.lru_order.retain(|k| k != key);
             self.lru_order.push_back(*key);
+            #[cfg(feature = "lepus")]
+            self.score_index.mark_dirty(*key);
         }
     }
 
@@ -432,6 +1455,93 @@ impl<T: TimeSource> HostingCache<T> {
         self.contracts.get(key)
     }
 
+    /// Sub-scores of a hosted contract's persistence score as of now, along
+    /// with the inputs they were computed from.
+    #[cfg(feature = "lepus")]
+    pub fn score_report(&self, key: &ContractKey) -> Option<ScoreReport> {
+        let contract = self.contracts.get(key)?;
+        Some(self.build_score_report(key, contract, self.time_source.now()))
+    }
+
+    /// Persistence score of a hosted contract as of now.
+    #[cfg(feature = "lepus")]
+    pub fn persistence_score(&self, key: &ContractKey) -> Option<f64> {
+        let contract = self.contracts.get(key)?;
+        Some(contract.persistence_score(self.time_source.now(), &self.cwp_config))
+    }
+
+    /// Score reports for a page of hosted contracts in `sort` order, all
+    /// scored as of the same instant.
+    ///
+    /// Scores every hosted contract, so it is only meant for operator
+    /// diagnostics.
+    #[cfg(feature = "lepus")]
+    pub fn list_score_reports(
+        &self,
+        sort: HostedContractSort,
+        descending: bool,
+        offset: usize,
+        limit: usize,
+    ) -> HostedContractPage {
+        let now = self.time_source.now();
+        let mut reports: Vec<_> = self
+            .contracts
+            .iter()
+            .map(|(key, contract)| self.build_score_report(key, contract, now))
+            .collect();
+        reports.sort_by(|a, b| sort.compare(a, b, descending));
+        HostedContractPage {
+            total: reports.len(),
+            offset,
+            contracts: reports.into_iter().skip(offset).take(limit).collect(),
+        }
+    }
+
+    #[cfg(feature = "lepus")]
+    fn build_score_report(
+        &self,
+        key: &ContractKey,
+        contract: &HostedContract,
+        now: Instant,
+    ) -> ScoreReport {
+        let since = |instant: Instant| now.saturating_duration_since(instant).as_secs_f64();
+        let last_accessed_secs_ago = since(contract.last_accessed);
+        ScoreReport {
+            key: key.to_string(),
+            breakdown: contract.score_breakdown(now, &self.cwp_config),
+            size_bytes: contract.size_bytes,
+            access_type: contract.access_type.as_str(),
+            last_accessed_secs_ago,
+            ttl_protected: last_accessed_secs_ago < self.min_ttl.as_secs_f64(),
+            warmup_protected: contract.in_warmup(now, &self.cwp_config),
+            deposited_xlm: contract.commitment.deposited_xlm,
+            pooled_xlm: contract
+                .commitment
+                .pooled
+                .map(|pooled| pooled.deposited_xlm),
+            oracle_check_secs_ago: contract.commitment.last_oracle_check.map(since),
+            creator_pubkey: contract.identity.creator_pubkey.map(hex::encode),
+            creator_verified: contract.identity.creator_verified,
+            subscriber_pubkey: contract.identity.subscriber_pubkey.map(hex::encode),
+            subscriber_verified: contract.identity.subscriber_verified,
+            bytes_served: contract.bytes_served,
+            effective_bytes_served: contract.effective_bytes_served(&self.cwp_config),
+            bytes_consumed: contract.bytes_consumed,
+            client_subscribers: contract.client_subscribers,
+            validation_failures: contract.misbehavior.validation_failures,
+            envelope_forgeries: contract.misbehavior.envelope_forgeries,
+        }
+    }
+
+    /// The key of the hosted contract with this instance id.
+    #[cfg(feature = "lepus")]
+    pub fn find_key(&self, instance_id: &ContractInstanceId) -> Option<ContractKey> {
+        self.contracts
+            .keys()
+            .find(|key| key.id() == instance_id)
+            .copied()
+    }
+
     /// Get the current number of hosted contracts.
     pub fn len(&self) -> usize {
         self.contracts.len()
@@ -455,10 +1565,167 @@ impl<T: TimeSource> HostingCache<T> {
         self.budget_bytes
     }
 
+    /// Contracts that would be evicted, in order, if a new contract of
+    /// `size_needed` bytes were added now. The cache is not modified.
+    ///
+    /// Follows the same watermark and TTL rules as `record_access`. With
+    /// Lepus, contracts are ranked by their exact score at this instant, so
+    /// near-ties may come out in a different order than the score index
+    /// would pick them. Per-creator quota evictions and budget partitions are
+    /// not simulated.
+    pub fn simulate_eviction(&self, size_needed: u64) -> Vec<EvictionCandidate> {
+        if self.current_bytes + size_needed <= self.high_watermark_bytes() {
+            return Vec::new();
+        }
+        let evict_to = self.low_watermark_bytes();
+        let now = self.time_source.now();
+        let past_ttl = |contract: &HostedContract| {
+            now.saturating_duration_since(contract.last_accessed) >= self.min_ttl
+        };
+
+        // LRU eviction stops at the first contract still within its TTL
+        #[cfg(not(feature = "lepus"))]
+        let ranked: Vec<EvictionCandidate> = self
+            .lru_order
+            .iter()
+            .filter_map(|key| Some((key, self.contracts.get(&key)?)))
+            .take_while(|(_, contract)| past_ttl(contract))
+            .map(|(key, contract)| EvictionCandidate {
+                key,
+                size_bytes: contract.size_bytes,
+                score: None,
+            })
+            .collect();
+
+        // CWP eviction skips protected contracts and takes the lowest score
+        #[cfg(feature = "lepus")]
+        let ranked: Vec<EvictionCandidate> = {
+            let mut scored: Vec<_> = self
+                .contracts
+                .iter()
+                .filter(|(_, contract)| {
+                    past_ttl(contract) && !contract.in_warmup(now, &self.cwp_config)
+                })
+                .map(|(key, contract)| {
+                    (
+                        *key,
+                        contract,
+                        contract.persistence_score(now, &self.cwp_config),
+                    )
+                })
+                .collect();
+            scored.sort_by(|(a_key, a, a_score), (b_key, b, b_score)| {
+                a_score
+                    .total_cmp(b_score)
+                    .then(a.last_accessed.cmp(&b.last_accessed))
+                    .then(a_key.id().as_bytes().cmp(b_key.id().as_bytes()))
+            });
+            scored
+                .into_iter()
+                .map(|(key, contract, score)| EvictionCandidate {
+                    key,
+                    size_bytes: contract.size_bytes,
+                    score: Some(score),
+                })
+                .collect()
+        };
+
+        let mut remaining = self.current_bytes;
+        let mut victims = Vec::new();
+        for candidate in ranked {
+            if remaining + size_needed <= evict_to {
+                break;
+            }
+            remaining = remaining.saturating_sub(candidate.size_bytes);
+            victims.push(candidate);
+        }
+        victims
+    }
+
+    /// Byte level above which eviction starts.
+    fn high_watermark_bytes(&self) -> u64 {
+        (self.budget_bytes as f64 * self.high_watermark) as u64
+    }
+
+    /// Byte level eviction frees space down to once started.
+    fn low_watermark_bytes(&self) -> u64 {
+        (self.budget_bytes as f64 * self.low_watermark) as u64
+    }
+
+    /// The CWP configuration scoring evictions.
+    #[cfg(feature = "lepus")]
+    pub fn cwp_config(&self) -> &CWPConfig {
+        &self.cwp_config
+    }
+
+    /// Replace the CWP configuration, returning the previous one.
+    ///
+    /// Every contract is rescored before the next eviction. Creators over a
+    /// new quota are trimmed on the next access, as if they had just gone
+    /// over it.
+    #[cfg(feature = "lepus")]
+    pub fn set_cwp_config(&mut self, cwp_config: CWPConfig) -> CWPConfig {
+        self.high_watermark = cwp_config.eviction_high_watermark;
+        self.low_watermark = cwp_config.eviction_low_watermark;
+        let previous = std::mem::replace(&mut self.cwp_config, cwp_config);
+        self.score_index.invalidate();
+        if previous.aggregate_creator_commitment || self.cwp_config.aggregate_creator_commitment {
+            let creators: Vec<[u8; 32]> = self.creator_usage.keys().copied().collect();
+            for creator in creators {
+                self.pool_creator_commitment(&creator);
+            }
+        }
+        self.over_quota_creators = self
+            .creator_usage
+            .keys()
+            .filter(|creator| self.creator_over_quota(creator))
+            .copied()
+            .collect();
+        previous
+    }
+
+    /// Summarize cache occupancy for metrics.
+    ///
+    /// With Lepus this scores every hosted contract, so callers should only
+    /// use it on a periodic path such as the expiry sweep.
+    pub fn occupancy(&self) -> HostingOccupancy {
+        #[cfg_attr(not(feature = "lepus"), allow(unused_mut))]
+        let mut occupancy = HostingOccupancy {
+            current_bytes: self.current_bytes,
+            budget_bytes: self.budget_bytes,
+            contracts: self.contracts.len() as u64,
+            #[cfg(feature = "lepus")]
+            committed_contracts: 0,
+            #[cfg(feature = "lepus")]
+            uncommitted_contracts: 0,
+            #[cfg(feature = "lepus")]
+            score_histogram: [0; super::metrics::SCORE_HISTOGRAM_BUCKETS],
+        };
+        #[cfg(feature = "lepus")]
+        {
+            let now = self.time_source.now();
+            for contract in self.contracts.values() {
+                if contract.is_committed() {
+                    occupancy.committed_contracts += 1;
+                } else {
+                    occupancy.uncommitted_contracts += 1;
+                }
+                let score = contract.persistence_score(now, &self.cwp_config);
+                occupancy.score_histogram[HostingOccupancy::score_bucket(score)] += 1;
+            }
+        }
+        occupancy
+    }
+
     /// Get all hosted contract keys in LRU order (oldest first).
     #[cfg(test)]
     pub fn keys_lru_order(&self) -> Vec<ContractKey> {
-        self.lru_order.iter().cloned().collect()
+        self.lru_order.iter().collect()
+    }
+
+    /// Drain notices for contracts evicted since the last call.
+    pub fn take_evictions(&mut self) -> Vec<EvictionNotice> {
+        std::mem::take(&mut self.pending_evictions)
     }
 
     /// Iterate over all hosted contract keys.
@@ -468,6 +1735,9 @@ impl<T: TimeSource> HostingCache<T> {
 
     /// Sweep for contracts that are over budget and past TTL.
     ///
+    /// Once usage is above the high watermark, contracts are evicted until it
+    /// is back down to the low watermark.
+    ///
     /// The `should_retain` predicate is called for each candidate contract before eviction.
     /// If it returns `true`, the contract is skipped (kept in cache) even if over TTL.
     /// This allows protecting contracts with client subscriptions from eviction.
@@ -478,14 +1748,21 @@ impl<T: TimeSource> HostingCache<T> {
         F: Fn(&ContractKey) -> bool,
     {
         let now = self.time_source.now();
+        #[cfg(feature = "lepus")]
+        self.confirm_commitment_decreases(now);
         let mut evicted = Vec::new();
+        let evict_to = if self.current_bytes > self.high_watermark_bytes() {
+            self.low_watermark_bytes()
+        } else {
+            u64::MAX
+        };
 
         #[cfg(not(feature = "lepus"))]
         {
             let mut skipped_keys = Vec::new();
 
-            while self.current_bytes > self.budget_bytes && !self.lru_order.is_empty() {
-                if let Some(oldest_key) = self.lru_order.front().cloned() {
+            while self.current_bytes > evict_to && !self.lru_order.is_empty() {
+                if let Some(oldest_key) = self.lru_order.front() {
                     if let Some(oldest) = self.contracts.get(&oldest_key) {
                         let age = now.saturating_duration_since(oldest.last_accessed);
                         if age >= self.min_ttl {
@@ -495,6 +1772,14 @@ impl<T: TimeSource> HostingCache<T> {
                                 continue;
                             }
                             if let Some(removed) = self.contracts.remove(&oldest_key) {
+                                self.pending_evictions.push(EvictionNotice {
+                                    key: oldest_key,
+                                    reason: EvictionReason::Sweep,
+                                    score: None,
+                                    size_bytes: removed.size_bytes,
+                                    used_bytes: self.current_bytes,
+                                    budget_bytes: self.budget_bytes,
+                                });
                                 self.current_bytes =
                                     self.current_bytes.saturating_sub(removed.size_bytes);
                                 self.lru_order.pop_front();
@@ -516,15 +1801,21 @@ impl<T: TimeSource> HostingCache<T> {
             }
         }
 
-        // CWP sweep: find lowest-scoring contract past min_ttl, respecting should_retain
+        // CWP sweep: find lowest-scoring contract past min_ttl, respecting
+        // should_retain and budget partitions
         #[cfg(feature = "lepus")]
-        {
-            while self.current_bytes > self.budget_bytes && !self.contracts.is_empty() {
-                let victim = self.find_lowest_score_victim_with_retain(now, &should_retain);
+        if self.current_bytes > evict_to {
+            let mut partition = self.partition_usage();
+            while self.current_bytes > evict_to && !self.contracts.is_empty() {
+                let class = partition.as_ref().and_then(PartitionUsage::over_share);
+                let victim = self.find_lowest_score_victim_with_retain(now, &should_retain, class);
                 if let Some(victim_key) = victim {
-                    if let Some(removed) = self.contracts.remove(&victim_key) {
-                        self.current_bytes = self.current_bytes.saturating_sub(removed.size_bytes);
-                        self.lru_order.retain(|k| k != &victim_key);
+                    if self.evict_partitioned(
+                        &victim_key,
+                        EvictionReason::Sweep,
+                        now,
+                        partition.as_mut(),
+                    ) {
                         evicted.push(victim_key);
                     }
                 } else {
@@ -538,56 +1829,280 @@ impl<T: TimeSource> HostingCache<T> {
     }
 
     /// Find the contract with the lowest CWP persistence score that is eligible
-    /// for eviction (past min_ttl).
+    /// for eviction (past min_ttl), only among committed or uncommitted
+    /// contracts if `committed` is set.
     ///
     /// Tie-breaking: lowest score → oldest last_accessed → smallest key bytes.
     #[cfg(feature = "lepus")]
-    fn find_lowest_score_victim(&self, now: Instant) -> Option<ContractKey> {
-        self.find_lowest_score_victim_with_retain(now, &|_| false)
+    fn find_lowest_score_victim(
+        &mut self,
+        now: Instant,
+        committed: Option<bool>,
+    ) -> Option<ContractKey> {
+        self.find_lowest_score_victim_with_retain(now, &|_| false, committed)
     }
 
     /// Find the contract with the lowest CWP persistence score that is eligible
-    /// for eviction (past min_ttl), respecting a should_retain predicate.
+    /// for eviction (past min_ttl), respecting a should_retain predicate and
+    /// an optional budget partition.
+    ///
+    /// Walks the score index from the lowest score, so the cost is
+    /// proportional to the number of TTL-protected, retained or
+    /// other-partition contracts ahead of the victim rather than to the cache
+    /// size.
     #[cfg(feature = "lepus")]
     fn find_lowest_score_victim_with_retain(
-        &self,
+        &mut self,
         now: Instant,
         should_retain: &dyn Fn(&ContractKey) -> bool,
+        committed: Option<bool>,
     ) -> Option<ContractKey> {
-        let mut best: Option<(OrderedFloat<f64>, Instant, ContractKey)> = None;
+        self.score_index
+            .refresh(&self.contracts, now, &self.cwp_config);
 
-        for (key, contract) in &self.contracts {
+        self.score_index.order.values().copied().find(|key| {
+            let Some(contract) = self.contracts.get(key) else {
+                return false;
+            };
+            if let Some(committed) = committed {
+                if contract.is_committed() != committed {
+                    return false;
+                }
+            }
             let age = now.saturating_duration_since(contract.last_accessed);
-            if age < self.min_ttl {
-                continue; // Protected by TTL
+            // Skip contracts protected by TTL or warm-up, or that the caller
+            // wants to keep
+            age >= self.min_ttl && !contract.in_warmup(now, &self.cwp_config) && !should_retain(key)
+        })
+    }
+
+    /// Bytes hosted per budget partition, or `None` without
+    /// `committed_budget_share`. Scans every contract, so it is only taken
+    /// once eviction is needed.
+    #[cfg(feature = "lepus")]
+    fn partition_usage(&self) -> Option<PartitionUsage> {
+        let share = self.cwp_config.committed_budget_share?;
+        let committed_limit = (self.budget_bytes as f64 * share) as u64;
+        let mut usage = PartitionUsage {
+            committed: 0,
+            uncommitted: 0,
+            committed_limit,
+            uncommitted_limit: self.budget_bytes.saturating_sub(committed_limit),
+        };
+        for contract in self.contracts.values() {
+            let bytes = if contract.is_committed() {
+                &mut usage.committed
+            } else {
+                &mut usage.uncommitted
+            };
+            *bytes = bytes.saturating_add(contract.size_bytes);
+        }
+        Some(usage)
+    }
+
+    /// `evict`, keeping `partition` in step.
+    #[cfg(feature = "lepus")]
+    fn evict_partitioned(
+        &mut self,
+        key: &ContractKey,
+        reason: EvictionReason,
+        now: Instant,
+        partition: Option<&mut PartitionUsage>,
+    ) -> bool {
+        let Some(contract) = self.contracts.get(key) else {
+            return false;
+        };
+        let (committed, size_bytes) = (contract.is_committed(), contract.size_bytes);
+        if let Some(usage) = partition {
+            usage.release(committed, size_bytes);
+        }
+        self.evict(key, reason, now)
+    }
+
+    /// Remove a contract and all its bookkeeping, recording an eviction
+    /// notice with its score breakdown as of `now`. Returns `true` if it was
+    /// hosted.
+    #[cfg(feature = "lepus")]
+    fn evict(&mut self, key: &ContractKey, reason: EvictionReason, now: Instant) -> bool {
+        let Some(removed) = self.contracts.remove(key) else {
+            return false;
+        };
+        let breakdown = removed.score_breakdown(now, &self.cwp_config);
+        self.pending_evictions.push(EvictionNotice {
+            key: *key,
+            reason,
+            score: Some(breakdown.score),
+            size_bytes: removed.size_bytes,
+            used_bytes: self.current_bytes,
+            budget_bytes: self.budget_bytes,
+            breakdown,
+            identity: removed.identity.clone(),
+            commitment: removed.commitment.clone(),
+        });
+        self.current_bytes = self.current_bytes.saturating_sub(removed.size_bytes);
+        self.lru_order.remove(key);
+        self.score_index.remove(key);
+        self.detach_creator(key, removed.identity.creator_pubkey, removed.size_bytes);
+        true
+    }
+
+    /// Attribute a contract's bytes to its creator, flagging the creator if
+    /// that puts it over quota.
+    #[cfg(feature = "lepus")]
+    fn attach_creator(&mut self, key: ContractKey, creator: Option<[u8; 32]>, size_bytes: u64) {
+        let Some(creator) = creator else {
+            return;
+        };
+        let usage = self.creator_usage.entry(creator).or_default();
+        if usage.contracts.insert(key) {
+            usage.bytes = usage.bytes.saturating_add(size_bytes);
+        }
+        if self.creator_over_quota(&creator) {
+            self.over_quota_creators.insert(creator);
+        }
+        if self.cwp_config.aggregate_creator_commitment {
+            self.pool_creator_commitment(&creator);
+        }
+    }
+
+    /// Undo `attach_creator` for a contract.
+    #[cfg(feature = "lepus")]
+    fn detach_creator(&mut self, key: &ContractKey, creator: Option<[u8; 32]>, size_bytes: u64) {
+        let Some(creator) = creator else {
+            return;
+        };
+        if let Some(usage) = self.creator_usage.get_mut(&creator) {
+            if usage.contracts.remove(key) {
+                usage.bytes = usage.bytes.saturating_sub(size_bytes);
             }
-            if should_retain(key) {
-                continue; // Caller wants to keep this one
+            if usage.contracts.is_empty() {
+                self.creator_usage.remove(&creator);
+            }
+        }
+        if self.cwp_config.aggregate_creator_commitment {
+            if let Some(contract) = self.contracts.get_mut(key) {
+                contract.commitment.pooled = None;
             }
+            self.pool_creator_commitment(&creator);
+        }
+    }
 
-            let score = OrderedFloat(contract.persistence_score(now, &self.cwp_config));
-            let candidate = (score, contract.last_accessed, *key);
-
-            let dominated = match &best {
-                None => true,
-                Some(current_best) => {
-                    // Lower score is worse (evict first). On tie: older is worse.
-                    // On tie again: compare key bytes for determinism.
-                    candidate.0 < current_best.0
-                        || (candidate.0 == current_best.0
-                            && (candidate.1 < current_best.1
-                                || (candidate.1 == current_best.1
-                                    && candidate.2.id().as_bytes()
-                                        < current_best.2.id().as_bytes())))
-                }
+    /// Spread a creator's verified deposits over its verified contracts in
+    /// proportion to size, or clear the pooled deposits when
+    /// `aggregate_creator_commitment` is off.
+    ///
+    /// Costs O(contracts of the creator), and runs whenever one of them is
+    /// accessed or its deposit or identity changes.
+    #[cfg(feature = "lepus")]
+    fn pool_creator_commitment(&mut self, creator: &[u8; 32]) {
+        let Some(usage) = self.creator_usage.get(creator) else {
+            return;
+        };
+        let members: Vec<ContractKey> = usage
+            .contracts
+            .iter()
+            .filter(|key| {
+                self.contracts
+                    .get(key)
+                    .is_some_and(|contract| contract.identity.creator_verified)
+            })
+            .copied()
+            .collect();
+        let mut total_xlm = 0u64;
+        let mut total_bytes = 0u64;
+        let mut last_oracle_check = None;
+        for contract in members.iter().filter_map(|key| self.contracts.get(key)) {
+            total_xlm = total_xlm.saturating_add(contract.commitment.deposited_xlm);
+            total_bytes = total_bytes.saturating_add(contract.size_bytes);
+            if contract.commitment.deposited_xlm > 0 {
+                last_oracle_check = last_oracle_check.max(contract.commitment.last_oracle_check);
+            }
+        }
+        let enabled = self.cwp_config.aggregate_creator_commitment && total_bytes > 0;
+        for key in members {
+            let Some(contract) = self.contracts.get_mut(&key) else {
+                continue;
             };
+            let pooled = enabled.then(|| PooledCommitment {
+                deposited_xlm: (total_xlm as u128 * contract.size_bytes as u128
+                    / total_bytes as u128) as u64,
+                last_oracle_check,
+            });
+            if contract.commitment.pooled != pooled {
+                contract.commitment.pooled = pooled;
+                self.score_index.mark_dirty(key);
+            }
+        }
+    }
+
+    #[cfg(feature = "lepus")]
+    fn creator_over_quota(&self, creator: &[u8; 32]) -> bool {
+        let Some(usage) = self.creator_usage.get(creator) else {
+            return false;
+        };
+        self.cwp_config
+            .max_bytes_per_creator
+            .is_some_and(|max| usage.bytes > max)
+            || self
+                .cwp_config
+                .max_contracts_per_creator
+                .is_some_and(|max| usage.contracts.len() > max)
+    }
 
-            if dominated {
-                best = Some(candidate);
+    /// Evict the lowest-scoring contracts of every flagged creator until each
+    /// is back under quota, never evicting `protected`.
+    ///
+    /// Uses the same tie-breaking as `find_lowest_score_victim`, but scores
+    /// only the creator's own contracts.
+    #[cfg(feature = "lepus")]
+    fn enforce_creator_quotas(
+        &mut self,
+        now: Instant,
+        protected: &ContractKey,
+    ) -> Vec<ContractKey> {
+        let mut evicted = Vec::new();
+        for creator in std::mem::take(&mut self.over_quota_creators) {
+            while self.creator_over_quota(&creator) {
+                let victim = self.creator_usage.get(&creator).and_then(|usage| {
+                    usage
+                        .contracts
+                        .iter()
+                        .filter(|key| *key != protected)
+                        .filter_map(|key| Some((*key, self.contracts.get(key)?)))
+                        .min_by(|(a_key, a), (b_key, b)| {
+                            a.persistence_score(now, &self.cwp_config)
+                                .total_cmp(&b.persistence_score(now, &self.cwp_config))
+                                .then(a.last_accessed.cmp(&b.last_accessed))
+                                .then(a_key.id().as_bytes().cmp(b_key.id().as_bytes()))
+                        })
+                        .map(|(key, _)| key)
+                });
+                let Some(victim) = victim else {
+                    // Only the protected contract is left; recheck on its next access.
+                    self.over_quota_creators.insert(creator);
+                    break;
+                };
+                if !self.evict(&victim, EvictionReason::Quota, now) {
+                    break;
+                }
+                tracing::debug!(
+                    contract = %victim,
+                    creator = %hex::encode(creator),
+                    "Evicted contract over per-creator hosting quota"
+                );
+                evicted.push(victim);
             }
         }
+        evicted
+    }
 
-        best.map(|(_, _, key)| key)
+    /// Hosted bytes and contract count attributed to a creator.
+    #[cfg(feature = "lepus")]
+    #[allow(dead_code)] // Public API for introspection
+    pub fn creator_usage(&self, creator: &[u8; 32]) -> (u64, usize) {
+        self.creator_usage
+            .get(creator)
+            .map_or((0, 0), |usage| (usage.bytes, usage.contracts.len()))
     }
 
     /// Load a contract entry from persisted data during startup.
@@ -628,13 +2143,94 @@ impl<T: TimeSource> HostingCache<T> {
             bytes_served: 0,
             #[cfg(feature = "lepus")]
             bytes_consumed: 0,
+            #[cfg(feature = "lepus")]
+            contribution: ContributionWindow::default(),
+            #[cfg(feature = "lepus")]
+            served_by_peer: HashMap::new(),
+            #[cfg(feature = "lepus")]
+            misbehavior: MisbehaviorState::default(),
+            #[cfg(feature = "lepus")]
+            client_subscribers: 0,
+            #[cfg(feature = "lepus")]
+            state_history: VecDeque::new(),
         };
 
         self.contracts.insert(key, contract);
         self.current_bytes = self.current_bytes.saturating_add(size_bytes);
+        #[cfg(feature = "lepus")]
+        self.score_index.mark_dirty(key);
         // Note: LRU order will be sorted after all entries are loaded
     }
 
+    /// Load a contract whose state was stored without hosting metadata, as
+    /// the lowest-priority entry in the cache.
+    ///
+    /// It is made older than every entry loaded so far and past its TTL, so
+    /// it is the first eviction candidate until something accesses it.
+    /// Returns the last-access age it was given.
+    pub fn load_orphaned_entry(&mut self, key: ContractKey, size_bytes: u64) -> Duration {
+        let now = self.time_source.now();
+        let oldest_age = self
+            .contracts
+            .values()
+            .map(|contract| now.saturating_duration_since(contract.last_accessed))
+            .max()
+            .unwrap_or_default();
+        let age = (oldest_age + Duration::from_millis(1)).max(self.min_ttl);
+        self.load_persisted_entry(key, size_bytes, AccessType::Get, age);
+        age
+    }
+
+    /// Restore persisted CWP scoring state for an entry loaded at startup or
+    /// rehydrated from the cold tier.
+    ///
+    /// `last_oracle_check_age` is how long ago the oracle last checked the
+    /// deposit, like `last_access_age` in `load_persisted_entry`.
+    /// Returns `true` if the key was found.
+    #[cfg(feature = "lepus")]
+    pub fn restore_lepus_state(
+        &mut self,
+        key: &ContractKey,
+        deposited_xlm: u64,
+        last_oracle_check_age: Option<Duration>,
+        identity: IdentityState,
+        bytes_served: u64,
+        bytes_consumed: u64,
+    ) -> bool {
+        let now = self.time_source.now();
+        if let Some(contract) = self.contracts.get_mut(key) {
+            contract.commitment = CommitmentState {
+                deposited_xlm,
+                last_oracle_check: last_oracle_check_age
+                    .map(|age| now.checked_sub(age).unwrap_or(now)),
+                pooled: None,
+                warmup_started: None,
+                pending_decrease: None,
+            };
+            let previous_creator = contract.identity.creator_pubkey;
+            let creator = identity.creator_pubkey;
+            let size_bytes = contract.size_bytes;
+            contract.identity = identity;
+            contract.bytes_served = bytes_served;
+            contract.bytes_consumed = bytes_consumed;
+            // Buckets aren't persisted: credit the restored totals to the
+            // last access, so traffic from long ago doesn't count as recent.
+            contract.contribution = ContributionWindow::default();
+            contract.contribution.record(
+                contract.last_accessed,
+                bytes_served,
+                bytes_consumed,
+                &self.cwp_config,
+            );
+            self.score_index.mark_dirty(*key);
+            self.detach_creator(key, previous_creator, size_bytes);
+            self.attach_creator(*key, creator, size_bytes);
+            true
+        } else {
+            false
+        }
+    }
+
     /// Sort the LRU order by last_accessed time after bulk loading.
     ///
     /// Call this after `load_persisted_entry` calls are complete.
@@ -656,36 +2252,92 @@ impl<T: TimeSource> HostingCache<T> {
     /// Record bytes served (sent to other peers) for a hosted contract.
     #[cfg(feature = "lepus")]
     pub fn record_bytes_served(&mut self, key: &ContractKey, bytes: u64) {
+        let now = self.time_source.now();
+        if let Some(contract) = self.contracts.get_mut(key) {
+            contract.bytes_served = contract.bytes_served.saturating_add(bytes);
+            contract
+                .contribution
+                .record(now, bytes, 0, &self.cwp_config);
+            self.score_index.mark_dirty(*key);
+        }
+    }
+
+    /// Record bytes served to a specific remote peer for a hosted contract.
+    ///
+    /// Counts towards `bytes_served` like `record_bytes_served`, and is also
+    /// attributed to `peer` so `max_peer_contribution_share` can cap it.
+    #[cfg(feature = "lepus")]
+    pub fn record_peer_bytes_served(&mut self, key: &ContractKey, peer: &PeerKey, bytes: u64) {
+        let now = self.time_source.now();
         if let Some(contract) = self.contracts.get_mut(key) {
             contract.bytes_served = contract.bytes_served.saturating_add(bytes);
+            contract
+                .contribution
+                .record(now, bytes, 0, &self.cwp_config);
+            if let Some(served) = contract.served_by_peer.get_mut(peer) {
+                *served = served.saturating_add(bytes);
+            } else if contract.served_by_peer.len() < MAX_TRACKED_PEERS_PER_CONTRACT {
+                contract.served_by_peer.insert(peer.clone(), bytes);
+            }
+            self.score_index.mark_dirty(*key);
         }
     }
 
     /// Record bytes consumed (received from other peers) for a hosted contract.
     #[cfg(feature = "lepus")]
     pub fn record_bytes_consumed(&mut self, key: &ContractKey, bytes: u64) {
+        let now = self.time_source.now();
         if let Some(contract) = self.contracts.get_mut(key) {
             contract.bytes_consumed = contract.bytes_consumed.saturating_add(bytes);
+            contract
+                .contribution
+                .record(now, 0, bytes, &self.cwp_config);
+            self.score_index.mark_dirty(*key);
         }
     }
 
     /// Get a mutable reference to a hosted contract's metadata.
+    ///
+    /// The contract is rescored before the next eviction, since the caller
+    /// may change any score input.
     #[cfg(feature = "lepus")]
     #[allow(dead_code)] // Public API for future Oracle/identity integration
     pub fn get_mut(&mut self, key: &ContractKey) -> Option<&mut HostedContract> {
-        self.contracts.get_mut(key)
+        let contract = self.contracts.get_mut(key)?;
+        self.score_index.mark_dirty(*key);
+        Some(contract)
     }
 
-    /// Get all hosted contract keys.
+    /// Drop creator verification from every hosted contract signed by
+    /// `creator`, returning the contracts that were verified before.
     #[cfg(feature = "lepus")]
-    pub fn contract_keys(&self) -> Vec<ContractKey> {
-        self.contracts.keys().cloned().collect()
+    pub fn revoke_creator(&mut self, creator: &[u8; 32]) -> Vec<ContractKey> {
+        let keys: Vec<ContractKey> = self
+            .creator_usage
+            .get(creator)
+            .map(|usage| usage.contracts.iter().copied().collect())
+            .unwrap_or_default();
+        let mut revoked = Vec::new();
+        for key in keys {
+            let Some(contract) = self.contracts.get_mut(&key) else {
+                continue;
+            };
+            if contract.identity.creator_verified {
+                contract.identity.creator_verified = false;
+                contract.commitment.warmup_started = None;
+                self.score_index.mark_dirty(key);
+                revoked.push(key);
+            }
+        }
+        revoked
     }
 
     /// Update the identity verification state for a hosted contract.
     ///
     /// Sets creator and subscriber identity fields on the contract's
-    /// `IdentityState`. Returns `true` if the key was found.
+    /// `IdentityState`. A creator verifying before the oracle has reported on
+    /// the deposit starts the commitment warm-up. Returns `true` if the key
+    /// was found.
     #[cfg(feature = "lepus")]
     pub fn update_identity(
         &mut self,
@@ -696,18 +2348,96 @@ impl<T: TimeSource> HostingCache<T> {
         subscriber_verified: bool,
         recipient_pubkey: Option<[u8; 32]>,
     ) -> bool {
+        let now = self.time_source.now();
         if let Some(contract) = self.contracts.get_mut(key) {
+            let previous_creator = contract.identity.creator_pubkey;
+            let size_bytes = contract.size_bytes;
+            if !creator_verified {
+                contract.commitment.warmup_started = None;
+            } else if !contract.identity.creator_verified
+                && contract.commitment.last_oracle_check.is_none()
+            {
+                contract.commitment.warmup_started = Some(now);
+            }
             contract.identity.creator_pubkey = creator_pubkey;
             contract.identity.creator_verified = creator_verified;
             contract.identity.subscriber_pubkey = subscriber_pubkey;
             contract.identity.subscriber_verified = subscriber_verified;
             contract.identity.recipient_pubkey = recipient_pubkey;
+            self.score_index.mark_dirty(*key);
+            self.detach_creator(key, previous_creator, size_bytes);
+            self.attach_creator(*key, creator_pubkey, size_bytes);
             true
         } else {
             false
         }
     }
 
+    /// Whether `key` was accessed with the state hashing to `state_hash`
+    /// within `state_refresh_cooldown_secs`, in which case the access should
+    /// not refresh it.
+    #[cfg(feature = "lepus")]
+    pub fn state_seen_recently(&self, key: &ContractKey, state_hash: &[u8; 32]) -> bool {
+        let Some(contract) = self.contracts.get(key) else {
+            return false;
+        };
+        let now = self.time_source.now();
+        contract.state_history.iter().any(|(hash, refreshed_at)| {
+            hash == state_hash
+                && now.saturating_duration_since(*refreshed_at).as_secs_f64()
+                    < self.cwp_config.state_refresh_cooldown_secs
+        })
+    }
+
+    /// Remember that an access with the state hashing to `state_hash` just
+    /// refreshed `key`, forgetting the oldest state beyond
+    /// `STATE_HISTORY_LEN`.
+    #[cfg(feature = "lepus")]
+    pub fn record_state_hash(&mut self, key: &ContractKey, state_hash: [u8; 32]) {
+        let now = self.time_source.now();
+        if let Some(contract) = self.contracts.get_mut(key) {
+            contract
+                .state_history
+                .retain(|(hash, _)| *hash != state_hash);
+            contract.state_history.push_back((state_hash, now));
+            if contract.state_history.len() > STATE_HISTORY_LEN {
+                contract.state_history.pop_front();
+            }
+        }
+    }
+
+    /// Set how many local clients are subscribed to a hosted contract.
+    #[cfg(feature = "lepus")]
+    pub fn set_client_subscribers(&mut self, key: &ContractKey, count: u32) {
+        if let Some(contract) = self.contracts.get_mut(key) {
+            if contract.client_subscribers != count {
+                contract.client_subscribers = count;
+                self.score_index.mark_dirty(*key);
+            }
+        }
+    }
+
+    /// Count a PUT or UPDATE of a hosted contract rejected by validation.
+    #[cfg(feature = "lepus")]
+    pub fn record_validation_failure(&mut self, key: &ContractKey) {
+        if let Some(contract) = self.contracts.get_mut(key) {
+            contract.misbehavior.validation_failures =
+                contract.misbehavior.validation_failures.saturating_add(1);
+            self.score_index.mark_dirty(*key);
+        }
+    }
+
+    /// Count a state of a hosted contract whose identity envelope failed
+    /// creator signature verification.
+    #[cfg(feature = "lepus")]
+    pub fn record_envelope_forgery(&mut self, key: &ContractKey) {
+        if let Some(contract) = self.contracts.get_mut(key) {
+            contract.misbehavior.envelope_forgeries =
+                contract.misbehavior.envelope_forgeries.saturating_add(1);
+            self.score_index.mark_dirty(*key);
+        }
+    }
+
     /// Update subscriber identity from the subscription handshake.
     ///
     /// Verifies whether the declared subscriber pubkey matches the datapod's
@@ -720,14 +2450,8 @@ impl<T: TimeSource> HostingCache<T> {
         subscriber_pubkey: &[u8; 32],
     ) -> bool {
         if let Some(contract) = self.contracts.get_mut(key) {
-            contract.identity.subscriber_pubkey = Some(*subscriber_pubkey);
-            contract.identity.subscriber_verified = match &contract.identity.recipient_pubkey {
-                Some(recipient) => {
-                    // Public content ([0u8;32]) verifies any subscriber
-                    *recipient == [0u8; 32] || recipient == subscriber_pubkey
-                }
-                None => false, // No envelope parsed yet
-            };
+            contract.identity.set_subscriber(subscriber_pubkey);
+            self.score_index.mark_dirty(*key);
             true
         } else {
             false
@@ -755,19 +2479,74 @@ impl<T: TimeSource> HostingCache<T> {
     ///
     /// Sets `deposited_xlm` and `last_oracle_check` on the contract's
     /// `CommitmentState`. Returns `true` if the key was found.
+    ///
+    /// A deposit lower than the current one only replaces it once lower
+    /// deposits have been reported for `commitment_decrease_confirmation_secs`,
+    /// checked here and on every sweep. Until then the current deposit keeps
+    /// being scored. A deposit at least as high applies at once and cancels
+    /// any pending decrease.
+    #[cfg(feature = "lepus")]
+    pub fn update_commitment(
+        &mut self,
+        key: &ContractKey,
+        deposited_xlm: u64,
+        check_time: Instant,
+    ) -> bool {
+        let window = self.cwp_config.commitment_decrease_confirmation_secs;
+        let Some(contract) = self.contracts.get_mut(key) else {
+            return false;
+        };
+        let commitment = &mut contract.commitment;
+        commitment.last_oracle_check = Some(check_time);
+        commitment.warmup_started = None;
+        if deposited_xlm < commitment.deposited_xlm {
+            let since = commitment
+                .pending_decrease
+                .map_or(check_time, |pending| pending.since);
+            commitment.pending_decrease = Some(PendingDecrease {
+                deposited_xlm,
+                since,
+            });
+            if check_time.saturating_duration_since(since).as_secs_f64() < window {
+                self.score_index.mark_dirty(*key);
+                return true;
+            }
+        }
+        self.apply_commitment(key, deposited_xlm);
+        true
+    }
+
+    /// Apply pending commitment decreases whose confirmation window has
+    /// passed.
+    #[cfg(feature = "lepus")]
+    fn confirm_commitment_decreases(&mut self, now: Instant) {
+        let window = self.cwp_config.commitment_decrease_confirmation_secs;
+        let confirmed: Vec<(ContractKey, u64)> = self
+            .contracts
+            .iter()
+            .filter_map(|(key, contract)| {
+                let pending = contract.commitment.pending_decrease?;
+                (now.saturating_duration_since(pending.since).as_secs_f64() >= window)
+                    .then_some((*key, pending.deposited_xlm))
+            })
+            .collect();
+        for (key, deposited_xlm) in confirmed {
+            self.apply_commitment(&key, deposited_xlm);
+        }
+    }
+
+    /// Replace a hosted contract's deposit, dropping any pending decrease.
     #[cfg(feature = "lepus")]
-    pub fn update_commitment(
-        &mut self,
-        key: &ContractKey,
-        deposited_xlm: u64,
-        check_time: Instant,
-    ) -> bool {
-        if let Some(contract) = self.contracts.get_mut(key) {
-            contract.commitment.deposited_xlm = deposited_xlm;
-            contract.commitment.last_oracle_check = Some(check_time);
-            true
-        } else {
-            false
+    fn apply_commitment(&mut self, key: &ContractKey, deposited_xlm: u64) {
+        let Some(contract) = self.contracts.get_mut(key) else {
+            return;
+        };
+        contract.commitment.deposited_xlm = deposited_xlm;
+        contract.commitment.pending_decrease = None;
+        let creator = contract.identity.creator_pubkey;
+        self.score_index.mark_dirty(*key);
+        if let Some(creator) = creator.filter(|_| self.cwp_config.aggregate_creator_commitment) {
+            self.pool_creator_commitment(&creator);
         }
     }
 }
@@ -900,6 +2679,66 @@ mod tests {
         assert!(cache.contains(&key3));
     }
 
+    #[test]
+    fn test_take_evictions_reports_reason() {
+        let (mut cache, time) = make_cache(200, Duration::from_secs(60));
+        let key1 = make_key(1);
+        let key2 = make_key(2);
+
+        cache.record_access(key1, 100, AccessType::Get);
+        cache.record_access(key2, 100, AccessType::Get);
+        assert!(cache.take_evictions().is_empty());
+        time.advance_time(Duration::from_secs(61));
+
+        cache.record_access(make_key(3), 150, AccessType::Get);
+        let evictions = cache.take_evictions();
+        assert_eq!(evictions.len(), 2);
+        assert!(evictions
+            .iter()
+            .all(|e| e.reason == EvictionReason::Capacity));
+        // Only CWP scoring has a score to report
+        assert!(evictions
+            .iter()
+            .all(|e| e.score.is_some() == cfg!(feature = "lepus")));
+        assert!(evictions[0].to_string().contains("reason: capacity"));
+        // Budget pressure as each eviction happened
+        assert_eq!(evictions[0].used_bytes, 200);
+        assert_eq!(evictions[1].used_bytes, 100);
+        assert!(evictions.iter().all(|e| e.size_bytes == 100));
+        assert!(evictions.iter().all(|e| e.budget_bytes == 200));
+        assert!(cache.take_evictions().is_empty());
+    }
+
+    #[test]
+    fn test_lru_list_moves_and_unlinks() {
+        let mut list = LruList::default();
+        let keys: Vec<_> = (1..=4).map(make_key).collect();
+        for key in &keys {
+            list.push_back(*key);
+        }
+
+        // Move from the middle, front and back
+        list.push_back(keys[1]);
+        list.push_back(keys[0]);
+        list.push_back(keys[0]);
+        assert_eq!(
+            list.iter().collect::<Vec<_>>(),
+            vec![keys[2], keys[3], keys[1], keys[0]]
+        );
+
+        assert!(list.remove(&keys[3]));
+        assert!(!list.remove(&keys[3]));
+        assert_eq!(list.pop_front(), Some(keys[2]));
+        assert_eq!(list.front(), Some(keys[1]));
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![keys[1], keys[0]]);
+
+        list.remove(&keys[0]);
+        list.remove(&keys[1]);
+        assert!(list.is_empty());
+        assert_eq!(list.front(), None);
+        assert_eq!(list.iter().count(), 0);
+    }
+
     #[test]
     fn test_access_refreshes_lru_position() {
         let (mut cache, time) = make_cache(200, Duration::from_secs(60));
@@ -993,6 +2832,39 @@ mod tests {
         assert!(cache.contains(&large));
     }
 
+    #[test]
+    fn test_simulate_eviction_matches_record_access() {
+        let (mut cache, time) = make_cache(300, Duration::from_secs(60));
+        let key1 = make_key(1);
+        let key2 = make_key(2);
+        let key3 = make_key(3);
+
+        cache.record_access(key1, 100, AccessType::Get);
+        time.advance_time(Duration::from_secs(1));
+        cache.record_access(key2, 100, AccessType::Get);
+        time.advance_time(Duration::from_secs(1));
+        cache.record_access(key3, 100, AccessType::Get);
+
+        // Everything is within TTL
+        assert!(cache.simulate_eviction(150).is_empty());
+        time.advance_time(Duration::from_secs(61));
+        // Fits without eviction
+        assert!(cache.simulate_eviction(0).is_empty());
+
+        let victims = cache.simulate_eviction(150);
+        assert_eq!(
+            victims.iter().map(|v| v.key).collect::<Vec<_>>(),
+            vec![key1, key2]
+        );
+        assert!(victims.iter().all(|v| v.size_bytes == 100));
+        // The simulation didn't touch the cache
+        assert_eq!(cache.len(), 3);
+        assert_eq!(cache.current_bytes(), 300);
+
+        let result = cache.record_access(make_key(4), 150, AccessType::Get);
+        assert_eq!(result.evicted, vec![key1, key2]);
+    }
+
     #[test]
     fn test_sweep_expired() {
         let (mut cache, time) = make_cache(200, Duration::from_secs(60));
@@ -1116,6 +2988,13 @@ mod tests {
             creator_verified: bool,
             subscriber_verified: bool,
         ) -> HostedContract {
+            let mut contribution = ContributionWindow::default();
+            contribution.record(
+                last_accessed,
+                bytes_served,
+                bytes_consumed,
+                &CWPConfig::default(),
+            );
             HostedContract {
                 size_bytes,
                 last_accessed,
@@ -1123,6 +3002,9 @@ mod tests {
                 commitment: CommitmentState {
                     deposited_xlm,
                     last_oracle_check: None,
+                    pooled: None,
+                    warmup_started: None,
+                    pending_decrease: None,
                 },
                 identity: IdentityState {
                     creator_pubkey: None,
@@ -1133,6 +3015,11 @@ mod tests {
                 },
                 bytes_served,
                 bytes_consumed,
+                contribution,
+                served_by_peer: HashMap::new(),
+                misbehavior: MisbehaviorState::default(),
+                client_subscribers: 0,
+                state_history: VecDeque::new(),
             }
         }
 
@@ -1166,8 +3053,9 @@ mod tests {
         fn test_contribution_score_zero_consumed() {
             let config = CWPConfig::default();
             // bytes_consumed = 0 should not panic (max(0, 1) = 1)
-            let contract = make_cwp_contract(1000, Instant::now(), 100, 0, 0, false, false);
-            let score = contract.contribution_score(&config);
+            let now = Instant::now();
+            let contract = make_cwp_contract(1000, now, 100, 0, 0, false, false);
+            let score = contract.contribution_score(now, &config);
             // 100 / 1 / 1.5 = 66.67, clamped to 1.0
             assert!(
                 (score - 1.0).abs() < 0.001,
@@ -1179,8 +3067,9 @@ mod tests {
         #[test]
         fn test_contribution_score_exceeds_target() {
             let config = CWPConfig::default();
-            let contract = make_cwp_contract(1000, Instant::now(), 3000, 1000, 0, false, false);
-            let score = contract.contribution_score(&config);
+            let now = Instant::now();
+            let contract = make_cwp_contract(1000, now, 3000, 1000, 0, false, false);
+            let score = contract.contribution_score(now, &config);
             // ratio = 3.0, target = 1.5, 3.0/1.5 = 2.0 → clamped to 1.0
             assert!(
                 (score - 1.0).abs() < 0.001,
@@ -1192,8 +3081,9 @@ mod tests {
         #[test]
         fn test_commitment_score_zero_deposit() {
             let config = CWPConfig::default();
-            let contract = make_cwp_contract(1000, Instant::now(), 0, 0, 0, false, false);
-            let score = contract.commitment_score(&config);
+            let now = Instant::now();
+            let contract = make_cwp_contract(1000, now, 0, 0, 0, false, false);
+            let score = contract.commitment_score(now, &config);
             assert!(
                 score.abs() < 0.001,
                 "Zero deposit should give 0.0, got {}",
@@ -1201,6 +3091,29 @@ mod tests {
             );
         }
 
+        #[test]
+        fn test_commitment_score_decays_without_oracle_check() {
+            let config = CWPConfig::default();
+            let now = Instant::now();
+            let halflife = Duration::from_secs_f64(config.commitment_staleness_halflife_secs);
+            let mut contract = make_cwp_contract(1000, now, 0, 0, 10, false, false);
+            // No recorded check: full commitment
+            assert!((contract.commitment_score(now, &config) - 1.0).abs() < 0.001);
+
+            contract.commitment.last_oracle_check = Some(now);
+            assert!((contract.commitment_score(now, &config) - 1.0).abs() < 0.001);
+
+            // One staleness half-life without re-confirmation halves it
+            let stale = now + halflife;
+            let score = contract.commitment_score(stale, &config);
+            assert!((score - 0.5).abs() < 0.01, "Expected ~0.5, got {score}");
+            assert!(contract.commitment_score(stale + halflife, &config) < score);
+
+            // A fresh oracle check restores full weight
+            contract.commitment.last_oracle_check = Some(stale);
+            assert!((contract.commitment_score(stale, &config) - 1.0).abs() < 0.001);
+        }
+
         #[test]
         fn test_identity_score_both_verified() {
             let contract = make_cwp_contract(1000, Instant::now(), 0, 0, 0, true, true);
@@ -1223,6 +3136,81 @@ mod tests {
             );
         }
 
+        #[test]
+        fn test_misbehavior_ranks_below_neutral_stranger() {
+            let (mut cache, time) = make_cache(300, Duration::from_secs(60));
+            let config = CWPConfig::default();
+            let forger = make_key(1);
+            let flaky = make_key(2);
+            let stranger = make_key(3);
+
+            // Accessed first, so the stranger has the lowest recency
+            cache.record_access(stranger, 100, AccessType::Get);
+            time.advance_time(Duration::from_secs(1));
+            cache.record_access(forger, 100, AccessType::Get);
+            cache.record_access(flaky, 100, AccessType::Get);
+
+            for _ in 0..2 {
+                cache.record_envelope_forgery(&forger);
+            }
+            cache.record_validation_failure(&flaky);
+            let contract = cache.get(&forger).unwrap();
+            assert_eq!(contract.misbehavior.envelope_forgeries, 2);
+            assert_eq!(contract.penalty_score(&config), 1.0);
+            // 1 of 5 events
+            let contract = cache.get(&flaky).unwrap();
+            assert!((contract.penalty_score(&config) - 0.2).abs() < 1e-9);
+
+            time.advance_time(Duration::from_secs(61));
+            let result = cache.record_access(make_key(4), 100, AccessType::Get);
+            assert_eq!(result.evicted, vec![forger]);
+            let result = cache.record_access(make_key(5), 100, AccessType::Get);
+            assert_eq!(result.evicted, vec![flaky]);
+            assert!(cache.contains(&stranger));
+        }
+
+        #[test]
+        fn test_client_subscribers_break_ties() {
+            let (mut cache, time) = make_cache(200, Duration::from_secs(60));
+            let watched = make_key(1);
+            let unwatched = make_key(2);
+            cache.record_access(watched, 100, AccessType::Get);
+            cache.record_access(unwatched, 100, AccessType::Get);
+            assert_eq!(cache.find_key(watched.id()), Some(watched));
+
+            // Same access time, so only the subscription bonus differs
+            cache.set_client_subscribers(&watched, 3);
+            let config = CWPConfig::default();
+            let contract = cache.get(&watched).unwrap();
+            assert!((contract.subscription_score(&config) - 0.3).abs() < 1e-9);
+
+            time.advance_time(Duration::from_secs(61));
+            let result = cache.record_access(make_key(3), 100, AccessType::Get);
+            assert_eq!(result.evicted, vec![unwatched]);
+        }
+
+        #[test]
+        fn test_size_penalty_prefers_small_contracts() {
+            let now = Instant::now();
+            let datapod = make_cwp_contract(2 * 1024, now, 0, 0, 0, true, true);
+            let bulk = make_cwp_contract(90 * 1024 * 1024, now, 0, 0, 0, true, true);
+
+            // Disabled by default: identical sub-scores, identical scores
+            let config = CWPConfig::default();
+            assert_eq!(
+                datapod.persistence_score(now, &config),
+                bulk.persistence_score(now, &config)
+            );
+
+            let config = CWPConfig {
+                size_penalty_weight: 1.0,
+                ..CWPConfig::default()
+            };
+            assert!(datapod.size_factor(&config) > 0.99);
+            assert!(bulk.size_factor(&config) < 0.02);
+            assert!(datapod.persistence_score(now, &config) > bulk.persistence_score(now, &config));
+        }
+
         #[test]
         fn test_persistence_score_weighted_sum() {
             let config = CWPConfig::default();
@@ -1331,6 +3319,315 @@ mod tests {
             assert_eq!(contract.bytes_consumed, 300);
         }
 
+        #[test]
+        fn test_peer_contribution_share_caps_single_peer() {
+            use crate::transport::TransportKeypair;
+
+            let config = CWPConfig {
+                max_peer_contribution_share: 0.5,
+                ..CWPConfig::default()
+            };
+            let time_source = SharedMockTimeSource::new();
+            let mut cache = HostingCache::new_with_cwp(
+                1000,
+                Duration::from_secs(60),
+                time_source.clone(),
+                config.clone(),
+            );
+            let key = make_key(1);
+            let self_dealer = PeerKey(TransportKeypair::new().public().clone());
+            let other = PeerKey(TransportKeypair::new().public().clone());
+
+            cache.record_access(key, 100, AccessType::Get);
+            cache.record_bytes_consumed(&key, 1000);
+            cache.record_peer_bytes_served(&key, &self_dealer, 900);
+            cache.record_peer_bytes_served(&key, &other, 100);
+
+            let now = time_source.now();
+            let contract = cache.get(&key).unwrap();
+            assert_eq!(contract.bytes_served, 1000);
+            // The self-dealing peer only counts up to half of bytes served
+            assert_eq!(contract.effective_bytes_served(&config), 600);
+            assert!(
+                contract.contribution_score(now, &config)
+                    < contract.contribution_score(now, &CWPConfig::default())
+            );
+
+            // Unattributed bytes are not capped
+            cache.record_bytes_served(&key, 1000);
+            let contract = cache.get(&key).unwrap();
+            assert_eq!(contract.effective_bytes_served(&config), 2000);
+        }
+
+        #[test]
+        fn test_contribution_decays_over_window() {
+            let (mut cache, time_source) = make_quota_cache(CWPConfig::default());
+            let config = CWPConfig::default();
+            let day = Duration::from_secs(86_400);
+            let key = make_key(1);
+            cache.record_access(key, 100, AccessType::Get);
+            cache.record_bytes_served(&key, 3000);
+            let score = |cache: &HostingCache<SharedMockTimeSource>| {
+                cache
+                    .get(&key)
+                    .unwrap()
+                    .contribution_score(time_source.now(), &config)
+            };
+            assert!((score(&cache) - 1.0).abs() < 0.001);
+
+            // Twenty days later it consumes as much as it once served: the
+            // lifetime ratio of 1.0 would still score 0.667, but the old
+            // traffic now counts a third
+            time_source.advance_time(day * 20);
+            cache.record_bytes_consumed(&key, 3000);
+            let decayed = score(&cache);
+            assert!((decayed - 2.0 / 9.0).abs() < 0.001, "got {decayed}");
+
+            // Traffic older than the window no longer counts at all
+            time_source.advance_time(day * 31);
+            assert_eq!(score(&cache), 0.0);
+            let contract = cache.get(&key).unwrap();
+            assert_eq!(
+                (contract.bytes_served, contract.bytes_consumed),
+                (3000, 3000)
+            );
+        }
+
+        #[test]
+        fn test_budget_partitions_protect_committed_contracts() {
+            // A committed contract that scores below a busy uncommitted one
+            let setup = |committed_budget_share| {
+                let time_source = SharedMockTimeSource::new();
+                let mut cache = HostingCache::new_with_cwp(
+                    1000,
+                    Duration::from_secs(60),
+                    time_source.clone(),
+                    CWPConfig {
+                        commitment_density_target: 1.0,
+                        committed_budget_share,
+                        ..CWPConfig::default()
+                    },
+                );
+                let committed = make_key(1);
+                let busy = make_key(2);
+                cache.record_access(committed, 700, AccessType::Get);
+                cache.update_commitment(&committed, 1, time_source.now());
+                cache.record_access(busy, 200, AccessType::Get);
+                cache.record_bytes_served(&busy, 10_000);
+                time_source.advance_time(Duration::from_secs(61));
+                (cache, time_source, committed, busy)
+            };
+
+            let (mut cache, _, committed, _) = setup(None);
+            let result = cache.record_access(make_key(3), 200, AccessType::Get);
+            assert_eq!(result.evicted, vec![committed]);
+
+            // Uncommitted bytes (400) are over their 30% share, so the
+            // victim comes from them despite the higher score
+            let (mut cache, time_source, committed, busy) = setup(Some(0.7));
+            let result = cache.record_access(make_key(3), 200, AccessType::Get);
+            assert_eq!(result.evicted, vec![busy]);
+
+            // A burst of uncommitted contracts within their TTL can't squeeze
+            // the committed partition; the budget is exceeded instead
+            time_source.advance_time(Duration::from_secs(1));
+            let result = cache.record_access(make_key(4), 300, AccessType::Get);
+            assert!(result.evicted.is_empty());
+            assert!(cache.contains(&committed));
+            assert_eq!(cache.current_bytes(), 1200);
+        }
+
+        #[test]
+        fn test_commitment_warmup_protects_until_oracle_reports() {
+            let time_source = SharedMockTimeSource::new();
+            let config = CWPConfig {
+                commitment_warmup_secs: 600.0,
+                ..CWPConfig::default()
+            };
+            let mut cache = HostingCache::new_with_cwp(
+                300,
+                Duration::from_secs(60),
+                time_source.clone(),
+                config.clone(),
+            );
+            let datapod = make_key(1);
+            let busy = make_key(2);
+            cache.record_access(datapod, 100, AccessType::Put);
+            set_creator(&mut cache, &datapod, [7u8; 32]);
+            // Outscores the datapod on contribution and subscriptions
+            cache.record_access(busy, 100, AccessType::Get);
+            cache.record_bytes_served(&busy, 10_000);
+            cache.set_client_subscribers(&busy, 10);
+            time_source.advance_time(Duration::from_secs(61));
+
+            let report = cache.score_report(&datapod).unwrap();
+            assert!(report.warmup_protected && !report.ttl_protected);
+            let result = cache.record_access(make_key(3), 200, AccessType::Get);
+            assert_eq!(result.evicted, vec![busy]);
+
+            // The oracle reporting, even no deposit, ends the warm-up
+            cache.update_commitment(&datapod, 0, time_source.now());
+            assert!(!cache
+                .get(&datapod)
+                .unwrap()
+                .in_warmup(time_source.now(), &config));
+
+            // So does the window running out
+            let late = make_key(4);
+            cache.record_access(late, 100, AccessType::Put);
+            set_creator(&mut cache, &late, [8u8; 32]);
+            assert!(cache
+                .get(&late)
+                .unwrap()
+                .in_warmup(time_source.now(), &config));
+            time_source.advance_time(Duration::from_secs(600));
+            assert!(!cache
+                .get(&late)
+                .unwrap()
+                .in_warmup(time_source.now(), &config));
+        }
+
+        #[test]
+        fn test_commitment_decrease_waits_for_confirmation() {
+            let time_source = SharedMockTimeSource::new();
+            let mut cache = HostingCache::new_with_cwp(
+                10_000,
+                Duration::from_secs(60),
+                time_source.clone(),
+                CWPConfig {
+                    commitment_decrease_confirmation_secs: 300.0,
+                    ..CWPConfig::default()
+                },
+            );
+            let key = make_key(1);
+            let deposit = |cache: &HostingCache<SharedMockTimeSource>| {
+                cache.get(&key).unwrap().commitment.deposited_xlm
+            };
+            cache.record_access(key, 100, AccessType::Put);
+            cache.update_commitment(&key, 1_000, time_source.now());
+
+            // A brief zero is held back and cancelled by the next report
+            cache.update_commitment(&key, 0, time_source.now());
+            assert_eq!(deposit(&cache), 1_000);
+            time_source.advance_time(Duration::from_secs(200));
+            cache.update_commitment(&key, 1_000, time_source.now());
+            assert_eq!(cache.get(&key).unwrap().commitment.pending_decrease, None);
+            time_source.advance_time(Duration::from_secs(200));
+            cache.sweep_expired(|_| false);
+            assert_eq!(deposit(&cache), 1_000);
+
+            // A decrease reported for the whole window applies on the next
+            // report, with the latest value
+            cache.update_commitment(&key, 400, time_source.now());
+            time_source.advance_time(Duration::from_secs(200));
+            cache.update_commitment(&key, 300, time_source.now());
+            assert_eq!(deposit(&cache), 1_000);
+            time_source.advance_time(Duration::from_secs(100));
+            cache.update_commitment(&key, 300, time_source.now());
+            assert_eq!(deposit(&cache), 300);
+
+            // Or on the sweep, without another report
+            cache.update_commitment(&key, 100, time_source.now());
+            time_source.advance_time(Duration::from_secs(300));
+            cache.sweep_expired(|_| false);
+            assert_eq!(deposit(&cache), 100);
+
+            // Increases apply at once
+            cache.update_commitment(&key, 5_000, time_source.now());
+            assert_eq!(deposit(&cache), 5_000);
+        }
+
+        #[test]
+        fn test_state_refresh_cooldown_ignores_repeated_states() {
+            let (mut cache, time_source) = make_quota_cache(CWPConfig {
+                state_refresh_cooldown_secs: 60.0,
+                ..CWPConfig::default()
+            });
+            let key = make_key(1);
+            let (state_a, state_b) = ([1u8; 32], [2u8; 32]);
+            assert!(!cache.state_seen_recently(&key, &state_a));
+
+            cache.record_access(key, 100, AccessType::Put);
+            cache.record_state_hash(&key, state_a);
+            time_source.advance_time(Duration::from_secs(10));
+            cache.record_state_hash(&key, state_b);
+
+            // Flipping back to an earlier state within the cooldown is churn
+            time_source.advance_time(Duration::from_secs(10));
+            assert!(cache.state_seen_recently(&key, &state_a));
+            assert!(cache.state_seen_recently(&key, &state_b));
+            assert!(!cache.state_seen_recently(&key, &[3u8; 32]));
+
+            // Once the cooldown has passed it refreshes again
+            time_source.advance_time(Duration::from_secs(41));
+            assert!(!cache.state_seen_recently(&key, &state_a));
+            assert!(cache.state_seen_recently(&key, &state_b));
+
+            // Only the most recent distinct states are remembered
+            for seed in 10..10 + STATE_HISTORY_LEN as u8 {
+                cache.record_state_hash(&key, [seed; 32]);
+            }
+            assert!(!cache.state_seen_recently(&key, &state_b));
+            assert_eq!(
+                cache.get(&key).unwrap().state_history.len(),
+                STATE_HISTORY_LEN
+            );
+        }
+
+        #[test]
+        fn test_watermarks_evict_in_batches() {
+            let time_source = SharedMockTimeSource::new();
+            let mut cache = HostingCache::new_with_cwp(
+                1000,
+                Duration::from_secs(60),
+                time_source.clone(),
+                CWPConfig {
+                    eviction_high_watermark: 0.9,
+                    eviction_low_watermark: 0.5,
+                    ..CWPConfig::default()
+                },
+            );
+
+            // Up to the high watermark: nothing evicted
+            for i in 0..9 {
+                assert!(cache
+                    .record_access(make_key(i), 100, AccessType::Get)
+                    .evicted
+                    .is_empty());
+            }
+            time_source.advance_time(Duration::from_secs(61));
+
+            // Crossing it evicts down to the low watermark in one go
+            let result = cache.record_access(make_key(9), 100, AccessType::Get);
+            assert_eq!(result.evicted.len(), 5);
+            assert_eq!(cache.current_bytes(), 500);
+
+            // The next inserts fit without evicting
+            for i in 10..14 {
+                assert!(cache
+                    .record_access(make_key(i), 100, AccessType::Get)
+                    .evicted
+                    .is_empty());
+            }
+            assert_eq!(cache.current_bytes(), 900);
+            assert!(cache.sweep_expired(|_| false).is_empty());
+
+            // Only the four remaining old contracts can go; TTL-protected
+            // inserts then exceed the high watermark
+            let result = cache.record_access(make_key(14), 100, AccessType::Get);
+            assert_eq!(result.evicted.len(), 4);
+            for i in 15..19 {
+                cache.record_access(make_key(i), 100, AccessType::Get);
+            }
+            assert_eq!(cache.current_bytes(), 1000);
+
+            // The sweep brings usage back down to the low watermark
+            time_source.advance_time(Duration::from_secs(61));
+            let swept = cache.sweep_expired(|_| false);
+            assert_eq!(swept.len(), 5);
+            assert_eq!(cache.current_bytes(), 500);
+        }
+
         #[test]
         fn test_contribution_affects_eviction_order() {
             let (mut cache, time) = make_cache(300, Duration::from_secs(60));
@@ -1359,6 +3656,368 @@ mod tests {
             assert!(cache.contains(&key2));
         }
 
+        /// Exhaustive scan with the same eligibility and tie-breaking as the index.
+        fn brute_force_victim(
+            cache: &HostingCache<SharedMockTimeSource>,
+            now: Instant,
+        ) -> Option<ContractKey> {
+            cache
+                .contracts
+                .iter()
+                .filter(|(_, c)| now.saturating_duration_since(c.last_accessed) >= cache.min_ttl)
+                .min_by(|(ka, a), (kb, b)| {
+                    let sa = a.persistence_score(now, &cache.cwp_config);
+                    let sb = b.persistence_score(now, &cache.cwp_config);
+                    sa.total_cmp(&sb)
+                        .then(a.last_accessed.cmp(&b.last_accessed))
+                        .then(ka.id().as_bytes().cmp(kb.id().as_bytes()))
+                })
+                .map(|(k, _)| *k)
+        }
+
+        #[test]
+        fn test_score_index_matches_exhaustive_scan() {
+            let (mut cache, time) = make_cache(100_000, Duration::from_secs(60));
+            for i in 0..20u8 {
+                cache.record_access(make_key(i), 100 + i as u64, AccessType::Get);
+                time.advance_time(Duration::from_secs(1));
+            }
+            time.advance_time(Duration::from_secs(61));
+
+            let now = cache.time_source.now();
+            assert_eq!(
+                cache.find_lowest_score_victim(now, None),
+                brute_force_victim(&cache, now)
+            );
+
+            // Mutate scores after the index was built; the dirty entries must
+            // be rescored before the next lookup.
+            for i in (0..20u8).step_by(3) {
+                cache.record_bytes_served(&make_key(i), 1000 * i as u64);
+            }
+            cache.update_commitment(&make_key(1), 50, now);
+            cache.touch(&make_key(2));
+            time.advance_time(Duration::from_secs(5));
+
+            let now = cache.time_source.now();
+            assert_eq!(
+                cache.find_lowest_score_victim(now, None),
+                brute_force_victim(&cache, now)
+            );
+        }
+
+        #[test]
+        fn test_score_index_tracks_mutations_between_evictions() {
+            let (mut cache, time) = make_cache(300, Duration::from_secs(60));
+            let key1 = make_key(1);
+            let key2 = make_key(2);
+            let key3 = make_key(3);
+
+            cache.record_access(key1, 100, AccessType::Get);
+            cache.record_access(key2, 100, AccessType::Get);
+            cache.record_access(key3, 100, AccessType::Get);
+            cache.record_bytes_served(&key3, 10_000);
+            time.advance_time(Duration::from_secs(61));
+
+            // key1 and key2 tie on score; key1 loses on key bytes
+            let result = cache.record_access(make_key(4), 100, AccessType::Get);
+            assert_eq!(result.evicted, vec![key1]);
+
+            // A commitment arriving after the index was built protects key2
+            cache.update_commitment(&key2, 1_000, cache.time_source.now());
+            time.advance_time(Duration::from_secs(61));
+
+            let result = cache.record_access(make_key(5), 100, AccessType::Get);
+            assert_eq!(result.evicted, vec![make_key(4)]);
+            assert!(cache.contains(&key2));
+            assert!(cache.contains(&key3));
+        }
+
+        #[test]
+        fn test_score_index_forgets_evicted_contracts() {
+            let (mut cache, time) = make_cache(200, Duration::from_secs(60));
+            for i in 0..10u8 {
+                cache.record_access(make_key(i), 100, AccessType::Get);
+                time.advance_time(Duration::from_secs(61));
+            }
+
+            assert_eq!(cache.len(), 2);
+            let now = cache.time_source.now();
+            cache.find_lowest_score_victim(now, None);
+            assert_eq!(cache.score_index.positions.len(), cache.len());
+            assert_eq!(cache.score_index.order.len(), cache.len());
+        }
+
+        #[test]
+        fn test_restore_lepus_state_after_load() {
+            let (mut cache, time) = make_cache(10_000, Duration::from_secs(60));
+            time.advance_time(Duration::from_secs(3600));
+            let key = make_key(1);
+            let config = CWPConfig::default();
+
+            cache.load_persisted_entry(key, 2048, AccessType::Put, Duration::from_secs(10));
+            let now = cache.time_source.now();
+            let flat = cache.get(&key).unwrap().persistence_score(now, &config);
+
+            let identity = IdentityState {
+                creator_pubkey: Some([1u8; 32]),
+                creator_verified: true,
+                ..Default::default()
+            };
+            assert!(cache.restore_lepus_state(
+                &key,
+                10,
+                Some(Duration::from_secs(30)),
+                identity,
+                5000,
+                2000,
+            ));
+            assert!(!cache.restore_lepus_state(
+                &make_key(2),
+                10,
+                None,
+                IdentityState::default(),
+                0,
+                0,
+            ));
+
+            let contract = cache.get(&key).unwrap();
+            assert_eq!(contract.commitment.deposited_xlm, 10);
+            assert_eq!(
+                contract.commitment.last_oracle_check,
+                Some(now - Duration::from_secs(30))
+            );
+            assert!(contract.identity.creator_verified);
+            assert_eq!(contract.bytes_served, 5000);
+            assert_eq!(contract.bytes_consumed, 2000);
+            assert!(contract.persistence_score(now, &config) > flat + 0.5);
+        }
+
+        fn make_quota_cache(
+            config: CWPConfig,
+        ) -> (HostingCache<SharedMockTimeSource>, SharedMockTimeSource) {
+            let time_source = SharedMockTimeSource::new();
+            let cache = HostingCache::new_with_cwp(
+                1_000_000,
+                Duration::from_secs(60),
+                time_source.clone(),
+                config,
+            );
+            (cache, time_source)
+        }
+
+        fn set_creator(
+            cache: &mut HostingCache<SharedMockTimeSource>,
+            key: &ContractKey,
+            creator: [u8; 32],
+        ) {
+            assert!(cache.update_identity(key, Some(creator), true, None, false, None));
+        }
+
+        #[test]
+        fn test_score_report_explains_score() {
+            let (mut cache, time) = make_cache(1000, Duration::from_secs(60));
+            let key = make_key(1);
+            assert!(cache.score_report(&key).is_none());
+
+            cache.record_access(key, 100, AccessType::Put);
+            cache.update_identity(&key, Some([7u8; 32]), true, None, false, None);
+            cache.record_bytes_served(&key, 300);
+            cache.record_bytes_consumed(&key, 100);
+            cache.record_validation_failure(&key);
+            time.advance_time(Duration::from_secs(90));
+
+            let report = cache.score_report(&key).unwrap();
+            let contract = cache.get(&key).unwrap();
+            let now = cache.time_source.now();
+            assert_eq!(
+                report.breakdown,
+                contract.score_breakdown(now, &cache.cwp_config)
+            );
+            assert_eq!(
+                report.breakdown.score,
+                contract.persistence_score(now, &cache.cwp_config)
+            );
+            assert_eq!(report.breakdown.identity, 0.6);
+            assert_eq!(report.access_type, "put");
+            assert_eq!(report.last_accessed_secs_ago, 90.0);
+            assert!(!report.ttl_protected);
+            assert_eq!(report.oracle_check_secs_ago, None);
+            assert_eq!(report.creator_pubkey, Some(hex::encode([7u8; 32])));
+            assert_eq!(report.bytes_served, 300);
+            assert_eq!(report.validation_failures, 1);
+        }
+
+        #[test]
+        fn test_list_score_reports_sorts_and_paginates() {
+            let (mut cache, time) = make_cache(10_000, Duration::from_secs(60));
+            for (seed, size) in [(1, 300), (2, 100), (3, 200)] {
+                cache.record_access(make_key(seed), size, AccessType::Get);
+                time.advance_time(Duration::from_secs(10));
+            }
+            // Key 2 outscores the others
+            cache.update_identity(&make_key(2), Some([7u8; 32]), true, None, false, None);
+
+            let page = cache.list_score_reports(HostedContractSort::Score, true, 0, 2);
+            assert_eq!(page.total, 3);
+            assert_eq!(page.contracts.len(), 2);
+            assert_eq!(page.contracts[0].key, make_key(2).to_string());
+            assert!(page.contracts[0].creator_verified);
+            assert_eq!(page.contracts[0], cache.score_report(&make_key(2)).unwrap());
+
+            let sizes = |page: HostedContractPage| -> Vec<u64> {
+                page.contracts.iter().map(|c| c.size_bytes).collect()
+            };
+            assert_eq!(
+                sizes(cache.list_score_reports(HostedContractSort::Size, false, 1, 10)),
+                vec![200, 300]
+            );
+            assert_eq!(
+                sizes(cache.list_score_reports(HostedContractSort::LastAccessed, false, 0, 10)),
+                vec![300, 100, 200]
+            );
+            assert!(cache
+                .list_score_reports(HostedContractSort::Score, false, 3, 10)
+                .contracts
+                .is_empty());
+        }
+
+        #[test]
+        fn test_creator_commitment_aggregation_spreads_deposits() {
+            let (mut cache, _time) = make_quota_cache(CWPConfig {
+                aggregate_creator_commitment: true,
+                ..Default::default()
+            });
+            let creator = [7u8; 32];
+            let anchor = make_key(1);
+            for seed in 1..=3 {
+                let key = make_key(seed);
+                cache.record_access(key, 100_000, AccessType::Put);
+                set_creator(&mut cache, &key, creator);
+            }
+            // Same creator but unverified: not part of the pool
+            let unverified = make_key(4);
+            cache.record_access(unverified, 100_000, AccessType::Put);
+            cache.update_identity(&unverified, Some(creator), false, None, false, None);
+
+            let now = cache.time_source.now();
+            cache.update_commitment(&anchor, 150, now);
+            let config = cache.cwp_config().clone();
+            let commitment = |cache: &HostingCache<_>, seed| {
+                cache
+                    .get(&make_key(seed))
+                    .unwrap()
+                    .commitment_score(now, &config)
+            };
+            // 150 XLM over 300 KB of verified contracts: 50 each, half of target
+            for seed in 1..=3 {
+                assert!((commitment(&cache, seed) - 0.5).abs() < 1e-9);
+            }
+            assert_eq!(commitment(&cache, 4), 0.0);
+            // Occupancy counts pooled contracts as committed, as scoring does
+            let occupancy = cache.occupancy();
+            assert_eq!(occupancy.committed_contracts, 3);
+            assert_eq!(occupancy.uncommitted_contracts, 1);
+
+            // Turning aggregation off goes back to each contract's own deposit
+            cache.set_cwp_config(CWPConfig::default());
+            assert_eq!(commitment(&cache, 1), 1.0);
+            assert_eq!(commitment(&cache, 2), 0.0);
+            assert_eq!(cache.occupancy().committed_contracts, 1);
+        }
+
+        #[test]
+        fn test_set_cwp_config_applies_new_quota() {
+            let (mut cache, _time) = make_quota_cache(CWPConfig::default());
+            let creator = [7u8; 32];
+            for seed in 1..=2 {
+                let key = make_key(seed);
+                cache.record_access(key, 100, AccessType::Put);
+                set_creator(&mut cache, &key, creator);
+            }
+            cache.update_commitment(&make_key(1), 1000, cache.time_source.now());
+
+            let previous = cache.set_cwp_config(CWPConfig {
+                max_contracts_per_creator: Some(1),
+                ..Default::default()
+            });
+            assert_eq!(previous, CWPConfig::default());
+            assert_eq!(cache.cwp_config().max_contracts_per_creator, Some(1));
+
+            // Enforced on the next access, evicting the unfunded contract
+            let result = cache.record_access(make_key(3), 100, AccessType::Put);
+            assert_eq!(result.evicted, vec![make_key(2)]);
+            assert_eq!(cache.creator_usage(&creator), (100, 1));
+        }
+
+        #[test]
+        fn test_creator_contract_quota_evicts_own_lowest_score() {
+            let (mut cache, time) = make_quota_cache(CWPConfig {
+                max_contracts_per_creator: Some(2),
+                ..Default::default()
+            });
+            let flooder = [7u8; 32];
+            let other = [8u8; 32];
+
+            for seed in 1..=3 {
+                let key = make_key(seed);
+                cache.record_access(key, 100, AccessType::Put);
+                set_creator(&mut cache, &key, flooder);
+                time.advance_time(Duration::from_secs(10));
+            }
+            // Deposit keeps the oldest flooder contract ahead of the others.
+            cache.update_commitment(&make_key(1), 1000, cache.time_source.now());
+            assert_eq!(cache.creator_usage(&flooder), (300, 3));
+
+            // The next access, by anyone, enforces the quota even though the
+            // cache has room, and ignores TTL.
+            let bystander = make_key(4);
+            let result = cache.record_access(bystander, 100, AccessType::Put);
+            assert_eq!(result.evicted, vec![make_key(2)]);
+            set_creator(&mut cache, &bystander, other);
+            assert_eq!(cache.creator_usage(&flooder), (200, 2));
+            assert_eq!(cache.creator_usage(&other), (100, 1));
+            assert!(cache.contains(&make_key(1)));
+            assert!(cache.contains(&make_key(3)));
+            assert_eq!(cache.current_bytes(), 300);
+
+            let evictions = cache.take_evictions();
+            assert_eq!(evictions.len(), 1);
+            assert_eq!(evictions[0].reason, EvictionReason::Quota);
+
+            let result = cache.record_access(make_key(5), 100, AccessType::Put);
+            assert!(result.evicted.is_empty());
+        }
+
+        #[test]
+        fn test_creator_byte_quota_spares_accessed_contract() {
+            let (mut cache, time) = make_quota_cache(CWPConfig {
+                max_bytes_per_creator: Some(1000),
+                ..Default::default()
+            });
+            let creator = [7u8; 32];
+            let funded = make_key(1);
+            let growing = make_key(2);
+
+            cache.record_access(funded, 600, AccessType::Put);
+            set_creator(&mut cache, &funded, creator);
+            cache.update_commitment(&funded, 1000, cache.time_source.now());
+            cache.record_access(growing, 300, AccessType::Put);
+            set_creator(&mut cache, &growing, creator);
+            time.advance_time(Duration::from_secs(3600));
+
+            // Growing past the quota evicts the creator's other contract,
+            // never the one being accessed, even though it scores lower.
+            let result = cache.record_access(growing, 800, AccessType::Get);
+            assert_eq!(result.evicted, vec![funded]);
+            assert_eq!(cache.creator_usage(&creator), (800, 1));
+
+            // Alone over quota: kept, since it is the accessed contract.
+            let result = cache.record_access(growing, 1200, AccessType::Get);
+            assert!(result.evicted.is_empty());
+            assert_eq!(cache.creator_usage(&creator), (1200, 1));
+        }
+
         // =================================================================
         // Andromica Datapod Validation Tests (Phase 4)
         // =================================================================
@@ -1516,7 +4175,7 @@ mod tests {
 
             // 2KB datapod with 10 XLM: density = 10 / (2048 * 0.001) = 4.88 → clamped to 1.0
             let small = make_cwp_contract(DATAPOD_SIZE, now, 0, 0, 10, false, false);
-            let small_score = small.commitment_score(&config);
+            let small_score = small.commitment_score(now, &config);
             assert!(
                 (small_score - 1.0).abs() < 0.001,
                 "Small datapod with 10 XLM should have commitment 1.0, got {small_score}"
@@ -1524,7 +4183,7 @@ mod tests {
 
             // 50KB contract with 10 XLM: density = 10 / (51200 * 0.001) = 0.195
             let large = make_cwp_contract(51200, now, 0, 0, 10, false, false);
-            let large_score = large.commitment_score(&config);
+            let large_score = large.commitment_score(now, &config);
             assert!(
                 large_score < 0.25,
                 "Large contract with same deposit should have low commitment, got {large_score}"
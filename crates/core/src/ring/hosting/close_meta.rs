@@ -0,0 +1,582 @@
+//! Proof source reading stellar-core's ledger close meta stream (Lepus).
+//!
+//! A relayer running beside its own stellar-core, or captive core, can take
+//! each closed ledger straight from core's metadata output stream
+//! (`METADATA_OUTPUT_STREAM`) rather than piecing its proof together from
+//! RPC calls. The stream is a sequence of `LedgerCloseMeta` XDR records,
+//! each fragment preceded by a 4-byte record mark as in history archives.
+//! It is read from a file, tailed as core appends to it, from a named pipe,
+//! or from a Unix socket given as `unix:<path>`.
+//!
+//! Every ledger whose transaction metas carry an indexed event of the hvym
+//! contract is kept as a `DepositProof`, for the last `RETAINED_LEDGERS`
+//! ledgers read, and queries are answered from those. Ledgers the stream
+//! missed, e.g. while core was down, come from Soroban RPC when endpoints
+//! are configured. SCP envelopes core leaves out are taken from the history
+//! archive.
+
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use stellar_xdr::curr::{LedgerCloseMeta, Limits, ReadXdr, TransactionResultMeta};
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+use super::deposit_index::DepositProof;
+use super::history_archive::HistoryArchive;
+use super::oracle::{
+    hvym_event_kinds, DepositEvents, OracleConfig, OracleError, StellarProofRelayer,
+    StellarProofSource,
+};
+use super::validator_set::{decode_strkey, STRKEY_CONTRACT};
+use crate::config::GlobalExecutor;
+
+/// Ledgers whose proofs are kept, about an hour of closes.
+const RETAINED_LEDGERS: u32 = 720;
+
+/// Largest record accepted, so a corrupt record mark can't make the reader
+/// allocate without bound. Busy ledgers' close meta runs to a few MiB.
+const MAX_RECORD_BYTES: usize = 64 * 1024 * 1024;
+
+/// How often a file at its end is checked for more records.
+const TAIL_INTERVAL: Duration = Duration::from_millis(200);
+
+const REOPEN_BASE_BACKOFF: Duration = Duration::from_secs(1);
+const REOPEN_MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Where the close meta stream is read from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CloseMetaStream {
+    /// A file core appends to, or a named pipe.
+    File(PathBuf),
+    /// A Unix socket core writes to.
+    Socket(PathBuf),
+}
+
+impl CloseMetaStream {
+    /// The stream at `spec`: `unix:<path>` for a Unix socket, otherwise the
+    /// path of a file or named pipe.
+    pub fn parse(spec: &str) -> Self {
+        let spec = spec.trim();
+        match spec.strip_prefix("unix:") {
+            Some(path) => Self::Socket(PathBuf::from(path)),
+            None => Self::File(PathBuf::from(spec)),
+        }
+    }
+
+    /// Open the stream, returning whether it is a file to be tailed rather
+    /// than a stream that ends when core stops writing.
+    async fn open(&self) -> std::io::Result<(Box<dyn AsyncRead + Send + Unpin>, bool)> {
+        match self {
+            Self::File(path) => {
+                let file = tokio::fs::File::open(path).await?;
+                let tail = file.metadata().await?.is_file();
+                Ok((Box::new(file), tail))
+            }
+            #[cfg(unix)]
+            Self::Socket(path) => {
+                let socket = tokio::net::UnixStream::connect(path).await?;
+                Ok((Box::new(socket), false))
+            }
+            #[cfg(not(unix))]
+            Self::Socket(_) => Err(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                "Unix sockets are not supported on this platform",
+            )),
+        }
+    }
+}
+
+/// The ledgers read from the stream.
+#[derive(Debug, Default)]
+struct ClosedLedgers {
+    /// Proofs of the retained ledgers with indexed events.
+    proofs: BTreeMap<u32, DepositProof>,
+    /// First of the retained ledgers read without a break up to `latest`.
+    read_from: Option<u32>,
+    latest: Option<u32>,
+}
+
+impl ClosedLedgers {
+    /// Take in `ledger_seq`, with its proof if it has indexed events. A
+    /// ledger not following the last one read starts the run of ledgers
+    /// read over; ledgers already read are ignored.
+    fn push(&mut self, ledger_seq: u32, proof: Option<DepositProof>) {
+        match self.latest {
+            Some(latest) if ledger_seq <= latest => return,
+            Some(latest) if ledger_seq == latest + 1 => {}
+            _ => self.read_from = Some(ledger_seq),
+        }
+        self.latest = Some(ledger_seq);
+        if let Some(proof) = proof {
+            self.proofs.insert(ledger_seq, proof);
+        }
+        let oldest = ledger_seq.saturating_sub(RETAINED_LEDGERS - 1);
+        self.proofs = self.proofs.split_off(&oldest);
+        if let Some(read_from) = &mut self.read_from {
+            *read_from = (*read_from).max(oldest);
+        }
+    }
+
+    /// The ledgers with events read after `since_ledger`. The search starts
+    /// no earlier than the ledgers read without a break, so ledgers the
+    /// stream missed show up as a gap.
+    fn events_after(&self, since_ledger: u32) -> DepositEvents {
+        let Some(read_from) = self.read_from else {
+            return DepositEvents::default();
+        };
+        let scanned_from = since_ledger.saturating_add(1).max(read_from);
+        let ledgers: Vec<u32> = self
+            .proofs
+            .range(scanned_from..)
+            .map(|(&seq, _)| seq)
+            .collect();
+        let event_kinds = ledgers
+            .iter()
+            .map(|seq| (*seq, self.proofs[seq].event_kinds.clone()))
+            .collect();
+        DepositEvents {
+            ledgers,
+            event_kinds,
+            cursor: None,
+            latest_ledger: self.latest,
+            scanned_from: Some(scanned_from),
+        }
+    }
+}
+
+/// Proof source fed by a ledger close meta stream, falling back on Soroban
+/// RPC for what the stream didn't carry.
+pub struct CloseMetaSource {
+    closed: Arc<Mutex<ClosedLedgers>>,
+    rpc: Option<StellarProofRelayer>,
+    archive: Option<HistoryArchive>,
+    reader: tokio::task::JoinHandle<()>,
+}
+
+impl CloseMetaSource {
+    /// Start reading `stream` for the relayer configured by `config`.
+    pub fn new(config: &OracleConfig, stream: CloseMetaStream) -> Result<Self, OracleError> {
+        let contract_id = config
+            .soroban_contract_id
+            .as_deref()
+            .ok_or(OracleError::NotConfigured)?;
+        let hvym_contract = decode_strkey(contract_id, STRKEY_CONTRACT)
+            .map_err(|e| OracleError::Other(format!("soroban contract ID {contract_id}: {e}")))?;
+        let rpc = if config.rpc_urls.is_empty() {
+            None
+        } else {
+            Some(StellarProofRelayer::new(config)?)
+        };
+        let archive = match &config.history_archive_url {
+            Some(url) => {
+                let client = reqwest::Client::builder()
+                    .timeout(config.http_timeout)
+                    .build()
+                    .map_err(OracleError::RpcError)?;
+                Some(HistoryArchive::new(client, url))
+            }
+            None => None,
+        };
+        let closed = Arc::new(Mutex::new(ClosedLedgers::default()));
+        let reader = GlobalExecutor::spawn(read_stream(stream, hvym_contract, closed.clone()));
+        Ok(Self {
+            closed,
+            rpc,
+            archive,
+            reader,
+        })
+    }
+}
+
+impl Drop for CloseMetaSource {
+    fn drop(&mut self) {
+        self.reader.abort();
+    }
+}
+
+impl StellarProofSource for CloseMetaSource {
+    fn query_deposit_events(
+        &self,
+        since_ledger: u32,
+        _cursor: Option<String>,
+    ) -> Pin<Box<dyn Future<Output = Result<DepositEvents, OracleError>> + Send + '_>> {
+        Box::pin(async move {
+            let events = self.closed.lock().events_after(since_ledger);
+            // Nothing read yet, or the stream missed ledgers after `since_ledger`
+            let missed = since_ledger > 0
+                && !events
+                    .scanned_from
+                    .is_some_and(|scanned_from| scanned_from <= since_ledger + 1);
+            match &self.rpc {
+                Some(rpc) if missed => {
+                    tracing::debug!(
+                        since_ledger,
+                        read_from = ?events.scanned_from,
+                        "Lepus relayer: ledgers not in the close meta stream, querying RPC"
+                    );
+                    rpc.query_deposit_events(since_ledger, None).await
+                }
+                _ => Ok(events),
+            }
+        })
+    }
+
+    fn fetch_proof_for_ledger(
+        &self,
+        ledger_seq: u32,
+    ) -> Pin<Box<dyn Future<Output = Result<DepositProof, OracleError>> + Send + '_>> {
+        Box::pin(async move {
+            let proof = self.closed.lock().proofs.get(&ledger_seq).cloned();
+            let Some(mut proof) = proof else {
+                return match &self.rpc {
+                    Some(rpc) => rpc.fetch_proof_for_ledger(ledger_seq).await,
+                    None => Err(OracleError::ParseError(format!(
+                        "ledger {ledger_seq} not in the close meta stream"
+                    ))),
+                };
+            };
+            if proof.scp_envelopes.is_empty() {
+                if let Some(archive) = &self.archive {
+                    let archived = archive.ledger(ledger_seq).await?;
+                    proof.scp_envelopes = archived.scp_envelopes;
+                    proof.transaction_set = archived.transaction_set;
+                }
+            }
+            Ok(proof)
+        })
+    }
+
+    fn latest_ledger(&self) -> Pin<Box<dyn Future<Output = Result<u32, OracleError>> + Send + '_>> {
+        Box::pin(async move {
+            let latest = self.closed.lock().latest;
+            match (latest, &self.rpc) {
+                (Some(latest), _) => Ok(latest),
+                (None, Some(rpc)) => rpc.latest_ledger().await,
+                (None, None) => Err(OracleError::Other(
+                    "no ledger read from the close meta stream yet".to_string(),
+                )),
+            }
+        })
+    }
+
+    fn query_deposit_events_alternate(
+        &self,
+        since_ledger: u32,
+    ) -> Pin<Box<dyn Future<Output = Result<DepositEvents, OracleError>> + Send + '_>> {
+        Box::pin(async move {
+            match &self.rpc {
+                Some(rpc) => rpc.query_deposit_events(since_ledger, None).await,
+                None => Err(OracleError::NotConfigured),
+            }
+        })
+    }
+}
+
+/// Read `stream` into `closed`, opening it again with backoff whenever it
+/// ends or fails.
+async fn read_stream(
+    stream: CloseMetaStream,
+    hvym_contract: [u8; 32],
+    closed: Arc<Mutex<ClosedLedgers>>,
+) {
+    let mut backoff = REOPEN_BASE_BACKOFF;
+    loop {
+        let opened = tokio::time::Instant::now();
+        let read = match stream.open().await {
+            Ok((reader, tail)) => {
+                tracing::info!(?stream, "Lepus relayer: reading ledger close meta stream");
+                let mut records = RecordReader::new(reader, tail);
+                read_records(&mut records, &hvym_contract, &closed).await
+            }
+            Err(e) => Err(e.into()),
+        };
+        match read {
+            Ok(()) => tracing::info!(?stream, "Lepus relayer: ledger close meta stream ended"),
+            Err(e) => tracing::warn!(
+                ?stream,
+                error = %e,
+                retry_in_secs = backoff.as_secs(),
+                "Lepus relayer: ledger close meta stream failed"
+            ),
+        }
+        // A stream that held for a while starts the backoff over
+        if opened.elapsed() > REOPEN_MAX_BACKOFF {
+            backoff = REOPEN_BASE_BACKOFF;
+        }
+        tokio::time::sleep(backoff).await;
+        backoff = (backoff * 2).min(REOPEN_MAX_BACKOFF);
+    }
+}
+
+async fn read_records<R: AsyncRead + Unpin>(
+    records: &mut RecordReader<R>,
+    hvym_contract: &[u8; 32],
+    closed: &Mutex<ClosedLedgers>,
+) -> anyhow::Result<()> {
+    while let Some(record) = records.next().await? {
+        let lcm = LedgerCloseMeta::from_xdr(record, Limits::none())
+            .map_err(|e| anyhow::anyhow!("XDR decode LedgerCloseMeta: {e}"))?;
+        let (ledger_seq, proof) = ledger_proof(lcm, hvym_contract)?;
+        tracing::trace!(
+            ledger_seq,
+            events = proof.is_some(),
+            "Lepus relayer: ledger read from close meta stream"
+        );
+        closed.lock().push(ledger_seq, proof);
+    }
+    Ok(())
+}
+
+/// The sequence of the ledger closed in `lcm`, and its proof if it holds
+/// events the deposit-index indexes.
+fn ledger_proof(
+    lcm: LedgerCloseMeta,
+    hvym_contract: &[u8; 32],
+) -> Result<(u32, Option<DepositProof>), OracleError> {
+    let ledger_seq = match &lcm {
+        LedgerCloseMeta::V0(v0) => v0.ledger_header.header.ledger_seq,
+        LedgerCloseMeta::V1(v1) => v1.ledger_header.header.ledger_seq,
+        LedgerCloseMeta::V2(v2) => v2.ledger_header.header.ledger_seq,
+    };
+    let mut proof = StellarProofRelayer::close_meta_proof(ledger_seq, lcm)?;
+    proof.event_kinds = indexed_event_kinds(&proof.tx_result_metas, hvym_contract)?;
+    Ok((ledger_seq, (!proof.event_kinds.is_empty()).then_some(proof)))
+}
+
+/// Topics of the indexed events `hvym_contract` emitted in `metas`, base64
+/// transaction result metas, in order and without duplicates.
+fn indexed_event_kinds(
+    metas: &[String],
+    hvym_contract: &[u8; 32],
+) -> Result<Vec<String>, OracleError> {
+    let mut kinds = Vec::new();
+    for meta in metas {
+        let meta = base64::decode(meta)
+            .map_err(|e| OracleError::ParseError(format!("base64 decode: {e}")))?;
+        let meta = TransactionResultMeta::from_xdr(meta, Limits::none()).map_err(|e| {
+            OracleError::ParseError(format!("XDR decode TransactionResultMeta: {e}"))
+        })?;
+        hvym_event_kinds(&meta.tx_apply_processing, hvym_contract, &mut kinds);
+    }
+    Ok(kinds)
+}
+
+/// Reads records from a record-marked XDR stream. At the end of a file it
+/// waits for core to append more; any other stream ends with the last
+/// record.
+struct RecordReader<R> {
+    inner: R,
+    tail: bool,
+}
+
+impl<R: AsyncRead + Unpin> RecordReader<R> {
+    fn new(inner: R, tail: bool) -> Self {
+        Self { inner, tail }
+    }
+
+    /// The next record, joined from its fragments, or `None` once the
+    /// stream ends between records.
+    async fn next(&mut self) -> std::io::Result<Option<Vec<u8>>> {
+        let mut record = Vec::new();
+        loop {
+            let mut mark = [0u8; 4];
+            if !self.fill(&mut mark, record.is_empty()).await? {
+                return Ok(None);
+            }
+            let mark = u32::from_be_bytes(mark);
+            let len = (mark & 0x7fff_ffff) as usize;
+            if record.len() + len > MAX_RECORD_BYTES {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("record over {MAX_RECORD_BYTES} bytes"),
+                ));
+            }
+            let start = record.len();
+            record.resize(start + len, 0);
+            self.fill(&mut record[start..], false).await?;
+            if mark & 0x8000_0000 != 0 {
+                return Ok(Some(record));
+            }
+        }
+    }
+
+    /// Fill `buf` from the stream. Returns `false` if the stream ended
+    /// before any of it, which is only the end of the stream when
+    /// `may_end`; ending anywhere else is an error.
+    async fn fill(&mut self, buf: &mut [u8], may_end: bool) -> std::io::Result<bool> {
+        let mut filled = 0;
+        while filled < buf.len() {
+            let read = self.inner.read(&mut buf[filled..]).await?;
+            if read > 0 {
+                filled += read;
+            } else if self.tail {
+                tokio::time::sleep(TAIL_INTERVAL).await;
+            } else if filled == 0 && may_end {
+                return Ok(false);
+            } else {
+                return Err(std::io::ErrorKind::UnexpectedEof.into());
+            }
+        }
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ring::hosting::proof_fixtures::ProofFixtures;
+    use freenet_stdlib::prelude::ContractInstanceId;
+    use std::io::Write;
+    use stellar_xdr::curr::WriteXdr;
+
+    /// `record` as a stream of fragments of at most `fragment` bytes.
+    fn record_marked(record: &[u8], fragment: usize) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let chunks: Vec<&[u8]> = record.chunks(fragment).collect();
+        for (i, chunk) in chunks.iter().enumerate() {
+            let last = if i + 1 == chunks.len() {
+                0x8000_0000
+            } else {
+                0
+            };
+            bytes.extend((chunk.len() as u32 | last).to_be_bytes());
+            bytes.extend(*chunk);
+        }
+        bytes
+    }
+
+    fn proof(ledger_seq: u32) -> DepositProof {
+        DepositProof {
+            ledger_seq,
+            scp_envelopes: vec!["env".to_string()],
+            transaction_set: String::new(),
+            tx_result_metas: vec![],
+            event_kinds: vec!["DEPOSIT".to_string()],
+            part: None,
+            attribution: None,
+        }
+    }
+
+    /// Wait until `source` has read `ledger`.
+    async fn read_up_to(source: &CloseMetaSource, ledger: u32) {
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while source.latest_ledger().await.ok() != Some(ledger) {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+        })
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_record_reader_joins_fragments() {
+        let mut bytes = record_marked(b"first record", 5);
+        bytes.extend(record_marked(b"second", 100));
+        let mut records = RecordReader::new(bytes.as_slice(), false);
+        assert_eq!(records.next().await.unwrap().unwrap(), b"first record");
+        assert_eq!(records.next().await.unwrap().unwrap(), b"second");
+        assert!(records.next().await.unwrap().is_none());
+
+        // A stream cut off inside a record is an error, not its end
+        let bytes = record_marked(b"cut off", 100);
+        let mut records = RecordReader::new(&bytes[..6], false);
+        assert!(records.next().await.is_err());
+        let mut records = RecordReader::new(&bytes[..2], false);
+        assert!(records.next().await.is_err());
+    }
+
+    #[test]
+    fn test_closed_ledgers_track_gaps() {
+        let mut closed = ClosedLedgers::default();
+        assert_eq!(closed.events_after(99), DepositEvents::default());
+
+        closed.push(100, None);
+        closed.push(101, Some(proof(101)));
+        closed.push(102, None);
+        closed.push(101, None);
+        let events = closed.events_after(99);
+        assert_eq!(events.ledgers, [101]);
+        assert_eq!(events.event_kinds[&101], ["DEPOSIT"]);
+        assert_eq!(events.latest_ledger, Some(102));
+        assert_eq!(events.scanned_from, Some(100));
+        assert!(closed.events_after(101).ledgers.is_empty());
+
+        // Reading resumes after a break: the search can't reach back past it
+        closed.push(110, Some(proof(110)));
+        let events = closed.events_after(101);
+        assert_eq!(events.ledgers, [110]);
+        assert_eq!(events.scanned_from, Some(110));
+        assert!(closed.proofs.contains_key(&101));
+
+        // Only the last ledgers read are kept
+        closed.push(111, None);
+        closed.push(101 + RETAINED_LEDGERS, Some(proof(101 + RETAINED_LEDGERS)));
+        assert_eq!(closed.read_from, Some(101 + RETAINED_LEDGERS));
+        assert_eq!(
+            closed.proofs.keys().copied().collect::<Vec<_>>(),
+            [110, 101 + RETAINED_LEDGERS]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_close_meta_file_tailed() {
+        const HVYM_CONTRACT: &str = "CD3KT3NS3GMAQTTNVS5HIMV7Q6ISZNRIFXF7LIOMUOC5JC5VMG4UVOHQ";
+        let fixtures = ProofFixtures::new(1, 1)
+            .with_hvym_contract(decode_strkey(HVYM_CONTRACT, STRKEY_CONTRACT).unwrap());
+        let instance_id = ContractInstanceId::new([7; 32]);
+        let record = |ledger_seq, deposits: &[(ContractInstanceId, i128)]| {
+            let lcm = fixtures.close_meta(ledger_seq, deposits);
+            record_marked(&lcm.to_xdr(Limits::none()).unwrap(), 1_000)
+        };
+
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(&record(500, &[])).unwrap();
+        file.write_all(&record(501, &[(instance_id, 50_000_000)]))
+            .unwrap();
+        file.flush().unwrap();
+        let config = OracleConfig {
+            soroban_contract_id: Some(HVYM_CONTRACT.to_string()),
+            ..Default::default()
+        };
+        let source = CloseMetaSource::new(
+            &config,
+            CloseMetaStream::parse(&file.path().to_string_lossy()),
+        )
+        .unwrap();
+
+        read_up_to(&source, 501).await;
+        let events = source.query_deposit_events(499, None).await.unwrap();
+        assert_eq!(events.ledgers, [501]);
+        assert_eq!(events.event_kinds[&501], ["DEPOSIT"]);
+        let proof = source.fetch_proof_for_ledger(501).await.unwrap();
+        let expected = fixtures.deposit_proof(501, &[(instance_id, 50_000_000)]);
+        assert_eq!(proof.tx_result_metas, expected.tx_result_metas);
+        assert_eq!(proof.scp_envelopes, expected.scp_envelopes);
+        assert!(source.fetch_proof_for_ledger(500).await.is_err());
+
+        // Ledgers appended later are picked up
+        file.write_all(&record(502, &[(instance_id, 1_000)]))
+            .unwrap();
+        file.flush().unwrap();
+        read_up_to(&source, 502).await;
+        let events = source.query_deposit_events(501, None).await.unwrap();
+        assert_eq!(events.ledgers, [502]);
+    }
+
+    #[test]
+    fn test_close_meta_stream_spec() {
+        assert_eq!(
+            CloseMetaStream::parse("/var/lib/stellar/meta.xdr"),
+            CloseMetaStream::File(PathBuf::from("/var/lib/stellar/meta.xdr"))
+        );
+        assert_eq!(
+            CloseMetaStream::parse(" unix:/run/stellar/meta.sock"),
+            CloseMetaStream::Socket(PathBuf::from("/run/stellar/meta.sock"))
+        );
+    }
+}
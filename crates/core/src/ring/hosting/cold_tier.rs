@@ -0,0 +1,380 @@
+//! On-disk cold tier for contracts evicted from the hosting cache (Lepus).
+//!
+//! Instead of forgetting an evicted contract outright, `HostingManager`
+//! archives its state and persisted hosting metadata here, gzip-compressed,
+//! one file per contract. The next GET for the contract takes the archive
+//! back out and re-hosts it with its CWP scoring state intact, so a contract
+//! that falls out of the hot budget doesn't have to be re-fetched and
+//! re-scored from scratch.
+//!
+//! The tier has its own byte budget, counted on compressed archive size.
+//! When it is full the oldest archives are dropped first.
+//!
+//! # Archive format
+//!
+//! `<instance_id>.cold`:
+//! `[version: 1][instance_id: 32][code_hash: 32][metadata_len: 2 LE][HostingMetadata][gzip(state)]`
+//!
+//! `metadata_len` is 0 when no hosting metadata was persisted for the contract.
+
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use freenet_stdlib::prelude::{CodeHash, ContractInstanceId, ContractKey};
+
+use crate::contract::storages::HostingMetadata;
+
+/// Directory under the node's database directory holding the archives.
+pub(crate) const COLD_TIER_DIR: &str = "hosting-cold";
+
+const ARCHIVE_EXTENSION: &str = "cold";
+const ARCHIVE_VERSION: u8 = 1;
+/// Fixed-size prefix: version, instance id, code hash, metadata length.
+const HEADER_LEN: usize = 1 + 32 + 32 + 2;
+
+/// A contract taken back out of the cold tier.
+#[derive(Debug)]
+pub(crate) struct ColdEntry {
+    pub key: ContractKey,
+    pub state: Vec<u8>,
+    /// Hosting metadata persisted at eviction time, including the Lepus
+    /// scoring state when it had been written.
+    pub metadata: Option<HostingMetadata>,
+}
+
+/// Compressed archive of evicted contracts with its own byte budget.
+pub(crate) struct ColdTier {
+    dir: PathBuf,
+    budget_bytes: u64,
+    current_bytes: u64,
+    /// Archived contracts and their size on disk.
+    entries: HashMap<ContractInstanceId, (ContractKey, u64)>,
+    /// Archive order, oldest first.
+    order: VecDeque<ContractInstanceId>,
+}
+
+impl ColdTier {
+    /// Open the cold tier at `dir`, indexing archives left by a previous run.
+    ///
+    /// Unreadable archives and leftover temporary files are removed. If the
+    /// existing archives exceed `budget_bytes`, the oldest are dropped.
+    pub fn open(dir: impl Into<PathBuf>, budget_bytes: u64) -> io::Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+
+        let mut found = Vec::new();
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some(ARCHIVE_EXTENSION) {
+                if path.is_file() {
+                    fs::remove_file(&path).ok();
+                }
+                continue;
+            }
+            match read_header(&path) {
+                Ok(key) => {
+                    let meta = fs::metadata(&path)?;
+                    found.push((meta.modified()?, key, meta.len()));
+                }
+                Err(e) => {
+                    tracing::warn!(path = %path.display(), error = %e,
+                        "Removing unreadable cold tier archive");
+                    fs::remove_file(&path).ok();
+                }
+            }
+        }
+        found.sort_by_key(|(modified, _, _)| *modified);
+
+        let mut tier = Self {
+            dir,
+            budget_bytes,
+            current_bytes: 0,
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+        };
+        for (_, key, size) in found {
+            tier.entries.insert(*key.id(), (key, size));
+            tier.order.push_back(*key.id());
+            tier.current_bytes += size;
+        }
+        let dropped = tier.enforce_budget();
+        tracing::info!(
+            archived_contracts = tier.entries.len(),
+            archived_bytes = tier.current_bytes,
+            dropped = dropped.len(),
+            "Opened hosting cold tier"
+        );
+        Ok(tier)
+    }
+
+    /// Archive an evicted contract's state and hosting metadata.
+    ///
+    /// Replaces any existing archive for the same contract. Returns the
+    /// contracts whose archives were dropped to stay within budget; an
+    /// archive larger than the whole budget is not written.
+    pub fn archive(
+        &mut self,
+        key: &ContractKey,
+        state: &[u8],
+        metadata: Option<&HostingMetadata>,
+    ) -> io::Result<Vec<ContractKey>> {
+        let bytes = encode(key, state, metadata)?;
+        let size = bytes.len() as u64;
+        if size > self.budget_bytes {
+            tracing::debug!(contract = %key, size, budget = self.budget_bytes,
+                "Contract too large for cold tier, not archiving");
+            return Ok(Vec::new());
+        }
+
+        let path = self.archive_path(key.id());
+        let tmp_path = path.with_extension("tmp");
+        {
+            let mut file = fs::File::create(&tmp_path)?;
+            file.write_all(&bytes)?;
+            file.sync_all()?;
+        }
+        fs::rename(&tmp_path, &path)?;
+
+        self.forget(key.id());
+        self.entries.insert(*key.id(), (*key, size));
+        self.order.push_back(*key.id());
+        self.current_bytes += size;
+        Ok(self.enforce_budget())
+    }
+
+    /// Take a contract out of the cold tier, removing its archive.
+    pub fn take(&mut self, instance_id: &ContractInstanceId) -> io::Result<Option<ColdEntry>> {
+        if !self.forget(instance_id) {
+            return Ok(None);
+        }
+        let path = self.archive_path(instance_id);
+        let bytes = fs::read(&path);
+        fs::remove_file(&path).ok();
+        decode(&bytes?).map(Some)
+    }
+
+    /// Whether an archive exists for the contract.
+    pub fn contains(&self, instance_id: &ContractInstanceId) -> bool {
+        self.entries.contains_key(instance_id)
+    }
+
+    /// Number of archived contracts.
+    #[allow(dead_code)] // Public API for introspection
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Compressed bytes currently archived.
+    #[allow(dead_code)] // Public API for introspection
+    pub fn current_bytes(&self) -> u64 {
+        self.current_bytes
+    }
+
+    fn archive_path(&self, instance_id: &ContractInstanceId) -> PathBuf {
+        self.dir.join(format!("{instance_id}.{ARCHIVE_EXTENSION}"))
+    }
+
+    /// Drop a contract from the index. Returns `true` if it was archived.
+    fn forget(&mut self, instance_id: &ContractInstanceId) -> bool {
+        let Some((_, size)) = self.entries.remove(instance_id) else {
+            return false;
+        };
+        self.order.retain(|id| id != instance_id);
+        self.current_bytes = self.current_bytes.saturating_sub(size);
+        true
+    }
+
+    /// Remove the oldest archives until within budget.
+    fn enforce_budget(&mut self) -> Vec<ContractKey> {
+        let mut dropped = Vec::new();
+        while self.current_bytes > self.budget_bytes {
+            let Some(oldest) = self.order.front().copied() else {
+                break;
+            };
+            let key = self.entries.get(&oldest).map(|(key, _)| *key);
+            self.forget(&oldest);
+            fs::remove_file(self.archive_path(&oldest)).ok();
+            dropped.extend(key);
+        }
+        dropped
+    }
+}
+
+fn encode(
+    key: &ContractKey,
+    state: &[u8],
+    metadata: Option<&HostingMetadata>,
+) -> io::Result<Vec<u8>> {
+    let metadata_bytes = metadata.map(HostingMetadata::to_bytes).unwrap_or_default();
+    let metadata_len = u16::try_from(metadata_bytes.len())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "hosting metadata too large"))?;
+
+    let mut buf = Vec::with_capacity(HEADER_LEN + metadata_bytes.len() + state.len() / 2);
+    buf.push(ARCHIVE_VERSION);
+    buf.extend_from_slice(key.id().as_bytes());
+    buf.extend_from_slice(&**key.code_hash());
+    buf.extend_from_slice(&metadata_len.to_le_bytes());
+    buf.extend_from_slice(&metadata_bytes);
+
+    let mut encoder = GzEncoder::new(buf, Compression::default());
+    encoder.write_all(state)?;
+    encoder.finish()
+}
+
+fn decode(bytes: &[u8]) -> io::Result<ColdEntry> {
+    let key = parse_header(bytes)?;
+    let metadata_len = u16::from_le_bytes([bytes[HEADER_LEN - 2], bytes[HEADER_LEN - 1]]) as usize;
+    let metadata_end = HEADER_LEN + metadata_len;
+    let metadata_bytes = bytes
+        .get(HEADER_LEN..metadata_end)
+        .ok_or_else(|| invalid_data("truncated hosting metadata"))?;
+    let metadata = if metadata_bytes.is_empty() {
+        None
+    } else {
+        Some(
+            HostingMetadata::from_bytes(metadata_bytes)
+                .ok_or_else(|| invalid_data("malformed hosting metadata"))?,
+        )
+    };
+
+    let mut state = Vec::new();
+    GzDecoder::new(&bytes[metadata_end..]).read_to_end(&mut state)?;
+    Ok(ColdEntry {
+        key,
+        state,
+        metadata,
+    })
+}
+
+fn read_header(path: &Path) -> io::Result<ContractKey> {
+    let mut header = [0u8; HEADER_LEN];
+    fs::File::open(path)?.read_exact(&mut header)?;
+    parse_header(&header)
+}
+
+fn parse_header(bytes: &[u8]) -> io::Result<ContractKey> {
+    if bytes.len() < HEADER_LEN {
+        return Err(invalid_data("truncated archive header"));
+    }
+    if bytes[0] != ARCHIVE_VERSION {
+        return Err(invalid_data("unsupported archive version"));
+    }
+    let mut instance_id = [0u8; 32];
+    instance_id.copy_from_slice(&bytes[1..33]);
+    let mut code_hash = [0u8; 32];
+    code_hash.copy_from_slice(&bytes[33..65]);
+    Ok(ContractKey::from_id_and_code(
+        ContractInstanceId::new(instance_id),
+        CodeHash::new(code_hash),
+    ))
+}
+
+fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::storages::LepusHostingMetadata;
+
+    fn make_contract_key(seed: u8) -> ContractKey {
+        ContractKey::from_id_and_code(
+            ContractInstanceId::new([seed; 32]),
+            CodeHash::new([seed.wrapping_add(1); 32]),
+        )
+    }
+
+    #[test]
+    fn test_archive_and_take_roundtrip() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut tier = ColdTier::open(temp_dir.path(), 1024 * 1024).unwrap();
+        let key = make_contract_key(1);
+        let state = vec![7u8; 4096];
+        let metadata = HostingMetadata::new(1000, 1, 4096, **key.code_hash()).with_lepus(
+            LepusHostingMetadata {
+                deposited_xlm: 25,
+                bytes_served: 300,
+                ..Default::default()
+            },
+        );
+
+        let dropped = tier.archive(&key, &state, Some(&metadata)).unwrap();
+        assert!(dropped.is_empty());
+        assert!(tier.contains(key.id()));
+        // Compressible state takes far less room than it would hot
+        assert!(tier.current_bytes() < 1024);
+
+        let entry = tier.take(key.id()).unwrap().unwrap();
+        assert_eq!(entry.key, key);
+        assert_eq!(entry.state, state);
+        let lepus = entry.metadata.unwrap().lepus.unwrap();
+        assert_eq!(lepus.deposited_xlm, 25);
+        assert_eq!(lepus.bytes_served, 300);
+
+        assert!(!tier.contains(key.id()));
+        assert_eq!(tier.current_bytes(), 0);
+        assert!(tier.take(key.id()).unwrap().is_none());
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_budget_drops_oldest_archive() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let key1 = make_contract_key(1);
+        let key2 = make_contract_key(2);
+        let key3 = make_contract_key(3);
+        // Size the budget for two archives
+        let archive_len = encode(&key1, &[1u8; 64], None).unwrap().len() as u64;
+        let mut tier = ColdTier::open(temp_dir.path(), archive_len * 5 / 2).unwrap();
+
+        tier.archive(&key1, &[1u8; 64], None).unwrap();
+        tier.archive(&key2, &[2u8; 64], None).unwrap();
+        let dropped = tier.archive(&key3, &[3u8; 64], None).unwrap();
+
+        assert_eq!(dropped, vec![key1]);
+        assert!(!tier.contains(key1.id()));
+        assert!(tier.contains(key2.id()));
+        assert!(tier.contains(key3.id()));
+        assert_eq!(tier.len(), 2);
+
+        // Larger than the whole budget: skipped
+        let mut seed = 1u32;
+        let huge: Vec<u8> = (0..archive_len * 4)
+            .map(|_| {
+                seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                (seed >> 16) as u8
+            })
+            .collect();
+        assert!(tier
+            .archive(&make_contract_key(4), &huge, None)
+            .unwrap()
+            .is_empty());
+        assert_eq!(tier.len(), 2);
+    }
+
+    #[test]
+    fn test_reopen_indexes_existing_archives() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let key = make_contract_key(1);
+        {
+            let mut tier = ColdTier::open(temp_dir.path(), 1024 * 1024).unwrap();
+            tier.archive(&key, b"state", None).unwrap();
+        }
+        fs::write(temp_dir.path().join("leftover.tmp"), b"partial").unwrap();
+        fs::write(temp_dir.path().join("garbage.cold"), b"xx").unwrap();
+
+        let mut tier = ColdTier::open(temp_dir.path(), 1024 * 1024).unwrap();
+        assert_eq!(tier.len(), 1);
+        let entry = tier.take(key.id()).unwrap().unwrap();
+        assert_eq!(entry.key, key);
+        assert_eq!(entry.state, b"state");
+        assert!(entry.metadata.is_none());
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 0);
+    }
+}
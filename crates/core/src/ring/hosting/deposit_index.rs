@@ -18,6 +18,26 @@ use serde::{Deserialize, Serialize};
 // Duplicated types from contracts/deposit-index/src/types.rs
 // =============================================================================
 
+/// Validator organization for quorum checking.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ValidatorOrg {
+    pub name: String,
+    /// Ed25519 public keys of this org's validators (hex 32 bytes each)
+    pub validators: Vec<String>,
+}
+
+/// Contract parameters baked into the deposit-index's ContractKey.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct DepositIndexParams {
+    /// SHA-256 of the Stellar network passphrase (hex 32 bytes)
+    pub network_id: String,
+    pub organizations: Vec<ValidatorOrg>,
+    /// 0 = default `(orgs.len() * 2 / 3) + 1`
+    pub quorum_org_threshold: usize,
+    /// hvym-freenet-service Soroban contract address (hex 32 bytes)
+    pub hvym_contract_address: String,
+}
+
 /// The full contract state: a versioned deposit map.
 #[derive(Serialize, Deserialize, Clone, Debug, Default)]
 pub struct DepositMap {
@@ -33,8 +53,126 @@ pub struct DepositEntry {
     pub contract_id: String,
     /// Cumulative deposited amount (stroops)
     pub total_deposited: i128,
+    /// Cumulative USD value at deposit time (7 decimals)
+    #[serde(default)]
+    pub total_usd: i128,
     /// Ledger sequence of the most recent deposit for this contract
     pub last_ledger: u32,
+    /// Drain schedule from the latest STREAM event (streaming deposits only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stream: Option<DrainSchedule>,
+}
+
+impl DepositEntry {
+    /// Deposited amount still counting as live commitment at `ledger`.
+    ///
+    /// Flat deposits are fully live; streaming deposits lose whatever has
+    /// drained since the schedule's checkpoint.
+    pub fn live_amount(&self, ledger: u32) -> i128 {
+        match &self.stream {
+            Some(schedule) => self.total_deposited - schedule.drained_at(ledger),
+            None => self.total_deposited,
+        }
+    }
+}
+
+/// The deposit-index state this node last synced, indexed by contract.
+#[derive(Clone, Debug, Default)]
+pub struct DepositSnapshot {
+    /// Version of the synced deposit map, 0 before the first sync.
+    pub version: u64,
+    /// Latest ledger the synced deposit map reflects.
+    pub last_ledger_seq: u32,
+    entries: HashMap<ContractInstanceId, DepositEntry>,
+}
+
+impl DepositSnapshot {
+    /// Index `map` by contract.
+    ///
+    /// Entries whose contract id is not 32 bytes of hex are skipped.
+    pub fn new(map: DepositMap) -> Self {
+        let entries = map
+            .deposits
+            .into_iter()
+            .filter_map(|entry| {
+                let bytes: [u8; 32] = hex::decode(&entry.contract_id).ok()?.try_into().ok()?;
+                Some((ContractInstanceId::new(bytes), entry))
+            })
+            .collect();
+        Self {
+            version: map.version,
+            last_ledger_seq: map.last_ledger_seq,
+            entries,
+        }
+    }
+
+    /// The entry for `id`, if it has one.
+    pub fn entry(&self, id: &ContractInstanceId) -> Option<&DepositEntry> {
+        self.entries.get(id)
+    }
+
+    /// Live deposit of `id` as of `last_ledger_seq`, if it has an entry.
+    pub fn live_deposit(&self, id: &ContractInstanceId) -> Option<u64> {
+        self.entry(id)
+            .map(|entry| to_stroops(entry.live_amount(self.last_ledger_seq)))
+    }
+
+    /// What this node knows of `id`'s deposit, for client applications.
+    pub fn deposit_info(&self, id: &ContractInstanceId) -> DepositInfo {
+        DepositInfo {
+            instance_id: id.to_string(),
+            entry: self.entry(id).cloned(),
+            live_stroops: self.live_deposit(id).unwrap_or(0),
+            sync_version: self.version,
+            sync_ledger: self.last_ledger_seq,
+        }
+    }
+}
+
+/// A contract's deposit as recorded in the deposit-index state this node
+/// last synced, served to client applications over the WebSocket API.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct DepositInfo {
+    pub instance_id: String,
+    /// `None` when the synced deposit map has no entry for the contract.
+    pub entry: Option<DepositEntry>,
+    /// Undrained deposit (stroops) as of `sync_ledger`.
+    pub live_stroops: u64,
+    /// Version of the synced deposit map, 0 before the first sync.
+    pub sync_version: u64,
+    /// Latest ledger the synced deposit map reflects.
+    pub sync_ledger: u32,
+}
+
+/// Convert an i128 amount to u64 stroops, clamping negatives to zero and
+/// capping at `u64::MAX`.
+fn to_stroops(amount: i128) -> u64 {
+    if amount < 0 {
+        0
+    } else if amount > i128::from(u64::MAX) {
+        u64::MAX
+    } else {
+        amount as u64
+    }
+}
+
+/// Streaming drain parameters from the hvym-freenet-service `STREAM` event.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct DrainSchedule {
+    pub drain_rate: i128,
+    pub drained: i128,
+    pub drain_cap: i128,
+    pub checkpoint: u32,
+}
+
+impl DrainSchedule {
+    /// Total amount drained as of `ledger`, never exceeding `drain_cap`.
+    pub fn drained_at(&self, ledger: u32) -> i128 {
+        let elapsed = i128::from(ledger.saturating_sub(self.checkpoint));
+        self.drained
+            .saturating_add(self.drain_rate.saturating_mul(elapsed))
+            .min(self.drain_cap)
+    }
 }
 
 /// A proof submitted as UpdateData::Delta.
@@ -44,61 +182,219 @@ pub struct DepositProof {
     pub scp_envelopes: Vec<String>,
     pub transaction_set: String,
     pub tx_result_metas: Vec<String>,
+    /// Topics of the indexed events in the ledger, e.g. `DEPOSIT`, `WITHDRAW`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub event_kinds: Vec<String>,
+    /// Where `tx_result_metas` fall in the ledger when the proof is split.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub part: Option<ProofPart>,
+    /// The relayer that submitted the proof, if it signed it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attribution: Option<RelayerAttribution>,
+}
+
+/// A relayer's signature over a proof, made with its Stellar key.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RelayerAttribution {
+    /// Relayer's Stellar Ed25519 public key (hex 32 bytes)
+    pub relayer: String,
+    /// Ed25519 signature over `DepositProof::attribution_message` (hex 64
+    /// bytes)
+    pub signature: String,
+}
+
+/// Room an attribution takes in a proof's JSON.
+pub const ATTRIBUTION_JSON_BYTES: usize = 256;
+
+/// Domain separator of attribution signatures, as in the contract.
+const ATTRIBUTION_DOMAIN: &[u8] = b"lepus-relayer-attribution-v1";
+
+/// The slice of a ledger's transaction result metas a split proof carries.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ProofPart {
+    pub offset: u32,
+    pub total_metas: u32,
+}
+
+impl DepositProof {
+    /// Size of the proof as UPDATE JSON.
+    pub fn json_len(&self) -> usize {
+        serde_json::to_vec(self).map_or(usize::MAX, |json| json.len())
+    }
+
+    /// Whether applying the proof completes its ledger, rather than leaving
+    /// parts of it to come.
+    pub fn completes_ledger(&self) -> bool {
+        self.part.map_or(true, |part| {
+            part.offset as usize + self.tx_result_metas.len() >= part.total_metas as usize
+        })
+    }
+
+    /// The digest a relayer signs to attribute the proof to itself, as the
+    /// deposit-index computes it: `SHA-256(domain || ledger_seq || offset ||
+    /// total_metas || fields)`, the integers big-endian and each field (the
+    /// transaction set, then every meta) prefixed with its length.
+    pub fn attribution_message(&self) -> [u8; 32] {
+        use sha2::{Digest, Sha256};
+
+        let (offset, total_metas) = match self.part {
+            Some(part) => (part.offset, part.total_metas),
+            None => (0, self.tx_result_metas.len() as u32),
+        };
+        let mut hasher = Sha256::new();
+        hasher.update(ATTRIBUTION_DOMAIN);
+        hasher.update(self.ledger_seq.to_be_bytes());
+        hasher.update(offset.to_be_bytes());
+        hasher.update(total_metas.to_be_bytes());
+        for field in std::iter::once(&self.transaction_set).chain(&self.tx_result_metas) {
+            hasher.update((field.len() as u64).to_be_bytes());
+            hasher.update(field.as_bytes());
+        }
+        hasher.finalize().into()
+    }
+
+    /// Mark the proof as submitted by the holder of Stellar key `relayer`,
+    /// with its `signature` over [`DepositProof::attribution_message`].
+    /// Sign each part of a split proof, after splitting.
+    pub fn attribute(&mut self, relayer: &[u8; 32], signature: &[u8; 64]) {
+        self.attribution = Some(RelayerAttribution {
+            relayer: hex::encode(relayer),
+            signature: hex::encode(signature),
+        });
+    }
+
+    /// Split the proof into parts of at most `max_bytes` of JSON, spreading
+    /// `tx_result_metas` over them in order. Every part repeats the SCP
+    /// envelopes and transaction set, so the deposit-index verifies each on
+    /// its own. A proof that fits is returned whole, and a meta too big to
+    /// share a part gets one to itself.
+    pub fn split(mut self, max_bytes: usize) -> Vec<DepositProof> {
+        if self.tx_result_metas.len() < 2 || self.json_len() <= max_bytes {
+            return vec![self];
+        }
+        let metas = std::mem::take(&mut self.tx_result_metas);
+        let total_metas = metas.len() as u32;
+        // A signature over the whole proof covers none of its parts
+        self.attribution = None;
+        // Sized with the widest offset so every part's overhead fits
+        self.part = Some(ProofPart {
+            offset: total_metas,
+            total_metas,
+        });
+        let budget = max_bytes.saturating_sub(self.json_len());
+
+        let mut parts: Vec<DepositProof> = Vec::new();
+        let mut offset = 0;
+        let mut chunk = Vec::new();
+        let mut chunk_bytes = 0;
+        let part = |offset: u32, tx_result_metas: Vec<String>| DepositProof {
+            tx_result_metas,
+            part: Some(ProofPart {
+                offset,
+                total_metas,
+            }),
+            ..self.clone()
+        };
+        for meta in metas {
+            // Quotes and a separating comma
+            let meta_bytes = meta.len() + 3;
+            if !chunk.is_empty() && chunk_bytes + meta_bytes > budget {
+                let chunk = std::mem::take(&mut chunk);
+                let len = chunk.len() as u32;
+                parts.push(part(offset, chunk));
+                offset += len;
+                chunk_bytes = 0;
+            }
+            chunk_bytes += meta_bytes;
+            chunk.push(meta);
+        }
+        parts.push(part(offset, chunk));
+        parts
+    }
+}
+
+/// The contract's state summary, as returned by `summarize_state`.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DepositMapSummary {
+    pub version: u64,
+    pub entry_count: usize,
+    pub last_ledger_seq: u32,
 }
 
 // =============================================================================
 // Configuration
 // =============================================================================
 
-/// Load the deposit-index `ContractInstanceId` from `LEPUS_DEPOSIT_INDEX_KEY`.
+/// Deposit-index identity, parsed once per process.
+struct DepositIndexIds {
+    instance_id: Option<ContractInstanceId>,
+    contract_key: Option<ContractKey>,
+}
+
+static DEPOSIT_INDEX: OnceLock<DepositIndexIds> = OnceLock::new();
+
+impl DepositIndexIds {
+    fn parse(key_hex: Option<&str>, code_hash_hex: Option<&str>) -> Self {
+        let instance_id = key_hex
+            .and_then(|hex| parse_hash(hex, "deposit-index key"))
+            .map(ContractInstanceId::new);
+        let contract_key = instance_id
+            .zip(code_hash_hex.and_then(|hex| parse_hash(hex, "deposit-index code hash")));
+        Self {
+            instance_id,
+            contract_key: contract_key
+                .map(|(id, code)| ContractKey::from_id_and_code(id, CodeHash::new(code))),
+        }
+    }
+
+    fn get() -> &'static Self {
+        DEPOSIT_INDEX.get_or_init(|| {
+            Self::parse(
+                std::env::var("LEPUS_DEPOSIT_INDEX_KEY").ok().as_deref(),
+                std::env::var("LEPUS_DEPOSIT_INDEX_CODE_HASH")
+                    .ok()
+                    .as_deref(),
+            )
+        })
+    }
+}
+
+fn parse_hash(hex_str: &str, what: &str) -> Option<[u8; 32]> {
+    let bytes = hex::decode(hex_str.trim()).ok()?;
+    let len = bytes.len();
+    let hash = bytes.try_into().ok();
+    if hash.is_none() {
+        tracing::warn!(len, "{what} must be exactly 32 bytes (64 hex chars)");
+    }
+    hash
+}
+
+/// Set the deposit-index from the oracle configuration (`[lepus.oracle]`
+/// with its environment overrides).
 ///
-/// The env var should contain a hex-encoded 32-byte contract instance ID.
-/// Result is cached via `OnceLock` for the process lifetime.
+/// Must run before the first lookup: the identity is fixed for the process
+/// lifetime, and without this call it is read from `LEPUS_DEPOSIT_INDEX_KEY`
+/// and `LEPUS_DEPOSIT_INDEX_CODE_HASH`.
+pub fn configure(key_hex: Option<&str>, code_hash_hex: Option<&str>) {
+    if DEPOSIT_INDEX
+        .set(DepositIndexIds::parse(key_hex, code_hash_hex))
+        .is_err()
+    {
+        tracing::debug!("Deposit-index already configured for this process");
+    }
+}
+
+/// The deposit-index `ContractInstanceId`, if configured.
 pub fn deposit_index_instance_id() -> Option<ContractInstanceId> {
-    static CACHED: OnceLock<Option<ContractInstanceId>> = OnceLock::new();
-    *CACHED.get_or_init(|| {
-        let hex_str = std::env::var("LEPUS_DEPOSIT_INDEX_KEY").ok()?;
-        let bytes = hex::decode(hex_str.trim()).ok()?;
-        if bytes.len() != 32 {
-            tracing::warn!(
-                len = bytes.len(),
-                "LEPUS_DEPOSIT_INDEX_KEY must be exactly 32 bytes (64 hex chars)"
-            );
-            return None;
-        }
-        let mut arr = [0u8; 32];
-        arr.copy_from_slice(&bytes);
-        Some(ContractInstanceId::new(arr))
-    })
+    DepositIndexIds::get().instance_id
 }
 
-/// Load the full `ContractKey` for the deposit-index contract.
+/// The full `ContractKey` for the deposit-index contract.
 ///
-/// Requires both `LEPUS_DEPOSIT_INDEX_KEY` (instance ID) and
-/// `LEPUS_DEPOSIT_INDEX_CODE_HASH` (code hash) to be set.
+/// Requires both the instance ID and the code hash to be configured.
 /// Relayer nodes need this to submit UPDATE operations.
 pub fn deposit_index_contract_key() -> Option<ContractKey> {
-    static CACHED: OnceLock<Option<ContractKey>> = OnceLock::new();
-    *CACHED.get_or_init(|| {
-        let instance_id = deposit_index_instance_id()?;
-
-        let code_hex = std::env::var("LEPUS_DEPOSIT_INDEX_CODE_HASH").ok()?;
-        let code_bytes = hex::decode(code_hex.trim()).ok()?;
-        if code_bytes.len() != 32 {
-            tracing::warn!(
-                len = code_bytes.len(),
-                "LEPUS_DEPOSIT_INDEX_CODE_HASH must be exactly 32 bytes (64 hex chars)"
-            );
-            return None;
-        }
-        let mut code_arr = [0u8; 32];
-        code_arr.copy_from_slice(&code_bytes);
-
-        Some(ContractKey::from_id_and_code(
-            instance_id,
-            CodeHash::new(code_arr),
-        ))
-    })
+    DepositIndexIds::get().contract_key
 }
 
 // =============================================================================
@@ -116,18 +412,19 @@ pub fn deposit_index_contract_key() -> Option<ContractKey> {
 /// * `state_bytes` — The new state bytes after the update.
 /// * `hosted_keys` — All contract keys this node is currently hosting.
 /// * `update_fn` — Callback to apply `(ContractKey, deposited_xlm)` updates.
+///
+/// Returns the new map, covering every contract whether hosted or not,
+/// when the update was to the deposit-index contract.
 pub fn check_deposit_index_update(
     key: &ContractKey,
     state_bytes: &[u8],
     hosted_keys: &[ContractKey],
     update_fn: impl FnOnce(&[(ContractKey, u64)]),
-) {
+) -> Option<DepositSnapshot> {
     // Fast path: is this the deposit-index contract?
-    let Some(expected_id) = deposit_index_instance_id() else {
-        return;
-    };
+    let expected_id = deposit_index_instance_id()?;
     if key.id() != &expected_id {
-        return;
+        return None;
     }
 
     // Deserialize the deposit map
@@ -138,7 +435,7 @@ pub fn check_deposit_index_update(
                 error = %e,
                 "Lepus: failed to deserialize deposit-index state"
             );
-            return;
+            return None;
         }
     };
 
@@ -154,15 +451,10 @@ pub fn check_deposit_index_update(
     let mut updates: Vec<(ContractKey, u64)> = Vec::new();
     for entry in &deposit_map.deposits {
         if let Some(&hosted_key) = hosted_lookup.get(&entry.contract_id) {
-            // Convert i128 stroops to u64, capping at u64::MAX
-            let xlm = if entry.total_deposited < 0 {
-                0u64
-            } else if entry.total_deposited > i128::from(u64::MAX) {
-                u64::MAX
-            } else {
-                entry.total_deposited as u64
-            };
-            updates.push((*hosted_key, xlm));
+            // Streaming deposits count only their undrained balance as of the
+            // latest verified ledger. Convert i128 stroops to u64, capping at u64::MAX
+            let live = entry.live_amount(deposit_map.last_ledger_seq);
+            updates.push((*hosted_key, to_stroops(live)));
         }
     }
 
@@ -175,6 +467,8 @@ pub fn check_deposit_index_update(
         );
         update_fn(&updates);
     }
+
+    Some(DepositSnapshot::new(deposit_map))
 }
 
 // =============================================================================
@@ -201,12 +495,16 @@ mod tests {
                 DepositEntry {
                     contract_id: hex::encode([1u8; 32]),
                     total_deposited: 5_000_000,
+                    total_usd: 0,
                     last_ledger: 999,
+                    stream: None,
                 },
                 DepositEntry {
                     contract_id: hex::encode([2u8; 32]),
                     total_deposited: 10_000_000,
+                    total_usd: 0,
                     last_ledger: 1000,
+                    stream: None,
                 },
             ],
         };
@@ -219,6 +517,16 @@ mod tests {
         assert_eq!(decoded.deposits[0].total_deposited, 5_000_000);
     }
 
+    #[test]
+    fn test_deposit_map_summary_from_contract() {
+        let summary: DepositMapSummary =
+            serde_json::from_slice(br#"{"version":7,"entry_count":3,"last_ledger_seq":51234}"#)
+                .unwrap();
+        assert_eq!(summary.version, 7);
+        assert_eq!(summary.entry_count, 3);
+        assert_eq!(summary.last_ledger_seq, 51_234);
+    }
+
     #[test]
     fn test_deposit_proof_round_trip() {
         let proof = DepositProof {
@@ -226,12 +534,117 @@ mod tests {
             scp_envelopes: vec!["AAAA".to_string()],
             transaction_set: "BBBB".to_string(),
             tx_result_metas: vec!["CCCC".to_string()],
+            event_kinds: vec!["WITHDRAW".to_string()],
+            part: None,
+            attribution: None,
         };
 
         let json = serde_json::to_vec(&proof).unwrap();
         let decoded: DepositProof = serde_json::from_slice(&json).unwrap();
         assert_eq!(decoded.ledger_seq, 500);
         assert_eq!(decoded.scp_envelopes.len(), 1);
+        assert_eq!(decoded.event_kinds, vec!["WITHDRAW".to_string()]);
+
+        // Proofs from relayers that don't label event kinds still decode
+        let unlabeled =
+            br#"{"ledger_seq":7,"scp_envelopes":[],"transaction_set":"","tx_result_metas":[]}"#;
+        let decoded: DepositProof = serde_json::from_slice(unlabeled).unwrap();
+        assert!(decoded.event_kinds.is_empty());
+    }
+
+    #[test]
+    fn test_attributed_proof_verifies() {
+        use ed25519_dalek::{Signer, Verifier};
+
+        let mut proof = DepositProof {
+            ledger_seq: 500,
+            scp_envelopes: vec!["AAAA".to_string()],
+            transaction_set: "BBBB".to_string(),
+            tx_result_metas: vec!["CCCC".to_string()],
+            event_kinds: Vec::new(),
+            part: None,
+            attribution: None,
+        };
+        // Pinned so it can't drift from the deposit-index's digest
+        assert_eq!(
+            hex::encode(proof.attribution_message()),
+            "1bda18c1dc9987fedb88bd4c8cfdf575d1cf67c971b43d15e296acdc0cc7ac07"
+        );
+
+        let key = ed25519_dalek::SigningKey::from_bytes(&[9; 32]);
+        let signature = key.sign(&proof.attribution_message());
+        proof.attribute(&key.verifying_key().to_bytes(), &signature.to_bytes());
+        let json = serde_json::to_vec(&proof).unwrap();
+        let decoded: DepositProof = serde_json::from_slice(&json).unwrap();
+        let attribution = decoded.attribution.clone().unwrap();
+        assert_eq!(
+            attribution.relayer,
+            hex::encode(key.verifying_key().to_bytes())
+        );
+        let signature: [u8; 64] = hex::decode(&attribution.signature)
+            .unwrap()
+            .try_into()
+            .unwrap();
+        key.verifying_key()
+            .verify(
+                &decoded.attribution_message(),
+                &ed25519_dalek::Signature::from_bytes(&signature),
+            )
+            .unwrap();
+
+        let mut unsigned = proof.clone();
+        unsigned.attribution = None;
+        assert!(proof.json_len() - unsigned.json_len() <= ATTRIBUTION_JSON_BYTES);
+
+        // Splitting drops the whole proof's signature
+        proof.tx_result_metas.push("DDDD".to_string());
+        let parts = proof.split(unsigned.json_len());
+        assert_eq!(parts.len(), 2);
+        assert!(parts.iter().all(|part| part.attribution.is_none()));
+    }
+
+    #[test]
+    fn test_split_oversized_proof() {
+        let proof = DepositProof {
+            ledger_seq: 500,
+            scp_envelopes: vec!["A".repeat(20)],
+            transaction_set: "B".repeat(30),
+            tx_result_metas: (0..10).map(|i| i.to_string().repeat(100)).collect(),
+            event_kinds: vec!["DEPOSIT".to_string()],
+            part: None,
+            attribution: None,
+        };
+        assert_eq!(proof.clone().split(proof.json_len()).len(), 1);
+        assert!(proof.completes_ledger());
+
+        let max_bytes = proof.json_len() / 2;
+        let parts = proof.clone().split(max_bytes);
+        assert!(parts.len() > 2, "{} parts", parts.len());
+        let mut offset = 0;
+        for (i, part) in parts.iter().enumerate() {
+            assert!(part.json_len() <= max_bytes);
+            assert_eq!(part.scp_envelopes, proof.scp_envelopes);
+            assert_eq!(part.transaction_set, proof.transaction_set);
+            assert_eq!(
+                part.part,
+                Some(ProofPart {
+                    offset,
+                    total_metas: 10
+                })
+            );
+            assert_eq!(part.completes_ledger(), i == parts.len() - 1);
+            offset += part.tx_result_metas.len() as u32;
+        }
+        let metas: Vec<String> = parts
+            .into_iter()
+            .flat_map(|part| part.tx_result_metas)
+            .collect();
+        assert_eq!(metas, proof.tx_result_metas);
+
+        // Metas too big for any part travel alone
+        let parts = proof.split(1);
+        assert_eq!(parts.len(), 10);
+        assert!(parts.iter().all(|part| part.tx_result_metas.len() == 1));
     }
 
     #[test]
@@ -261,18 +674,24 @@ mod tests {
                 DepositEntry {
                     contract_id: hex::encode(k1.id().as_bytes()),
                     total_deposited: 1_000_000,
+                    total_usd: 0,
                     last_ledger: 100,
+                    stream: None,
                 },
                 DepositEntry {
                     contract_id: hex::encode(k2.id().as_bytes()),
                     total_deposited: 2_000_000,
+                    total_usd: 0,
                     last_ledger: 100,
+                    stream: None,
                 },
                 DepositEntry {
                     // contract not hosted by this node
                     contract_id: hex::encode([99u8; 32]),
                     total_deposited: 9_999_999,
+                    total_usd: 0,
                     last_ledger: 100,
+                    stream: None,
                 },
             ],
         };
@@ -314,7 +733,9 @@ mod tests {
         let entry = DepositEntry {
             contract_id: hex::encode([1u8; 32]),
             total_deposited: -500,
+            total_usd: 0,
             last_ledger: 1,
+            stream: None,
         };
         let xlm = if entry.total_deposited < 0 {
             0u64
@@ -329,7 +750,9 @@ mod tests {
         let entry = DepositEntry {
             contract_id: hex::encode([1u8; 32]),
             total_deposited: i128::from(u64::MAX) + 1,
+            total_usd: 0,
             last_ledger: 1,
+            stream: None,
         };
         let xlm = if entry.total_deposited < 0 {
             0u64
@@ -341,6 +764,92 @@ mod tests {
         assert_eq!(xlm, u64::MAX);
     }
 
+    #[test]
+    fn test_stream_live_amount_drains_to_cap() {
+        let entry = DepositEntry {
+            contract_id: hex::encode([1u8; 32]),
+            total_deposited: 1_000_000,
+            total_usd: 0,
+            last_ledger: 100,
+            stream: Some(DrainSchedule {
+                drain_rate: 1_000,
+                drained: 0,
+                drain_cap: 700_000,
+                checkpoint: 100,
+            }),
+        };
+        assert_eq!(entry.live_amount(100), 1_000_000);
+        assert_eq!(entry.live_amount(200), 900_000);
+        // Burned share never drains
+        assert_eq!(entry.live_amount(10_000), 300_000);
+    }
+
+    #[test]
+    fn test_deposit_snapshot_covers_every_entry() {
+        let map = DepositMap {
+            version: 1,
+            last_ledger_seq: 200,
+            deposits: vec![
+                DepositEntry {
+                    contract_id: hex::encode([1u8; 32]),
+                    total_deposited: 1_000_000,
+                    total_usd: 0,
+                    last_ledger: 100,
+                    stream: Some(DrainSchedule {
+                        drain_rate: 1_000,
+                        drained: 0,
+                        drain_cap: 700_000,
+                        checkpoint: 100,
+                    }),
+                },
+                DepositEntry {
+                    contract_id: hex::encode([2u8; 32]),
+                    total_deposited: -5,
+                    total_usd: 0,
+                    last_ledger: 100,
+                    stream: None,
+                },
+                DepositEntry {
+                    contract_id: "not hex".to_string(),
+                    total_deposited: 7,
+                    total_usd: 0,
+                    last_ledger: 100,
+                    stream: None,
+                },
+            ],
+        };
+
+        let snapshot = DepositSnapshot::new(map);
+        assert_eq!(snapshot.entries.len(), 2);
+        let streaming = ContractInstanceId::new([1u8; 32]);
+        assert_eq!(snapshot.live_deposit(&streaming), Some(900_000));
+        assert_eq!(
+            snapshot.live_deposit(&ContractInstanceId::new([2u8; 32])),
+            Some(0)
+        );
+        assert_eq!(
+            snapshot.live_deposit(&ContractInstanceId::new([3u8; 32])),
+            None
+        );
+
+        let info = snapshot.deposit_info(&streaming);
+        assert_eq!(info.instance_id, streaming.to_string());
+        assert_eq!(info.entry.unwrap().last_ledger, 100);
+        assert_eq!(info.live_stroops, 900_000);
+        assert_eq!((info.sync_version, info.sync_ledger), (1, 200));
+        let missing = DepositSnapshot::default().deposit_info(&streaming);
+        assert_eq!(missing.entry, None);
+        assert_eq!(missing.sync_version, 0);
+    }
+
+    #[test]
+    fn test_entry_without_stream_deserializes() {
+        let json = br#"{"contract_id":"00","total_deposited":5,"last_ledger":1}"#;
+        let entry: DepositEntry = serde_json::from_slice(json).unwrap();
+        assert_eq!(entry.stream, None);
+        assert_eq!(entry.live_amount(u32::MAX), 5);
+    }
+
     #[test]
     fn test_empty_state_does_not_panic() {
         // Deserializing empty/invalid state should not panic
@@ -0,0 +1,255 @@
+//! On-disk audit log of hosting cache evictions (Lepus).
+//!
+//! Every contract evicted from the hosting cache is appended here as one JSON
+//! line with its score breakdown and the budget pressure at the time, so an
+//! operator can reconstruct why a datapod was dropped after the fact.
+//!
+//! The log is bounded: records go into `<name>` until it holds half of
+//! `max_records`, then it is rotated to `<name>.1`, replacing the previous
+//! rotation. Between `max_records / 2` and `max_records` of the most recent
+//! evictions are kept.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::cache::{EvictionNotice, ScoreBreakdown};
+
+/// File under the node's database directory holding the log.
+pub(crate) const EVICTION_LOG_FILE: &str = "hosting-evictions.jsonl";
+
+/// One evicted contract.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EvictionRecord {
+    pub key: String,
+    /// Wall-clock time of the eviction, in milliseconds since the Unix epoch.
+    pub timestamp_ms: u64,
+    pub reason: String,
+    pub size_bytes: u64,
+    /// Bytes hosted just before the eviction.
+    pub used_bytes: u64,
+    pub budget_bytes: u64,
+    pub breakdown: ScoreBreakdown,
+}
+
+impl EvictionRecord {
+    pub(crate) fn new(notice: &EvictionNotice, timestamp_ms: u64) -> Self {
+        Self {
+            key: notice.key.to_string(),
+            timestamp_ms,
+            reason: notice.reason.as_str().to_string(),
+            size_bytes: notice.size_bytes,
+            used_bytes: notice.used_bytes,
+            budget_bytes: notice.budget_bytes,
+            breakdown: notice.breakdown,
+        }
+    }
+}
+
+/// Filter for `EvictionLog::query`.
+#[derive(Debug, Clone, Default)]
+pub struct EvictionLogQuery {
+    /// Only records for this contract key, as displayed.
+    pub key: Option<String>,
+    /// Only records at or after this time, in milliseconds since the Unix epoch.
+    pub since_ms: Option<u64>,
+    /// At most this many records, keeping the most recent.
+    pub limit: Option<usize>,
+}
+
+/// Append-only eviction log with one rotation.
+pub(crate) struct EvictionLog {
+    path: PathBuf,
+    rotated_path: PathBuf,
+    /// Records per file before rotating.
+    segment_records: usize,
+    /// Records in the current file.
+    current_records: usize,
+}
+
+impl EvictionLog {
+    /// Open the log at `path`, continuing a file left by a previous run.
+    pub fn open(path: impl Into<PathBuf>, max_records: usize) -> io::Result<Self> {
+        let path = path.into();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let current_records = match File::open(&path) {
+            Ok(file) => BufReader::new(file).lines().count(),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => 0,
+            Err(e) => return Err(e),
+        };
+        let mut rotated_path = path.clone().into_os_string();
+        rotated_path.push(".1");
+        Ok(Self {
+            rotated_path: rotated_path.into(),
+            path,
+            segment_records: max_records.div_ceil(2).max(1),
+            current_records,
+        })
+    }
+
+    /// Append records, rotating the file when it fills up.
+    pub fn append(&mut self, records: &[EvictionRecord]) -> io::Result<()> {
+        let mut remaining = records;
+        while !remaining.is_empty() {
+            if self.current_records >= self.segment_records {
+                fs::rename(&self.path, &self.rotated_path)?;
+                self.current_records = 0;
+            }
+            let take = remaining
+                .len()
+                .min(self.segment_records - self.current_records);
+            let (batch, rest) = remaining.split_at(take);
+            let mut buf = Vec::new();
+            for record in batch {
+                serde_json::to_writer(&mut buf, record).map_err(io::Error::other)?;
+                buf.push(b'\n');
+            }
+            OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)?
+                .write_all(&buf)?;
+            self.current_records += batch.len();
+            remaining = rest;
+        }
+        Ok(())
+    }
+
+    /// Records matching `query`, oldest first.
+    ///
+    /// Lines that fail to parse (e.g. one torn by a crash mid-write) are
+    /// skipped.
+    pub fn query(&self, query: &EvictionLogQuery) -> io::Result<Vec<EvictionRecord>> {
+        let mut records = Vec::new();
+        for path in [&self.rotated_path, &self.path] {
+            read_records(path, query, &mut records)?;
+        }
+        if let Some(limit) = query.limit {
+            let excess = records.len().saturating_sub(limit);
+            records.drain(..excess);
+        }
+        Ok(records)
+    }
+}
+
+fn read_records(
+    path: &Path,
+    query: &EvictionLogQuery,
+    records: &mut Vec<EvictionRecord>,
+) -> io::Result<()> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e),
+    };
+    for line in BufReader::new(file).lines() {
+        let Ok(record) = serde_json::from_str::<EvictionRecord>(&line?) else {
+            continue;
+        };
+        if query.key.as_ref().is_some_and(|key| *key != record.key) {
+            continue;
+        }
+        if query
+            .since_ms
+            .is_some_and(|since| record.timestamp_ms < since)
+        {
+            continue;
+        }
+        records.push(record);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_record(key: &str, timestamp_ms: u64) -> EvictionRecord {
+        EvictionRecord {
+            key: key.to_string(),
+            timestamp_ms,
+            reason: "capacity".to_string(),
+            size_bytes: 100,
+            used_bytes: 1000,
+            budget_bytes: 1000,
+            breakdown: ScoreBreakdown {
+                commitment: 0.0,
+                identity: 0.6,
+                contribution: 0.0,
+                recency: 0.5,
+                subscription: 0.0,
+                penalty: 0.0,
+                size_factor: 1.0,
+                score: 0.2,
+            },
+        }
+    }
+
+    #[test]
+    fn test_query_filters_and_limits() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut log = EvictionLog::open(temp_dir.path().join(EVICTION_LOG_FILE), 100).unwrap();
+        log.append(&[
+            make_record("a", 1),
+            make_record("b", 2),
+            make_record("a", 3),
+        ])
+        .unwrap();
+
+        let all = log.query(&EvictionLogQuery::default()).unwrap();
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[0], make_record("a", 1));
+
+        let query = EvictionLogQuery {
+            key: Some("a".to_string()),
+            ..Default::default()
+        };
+        let timestamps: Vec<_> = log
+            .query(&query)
+            .unwrap()
+            .iter()
+            .map(|r| r.timestamp_ms)
+            .collect();
+        assert_eq!(timestamps, vec![1, 3]);
+
+        let query = EvictionLogQuery {
+            since_ms: Some(2),
+            limit: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(log.query(&query).unwrap(), vec![make_record("a", 3)]);
+    }
+
+    #[test]
+    fn test_rotation_bounds_records_and_survives_reopen() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join(EVICTION_LOG_FILE);
+        let mut log = EvictionLog::open(&path, 4).unwrap();
+        let records: Vec<_> = (0..5).map(|i| make_record("k", i)).collect();
+        log.append(&records).unwrap();
+
+        // Two per file: 0 and 1 were rotated out
+        let kept: Vec<_> = log
+            .query(&EvictionLogQuery::default())
+            .unwrap()
+            .iter()
+            .map(|r| r.timestamp_ms)
+            .collect();
+        assert_eq!(kept, vec![2, 3, 4]);
+
+        let mut log = EvictionLog::open(&path, 4).unwrap();
+        log.append(&[make_record("k", 5), make_record("k", 6)])
+            .unwrap();
+        let kept: Vec<_> = log
+            .query(&EvictionLogQuery::default())
+            .unwrap()
+            .iter()
+            .map(|r| r.timestamp_ms)
+            .collect();
+        assert_eq!(kept, vec![4, 5, 6]);
+    }
+}
@@ -0,0 +1,365 @@
+//! Stellar history archive access for the proof relayer (Lepus).
+//!
+//! Soroban RPC's `getLedgers` only carries a ledger's SCP messages if the RPC
+//! node keeps them, which most don't. History archives publish them for
+//! every checkpoint of 64 ledgers, alongside the transaction sets, as
+//! gzipped streams of XDR records under
+//! `{category}/ww/xx/yy/{category}-wwxxyyzz.xdr.gz`. The relayer takes a
+//! ledger's externalize envelopes and generalized transaction set from there
+//! and only the transaction result metas from RPC.
+//!
+//! A checkpoint is published a few minutes after its last ledger closes;
+//! until then fetching it fails with `OracleError::CheckpointPending`.
+
+use std::io::Read;
+use std::sync::Arc;
+
+use flate2::read::GzDecoder;
+use parking_lot::Mutex;
+use stellar_xdr::curr::{
+    GeneralizedTransactionSet, Limits, ReadXdr, ScpEnvelope, ScpHistoryEntry, ScpStatementPledges,
+    TransactionHistoryEntry, TransactionHistoryEntryExt, WriteXdr,
+};
+
+use super::oracle::OracleError;
+
+/// Ledgers per history archive checkpoint.
+pub const CHECKPOINT_FREQUENCY: u32 = 64;
+
+/// Last ledger of the checkpoint holding `ledger_seq`, which names its files.
+pub fn checkpoint_containing(ledger_seq: u32) -> u32 {
+    (ledger_seq / CHECKPOINT_FREQUENCY)
+        .saturating_add(1)
+        .saturating_mul(CHECKPOINT_FREQUENCY)
+        - 1
+}
+
+/// Path of a checkpoint file relative to the archive root.
+pub fn checkpoint_path(category: &str, checkpoint: u32) -> String {
+    let hex = format!("{checkpoint:08x}");
+    format!(
+        "{category}/{}/{}/{}/{category}-{hex}.xdr.gz",
+        &hex[0..2],
+        &hex[2..4],
+        &hex[4..6]
+    )
+}
+
+/// Decode a gzipped stream of XDR records, each preceded by a 4-byte
+/// record mark: its big-endian length, with the top bit flagging the last
+/// fragment. Archives write every record as a single fragment.
+pub fn decode_xdr_stream<T: ReadXdr>(gzipped: &[u8]) -> Result<Vec<T>, OracleError> {
+    let mut bytes = Vec::new();
+    GzDecoder::new(gzipped)
+        .read_to_end(&mut bytes)
+        .map_err(|e| OracleError::ParseError(format!("gunzip checkpoint: {e}")))?;
+
+    let mut records = Vec::new();
+    let mut rest = bytes.as_slice();
+    while !rest.is_empty() {
+        let Some((mark, tail)) = rest.split_at_checked(4) else {
+            return Err(OracleError::ParseError("truncated record mark".into()));
+        };
+        let len = (u32::from_be_bytes([mark[0], mark[1], mark[2], mark[3]]) & 0x7fff_ffff) as usize;
+        let Some((record, tail)) = tail.split_at_checked(len) else {
+            return Err(OracleError::ParseError(format!(
+                "truncated record: {len} bytes announced, {} left",
+                tail.len()
+            )));
+        };
+        records.push(
+            T::from_xdr(record, Limits::none())
+                .map_err(|e| OracleError::ParseError(format!("XDR decode record: {e}")))?,
+        );
+        rest = tail;
+    }
+    Ok(records)
+}
+
+/// The externalize envelopes recorded for `ledger_seq`.
+pub fn externalize_envelopes(entries: &[ScpHistoryEntry], ledger_seq: u32) -> Vec<ScpEnvelope> {
+    entries
+        .iter()
+        .filter_map(|ScpHistoryEntry::V0(entry)| {
+            (entry.ledger_messages.ledger_seq == ledger_seq).then_some(&entry.ledger_messages)
+        })
+        .flat_map(|messages| messages.messages.iter())
+        .filter(|envelope| {
+            matches!(
+                envelope.statement.pledges,
+                ScpStatementPledges::Externalize(_)
+            )
+        })
+        .cloned()
+        .collect()
+}
+
+/// The generalized transaction set of `ledger_seq`. Ledgers from before
+/// protocol 20 only have a legacy set, which the deposit-index can't verify.
+pub fn generalized_tx_set(
+    entries: &[TransactionHistoryEntry],
+    ledger_seq: u32,
+) -> Option<GeneralizedTransactionSet> {
+    entries
+        .iter()
+        .find(|entry| entry.ledger_seq == ledger_seq)
+        .and_then(|entry| match &entry.ext {
+            TransactionHistoryEntryExt::V1(tx_set) => Some(tx_set.clone()),
+            TransactionHistoryEntryExt::V0 => None,
+        })
+}
+
+/// SCP envelopes and transaction set of one ledger, base64-encoded XDR as
+/// `DepositProof` carries them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArchivedLedger {
+    pub scp_envelopes: Vec<String>,
+    pub transaction_set: String,
+}
+
+impl ArchivedLedger {
+    /// Pick `ledger_seq` out of its checkpoint's decoded files.
+    pub fn from_checkpoint(
+        ledger_seq: u32,
+        scp: &[ScpHistoryEntry],
+        transactions: &[TransactionHistoryEntry],
+    ) -> Result<Self, OracleError> {
+        let scp_envelopes = externalize_envelopes(scp, ledger_seq)
+            .iter()
+            .map(|envelope| envelope.to_xdr(Limits::none()).map(base64::encode))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| OracleError::ParseError(format!("scp_envelope XDR: {e}")))?;
+        if scp_envelopes.is_empty() {
+            return Err(OracleError::ParseError(format!(
+                "no externalize envelopes archived for ledger {ledger_seq}"
+            )));
+        }
+        let tx_set = generalized_tx_set(transactions, ledger_seq).ok_or_else(|| {
+            OracleError::ParseError(format!(
+                "no generalized transaction set archived for ledger {ledger_seq}"
+            ))
+        })?;
+        let tx_set_xdr = tx_set
+            .to_xdr(Limits::none())
+            .map_err(|e| OracleError::ParseError(format!("tx_set XDR: {e}")))?;
+        Ok(Self {
+            scp_envelopes,
+            transaction_set: base64::encode(tx_set_xdr),
+        })
+    }
+}
+
+/// Decoded `scp` and `transactions` files of one checkpoint.
+struct Checkpoint {
+    checkpoint: u32,
+    scp: Vec<ScpHistoryEntry>,
+    transactions: Vec<TransactionHistoryEntry>,
+}
+
+/// HTTP client for one history archive.
+pub struct HistoryArchive {
+    client: reqwest::Client,
+    base_url: String,
+    /// The last checkpoint fetched, since deposits often land in several
+    /// ledgers of the same one.
+    last: Mutex<Option<Arc<Checkpoint>>>,
+}
+
+impl HistoryArchive {
+    pub fn new(client: reqwest::Client, base_url: &str) -> Self {
+        Self {
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            last: Mutex::new(None),
+        }
+    }
+
+    /// SCP envelopes and transaction set of `ledger_seq`.
+    pub async fn ledger(&self, ledger_seq: u32) -> Result<ArchivedLedger, OracleError> {
+        let checkpoint = self.checkpoint(checkpoint_containing(ledger_seq)).await?;
+        ArchivedLedger::from_checkpoint(ledger_seq, &checkpoint.scp, &checkpoint.transactions)
+    }
+
+    async fn checkpoint(&self, checkpoint: u32) -> Result<Arc<Checkpoint>, OracleError> {
+        let last = self.last.lock().clone();
+        if let Some(last) = last.filter(|last| last.checkpoint == checkpoint) {
+            return Ok(last);
+        }
+        let scp = decode_xdr_stream(&self.fetch("scp", checkpoint).await?)?;
+        let transactions = decode_xdr_stream(&self.fetch("transactions", checkpoint).await?)?;
+        let fetched = Arc::new(Checkpoint {
+            checkpoint,
+            scp,
+            transactions,
+        });
+        *self.last.lock() = Some(fetched.clone());
+        Ok(fetched)
+    }
+
+    async fn fetch(&self, category: &str, checkpoint: u32) -> Result<Vec<u8>, OracleError> {
+        let url = format!(
+            "{}/{}",
+            self.base_url,
+            checkpoint_path(category, checkpoint)
+        );
+        let resp = self.client.get(&url).send().await?;
+        if resp.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(OracleError::CheckpointPending(checkpoint));
+        }
+        Ok(resp.error_for_status()?.bytes().await?.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+    use stellar_xdr::curr::{
+        Hash, LedgerScpMessages, NodeId, PublicKey, ScpBallot, ScpHistoryEntryV0, ScpNomination,
+        ScpStatement, ScpStatementExternalize, Signature, TransactionSet, TransactionSetV1,
+        Uint256, Value, VecM,
+    };
+
+    fn envelope(node: u8, pledges: ScpStatementPledges) -> ScpEnvelope {
+        ScpEnvelope {
+            statement: ScpStatement {
+                node_id: NodeId(PublicKey::PublicKeyTypeEd25519(Uint256([node; 32]))),
+                slot_index: 0,
+                pledges,
+            },
+            signature: Signature(vec![node; 64].try_into().unwrap()),
+        }
+    }
+
+    fn externalize(node: u8) -> ScpEnvelope {
+        envelope(
+            node,
+            ScpStatementPledges::Externalize(ScpStatementExternalize {
+                commit: ScpBallot {
+                    counter: 1,
+                    value: Value(vec![node; 8].try_into().unwrap()),
+                },
+                n_h: 1,
+                commit_quorum_set_hash: Hash([0; 32]),
+            }),
+        )
+    }
+
+    fn scp_entry(ledger_seq: u32, messages: Vec<ScpEnvelope>) -> ScpHistoryEntry {
+        ScpHistoryEntry::V0(ScpHistoryEntryV0 {
+            quorum_sets: VecM::default(),
+            ledger_messages: LedgerScpMessages {
+                ledger_seq,
+                messages: messages.try_into().unwrap(),
+            },
+        })
+    }
+
+    fn tx_entry(ledger_seq: u32, generalized: bool) -> TransactionHistoryEntry {
+        TransactionHistoryEntry {
+            ledger_seq,
+            tx_set: TransactionSet {
+                previous_ledger_hash: Hash([0; 32]),
+                txs: VecM::default(),
+            },
+            ext: if generalized {
+                TransactionHistoryEntryExt::V1(GeneralizedTransactionSet::V1(TransactionSetV1 {
+                    previous_ledger_hash: Hash([ledger_seq as u8; 32]),
+                    phases: VecM::default(),
+                }))
+            } else {
+                TransactionHistoryEntryExt::V0
+            },
+        }
+    }
+
+    /// Encode records the way archives publish a checkpoint file.
+    fn gzip_stream<T: WriteXdr>(records: &[T]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        for record in records {
+            let xdr = record.to_xdr(Limits::none()).unwrap();
+            encoder
+                .write_all(&(xdr.len() as u32 | 0x8000_0000).to_be_bytes())
+                .unwrap();
+            encoder.write_all(&xdr).unwrap();
+        }
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn test_checkpoint_naming() {
+        assert_eq!(checkpoint_containing(1), 63);
+        assert_eq!(checkpoint_containing(63), 63);
+        assert_eq!(checkpoint_containing(64), 127);
+        assert_eq!(checkpoint_containing(1_000_000), 1_000_063);
+        assert_eq!(
+            checkpoint_path("scp", 0x0096_7fff),
+            "scp/00/96/7f/scp-00967fff.xdr.gz"
+        );
+        assert_eq!(
+            checkpoint_path("transactions", 63),
+            "transactions/00/00/00/transactions-0000003f.xdr.gz"
+        );
+    }
+
+    #[test]
+    fn test_decode_xdr_stream() {
+        let entries = vec![tx_entry(64, true), tx_entry(65, false)];
+        let decoded: Vec<TransactionHistoryEntry> =
+            decode_xdr_stream(&gzip_stream(&entries)).unwrap();
+        assert_eq!(decoded, entries);
+
+        let empty: Vec<TransactionHistoryEntry> =
+            decode_xdr_stream(&gzip_stream::<TransactionHistoryEntry>(&[])).unwrap();
+        assert!(empty.is_empty());
+
+        // A record cut short is rejected rather than misread
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&(0x8000_0010u32).to_be_bytes()).unwrap();
+        encoder.write_all(&[0; 4]).unwrap();
+        let truncated = encoder.finish().unwrap();
+        assert!(decode_xdr_stream::<TransactionHistoryEntry>(&truncated).is_err());
+    }
+
+    #[test]
+    fn test_archived_ledger_from_checkpoint() {
+        let nomination = envelope(
+            9,
+            ScpStatementPledges::Nominate(ScpNomination {
+                quorum_set_hash: Hash([0; 32]),
+                votes: VecM::default(),
+                accepted: VecM::default(),
+            }),
+        );
+        let scp: Vec<ScpHistoryEntry> = decode_xdr_stream(&gzip_stream(&[
+            scp_entry(100, vec![externalize(1)]),
+            scp_entry(101, vec![externalize(2), nomination, externalize(3)]),
+        ]))
+        .unwrap();
+        let transactions: Vec<TransactionHistoryEntry> =
+            decode_xdr_stream(&gzip_stream(&[tx_entry(100, false), tx_entry(101, true)])).unwrap();
+
+        let ledger = ArchivedLedger::from_checkpoint(101, &scp, &transactions).unwrap();
+        let envelopes: Vec<ScpEnvelope> = ledger
+            .scp_envelopes
+            .iter()
+            .map(|b64| ScpEnvelope::from_xdr(base64::decode(b64).unwrap(), Limits::none()).unwrap())
+            .collect();
+        assert_eq!(envelopes, vec![externalize(2), externalize(3)]);
+        let tx_set = GeneralizedTransactionSet::from_xdr(
+            base64::decode(&ledger.transaction_set).unwrap(),
+            Limits::none(),
+        )
+        .unwrap();
+        let TransactionHistoryEntryExt::V1(expected) = tx_entry(101, true).ext else {
+            unreachable!()
+        };
+        assert_eq!(tx_set, expected);
+
+        // Legacy transaction sets and missing ledgers are errors
+        assert!(ArchivedLedger::from_checkpoint(100, &scp, &transactions).is_err());
+        assert!(ArchivedLedger::from_checkpoint(102, &scp, &transactions).is_err());
+    }
+}
@@ -9,33 +9,75 @@
 //! Bytes 97-128: recipient_pubkey (32 bytes; [0u8; 32] = public/open content)
 //! Bytes 129+:   state_payload (actual contract state)
 //! ```
+//!
+//! Version 0x02 inserts creation time, expiry and flags after the recipient; the
+//! signature then covers `recipient_pubkey || metadata || payload`:
+//!
+//! ```text
+//! Byte 0:        version (0x02)
+//! Bytes 1-128:   as in v1
+//! Bytes 129-136: created_at (u64 big-endian, unix seconds)
+//! Bytes 137-144: expires_at (u64 big-endian, unix seconds; 0 = never)
+//! Bytes 145-148: flags (u32 big-endian)
+//! Bytes 149+:    state_payload
+//! ```
 
 use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use lru::LruCache;
+use parking_lot::Mutex;
+use std::num::NonZeroUsize;
 use std::sync::OnceLock;
 
-/// Binary envelope version byte.
+/// Binary envelope version byte (v1).
 const ENVELOPE_VERSION: u8 = 0x01;
 
-/// Total header size: 1 (version) + 32 (creator) + 64 (sig) + 32 (recipient).
+/// Binary envelope version byte of v2 envelopes, which carry [`EnvelopeMetadata`].
+const ENVELOPE_VERSION_V2: u8 = 0x02;
+
+/// Total v1 header size: 1 (version) + 32 (creator) + 64 (sig) + 32 (recipient).
 const ENVELOPE_HEADER_SIZE: usize = 129;
 
+/// Total v2 header size: the v1 header + 8 (created_at) + 8 (expires_at) + 4 (flags).
+const ENVELOPE_V2_HEADER_SIZE: usize = 149;
+
 /// Sentinel value for public/open content (no specific recipient).
 const PUBLIC_RECIPIENT: [u8; 32] = [0u8; 32];
 
+/// Number of verified envelopes remembered by state hash.
+const VERIFIED_ENVELOPE_CACHE_SIZE: usize = 1024;
+
 /// Parsed identity envelope from contract state bytes.
 #[derive(Debug, Clone)]
 pub struct IdentityEnvelope {
+    pub version: u8,
     pub creator_pubkey: [u8; 32],
     pub creator_signature: [u8; 64],
     pub recipient_pubkey: [u8; 32],
+    /// Creation time, expiry and flags; `None` for v1 envelopes.
+    pub metadata: Option<EnvelopeMetadata>,
     pub payload_offset: usize,
 }
 
+/// Signed metadata carried by v2 envelopes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EnvelopeMetadata {
+    /// Unix seconds when the creator signed the state.
+    pub created_at: u64,
+    /// Unix seconds after which the envelope is stale; `None` if it never expires.
+    pub expires_at: Option<u64>,
+    pub flags: u32,
+}
+
 /// Result of identity verification for a contract.
 #[derive(Debug, Clone)]
 pub struct IdentityVerificationResult {
     pub creator_pubkey: Option<[u8; 32]>,
     pub creator_verified: bool,
+    /// The creator signature is valid but its key is on the revocation list,
+    /// so `creator_verified` is `false`.
+    pub creator_revoked: bool,
+    /// The keyring key the recipient matched, or the node's first key for
+    /// public content and non-matches.
     pub subscriber_pubkey: Option<[u8; 32]>,
     pub subscriber_verified: bool,
     /// The intended recipient from the identity envelope.
@@ -45,26 +87,32 @@ pub struct IdentityVerificationResult {
 
 /// Parse a Lepus identity envelope from contract state bytes.
 ///
-/// Returns `None` if the state is too short or the version byte doesn't match.
+/// Dispatches on the version byte (v1 or v2). Returns `None` if the version is
+/// unknown or the state is too short for that version's header.
 pub fn parse_envelope(state: &[u8]) -> Option<IdentityEnvelope> {
-    if state.len() < ENVELOPE_HEADER_SIZE {
+    let version = state.first().copied().unwrap_or_default();
+    let header_size = match version {
+        ENVELOPE_VERSION => ENVELOPE_HEADER_SIZE,
+        ENVELOPE_VERSION_V2 => ENVELOPE_V2_HEADER_SIZE,
+        _ => {
+            tracing::debug!(
+                version,
+                expected = ?[ENVELOPE_VERSION, ENVELOPE_VERSION_V2],
+                "Identity envelope version mismatch"
+            );
+            return None;
+        }
+    };
+
+    if state.len() < header_size {
         tracing::debug!(
             state_len = state.len(),
-            required = ENVELOPE_HEADER_SIZE,
+            required = header_size,
             "Identity envelope too short"
         );
         return None;
     }
 
-    if state[0] != ENVELOPE_VERSION {
-        tracing::debug!(
-            version = state[0],
-            expected = ENVELOPE_VERSION,
-            "Identity envelope version mismatch"
-        );
-        return None;
-    }
-
     let mut creator_pubkey = [0u8; 32];
     creator_pubkey.copy_from_slice(&state[1..33]);
 
@@ -74,11 +122,25 @@ pub fn parse_envelope(state: &[u8]) -> Option<IdentityEnvelope> {
     let mut recipient_pubkey = [0u8; 32];
     recipient_pubkey.copy_from_slice(&state[97..129]);
 
+    let metadata = (version == ENVELOPE_VERSION_V2).then(|| {
+        let be_u64 = |range: std::ops::Range<usize>| {
+            u64::from_be_bytes(state[range].try_into().expect("8-byte field"))
+        };
+        let expires_at = be_u64(137..145);
+        EnvelopeMetadata {
+            created_at: be_u64(129..137),
+            expires_at: (expires_at != 0).then_some(expires_at),
+            flags: u32::from_be_bytes(state[145..149].try_into().expect("4-byte field")),
+        }
+    });
+
     Some(IdentityEnvelope {
+        version,
         creator_pubkey,
         creator_signature,
         recipient_pubkey,
-        payload_offset: ENVELOPE_HEADER_SIZE,
+        metadata,
+        payload_offset: header_size,
     })
 }
 
@@ -96,10 +158,12 @@ pub fn verify_creator_signature(envelope: &IdentityEnvelope, state: &[u8]) -> bo
 
     let signature = Signature::from_bytes(&envelope.creator_signature);
 
-    // Signature covers: recipient_pubkey (32 bytes) || state_payload
+    // Signature covers: recipient_pubkey (32 bytes) || v2 metadata (empty for v1) || state_payload
+    let metadata = &state[ENVELOPE_HEADER_SIZE..envelope.payload_offset];
     let payload = &state[envelope.payload_offset..];
-    let mut message = Vec::with_capacity(32 + payload.len());
+    let mut message = Vec::with_capacity(32 + metadata.len() + payload.len());
     message.extend_from_slice(&envelope.recipient_pubkey);
+    message.extend_from_slice(metadata);
     message.extend_from_slice(payload);
 
     match verifying_key.verify(&message, &signature) {
@@ -111,38 +175,116 @@ pub fn verify_creator_signature(envelope: &IdentityEnvelope, state: &[u8]) -> bo
     }
 }
 
+/// How the node's keyring matched an envelope's recipient.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubscriberMatch {
+    /// Public/open content: any subscriber qualifies.
+    Public,
+    /// Addressed content whose recipient is this keyring key.
+    Key([u8; 32]),
+}
+
 /// Check if this node is a valid subscriber for the content.
 ///
-/// Returns `true` if recipient is `PUBLIC_RECIPIENT` (open content) or matches the node's pubkey.
-pub fn check_subscriber(recipient: &[u8; 32], node_pubkey: &[u8; 32]) -> bool {
-    *recipient == PUBLIC_RECIPIENT || recipient == node_pubkey
+/// Matches if recipient is `PUBLIC_RECIPIENT` (open content) or any key in the node's keyring.
+pub fn check_subscriber(recipient: &[u8; 32], keyring: &[[u8; 32]]) -> Option<SubscriberMatch> {
+    if *recipient == PUBLIC_RECIPIENT {
+        return Some(SubscriberMatch::Public);
+    }
+    keyring
+        .iter()
+        .find(|key| *key == recipient)
+        .map(|key| SubscriberMatch::Key(*key))
 }
 
-/// Read the node's Stellar public key from the `LEPUS_STELLAR_PUBKEY` env var.
+/// The node's Stellar public keys, for nodes operated on behalf of several users.
 ///
-/// The env var should contain a hex-encoded 32-byte Ed25519 public key.
+/// Read from `LEPUS_STELLAR_PUBKEYS` (comma- or whitespace-separated) and the
+/// single-key `LEPUS_STELLAR_PUBKEY`; each entry takes any form accepted by
+/// [`parse_stellar_pubkey`]. Invalid entries are skipped with a warning.
 /// Result is cached via `OnceLock` for the process lifetime.
-pub fn get_node_stellar_pubkey() -> Option<[u8; 32]> {
-    static CACHED: OnceLock<Option<[u8; 32]>> = OnceLock::new();
-    *CACHED.get_or_init(|| {
-        let hex_str = std::env::var("LEPUS_STELLAR_PUBKEY").ok()?;
-        let bytes = hex::decode(hex_str.trim()).ok()?;
-        if bytes.len() != 32 {
-            tracing::warn!(
-                len = bytes.len(),
-                "LEPUS_STELLAR_PUBKEY must be exactly 32 bytes (64 hex chars)"
+pub fn get_node_stellar_keyring() -> &'static [[u8; 32]] {
+    static CACHED: OnceLock<Vec<[u8; 32]>> = OnceLock::new();
+    CACHED.get_or_init(|| {
+        let mut keyring = Vec::new();
+        for var in ["LEPUS_STELLAR_PUBKEYS", "LEPUS_STELLAR_PUBKEY"] {
+            let Ok(value) = std::env::var(var) else {
+                continue;
+            };
+            keyring.extend(
+                parse_keyring(&value)
+                    .into_iter()
+                    .filter_map(|entry| match entry {
+                        Ok(pubkey) => Some(pubkey),
+                        Err(e) => {
+                            tracing::warn!(error = %e, "Ignoring invalid key in {var}");
+                            None
+                        }
+                    }),
             );
-            return None;
         }
-        let mut arr = [0u8; 32];
-        arr.copy_from_slice(&bytes);
-        Some(arr)
+        let mut seen = std::collections::HashSet::new();
+        keyring.retain(|key| seen.insert(*key));
+        keyring
+    })
+}
+
+/// Parse a comma- or whitespace-separated list of Stellar public keys.
+fn parse_keyring(value: &str) -> Vec<anyhow::Result<[u8; 32]>> {
+    value
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|entry| !entry.is_empty())
+        .map(parse_stellar_pubkey)
+        .collect()
+}
+
+/// Normalize a Stellar public key given as hex, a `G...` account ID or an
+/// `S...` secret seed to its raw 32 bytes.
+pub(crate) fn parse_stellar_pubkey(value: &str) -> anyhow::Result<[u8; 32]> {
+    use super::validator_set::{decode_strkey, STRKEY_ACCOUNT_ID, STRKEY_SEED};
+
+    let value = value.trim();
+    match value.as_bytes().first() {
+        Some(b'G') => decode_strkey(value, STRKEY_ACCOUNT_ID),
+        Some(b'S') => {
+            let seed = decode_strkey(value, STRKEY_SEED)?;
+            Ok(ed25519_dalek::SigningKey::from_bytes(&seed)
+                .verifying_key()
+                .to_bytes())
+        }
+        _ => {
+            let bytes = hex::decode(value)?;
+            bytes.try_into().map_err(|bytes: Vec<u8>| {
+                anyhow::anyhow!(
+                    "expected 32 bytes (64 hex chars), got {} bytes",
+                    bytes.len()
+                )
+            })
+        }
+    }
+}
+
+/// Results of [`verify_identity`] for enveloped states, keyed by the BLAKE3
+/// hash of the full state.
+///
+/// The keyring is fixed for the process lifetime, so a state's result never
+/// changes and repeated GET/UPDATE handling of an unchanged datapod can skip
+/// the Ed25519 verification.
+fn verified_envelope_cache() -> &'static Mutex<LruCache<[u8; 32], IdentityVerificationResult>> {
+    static CACHE: OnceLock<Mutex<LruCache<[u8; 32], IdentityVerificationResult>>> = OnceLock::new();
+    CACHE.get_or_init(|| {
+        Mutex::new(LruCache::new(
+            NonZeroUsize::new(VERIFIED_ENVELOPE_CACHE_SIZE)
+                .expect("VERIFIED_ENVELOPE_CACHE_SIZE must be > 0"),
+        ))
     })
 }
 
 /// Main entry point: parse envelope, verify creator signature, check subscriber.
 ///
 /// Returns an all-false/None result if the state has no valid envelope.
+/// Results for enveloped states are served from a bounded LRU by state hash.
+/// Creators on the synced revocation list are reported unverified.
 pub fn verify_identity(state: &[u8]) -> IdentityVerificationResult {
     let envelope = match parse_envelope(state) {
         Some(e) => e,
@@ -150,6 +292,7 @@ pub fn verify_identity(state: &[u8]) -> IdentityVerificationResult {
             return IdentityVerificationResult {
                 creator_pubkey: None,
                 creator_verified: false,
+                creator_revoked: false,
                 subscriber_pubkey: None,
                 subscriber_verified: false,
                 recipient_pubkey: None,
@@ -157,51 +300,93 @@ pub fn verify_identity(state: &[u8]) -> IdentityVerificationResult {
         }
     };
 
-    let creator_verified = verify_creator_signature(&envelope, state);
+    let state_hash = *blake3::hash(state).as_bytes();
+    let cached = verified_envelope_cache().lock().get(&state_hash).cloned();
+    let result = cached.unwrap_or_else(|| {
+        let result = verify_envelope(&envelope, state);
+        verified_envelope_cache()
+            .lock()
+            .put(state_hash, result.clone());
+        result
+    });
+
+    // The revocation list changes over time, so it's checked past the cache
+    if result.creator_verified && super::revocation::is_revoked(&envelope.creator_pubkey) {
+        tracing::debug!(
+            creator = hex::encode(envelope.creator_pubkey),
+            "Identity envelope signed by a revoked creator key"
+        );
+        return IdentityVerificationResult {
+            creator_verified: false,
+            creator_revoked: true,
+            ..result
+        };
+    }
+    result
+}
 
-    let node_pubkey = get_node_stellar_pubkey();
-    let subscriber_verified = match &node_pubkey {
-        Some(npk) => check_subscriber(&envelope.recipient_pubkey, npk),
-        None => {
-            // No node pubkey configured — only public content passes
-            envelope.recipient_pubkey == PUBLIC_RECIPIENT
-        }
+/// Verify a parsed envelope's creator signature and match its recipient.
+fn verify_envelope(envelope: &IdentityEnvelope, state: &[u8]) -> IdentityVerificationResult {
+    let creator_verified = verify_creator_signature(envelope, state);
+    if let Some(metadata) = &envelope.metadata {
+        tracing::debug!(
+            version = envelope.version,
+            created_at = metadata.created_at,
+            expires_at = ?metadata.expires_at,
+            flags = metadata.flags,
+            creator_verified,
+            "Verified identity envelope metadata"
+        );
+    }
+
+    // With an empty keyring only public content passes
+    let keyring = get_node_stellar_keyring();
+    let subscriber_match = check_subscriber(&envelope.recipient_pubkey, keyring);
+    let subscriber_pubkey = match subscriber_match {
+        Some(SubscriberMatch::Key(key)) => Some(key),
+        _ => keyring.first().copied(),
     };
 
     IdentityVerificationResult {
         creator_pubkey: Some(envelope.creator_pubkey),
         creator_verified,
-        subscriber_pubkey: node_pubkey,
-        subscriber_verified,
+        creator_revoked: false,
+        subscriber_pubkey,
+        subscriber_verified: subscriber_match.is_some(),
         recipient_pubkey: Some(envelope.recipient_pubkey),
     }
 }
 
+/// Build a valid identity-enveloped state for testing.
 #[cfg(test)]
-mod tests {
-    use super::*;
-    use ed25519_dalek::SigningKey;
-
-    /// Build a valid identity-enveloped state for testing.
-    fn make_test_state(signing_key: &SigningKey, recipient: [u8; 32], payload: &[u8]) -> Vec<u8> {
-        use ed25519_dalek::Signer;
+pub(crate) fn make_test_state(
+    signing_key: &ed25519_dalek::SigningKey,
+    recipient: [u8; 32],
+    payload: &[u8],
+) -> Vec<u8> {
+    use ed25519_dalek::Signer;
 
-        let creator_pubkey = signing_key.verifying_key().to_bytes();
+    let creator_pubkey = signing_key.verifying_key().to_bytes();
 
-        // Sign: recipient_pubkey || payload
-        let mut message = Vec::with_capacity(32 + payload.len());
-        message.extend_from_slice(&recipient);
-        message.extend_from_slice(payload);
-        let signature = signing_key.sign(&message);
+    // Sign: recipient_pubkey || payload
+    let mut message = Vec::with_capacity(32 + payload.len());
+    message.extend_from_slice(&recipient);
+    message.extend_from_slice(payload);
+    let signature = signing_key.sign(&message);
+
+    let mut state = Vec::with_capacity(ENVELOPE_HEADER_SIZE + payload.len());
+    state.push(ENVELOPE_VERSION);
+    state.extend_from_slice(&creator_pubkey);
+    state.extend_from_slice(&signature.to_bytes());
+    state.extend_from_slice(&recipient);
+    state.extend_from_slice(payload);
+    state
+}
 
-        let mut state = Vec::with_capacity(ENVELOPE_HEADER_SIZE + payload.len());
-        state.push(ENVELOPE_VERSION);
-        state.extend_from_slice(&creator_pubkey);
-        state.extend_from_slice(&signature.to_bytes());
-        state.extend_from_slice(&recipient);
-        state.extend_from_slice(payload);
-        state
-    }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::SigningKey;
 
     fn test_signing_key() -> SigningKey {
         // Deterministic key for tests
@@ -267,20 +452,53 @@ mod tests {
     #[test]
     fn test_check_subscriber_matching() {
         let node_pk = [7u8; 32];
-        assert!(check_subscriber(&node_pk, &node_pk));
+        assert_eq!(
+            check_subscriber(&node_pk, &[node_pk]),
+            Some(SubscriberMatch::Key(node_pk))
+        );
     }
 
     #[test]
     fn test_check_subscriber_public() {
         let node_pk = [7u8; 32];
-        assert!(check_subscriber(&PUBLIC_RECIPIENT, &node_pk));
+        assert_eq!(
+            check_subscriber(&PUBLIC_RECIPIENT, &[node_pk]),
+            Some(SubscriberMatch::Public)
+        );
+        assert_eq!(
+            check_subscriber(&PUBLIC_RECIPIENT, &[]),
+            Some(SubscriberMatch::Public)
+        );
     }
 
     #[test]
     fn test_check_subscriber_non_matching() {
         let recipient = [7u8; 32];
         let node_pk = [8u8; 32];
-        assert!(!check_subscriber(&recipient, &node_pk));
+        assert_eq!(check_subscriber(&recipient, &[node_pk]), None);
+        assert_eq!(check_subscriber(&recipient, &[]), None);
+    }
+
+    #[test]
+    fn test_check_subscriber_keyring_reports_matching_key() {
+        let keyring = [[7u8; 32], [8u8; 32], [9u8; 32]];
+        assert_eq!(
+            check_subscriber(&[8u8; 32], &keyring),
+            Some(SubscriberMatch::Key([8u8; 32]))
+        );
+        assert_eq!(check_subscriber(&[10u8; 32], &keyring), None);
+    }
+
+    #[test]
+    fn test_parse_keyring() {
+        let account = "GABAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEJXA";
+        let hex_key = hex::encode([3u8; 32]);
+        let entries = parse_keyring(&format!("{account}, {hex_key}\n,bogus"));
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].as_ref().unwrap(), &[2u8; 32]);
+        assert_eq!(entries[1].as_ref().unwrap(), &[3u8; 32]);
+        assert!(entries[2].is_err());
+        assert!(parse_keyring("  ").is_empty());
     }
 
     #[test]
@@ -291,6 +509,33 @@ mod tests {
         let hex_str = "0102030405060708091011121314151617181920212223242526272829303132";
         let bytes = hex::decode(hex_str).unwrap();
         assert_eq!(bytes.len(), 32);
+        assert_eq!(parse_stellar_pubkey(hex_str).unwrap().to_vec(), bytes);
+    }
+
+    #[test]
+    fn test_parse_stellar_pubkey_strkey() {
+        // Account ID with a raw key of [2u8; 32]
+        let account = "GABAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEJXA";
+        assert_eq!(parse_stellar_pubkey(account).unwrap(), [2u8; 32]);
+        assert_eq!(
+            parse_stellar_pubkey(&format!("  {account}\n")).unwrap(),
+            [2u8; 32]
+        );
+
+        // Secret seed of [42u8; 32] normalizes to its public key
+        let seed = "SAVCUKRKFIVCUKRKFIVCUKRKFIVCUKRKFIVCUKRKFIVCUKRKFIVCVLG5";
+        assert_eq!(
+            parse_stellar_pubkey(seed).unwrap(),
+            test_signing_key().verifying_key().to_bytes()
+        );
+
+        // Bad checksum, wrong StrKey type, short hex
+        assert!(parse_stellar_pubkey(&account.replace("EJXA", "EJXB")).is_err());
+        assert!(
+            parse_stellar_pubkey("CD3KT3NS3GMAQTTNVS5HIMV7Q6ISZNRIFXF7LIOMUOC5JC5VMG4UVOHQ")
+                .is_err()
+        );
+        assert!(parse_stellar_pubkey("0102").is_err());
     }
 
     #[test]
@@ -315,4 +560,109 @@ mod tests {
         assert!(result.subscriber_pubkey.is_none() || result.subscriber_pubkey.is_some());
         assert!(!result.subscriber_verified);
     }
+
+    #[test]
+    fn test_verify_identity_caches_by_state_hash() {
+        let sk = SigningKey::from_bytes(&[43u8; 32]);
+        let state = make_test_state(&sk, PUBLIC_RECIPIENT, b"cached payload");
+        let state_hash = *blake3::hash(&state).as_bytes();
+
+        let first = verify_identity(&state);
+        assert!(first.creator_verified);
+        assert!(verified_envelope_cache().lock().contains(&state_hash));
+
+        let second = verify_identity(&state);
+        assert_eq!(second.creator_pubkey, first.creator_pubkey);
+        assert_eq!(second.creator_verified, first.creator_verified);
+        assert_eq!(second.subscriber_verified, first.subscriber_verified);
+
+        // A forged variant hashes differently and is verified on its own
+        let mut forged = state.clone();
+        forged[33] ^= 0xFF;
+        assert!(!verify_identity(&forged).creator_verified);
+        assert!(verify_identity(&state).creator_verified);
+    }
+
+    fn make_test_state_v2(
+        signing_key: &SigningKey,
+        recipient: [u8; 32],
+        metadata: EnvelopeMetadata,
+        payload: &[u8],
+    ) -> Vec<u8> {
+        use ed25519_dalek::Signer;
+
+        let mut signed = recipient.to_vec();
+        signed.extend_from_slice(&metadata.created_at.to_be_bytes());
+        signed.extend_from_slice(&metadata.expires_at.unwrap_or(0).to_be_bytes());
+        signed.extend_from_slice(&metadata.flags.to_be_bytes());
+        signed.extend_from_slice(payload);
+        let signature = signing_key.sign(&signed);
+
+        let mut state = vec![ENVELOPE_VERSION_V2];
+        state.extend_from_slice(&signing_key.verifying_key().to_bytes());
+        state.extend_from_slice(&signature.to_bytes());
+        state.extend_from_slice(&signed);
+        state
+    }
+
+    #[test]
+    fn test_parse_envelope_v2() {
+        let sk = test_signing_key();
+        let metadata = EnvelopeMetadata {
+            created_at: 1_700_000_000,
+            expires_at: Some(1_800_000_000),
+            flags: 0b101,
+        };
+        let state = make_test_state_v2(&sk, [5u8; 32], metadata, b"v2 payload");
+
+        let env = parse_envelope(&state).expect("should parse v2 envelope");
+        assert_eq!(env.version, ENVELOPE_VERSION_V2);
+        assert_eq!(env.creator_pubkey, sk.verifying_key().to_bytes());
+        assert_eq!(env.recipient_pubkey, [5u8; 32]);
+        assert_eq!(env.metadata, Some(metadata));
+        assert_eq!(env.payload_offset, ENVELOPE_V2_HEADER_SIZE);
+        assert_eq!(&state[env.payload_offset..], b"v2 payload");
+        assert!(verify_creator_signature(&env, &state));
+
+        // v1 envelopes carry no metadata
+        let v1 = make_test_state(&sk, PUBLIC_RECIPIENT, b"v1 payload");
+        assert_eq!(parse_envelope(&v1).unwrap().metadata, None);
+    }
+
+    #[test]
+    fn test_parse_envelope_v2_never_expires() {
+        let sk = test_signing_key();
+        let metadata = EnvelopeMetadata {
+            created_at: 1,
+            expires_at: None,
+            flags: 0,
+        };
+        let state = make_test_state_v2(&sk, PUBLIC_RECIPIENT, metadata, b"");
+        assert_eq!(parse_envelope(&state).unwrap().metadata, Some(metadata));
+    }
+
+    #[test]
+    fn test_parse_envelope_v2_too_short() {
+        let sk = test_signing_key();
+        let mut state = make_test_state(&sk, PUBLIC_RECIPIENT, b"");
+        state[0] = ENVELOPE_VERSION_V2;
+        // Long enough for v1 but not for the v2 header
+        state.extend_from_slice(&[0u8; ENVELOPE_V2_HEADER_SIZE - ENVELOPE_HEADER_SIZE - 1]);
+        assert!(parse_envelope(&state).is_none());
+    }
+
+    #[test]
+    fn test_verify_creator_signature_v2_covers_metadata() {
+        let sk = test_signing_key();
+        let metadata = EnvelopeMetadata {
+            created_at: 1_700_000_000,
+            expires_at: Some(1_800_000_000),
+            flags: 1,
+        };
+        let mut state = make_test_state_v2(&sk, PUBLIC_RECIPIENT, metadata, b"payload");
+        // Extending the expiry invalidates the signature
+        state[144] ^= 0x01;
+        let env = parse_envelope(&state).unwrap();
+        assert!(!verify_creator_signature(&env, &state));
+    }
 }
@@ -0,0 +1,158 @@
+//! Identity and commitment knowledge about contracts that aren't hosted (Lepus).
+//!
+//! The hosting cache keeps a contract's identity and commitment only while it
+//! is hosted. These side tables remember them for contracts evicted from the
+//! cache or never hosted here (e.g. seen through a subscription handshake or
+//! an oracle report), so a contract hosted again doesn't start from zero.
+//!
+//! Each table is an LRU bounded by `CWPConfig::known_contracts_capacity`:
+//! the least recently updated entries are dropped first.
+
+use std::collections::HashSet;
+use std::num::NonZeroUsize;
+
+use freenet_stdlib::prelude::{ContractInstanceId, ContractKey};
+use lru::LruCache;
+use tokio::time::Instant;
+
+use super::cache::{CommitmentState, EvictionNotice, IdentityState};
+
+/// A deposit reported for a contract while it wasn't hosted.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct KnownCommitment {
+    pub deposited_xlm: u64,
+    pub last_oracle_check: Instant,
+}
+
+/// Bounded side tables of identities and commitments.
+pub(crate) struct KnownContracts {
+    identities: LruCache<ContractKey, IdentityState>,
+    commitments: LruCache<ContractKey, KnownCommitment>,
+    /// Contracts each identity pubkey was seen creating or subscribing to.
+    identity_contracts: LruCache<[u8; 32], HashSet<ContractInstanceId>>,
+}
+
+impl KnownContracts {
+    pub fn new(capacity: usize) -> Self {
+        let capacity = NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN);
+        Self {
+            identities: LruCache::new(capacity),
+            commitments: LruCache::new(capacity),
+            identity_contracts: LruCache::new(capacity),
+        }
+    }
+
+    /// Change the capacity of every table, dropping the least recently
+    /// updated entries if it shrinks.
+    pub fn resize(&mut self, capacity: usize) {
+        let capacity = NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN);
+        self.identities.resize(capacity);
+        self.commitments.resize(capacity);
+        self.identity_contracts.resize(capacity);
+    }
+
+    /// Replace the identity known for an unhosted contract.
+    pub fn record_identity(&mut self, key: ContractKey, identity: IdentityState) {
+        self.identities.put(key, identity);
+    }
+
+    /// Record a subscriber of an unhosted contract from the subscription
+    /// handshake.
+    pub fn record_subscriber(&mut self, key: ContractKey, subscriber_pubkey: &[u8; 32]) {
+        self.identities
+            .get_or_insert_mut(key, IdentityState::default)
+            .set_subscriber(subscriber_pubkey);
+    }
+
+    /// Record a deposit reported for an unhosted contract.
+    pub fn record_commitment(&mut self, key: ContractKey, deposited_xlm: u64, check_time: Instant) {
+        self.commitments.put(
+            key,
+            KnownCommitment {
+                deposited_xlm,
+                last_oracle_check: check_time,
+            },
+        );
+    }
+
+    /// Remember that `pubkey` created or subscribed to `key`.
+    pub fn record_identity_contract(&mut self, pubkey: &[u8; 32], key: &ContractKey) {
+        self.identity_contracts
+            .get_or_insert_mut(*pubkey, HashSet::new)
+            .insert(*key.id());
+    }
+
+    /// Remember what was known about an evicted contract.
+    ///
+    /// Contracts without an identity envelope, subscriber or oracle report
+    /// carry nothing worth keeping and are skipped.
+    pub fn remember_evicted(&mut self, notice: &EvictionNotice) {
+        if notice.identity != IdentityState::default() {
+            self.record_identity(notice.key, notice.identity.clone());
+        }
+        let CommitmentState {
+            deposited_xlm,
+            last_oracle_check,
+            ..
+        } = notice.commitment;
+        if let Some(check_time) = last_oracle_check {
+            self.record_commitment(notice.key, deposited_xlm, check_time);
+        }
+    }
+
+    /// Remove and return what is known about a contract that is being
+    /// hosted again.
+    pub fn take(&mut self, key: &ContractKey) -> (Option<IdentityState>, Option<KnownCommitment>) {
+        (self.identities.pop(key), self.commitments.pop(key))
+    }
+
+    /// Contracts `pubkey` was seen creating or subscribing to.
+    pub fn identity_contracts(&self, pubkey: &[u8; 32]) -> Option<&HashSet<ContractInstanceId>> {
+        self.identity_contracts.peek(pubkey)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use freenet_stdlib::prelude::CodeHash;
+
+    fn make_key(seed: u8) -> ContractKey {
+        ContractKey::from_id_and_code(
+            ContractInstanceId::new([seed; 32]),
+            CodeHash::new([seed.wrapping_add(1); 32]),
+        )
+    }
+
+    #[test]
+    fn test_tables_drop_least_recently_updated() {
+        let mut known = KnownContracts::new(2);
+        let now = Instant::now();
+        for seed in 1..=3 {
+            known.record_commitment(make_key(seed), u64::from(seed), now);
+            known.record_subscriber(make_key(seed), &[seed; 32]);
+        }
+        // Updating 2 makes 3 the next to go
+        known.record_commitment(make_key(2), 20, now);
+        known.record_commitment(make_key(4), 4, now);
+
+        let deposit = |known: &mut KnownContracts, seed| {
+            known
+                .take(&make_key(seed))
+                .1
+                .map(|commitment| commitment.deposited_xlm)
+        };
+        assert_eq!(deposit(&mut known, 1), None);
+        assert_eq!(deposit(&mut known, 2), Some(20));
+
+        let (identity, commitment) = known.take(&make_key(3));
+        assert_eq!(identity.unwrap().subscriber_pubkey, Some([3u8; 32]));
+        assert_eq!(commitment, None);
+        assert_eq!(known.take(&make_key(3)), (None, None));
+
+        known.record_commitment(make_key(5), 5, now);
+        known.resize(1);
+        assert_eq!(deposit(&mut known, 4), None);
+        assert_eq!(deposit(&mut known, 5), Some(5));
+    }
+}
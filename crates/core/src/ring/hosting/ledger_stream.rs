@@ -0,0 +1,497 @@
+//! Ledger-close notifications for the streaming relayer (Lepus).
+//!
+//! With `stream` on, the relay loop polls as soon as a ledger closes rather
+//! than only every `poll_interval`, so deposits reach the deposit-index
+//! within a ledger or two. Closes come from a Horizon `/ledgers` server-sent
+//! event stream when `horizon_url` is set, and otherwise from checking the
+//! Soroban RPC tip just before each close is due, as predicted from the
+//! closes seen so far. The poll interval stays as a fallback for when the
+//! stream goes quiet.
+
+use std::time::Duration;
+
+use tokio::sync::watch;
+use tokio::time::{Instant, Interval, MissedTickBehavior};
+
+use super::oracle::{OracleConfig, StellarProofSource};
+use crate::config::{GlobalExecutor, GlobalRng};
+
+/// Nominal time between Stellar ledger closes.
+const LEDGER_CLOSE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Bounds on the close period learned from the RPC tip, so a stalled or
+/// bursting RPC can't skew it far from the nominal five seconds.
+const MIN_CLOSE_PERIOD: Duration = Duration::from_secs(2);
+const MAX_CLOSE_PERIOD: Duration = Duration::from_secs(15);
+
+/// How long before a close is due the RPC tip is first checked, so the
+/// prediction keeps being pulled towards the earliest the tip moves.
+const CHECK_EARLY: Duration = Duration::from_millis(250);
+
+/// Most random delay added to each due check, so relayers sharing an RPC
+/// don't all ask at the same instant.
+const CHECK_JITTER_MS: u64 = 250;
+
+/// How soon the tip is checked again after a check that found no new ledger.
+const CHECK_RETRY: Duration = Duration::from_millis(500);
+
+/// A Horizon stream silent this long is reconnected. Ledgers close every
+/// five seconds, so this is a dozen missed closes.
+const STREAM_IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+const STREAM_BASE_BACKOFF: Duration = Duration::from_secs(1);
+const STREAM_MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// What wakes the relay loop for its next poll.
+pub(crate) enum LedgerCloses {
+    /// Every `poll_interval` only.
+    Interval(Interval),
+    /// On each ledger Horizon streams, or after `poll_interval` without one.
+    Horizon {
+        fallback: Interval,
+        closed: watch::Receiver<u32>,
+    },
+    /// When the RPC tip has advanced, checked around when each close is
+    /// due, or after `poll_interval` without one.
+    RpcTip {
+        fallback: Interval,
+        clock: CloseClock,
+        next_check: Instant,
+    },
+}
+
+impl LedgerCloses {
+    /// Ledger-close source for `config`, starting the Horizon stream task
+    /// if it uses one.
+    pub(crate) fn new(config: &OracleConfig) -> Self {
+        let fallback = skip_missed(tokio::time::interval_at(
+            tokio::time::Instant::now() + config.poll_interval,
+            config.poll_interval,
+        ));
+        if !config.stream {
+            return Self::Interval(fallback);
+        }
+        match &config.horizon_url {
+            Some(url) => {
+                let (tx, closed) = watch::channel(0);
+                let url = url.clone();
+                let connect_timeout = config.http_timeout;
+                GlobalExecutor::spawn(async move {
+                    stream_horizon_ledgers(url, connect_timeout, tx).await;
+                });
+                Self::Horizon { fallback, closed }
+            }
+            None => Self::RpcTip {
+                fallback,
+                clock: CloseClock::default(),
+                next_check: Instant::now(),
+            },
+        }
+    }
+
+    /// Poll every `period` from now on, with ledger closes still waking
+    /// polls in between.
+    pub(crate) fn set_poll_interval(&mut self, period: Duration) {
+        let fallback = match self {
+            Self::Interval(interval) => interval,
+            Self::Horizon { fallback, .. } | Self::RpcTip { fallback, .. } => fallback,
+        };
+        *fallback = skip_missed(tokio::time::interval_at(Instant::now() + period, period));
+    }
+
+    /// Wait until the next poll is due. A poll woken by a ledger close
+    /// pushes the fallback poll back by a full interval.
+    pub(crate) async fn wait(&mut self, source: &dyn StellarProofSource) {
+        match self {
+            Self::Interval(interval) => {
+                interval.tick().await;
+            }
+            Self::Horizon { fallback, closed } => {
+                tokio::select! {
+                    _ = fallback.tick() => {}
+                    changed = closed.changed() => {
+                        if changed.is_err() {
+                            // The stream task is gone; keep the interval
+                            *self = Self::Interval(fallback_only(fallback));
+                            return;
+                        }
+                        tracing::trace!(ledger = *closed.borrow(), "Lepus relayer: ledger closed");
+                        fallback.reset();
+                    }
+                }
+            }
+            Self::RpcTip {
+                fallback,
+                clock,
+                next_check,
+            } => loop {
+                tokio::select! {
+                    _ = fallback.tick() => return,
+                    _ = tokio::time::sleep_until(*next_check) => {
+                        let now = Instant::now();
+                        let Ok(latest) = source.latest_ledger().await else {
+                            *next_check = now + clock.period;
+                            continue;
+                        };
+                        // The first reading only sets the baseline
+                        let first = clock.last.is_none();
+                        if !clock.observe(latest, now) {
+                            *next_check = now + CHECK_RETRY;
+                            continue;
+                        }
+                        let jitter = GlobalRng::random_range(0..=CHECK_JITTER_MS);
+                        *next_check = clock.next_check(now, Duration::from_millis(jitter));
+                        if !first {
+                            fallback.reset();
+                            return;
+                        }
+                    }
+                }
+            },
+        }
+    }
+}
+
+/// Adapts the poll interval to how busy the deposit contract is, between
+/// the configured bounds: each poll that finds ledgers with events narrows
+/// it, the more so the more it found, and each poll that finds none widens
+/// it a little.
+pub(crate) struct PollPacer {
+    min: Duration,
+    max: Duration,
+    current: Duration,
+}
+
+impl PollPacer {
+    pub(crate) fn new(config: &OracleConfig) -> Self {
+        let (min, max) = config.poll_interval_bounds();
+        Self {
+            min,
+            max,
+            current: config.poll_interval,
+        }
+    }
+
+    /// Account for a poll that found `event_ledgers` ledgers with events.
+    /// Returns the new interval if it changed.
+    pub(crate) fn record(&mut self, event_ledgers: usize) -> Option<Duration> {
+        let next = match event_ledgers {
+            0 => self.current * 5 / 4,
+            // Halved for one ledger, quartered for three or more
+            n => self.current / (1 + n.min(3) as u32),
+        }
+        .clamp(self.min, self.max);
+        if next == self.current {
+            return None;
+        }
+        self.current = next;
+        Some(next)
+    }
+}
+
+/// Predicts when the RPC tip will next move from when it was seen to move
+/// before: the period is a moving average of the time per ledger between
+/// sightings, and the phase is the latest sighting.
+#[derive(Debug)]
+pub(crate) struct CloseClock {
+    /// Latest ledger seen and when it was first seen.
+    last: Option<(u32, Instant)>,
+    period: Duration,
+}
+
+impl Default for CloseClock {
+    fn default() -> Self {
+        Self {
+            last: None,
+            period: LEDGER_CLOSE_INTERVAL,
+        }
+    }
+}
+
+impl CloseClock {
+    /// Record the tip `ledger` read at `now`. Returns whether it is newer
+    /// than the last one seen.
+    fn observe(&mut self, ledger: u32, now: Instant) -> bool {
+        match self.last {
+            Some((seen, _)) if ledger <= seen => return false,
+            Some((seen, at)) => {
+                let sample = now.duration_since(at) / (ledger - seen);
+                let sample = sample.clamp(MIN_CLOSE_PERIOD, MAX_CLOSE_PERIOD);
+                self.period = (self.period * 3 + sample) / 4;
+            }
+            None => {}
+        }
+        self.last = Some((ledger, now));
+        true
+    }
+
+    /// When to check the tip next: `jitter` after a little before the next
+    /// close is due. Closes due by `now` are skipped.
+    fn next_check(&self, now: Instant, jitter: Duration) -> Instant {
+        let Some((_, seen_at)) = self.last else {
+            return now + jitter;
+        };
+        let mut due = seen_at + self.period;
+        while due <= now {
+            due += self.period;
+        }
+        (due - CHECK_EARLY).max(now) + jitter
+    }
+}
+
+fn skip_missed(mut interval: Interval) -> Interval {
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    interval
+}
+
+fn fallback_only(fallback: &Interval) -> Interval {
+    skip_missed(tokio::time::interval_at(
+        tokio::time::Instant::now() + fallback.period(),
+        fallback.period(),
+    ))
+}
+
+/// Follow Horizon's ledger stream, publishing each closed ledger's sequence
+/// on `closed`, and reconnect with backoff whenever it drops. Returns once
+/// nobody listens any more.
+async fn stream_horizon_ledgers(
+    horizon_url: String,
+    connect_timeout: Duration,
+    closed: watch::Sender<u32>,
+) {
+    let client = match reqwest::Client::builder()
+        .connect_timeout(connect_timeout)
+        .build()
+    {
+        Ok(client) => client,
+        Err(e) => {
+            tracing::error!(error = %e, "Lepus relayer: failed to create Horizon stream client");
+            return;
+        }
+    };
+    let url = format!("{}/ledgers?cursor=now", horizon_url.trim_end_matches('/'));
+    let mut backoff = STREAM_BASE_BACKOFF;
+    while !closed.is_closed() {
+        let connected = tokio::time::Instant::now();
+        match follow_stream(&client, &url, &closed).await {
+            Ok(()) => return,
+            Err(e) => {
+                tracing::warn!(
+                    horizon_url = %horizon_url,
+                    error = %e,
+                    retry_in_secs = backoff.as_secs(),
+                    "Lepus relayer: Horizon ledger stream dropped"
+                );
+            }
+        }
+        // A connection that held for a while starts the backoff over
+        if connected.elapsed() > STREAM_IDLE_TIMEOUT {
+            backoff = STREAM_BASE_BACKOFF;
+        }
+        tokio::time::sleep(backoff).await;
+        backoff = (backoff * 2).min(STREAM_MAX_BACKOFF);
+    }
+}
+
+/// One connection to the stream. `Ok` means the receiver went away; any
+/// error is worth reconnecting after.
+async fn follow_stream(
+    client: &reqwest::Client,
+    url: &str,
+    closed: &watch::Sender<u32>,
+) -> anyhow::Result<()> {
+    let mut resp = client
+        .get(url)
+        .header(reqwest::header::ACCEPT, "text/event-stream")
+        .send()
+        .await?
+        .error_for_status()?;
+    tracing::info!(url, "Lepus relayer: following Horizon ledger stream");
+    let mut events = SseParser::default();
+    loop {
+        let chunk = tokio::time::timeout(STREAM_IDLE_TIMEOUT, resp.chunk())
+            .await
+            .map_err(|_| anyhow::anyhow!("no events for {}s", STREAM_IDLE_TIMEOUT.as_secs()))??
+            .ok_or_else(|| anyhow::anyhow!("stream closed"))?;
+        for data in events.push(&chunk) {
+            if let Some(sequence) = ledger_sequence(&data) {
+                if closed.send(sequence).is_err() {
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+/// Sequence of a Horizon ledger record. Horizon also sends non-ledger data,
+/// such as its initial `"hello"`, which yields `None`.
+fn ledger_sequence(data: &str) -> Option<u32> {
+    #[derive(serde::Deserialize)]
+    struct Ledger {
+        sequence: u32,
+    }
+    serde_json::from_str::<Ledger>(data)
+        .ok()
+        .map(|ledger| ledger.sequence)
+}
+
+/// Incremental server-sent events parser yielding each event's data.
+#[derive(Default)]
+struct SseParser {
+    line: Vec<u8>,
+    data: Vec<String>,
+}
+
+impl SseParser {
+    /// Feed the next bytes of the stream, returning the data of the events
+    /// they complete.
+    fn push(&mut self, bytes: &[u8]) -> Vec<String> {
+        let mut events = Vec::new();
+        for &byte in bytes {
+            if byte != b'\n' {
+                self.line.push(byte);
+                continue;
+            }
+            if self.line.last() == Some(&b'\r') {
+                self.line.pop();
+            }
+            let line = String::from_utf8_lossy(&std::mem::take(&mut self.line)).into_owned();
+            if line.is_empty() {
+                if !self.data.is_empty() {
+                    events.push(std::mem::take(&mut self.data).join("\n"));
+                }
+            } else if let Some(data) = line.strip_prefix("data:") {
+                self.data
+                    .push(data.strip_prefix(' ').unwrap_or(data).to_string());
+            }
+            // `id:`, `event:`, `retry:` and comments are not needed
+        }
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ring::hosting::deposit_index::DepositProof;
+    use crate::ring::hosting::oracle::MockStellarProofSource;
+
+    /// A source whose RPC tip is `ledger`.
+    fn source_at(ledger: u32) -> MockStellarProofSource {
+        let proof = DepositProof {
+            ledger_seq: ledger,
+            scp_envelopes: Vec::new(),
+            transaction_set: String::new(),
+            tx_result_metas: Vec::new(),
+            event_kinds: Vec::new(),
+            part: None,
+            attribution: None,
+        };
+        MockStellarProofSource::new([(ledger, proof)].into_iter().collect())
+    }
+
+    #[test]
+    fn test_sse_parser_across_chunks() {
+        let mut parser = SseParser::default();
+        assert_eq!(
+            parser.push(b"retry: 1000\nevent: open\ndata: \"hello\"\n\n"),
+            vec!["\"hello\"".to_string()]
+        );
+        assert!(parser
+            .push(b"id: 1234\r\ndata: {\"sequence\": 51,\r\n")
+            .is_empty());
+        let events = parser.push(b"data: \"closed_at\": \"2026-01-01\"}\r\n\r\n: ping\n\n");
+        assert_eq!(events.len(), 1);
+        assert_eq!(ledger_sequence(&events[0]), Some(51));
+        assert_eq!(ledger_sequence("\"hello\""), None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_rpc_tip_wakes_on_new_ledger() {
+        let config = OracleConfig {
+            stream: true,
+            ..OracleConfig::default()
+        };
+        let mut closes = LedgerCloses::new(&config);
+        assert!(matches!(closes, LedgerCloses::RpcTip { .. }));
+
+        let source = source_at(100);
+        let started = tokio::time::Instant::now();
+        // The tip never moves past the baseline, so only the fallback wakes
+        closes.wait(&source).await;
+        assert!(started.elapsed() >= config.poll_interval);
+
+        let source = source_at(101);
+        let started = tokio::time::Instant::now();
+        closes.wait(&source).await;
+        assert!(started.elapsed() <= LEDGER_CLOSE_INTERVAL);
+    }
+
+    #[test]
+    fn test_poll_pacer_follows_activity() {
+        let config = OracleConfig {
+            poll_interval: Duration::from_secs(60),
+            poll_interval_min: Some(Duration::from_secs(10)),
+            poll_interval_max: Some(Duration::from_secs(300)),
+            ..OracleConfig::default()
+        };
+        let mut pacer = PollPacer::new(&config);
+        assert_eq!(pacer.record(1), Some(Duration::from_secs(30)));
+        assert_eq!(pacer.record(5), Some(Duration::from_secs(10)));
+        assert_eq!(pacer.record(1), None);
+
+        // Quiet polls widen it back, up to the bound
+        assert_eq!(pacer.record(0), Some(Duration::from_millis(12_500)));
+        let widest = (0..20).filter_map(|_| pacer.record(0)).last();
+        assert_eq!(widest, Some(Duration::from_secs(300)));
+        assert_eq!(pacer.record(0), None);
+
+        // Without bounds the interval never moves
+        let mut pacer = PollPacer::new(&OracleConfig::default());
+        assert_eq!(pacer.record(0), None);
+        assert_eq!(pacer.record(10), None);
+    }
+
+    #[test]
+    fn test_close_clock_learns_cadence() {
+        let mut clock = CloseClock::default();
+        let start = Instant::now();
+        assert!(clock.observe(100, start));
+        assert!(!clock.observe(100, start + Duration::from_secs(1)));
+
+        // Closes every 6s pull the period up from the nominal 5s
+        for n in 1..=20 {
+            assert!(clock.observe(100 + n, start + Duration::from_secs(6 * n as u64)));
+        }
+        let period = clock.period.as_secs_f64();
+        assert!((5.9..=6.0).contains(&period), "period {period}");
+
+        // The next check comes just before the next close, or the one
+        // after if that is already past
+        let seen_at = start + Duration::from_secs(120);
+        let check = clock.next_check(seen_at, Duration::ZERO);
+        assert_eq!(check, seen_at + clock.period - CHECK_EARLY);
+        let late = seen_at + clock.period + Duration::from_secs(1);
+        let check = clock.next_check(late, Duration::from_millis(100));
+        assert_eq!(
+            check,
+            seen_at + clock.period * 2 - CHECK_EARLY + Duration::from_millis(100)
+        );
+
+        // A tip that skipped ledgers counts as several closes
+        let mut clock = CloseClock::default();
+        clock.observe(10, start);
+        clock.observe(14, start + Duration::from_secs(20));
+        assert_eq!(clock.period, LEDGER_CLOSE_INTERVAL);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_interval_without_stream() {
+        let config = OracleConfig::default();
+        let mut closes = LedgerCloses::new(&config);
+        assert!(matches!(closes, LedgerCloses::Interval(_)));
+        let started = tokio::time::Instant::now();
+        closes.wait(&MockStellarProofSource::failing()).await;
+        assert_eq!(started.elapsed(), config.poll_interval);
+    }
+}
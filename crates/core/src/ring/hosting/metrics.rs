@@ -0,0 +1,286 @@
+//! Hosting cache metrics for periodic telemetry snapshots.
+//!
+//! Occupancy gauges (bytes, budget, contract counts and, with Lepus, the
+//! committed/uncommitted split and CWP score histogram) are published by
+//! `HostingManager` each time it sweeps the cache; `current_bytes` is also
+//! refreshed on every access. Eviction counters accumulate as contracts are
+//! evicted and reset on each snapshot.
+//!
+//! `TelemetryWorker` calls `take_snapshot()` on the transport snapshot
+//! interval and reports the result as a `hosting_cache_snapshot` event.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::LazyLock;
+
+use super::cache::EvictionReason;
+
+/// Global hosting cache metrics instance.
+pub static HOSTING_METRICS: LazyLock<HostingMetrics> = LazyLock::new(HostingMetrics::new);
+
+/// Number of equal-width buckets the CWP score range [0, 1] is split into.
+#[cfg(feature = "lepus")]
+pub const SCORE_HISTOGRAM_BUCKETS: usize = 10;
+
+/// Point-in-time occupancy of the hosting cache.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HostingOccupancy {
+    /// Bytes of contract state currently hosted.
+    pub current_bytes: u64,
+    /// Configured byte budget.
+    pub budget_bytes: u64,
+    /// Number of hosted contracts.
+    pub contracts: u64,
+    /// Hosted contracts with a non-zero deposit.
+    #[cfg(feature = "lepus")]
+    pub committed_contracts: u64,
+    /// Hosted contracts without a deposit.
+    #[cfg(feature = "lepus")]
+    pub uncommitted_contracts: u64,
+    /// Hosted contracts per CWP score bucket (lowest first).
+    #[cfg(feature = "lepus")]
+    pub score_histogram: [u64; SCORE_HISTOGRAM_BUCKETS],
+}
+
+impl HostingOccupancy {
+    /// Add another cache's occupancy into this one.
+    pub fn merge(&mut self, other: &HostingOccupancy) {
+        self.current_bytes += other.current_bytes;
+        self.budget_bytes += other.budget_bytes;
+        self.contracts += other.contracts;
+        #[cfg(feature = "lepus")]
+        {
+            self.committed_contracts += other.committed_contracts;
+            self.uncommitted_contracts += other.uncommitted_contracts;
+            for (bucket, count) in self.score_histogram.iter_mut().zip(other.score_histogram) {
+                *bucket += count;
+            }
+        }
+    }
+
+    /// Histogram bucket for a persistence score.
+    #[cfg(feature = "lepus")]
+    pub fn score_bucket(score: f64) -> usize {
+        ((score.clamp(0.0, 1.0) * SCORE_HISTOGRAM_BUCKETS as f64) as usize)
+            .min(SCORE_HISTOGRAM_BUCKETS - 1)
+    }
+}
+
+/// Accumulates hosting cache metrics for periodic reporting.
+///
+/// All operations are lock-free. As with transport metrics, a snapshot is
+/// not atomic across fields, which is acceptable for telemetry.
+#[derive(Debug)]
+pub struct HostingMetrics {
+    /// Whether occupancy has been published at least once.
+    published: AtomicBool,
+    current_bytes: AtomicU64,
+    budget_bytes: AtomicU64,
+    contracts: AtomicU64,
+    #[cfg(feature = "lepus")]
+    committed_contracts: AtomicU64,
+    #[cfg(feature = "lepus")]
+    uncommitted_contracts: AtomicU64,
+    #[cfg(feature = "lepus")]
+    score_histogram: [AtomicU64; SCORE_HISTOGRAM_BUCKETS],
+
+    // Eviction counters (reset each snapshot)
+    evictions_capacity: AtomicU64,
+    evictions_sweep: AtomicU64,
+    evictions_quota: AtomicU64,
+}
+
+impl Default for HostingMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HostingMetrics {
+    /// Create a new metrics collector.
+    pub fn new() -> Self {
+        Self {
+            published: AtomicBool::new(false),
+            current_bytes: AtomicU64::new(0),
+            budget_bytes: AtomicU64::new(0),
+            contracts: AtomicU64::new(0),
+            #[cfg(feature = "lepus")]
+            committed_contracts: AtomicU64::new(0),
+            #[cfg(feature = "lepus")]
+            uncommitted_contracts: AtomicU64::new(0),
+            #[cfg(feature = "lepus")]
+            score_histogram: std::array::from_fn(|_| AtomicU64::new(0)),
+            evictions_capacity: AtomicU64::new(0),
+            evictions_sweep: AtomicU64::new(0),
+            evictions_quota: AtomicU64::new(0),
+        }
+    }
+
+    /// Count contracts evicted for `reason`.
+    pub fn record_evictions(&self, reason: EvictionReason, count: usize) {
+        if count == 0 {
+            return;
+        }
+        let counter = match reason {
+            EvictionReason::Capacity => &self.evictions_capacity,
+            EvictionReason::Sweep => &self.evictions_sweep,
+            EvictionReason::Quota => &self.evictions_quota,
+        };
+        counter
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |v| {
+                Some(v.saturating_add(count as u64))
+            })
+            .ok();
+    }
+
+    /// Update the hosted byte gauge between full occupancy publications.
+    pub fn set_current_bytes(&self, bytes: u64) {
+        self.current_bytes.store(bytes, Ordering::Relaxed);
+    }
+
+    /// Publish a full occupancy reading.
+    pub fn publish(&self, occupancy: &HostingOccupancy) {
+        self.current_bytes
+            .store(occupancy.current_bytes, Ordering::Relaxed);
+        self.budget_bytes
+            .store(occupancy.budget_bytes, Ordering::Relaxed);
+        self.contracts.store(occupancy.contracts, Ordering::Relaxed);
+        #[cfg(feature = "lepus")]
+        {
+            self.committed_contracts
+                .store(occupancy.committed_contracts, Ordering::Relaxed);
+            self.uncommitted_contracts
+                .store(occupancy.uncommitted_contracts, Ordering::Relaxed);
+            for (bucket, count) in self.score_histogram.iter().zip(occupancy.score_histogram) {
+                bucket.store(count, Ordering::Relaxed);
+            }
+        }
+        self.published.store(true, Ordering::Relaxed);
+    }
+
+    /// Take a snapshot of current metrics and reset the eviction counters.
+    ///
+    /// Returns `None` until occupancy has been published.
+    pub fn take_snapshot(&self) -> Option<HostingCacheSnapshot> {
+        if !self.published.load(Ordering::Relaxed) {
+            return None;
+        }
+        Some(HostingCacheSnapshot {
+            current_bytes: self.current_bytes.load(Ordering::Relaxed),
+            budget_bytes: self.budget_bytes.load(Ordering::Relaxed),
+            contracts: self.contracts.load(Ordering::Relaxed),
+            evictions_capacity: self.evictions_capacity.swap(0, Ordering::Relaxed),
+            evictions_sweep: self.evictions_sweep.swap(0, Ordering::Relaxed),
+            evictions_quota: self.evictions_quota.swap(0, Ordering::Relaxed),
+            #[cfg(feature = "lepus")]
+            committed_contracts: self.committed_contracts.load(Ordering::Relaxed),
+            #[cfg(feature = "lepus")]
+            uncommitted_contracts: self.uncommitted_contracts.load(Ordering::Relaxed),
+            #[cfg(feature = "lepus")]
+            score_histogram: self
+                .score_histogram
+                .iter()
+                .map(|bucket| bucket.load(Ordering::Relaxed))
+                .collect(),
+        })
+    }
+}
+
+/// Periodic hosting cache metrics snapshot.
+///
+/// Gauges reflect the latest publication; eviction counts cover the period
+/// since the previous snapshot.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct HostingCacheSnapshot {
+    /// Bytes of contract state currently hosted.
+    pub current_bytes: u64,
+    /// Configured byte budget.
+    pub budget_bytes: u64,
+    /// Number of hosted contracts.
+    pub contracts: u64,
+    /// Contracts evicted to make room for new ones during the period.
+    pub evictions_capacity: u64,
+    /// Contracts evicted by the over-budget sweep during the period.
+    pub evictions_sweep: u64,
+    /// Contracts evicted over a per-creator quota during the period.
+    pub evictions_quota: u64,
+    /// Hosted contracts with a non-zero deposit.
+    #[cfg(feature = "lepus")]
+    pub committed_contracts: u64,
+    /// Hosted contracts without a deposit.
+    #[cfg(feature = "lepus")]
+    pub uncommitted_contracts: u64,
+    /// Hosted contracts per CWP score bucket of width 1/`SCORE_HISTOGRAM_BUCKETS`.
+    #[cfg(feature = "lepus")]
+    pub score_histogram: Vec<u64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_snapshot_before_publish() {
+        let metrics = HostingMetrics::new();
+        metrics.record_evictions(EvictionReason::Capacity, 3);
+        assert!(metrics.take_snapshot().is_none());
+    }
+
+    #[test]
+    #[allow(clippy::needless_update)] // Lepus adds more occupancy fields
+    fn test_snapshot_resets_evictions_keeps_gauges() {
+        let metrics = HostingMetrics::new();
+        metrics.publish(&HostingOccupancy {
+            current_bytes: 500,
+            budget_bytes: 1000,
+            contracts: 5,
+            ..Default::default()
+        });
+        metrics.record_evictions(EvictionReason::Capacity, 2);
+        metrics.record_evictions(EvictionReason::Sweep, 1);
+        metrics.record_evictions(EvictionReason::Sweep, 0);
+        metrics.record_evictions(EvictionReason::Quota, 4);
+        metrics.set_current_bytes(700);
+
+        let snapshot = metrics.take_snapshot().unwrap();
+        assert_eq!(snapshot.current_bytes, 700);
+        assert_eq!(snapshot.budget_bytes, 1000);
+        assert_eq!(snapshot.contracts, 5);
+        assert_eq!(snapshot.evictions_capacity, 2);
+        assert_eq!(snapshot.evictions_sweep, 1);
+        assert_eq!(snapshot.evictions_quota, 4);
+
+        let snapshot = metrics.take_snapshot().unwrap();
+        assert_eq!(snapshot.contracts, 5);
+        assert_eq!(snapshot.evictions_capacity, 0);
+        assert_eq!(snapshot.evictions_sweep, 0);
+        assert_eq!(snapshot.evictions_quota, 0);
+    }
+
+    #[cfg(feature = "lepus")]
+    #[test]
+    fn test_score_histogram_published() {
+        assert_eq!(HostingOccupancy::score_bucket(0.0), 0);
+        assert_eq!(HostingOccupancy::score_bucket(0.15), 1);
+        assert_eq!(
+            HostingOccupancy::score_bucket(1.0),
+            SCORE_HISTOGRAM_BUCKETS - 1
+        );
+
+        let metrics = HostingMetrics::new();
+        let mut score_histogram = [0; SCORE_HISTOGRAM_BUCKETS];
+        score_histogram[1] = 3;
+        score_histogram[9] = 1;
+        metrics.publish(&HostingOccupancy {
+            contracts: 4,
+            committed_contracts: 1,
+            uncommitted_contracts: 3,
+            score_histogram,
+            ..Default::default()
+        });
+
+        let snapshot = metrics.take_snapshot().unwrap();
+        assert_eq!(snapshot.committed_contracts, 1);
+        assert_eq!(snapshot.uncommitted_contracts, 3);
+        assert_eq!(snapshot.score_histogram, score_histogram.to_vec());
+    }
+}
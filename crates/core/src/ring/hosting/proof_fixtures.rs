@@ -0,0 +1,367 @@
+//! Deposit proofs signed by made-up Stellar validators, for tests (Lepus).
+//!
+//! Proofs are put together the way Stellar's are: externalize envelopes
+//! signed over the network ID, a transaction set whose hash the consensus
+//! value commits to, and `DEPOSIT` contract events in transaction result
+//! metas. The deposit-index contract verifies them like proofs of real
+//! ledgers when its parameters come from `ProofFixtures::params`.
+//!
+//! ```ignore
+//! let fixtures = ProofFixtures::new(3, 2);
+//! let params = fixtures.params();
+//! let proof = fixtures.deposit_proof(1_000, &[(instance_id, 50_000_000)]);
+//! ```
+
+use ed25519_dalek::{Signer, SigningKey};
+use freenet_stdlib::prelude::ContractInstanceId;
+use sha2::{Digest, Sha256};
+use stellar_xdr::curr::{
+    ContractEvent, ContractEventBody, ContractEventType, ContractEventV0, ContractId, EnvelopeType,
+    ExtensionPoint, GeneralizedTransactionSet, Hash, Int128Parts, LedgerCloseMeta,
+    LedgerCloseMetaV0, LedgerEntryChanges, LedgerHeader, LedgerHeaderExt, LedgerHeaderHistoryEntry,
+    LedgerHeaderHistoryEntryExt, LedgerScpMessages, Limits, NodeId, PublicKey, ScBytes, ScSymbol,
+    ScVal, ScpBallot, ScpEnvelope, ScpHistoryEntry, ScpHistoryEntryV0, ScpStatement,
+    ScpStatementExternalize, ScpStatementPledges, Signature, SorobanTransactionMeta,
+    SorobanTransactionMetaExt, StellarValue, StellarValueExt, TimePoint, TransactionMeta,
+    TransactionMetaV3, TransactionResult, TransactionResultExt, TransactionResultMeta,
+    TransactionResultPair, TransactionResultResult, TransactionSet, TransactionSetV1, Uint256,
+    Value, VecM, WriteXdr,
+};
+
+use super::deposit_index::{DepositIndexParams, DepositProof, ValidatorOrg};
+
+/// Passphrase of the network the fixtures' validators sign for.
+const NETWORK_PASSPHRASE: &str = "Test SDF Network ; September 2015";
+
+/// Validators of a made-up Stellar network, and the hvym contract whose
+/// deposits they attest to.
+pub struct ProofFixtures {
+    organizations: Vec<Vec<SigningKey>>,
+    hvym_contract: [u8; 32],
+}
+
+impl ProofFixtures {
+    /// `organizations` organizations of `validators` validators each. Keys
+    /// are derived from their indices, so every run signs the same proofs.
+    pub fn new(organizations: usize, validators: usize) -> Self {
+        let organizations = (0..organizations)
+            .map(|org| {
+                (0..validators)
+                    .map(|validator| {
+                        let mut secret = [0x4c; 32];
+                        secret[0] = org as u8;
+                        secret[1] = validator as u8;
+                        SigningKey::from_bytes(&secret)
+                    })
+                    .collect()
+            })
+            .collect();
+        Self {
+            organizations,
+            hvym_contract: [0xAA; 32],
+        }
+    }
+
+    /// The same validators attesting to deposits of the hvym contract with
+    /// the address `hvym_contract` instead.
+    pub fn with_hvym_contract(mut self, hvym_contract: [u8; 32]) -> Self {
+        self.hvym_contract = hvym_contract;
+        self
+    }
+
+    /// Deposit-index parameters trusting exactly these validators.
+    pub fn params(&self) -> DepositIndexParams {
+        DepositIndexParams {
+            network_id: hex::encode(network_id()),
+            organizations: self
+                .organizations
+                .iter()
+                .enumerate()
+                .map(|(i, keys)| ValidatorOrg {
+                    name: format!("Org{i}"),
+                    validators: keys
+                        .iter()
+                        .map(|key| hex::encode(key.verifying_key().to_bytes()))
+                        .collect(),
+                })
+                .collect(),
+            quorum_org_threshold: 0,
+            hvym_contract_address: hex::encode(self.hvym_contract),
+        }
+    }
+
+    /// A proof of `ledger_seq`, signed by every validator, with one
+    /// `DEPOSIT` event per entry of `deposits` in stroops.
+    pub fn deposit_proof(
+        &self,
+        ledger_seq: u32,
+        deposits: &[(ContractInstanceId, i128)],
+    ) -> DepositProof {
+        let (tx_set_xdr, value) = ledger_value(ledger_seq);
+        let scp_envelopes = self
+            .envelopes(ledger_seq, &value)
+            .iter()
+            .map(|envelope| base64::encode(to_xdr(envelope)))
+            .collect();
+        let tx_result_metas = self
+            .result_metas(ledger_seq, deposits)
+            .iter()
+            .map(|meta| base64::encode(to_xdr(meta)))
+            .collect();
+        DepositProof {
+            ledger_seq,
+            scp_envelopes,
+            transaction_set: base64::encode(tx_set_xdr),
+            tx_result_metas,
+            event_kinds: vec!["DEPOSIT".to_string()],
+            part: None,
+            attribution: None,
+        }
+    }
+
+    /// The close meta stellar-core streams for the ledger of
+    /// `deposit_proof(ledger_seq, deposits)`: the same envelopes and result
+    /// metas, with the transaction set in the legacy form `V0` carries.
+    pub fn close_meta(
+        &self,
+        ledger_seq: u32,
+        deposits: &[(ContractInstanceId, i128)],
+    ) -> LedgerCloseMeta {
+        let (_, value) = ledger_value(ledger_seq);
+        let header = LedgerHeader {
+            ledger_version: 22,
+            previous_ledger_hash: Hash([0; 32]),
+            scp_value: value.clone(),
+            tx_set_result_hash: Hash([0; 32]),
+            bucket_list_hash: Hash([0; 32]),
+            ledger_seq,
+            total_coins: 0,
+            fee_pool: 0,
+            inflation_seq: 0,
+            id_pool: 0,
+            base_fee: 100,
+            base_reserve: 5_000_000,
+            max_tx_set_size: 1_000,
+            skip_list: [Hash([0; 32]), Hash([0; 32]), Hash([0; 32]), Hash([0; 32])],
+            ext: LedgerHeaderExt::V0,
+        };
+        LedgerCloseMeta::V0(LedgerCloseMetaV0 {
+            ledger_header: LedgerHeaderHistoryEntry {
+                hash: Hash(Sha256::digest(to_xdr(&header)).into()),
+                header,
+                ext: LedgerHeaderHistoryEntryExt::V0,
+            },
+            tx_set: TransactionSet {
+                previous_ledger_hash: Hash([0; 32]),
+                txs: VecM::default(),
+            },
+            tx_processing: self
+                .result_metas(ledger_seq, deposits)
+                .try_into()
+                .expect("few metas"),
+            upgrades_processing: VecM::default(),
+            scp_info: vec![ScpHistoryEntry::V0(ScpHistoryEntryV0 {
+                quorum_sets: VecM::default(),
+                ledger_messages: LedgerScpMessages {
+                    ledger_seq,
+                    messages: self
+                        .envelopes(ledger_seq, &value)
+                        .try_into()
+                        .expect("few envelopes"),
+                },
+            })]
+            .try_into()
+            .expect("one entry"),
+        })
+    }
+
+    /// Every validator's externalize envelope for `value` at `ledger_seq`.
+    fn envelopes(&self, ledger_seq: u32, value: &StellarValue) -> Vec<ScpEnvelope> {
+        self.organizations
+            .iter()
+            .flatten()
+            .map(|key| signed_envelope(key, ledger_seq, value))
+            .collect()
+    }
+
+    /// One transaction result meta per entry of `deposits`.
+    fn result_metas(
+        &self,
+        ledger_seq: u32,
+        deposits: &[(ContractInstanceId, i128)],
+    ) -> Vec<TransactionResultMeta> {
+        deposits
+            .iter()
+            .map(|(instance_id, amount)| self.deposit_meta(instance_id, *amount, ledger_seq))
+            .collect()
+    }
+
+    /// A transaction result meta carrying the hvym contract's `DEPOSIT`
+    /// event for `instance_id`, in the v1 data layout
+    /// `(caller, amount, burned, ledger)`.
+    fn deposit_meta(
+        &self,
+        instance_id: &ContractInstanceId,
+        amount: i128,
+        ledger_seq: u32,
+    ) -> TransactionResultMeta {
+        let event = ContractEvent {
+            ext: ExtensionPoint::V0,
+            contract_id: Some(ContractId(Hash(self.hvym_contract))),
+            type_: ContractEventType::Contract,
+            body: ContractEventBody::V0(ContractEventV0 {
+                topics: vec![
+                    ScVal::Symbol(ScSymbol("DEPOSIT".try_into().expect("short symbol"))),
+                    ScVal::Bytes(ScBytes(
+                        instance_id
+                            .as_bytes()
+                            .to_vec()
+                            .try_into()
+                            .expect("32 bytes"),
+                    )),
+                ]
+                .try_into()
+                .expect("two topics"),
+                data: ScVal::Vec(Some(
+                    vec![
+                        ScVal::Void,
+                        i128_val(amount),
+                        i128_val(0),
+                        ScVal::U32(ledger_seq),
+                    ]
+                    .try_into()
+                    .expect("four fields"),
+                )),
+            }),
+        };
+        TransactionResultMeta {
+            result: TransactionResultPair {
+                transaction_hash: Hash(Sha256::digest(instance_id.as_bytes()).into()),
+                result: TransactionResult {
+                    fee_charged: 100,
+                    result: TransactionResultResult::TxSuccess(VecM::default()),
+                    ext: TransactionResultExt::V0,
+                },
+            },
+            fee_processing: LedgerEntryChanges(VecM::default()),
+            tx_apply_processing: TransactionMeta::V3(TransactionMetaV3 {
+                ext: ExtensionPoint::V0,
+                tx_changes_before: LedgerEntryChanges(VecM::default()),
+                operations: VecM::default(),
+                tx_changes_after: LedgerEntryChanges(VecM::default()),
+                soroban_meta: Some(SorobanTransactionMeta {
+                    ext: SorobanTransactionMetaExt::V0,
+                    events: vec![event].try_into().expect("one event"),
+                    return_value: ScVal::Void,
+                    diagnostic_events: VecM::default(),
+                }),
+            }),
+        }
+    }
+}
+
+/// The empty generalized transaction set of `ledger_seq`, as XDR, and the
+/// consensus value committing to it.
+fn ledger_value(ledger_seq: u32) -> (Vec<u8>, StellarValue) {
+    let tx_set = GeneralizedTransactionSet::V1(TransactionSetV1 {
+        previous_ledger_hash: Hash([0; 32]),
+        phases: VecM::default(),
+    });
+    let tx_set_xdr = to_xdr(&tx_set);
+    let value = StellarValue {
+        tx_set_hash: Hash(Sha256::digest(&tx_set_xdr).into()),
+        close_time: TimePoint(u64::from(ledger_seq) * 5),
+        upgrades: VecM::default(),
+        ext: StellarValueExt::Basic,
+    };
+    (tx_set_xdr, value)
+}
+
+fn network_id() -> [u8; 32] {
+    Sha256::digest(NETWORK_PASSPHRASE).into()
+}
+
+/// `key`'s externalize statement for `value` at `ledger_seq`, signed over
+/// `network_id || ENVELOPE_TYPE_SCP || statement` as stellar-core does.
+fn signed_envelope(key: &SigningKey, ledger_seq: u32, value: &StellarValue) -> ScpEnvelope {
+    let statement = ScpStatement {
+        node_id: NodeId(PublicKey::PublicKeyTypeEd25519(Uint256(
+            key.verifying_key().to_bytes(),
+        ))),
+        slot_index: u64::from(ledger_seq),
+        pledges: ScpStatementPledges::Externalize(ScpStatementExternalize {
+            commit: ScpBallot {
+                counter: 1,
+                value: Value(to_xdr(value).try_into().expect("small value")),
+            },
+            n_h: 1,
+            commit_quorum_set_hash: Hash([0; 32]),
+        }),
+    };
+    let mut message = network_id().to_vec();
+    message.extend(to_xdr(&EnvelopeType::Scp));
+    message.extend(to_xdr(&statement));
+    ScpEnvelope {
+        statement,
+        signature: Signature(
+            key.sign(&message)
+                .to_bytes()
+                .to_vec()
+                .try_into()
+                .expect("64-byte signature"),
+        ),
+    }
+}
+
+fn to_xdr(value: &impl WriteXdr) -> Vec<u8> {
+    value.to_xdr(Limits::none()).expect("fixture encodes")
+}
+
+fn i128_val(v: i128) -> ScVal {
+    ScVal::I128(Int128Parts {
+        hi: (v >> 64) as i64,
+        lo: v as u64,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use stellar_xdr::curr::ReadXdr;
+
+    #[test]
+    fn test_envelopes_verify_against_params() {
+        let fixtures = ProofFixtures::new(3, 2);
+        let params = fixtures.params();
+        let instance_id = ContractInstanceId::new([7; 32]);
+        let proof = fixtures.deposit_proof(1_000, &[(instance_id, 50_000_000)]);
+        assert_eq!(proof.scp_envelopes.len(), 6);
+        assert_eq!(proof.tx_result_metas.len(), 1);
+
+        let trusted: Vec<&String> = params
+            .organizations
+            .iter()
+            .flat_map(|org| &org.validators)
+            .collect();
+        for envelope in &proof.scp_envelopes {
+            let envelope =
+                ScpEnvelope::from_xdr(base64::decode(envelope).unwrap(), Limits::none()).unwrap();
+            let PublicKey::PublicKeyTypeEd25519(Uint256(signer)) = envelope.statement.node_id.0;
+            assert!(trusted.contains(&&hex::encode(signer)));
+
+            let mut message = network_id().to_vec();
+            message.extend(to_xdr(&EnvelopeType::Scp));
+            message.extend(to_xdr(&envelope.statement));
+            let signature: [u8; 64] = envelope.signature.0.as_slice().try_into().unwrap();
+            ed25519_dalek::VerifyingKey::from_bytes(&signer)
+                .unwrap()
+                .verify_strict(&message, &ed25519_dalek::Signature::from_bytes(&signature))
+                .unwrap();
+        }
+
+        // The same fixtures sign the same proofs
+        let again = ProofFixtures::new(3, 2).deposit_proof(1_000, &[(instance_id, 50_000_000)]);
+        assert_eq!(again.scp_envelopes, proof.scp_envelopes);
+        assert_eq!(again.tx_result_metas, proof.tx_result_metas);
+    }
+}
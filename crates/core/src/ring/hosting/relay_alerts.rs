@@ -0,0 +1,333 @@
+//! Alert hooks for a relayer that keeps failing or falls behind (Lepus).
+//!
+//! A relayer that silently stops relaying leaves commitment scores to go
+//! stale, so after each poll the relay loop hands the oracle status to
+//! `RelayAlerts`. It raises an alert when the consecutive failures reach
+//! `failure-threshold` or the lag exceeds `lag-ledgers`, and a resolving one
+//! when the condition clears, rather than one per poll. Each alert is POSTed
+//! to `webhook-url` as JSON and written to the standard input of `command`:
+//!
+//! ```json
+//! {"kind": "lag", "resolved": false, "message": "Lepus relayer is 900 ledgers behind the network",
+//!  "consecutive_failures": 0, "lag_ledgers": 900, "last_processed_ledger": 51234000,
+//!  "network_ledger": 51234900, "last_error": null}
+//! ```
+//!
+//! A relayer that finds the deposit-index running other code than the
+//! configured code hash raises a `code-hash-mismatch` alert before refusing
+//! to relay. Hooks run in the background, so a slow endpoint never holds the
+//! relayer up, and a hook that fails is only logged.
+
+use std::time::Duration;
+
+use serde::Serialize;
+use tokio::io::AsyncWriteExt;
+
+use super::oracle::OracleStatus;
+use crate::config::{GlobalExecutor, LepusRelayAlerts};
+
+/// How long an alert command may run before it is killed.
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// What an alert is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum AlertKind {
+    /// Polls keep failing.
+    ConsecutiveFailures,
+    /// The relayer trails the network.
+    Lag,
+    /// The deposit-index runs other code than the configured code hash, so
+    /// the relayer refuses to relay to it.
+    CodeHashMismatch,
+}
+
+/// One alert, as sent to the hooks.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub(crate) struct RelayAlert {
+    pub kind: AlertKind,
+    /// Whether the condition cleared, rather than began.
+    pub resolved: bool,
+    pub message: String,
+    pub consecutive_failures: u32,
+    pub lag_ledgers: Option<u32>,
+    pub last_processed_ledger: Option<u32>,
+    pub network_ledger: Option<u32>,
+    pub last_error: Option<String>,
+}
+
+/// Raises alerts from the relayer's status after each poll.
+pub(crate) struct RelayAlerts {
+    config: LepusRelayAlerts,
+    client: reqwest::Client,
+    failing: bool,
+    lagging: bool,
+}
+
+impl RelayAlerts {
+    /// Alerts for `config`, or `None` when no hook is set.
+    pub(crate) fn new(config: &LepusRelayAlerts, http_timeout: Duration) -> Option<Self> {
+        if !config.enabled() {
+            return None;
+        }
+        let client = reqwest::Client::builder()
+            .timeout(http_timeout)
+            .build()
+            .unwrap_or_default();
+        Some(Self {
+            config: config.clone(),
+            client,
+            failing: false,
+            lagging: false,
+        })
+    }
+
+    /// Raise the alerts due after a poll that left the relayer in `status`.
+    pub(crate) fn observe(&mut self, status: &OracleStatus) {
+        for alert in self.check(status) {
+            self.send(alert);
+        }
+    }
+
+    /// Raise a `kind` alert at once, for a condition that doesn't clear.
+    pub(crate) fn raise(&self, kind: AlertKind, message: String, status: &OracleStatus) {
+        self.send(alert(kind, false, message, status));
+    }
+
+    fn send(&self, alert: RelayAlert) {
+        if alert.resolved {
+            tracing::info!(kind = ?alert.kind, "{}", alert.message);
+        } else {
+            tracing::warn!(kind = ?alert.kind, "{}", alert.message);
+        }
+        let hooks = Hooks {
+            webhook_url: self.config.webhook_url.clone(),
+            command: self.config.command.clone(),
+            client: self.client.clone(),
+        };
+        GlobalExecutor::spawn(async move { hooks.deliver(&alert).await });
+    }
+
+    /// The alerts `status` starts or resolves.
+    fn check(&mut self, status: &OracleStatus) -> Vec<RelayAlert> {
+        let mut alerts = Vec::new();
+        let threshold = self.config.failure_threshold;
+        let failing = threshold > 0 && status.consecutive_failures >= threshold;
+        if failing != self.failing {
+            self.failing = failing;
+            let message = if failing {
+                format!(
+                    "Lepus relayer failed {} polls in a row",
+                    status.consecutive_failures
+                )
+            } else {
+                "Lepus relayer polls are succeeding again".to_string()
+            };
+            alerts.push(alert(
+                AlertKind::ConsecutiveFailures,
+                !failing,
+                message,
+                status,
+            ));
+        }
+        // Without a network ledger the lag is unknown; failures cover that
+        if let Some(lag) = status.lag_ledgers {
+            let max_lag = self.config.lag_ledgers;
+            let lagging = max_lag > 0 && lag > max_lag;
+            if lagging != self.lagging {
+                self.lagging = lagging;
+                let message = if lagging {
+                    format!("Lepus relayer is {lag} ledgers behind the network")
+                } else {
+                    format!("Lepus relayer caught up to within {lag} ledgers of the network")
+                };
+                alerts.push(alert(AlertKind::Lag, !lagging, message, status));
+            }
+        }
+        alerts
+    }
+}
+
+fn alert(kind: AlertKind, resolved: bool, message: String, status: &OracleStatus) -> RelayAlert {
+    RelayAlert {
+        kind,
+        resolved,
+        message,
+        consecutive_failures: status.consecutive_failures,
+        lag_ledgers: status.lag_ledgers,
+        last_processed_ledger: status.last_processed_ledger,
+        network_ledger: status.network_ledger,
+        last_error: status.last_error.clone(),
+    }
+}
+
+/// Where an alert goes.
+struct Hooks {
+    webhook_url: Option<String>,
+    command: Option<String>,
+    client: reqwest::Client,
+}
+
+impl Hooks {
+    async fn deliver(&self, alert: &RelayAlert) {
+        let Ok(body) = serde_json::to_vec(alert) else {
+            return;
+        };
+        if let Some(url) = &self.webhook_url {
+            let sent = self
+                .client
+                .post(url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .body(body.clone())
+                .send()
+                .await
+                .and_then(|response| response.error_for_status());
+            if let Err(e) = sent {
+                tracing::warn!(error = %e, "Lepus relayer: alert webhook failed");
+            }
+        }
+        if let Some(command) = &self.command {
+            if let Err(e) = run_command(command, &body).await {
+                tracing::warn!(error = %e, command, "Lepus relayer: alert command failed");
+            }
+        }
+    }
+}
+
+/// Run `command` through the shell with `input` on its standard input.
+async fn run_command(command: &str, input: &[u8]) -> anyhow::Result<()> {
+    #[cfg(windows)]
+    let mut cmd = tokio::process::Command::new("cmd");
+    #[cfg(windows)]
+    cmd.arg("/C");
+    #[cfg(not(windows))]
+    let mut cmd = tokio::process::Command::new("sh");
+    #[cfg(not(windows))]
+    cmd.arg("-c");
+    let mut child = cmd
+        .arg(command)
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::null())
+        .kill_on_drop(true)
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // A command that ignores its input may exit before reading it
+        let _ = stdin.write_all(input).await;
+    }
+    let status = tokio::time::timeout(COMMAND_TIMEOUT, child.wait())
+        .await
+        .map_err(|_| anyhow::anyhow!("timed out after {}s", COMMAND_TIMEOUT.as_secs()))??;
+    if !status.success() {
+        anyhow::bail!("exited with {status}");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn relay_alerts(failure_threshold: u32, lag_ledgers: u32) -> RelayAlerts {
+        RelayAlerts::new(
+            &LepusRelayAlerts {
+                webhook_url: Some("http://127.0.0.1:9/alerts".to_string()),
+                failure_threshold,
+                lag_ledgers,
+                ..Default::default()
+            },
+            Duration::from_secs(1),
+        )
+        .unwrap()
+    }
+
+    fn status(consecutive_failures: u32, lag_ledgers: Option<u32>) -> OracleStatus {
+        OracleStatus {
+            consecutive_failures,
+            lag_ledgers,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_alerts_fire_on_crossing_and_resolve_once() {
+        assert!(RelayAlerts::new(&LepusRelayAlerts::default(), Duration::from_secs(1)).is_none());
+
+        let mut alerts = relay_alerts(3, 100);
+        assert!(alerts.check(&status(2, Some(100))).is_empty());
+
+        let raised = alerts.check(&status(3, Some(101)));
+        let kinds: Vec<_> = raised.iter().map(|a| (a.kind, a.resolved)).collect();
+        assert_eq!(
+            kinds,
+            [
+                (AlertKind::ConsecutiveFailures, false),
+                (AlertKind::Lag, false)
+            ]
+        );
+        assert_eq!(raised[1].lag_ledgers, Some(101));
+
+        // Still failing and lagging: nothing new, and an unknown lag
+        // changes nothing
+        assert!(alerts.check(&status(4, Some(500))).is_empty());
+        assert!(alerts.check(&status(5, None)).is_empty());
+
+        let resolved = alerts.check(&status(0, Some(10)));
+        let kinds: Vec<_> = resolved.iter().map(|a| (a.kind, a.resolved)).collect();
+        assert_eq!(
+            kinds,
+            [
+                (AlertKind::ConsecutiveFailures, true),
+                (AlertKind::Lag, true)
+            ]
+        );
+
+        // A threshold of 0 disables the check
+        let mut alerts = relay_alerts(0, 0);
+        assert!(alerts.check(&status(1_000, Some(1_000_000))).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_alert_posted_to_webhook() {
+        use httptest::{matchers::*, responders::*, Expectation, Server};
+
+        let server = Server::run();
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("POST", "/alerts"),
+                request::body(json_decoded(eq(serde_json::json!({
+                    "kind": "consecutive-failures",
+                    "resolved": false,
+                    "message": "Lepus relayer failed 5 polls in a row",
+                    "consecutive_failures": 5,
+                    "lag_ledgers": null,
+                    "last_processed_ledger": 1000,
+                    "network_ledger": null,
+                    "last_error": "rpc down",
+                })))),
+            ])
+            .respond_with(status_code(200)),
+        );
+        let mut alerts = RelayAlerts::new(
+            &LepusRelayAlerts {
+                webhook_url: Some(server.url_str("/alerts")),
+                ..Default::default()
+            },
+            Duration::from_secs(5),
+        )
+        .unwrap();
+        let status = OracleStatus {
+            consecutive_failures: 5,
+            last_processed_ledger: Some(1_000),
+            last_error: Some("rpc down".to_string()),
+            ..Default::default()
+        };
+        let [alert] = alerts.check(&status).try_into().unwrap();
+        Hooks {
+            webhook_url: alerts.config.webhook_url.clone(),
+            command: None,
+            client: alerts.client.clone(),
+        }
+        .deliver(&alert)
+        .await;
+    }
+}
@@ -0,0 +1,239 @@
+//! Deposit-proof relayer metrics for periodic telemetry snapshots (Lepus).
+//!
+//! The relay loop counts proofs fetched and submitted and the failures of
+//! each, `StellarProofRelayer` times every Stellar RPC request, and each poll
+//! publishes the ledger lag. Counters accumulate between snapshots and reset
+//! on each one; the lag gauges keep their latest value.
+//!
+//! `TelemetryWorker` calls `take_snapshot()` on the transport snapshot
+//! interval and reports the result as an `oracle_relayer_snapshot` event.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::LazyLock;
+use std::time::Duration;
+
+/// Global relayer metrics instance.
+pub static RELAYER_METRICS: LazyLock<RelayerMetrics> = LazyLock::new(RelayerMetrics::new);
+
+/// Accumulates relayer metrics for periodic reporting.
+///
+/// All operations are lock-free; a snapshot is not atomic across fields.
+#[derive(Debug)]
+pub struct RelayerMetrics {
+    /// Whether the relayer has recorded anything; nodes that don't relay
+    /// never report.
+    active: AtomicBool,
+
+    // Counters (reset each snapshot)
+    proofs_fetched: AtomicU64,
+    proof_fetch_failures: AtomicU64,
+    proofs_submitted: AtomicU64,
+    submission_failures: AtomicU64,
+    rpc_requests: AtomicU64,
+    rpc_failures: AtomicU64,
+    rpc_latency_sum_ms: AtomicU64,
+    rpc_latency_max_ms: AtomicU64,
+    ledger_anomalies: AtomicU64,
+
+    // Gauges
+    last_processed_ledger: AtomicU64,
+    network_ledger: AtomicU64,
+}
+
+impl Default for RelayerMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RelayerMetrics {
+    /// Create a new metrics collector.
+    pub fn new() -> Self {
+        Self {
+            active: AtomicBool::new(false),
+            proofs_fetched: AtomicU64::new(0),
+            proof_fetch_failures: AtomicU64::new(0),
+            proofs_submitted: AtomicU64::new(0),
+            submission_failures: AtomicU64::new(0),
+            rpc_requests: AtomicU64::new(0),
+            rpc_failures: AtomicU64::new(0),
+            rpc_latency_sum_ms: AtomicU64::new(0),
+            rpc_latency_max_ms: AtomicU64::new(0),
+            ledger_anomalies: AtomicU64::new(0),
+            last_processed_ledger: AtomicU64::new(0),
+            network_ledger: AtomicU64::new(0),
+        }
+    }
+
+    /// Count a proof fetched for a ledger, or a failed attempt to.
+    pub fn record_proof_fetch(&self, ok: bool) {
+        self.count(if ok {
+            &self.proofs_fetched
+        } else {
+            &self.proof_fetch_failures
+        });
+    }
+
+    /// Count a proof submitted as an UPDATE, or a failed submission.
+    pub fn record_submission(&self, ok: bool) {
+        self.count(if ok {
+            &self.proofs_submitted
+        } else {
+            &self.submission_failures
+        });
+    }
+
+    /// Time one Stellar RPC request, `ok` unless it got no response.
+    pub fn record_rpc(&self, latency: Duration, ok: bool) {
+        self.count(&self.rpc_requests);
+        if !ok {
+            self.count(&self.rpc_failures);
+        }
+        let latency_ms = latency.as_millis() as u64;
+        self.rpc_latency_sum_ms
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |v| {
+                Some(v.saturating_add(latency_ms))
+            })
+            .ok();
+        self.rpc_latency_max_ms
+            .fetch_max(latency_ms, Ordering::Relaxed);
+    }
+
+    /// Count a gap or disorder in the ledgers a query returned.
+    pub fn record_ledger_anomaly(&self) {
+        self.count(&self.ledger_anomalies);
+    }
+
+    /// Publish the ledger lag after a poll. A `network_ledger` of `None`
+    /// keeps the previous reading.
+    pub fn set_ledgers(&self, last_processed_ledger: u32, network_ledger: Option<u32>) {
+        self.last_processed_ledger
+            .store(u64::from(last_processed_ledger), Ordering::Relaxed);
+        if let Some(network_ledger) = network_ledger {
+            self.network_ledger
+                .store(u64::from(network_ledger), Ordering::Relaxed);
+        }
+        self.active.store(true, Ordering::Relaxed);
+    }
+
+    fn count(&self, counter: &AtomicU64) {
+        counter
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |v| {
+                Some(v.saturating_add(1))
+            })
+            .ok();
+        self.active.store(true, Ordering::Relaxed);
+    }
+
+    /// Take a snapshot of current metrics and reset the counters.
+    ///
+    /// Returns `None` until the relayer has recorded something.
+    pub fn take_snapshot(&self) -> Option<RelayerSnapshot> {
+        if !self.active.load(Ordering::Relaxed) {
+            return None;
+        }
+        let rpc_requests = self.rpc_requests.swap(0, Ordering::Relaxed);
+        let rpc_latency_sum_ms = self.rpc_latency_sum_ms.swap(0, Ordering::Relaxed);
+        let last_processed_ledger = self.last_processed_ledger.load(Ordering::Relaxed);
+        let network_ledger = self.network_ledger.load(Ordering::Relaxed);
+        Some(RelayerSnapshot {
+            proofs_fetched: self.proofs_fetched.swap(0, Ordering::Relaxed),
+            proof_fetch_failures: self.proof_fetch_failures.swap(0, Ordering::Relaxed),
+            proofs_submitted: self.proofs_submitted.swap(0, Ordering::Relaxed),
+            submission_failures: self.submission_failures.swap(0, Ordering::Relaxed),
+            rpc_requests,
+            rpc_failures: self.rpc_failures.swap(0, Ordering::Relaxed),
+            rpc_latency_avg_ms: rpc_latency_sum_ms.checked_div(rpc_requests).unwrap_or(0),
+            rpc_latency_max_ms: self.rpc_latency_max_ms.swap(0, Ordering::Relaxed),
+            ledger_anomalies: self.ledger_anomalies.swap(0, Ordering::Relaxed),
+            last_processed_ledger,
+            network_ledger,
+            ledger_lag: if last_processed_ledger > 0 {
+                network_ledger.saturating_sub(last_processed_ledger)
+            } else {
+                0
+            },
+        })
+    }
+}
+
+/// Periodic relayer metrics snapshot.
+///
+/// Ledger gauges reflect the latest poll; the other counts cover the period
+/// since the previous snapshot.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RelayerSnapshot {
+    /// Proofs fetched from Stellar during the period.
+    pub proofs_fetched: u64,
+    /// Ledgers whose proof could not be fetched during the period.
+    pub proof_fetch_failures: u64,
+    /// Proofs submitted to the deposit-index during the period.
+    pub proofs_submitted: u64,
+    /// UPDATE submissions that failed during the period.
+    pub submission_failures: u64,
+    /// Stellar RPC requests sent during the period.
+    pub rpc_requests: u64,
+    /// Stellar RPC requests that got no response during the period.
+    pub rpc_failures: u64,
+    /// Mean Stellar RPC latency over the period.
+    pub rpc_latency_avg_ms: u64,
+    /// Slowest Stellar RPC request of the period.
+    pub rpc_latency_max_ms: u64,
+    /// Queries whose ledgers had a gap or were out of order during the
+    /// period.
+    pub ledger_anomalies: u64,
+    /// Last ledger relayed or found already indexed, 0 before the first.
+    pub last_processed_ledger: u64,
+    /// Latest ledger the RPC reported.
+    pub network_ledger: u64,
+    /// How many ledgers the relayer trails the network; 0 before the first
+    /// ledger is processed.
+    pub ledger_lag: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_snapshot_before_relaying() {
+        let metrics = RelayerMetrics::new();
+        assert!(metrics.take_snapshot().is_none());
+    }
+
+    #[test]
+    fn test_snapshot_resets_counters_keeps_ledgers() {
+        let metrics = RelayerMetrics::new();
+        metrics.record_proof_fetch(true);
+        metrics.record_proof_fetch(true);
+        metrics.record_proof_fetch(false);
+        metrics.record_submission(true);
+        metrics.record_submission(false);
+        metrics.record_rpc(Duration::from_millis(100), true);
+        metrics.record_rpc(Duration::from_millis(300), false);
+        metrics.record_ledger_anomaly();
+        metrics.set_ledgers(990, Some(1_000));
+
+        let snapshot = metrics.take_snapshot().unwrap();
+        assert_eq!(snapshot.proofs_fetched, 2);
+        assert_eq!(snapshot.proof_fetch_failures, 1);
+        assert_eq!(snapshot.proofs_submitted, 1);
+        assert_eq!(snapshot.submission_failures, 1);
+        assert_eq!(snapshot.rpc_requests, 2);
+        assert_eq!(snapshot.rpc_failures, 1);
+        assert_eq!(snapshot.rpc_latency_avg_ms, 200);
+        assert_eq!(snapshot.rpc_latency_max_ms, 300);
+        assert_eq!(snapshot.ledger_anomalies, 1);
+        assert_eq!(snapshot.ledger_lag, 10);
+
+        metrics.set_ledgers(995, None);
+        let snapshot = metrics.take_snapshot().unwrap();
+        assert_eq!(snapshot.proofs_fetched, 0);
+        assert_eq!(snapshot.rpc_requests, 0);
+        assert_eq!(snapshot.rpc_latency_avg_ms, 0);
+        assert_eq!(snapshot.rpc_latency_max_ms, 0);
+        assert_eq!(snapshot.ledger_anomalies, 0);
+        assert_eq!(snapshot.network_ledger, 1_000);
+        assert_eq!(snapshot.ledger_lag, 5);
+    }
+}
@@ -0,0 +1,194 @@
+//! Creator-key revocation list (Lepus).
+//!
+//! A compromised creator key would otherwise keep earning identity score for
+//! every datapod it signed. An optional revocation contract lists revoked
+//! creator pubkeys; its state is synced like any other contract, and each
+//! accepted update to it replaces the list kept here. Identity verification
+//! treats envelopes signed by a listed key as unverified.
+//!
+//! The contract state is JSON:
+//!
+//! ```json
+//! { "version": 3, "revoked": ["<hex or G... pubkey>", ...] }
+//! ```
+
+use std::collections::HashSet;
+use std::sync::OnceLock;
+
+use freenet_stdlib::prelude::{ContractInstanceId, ContractKey};
+use parking_lot::RwLock;
+use serde::Deserialize;
+
+/// The revocation contract's state.
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct RevocationState {
+    #[serde(default)]
+    pub version: u64,
+    /// Revoked creator pubkeys, in any form `parse_stellar_pubkey` accepts.
+    #[serde(default)]
+    pub revoked: Vec<String>,
+}
+
+/// Revoked creator keys as of the last synced revocation state.
+#[derive(Debug, Default)]
+pub(crate) struct RevocationList {
+    version: u64,
+    revoked: HashSet<[u8; 32]>,
+}
+
+impl RevocationList {
+    /// Whether `pubkey` is revoked.
+    pub fn contains(&self, pubkey: &[u8; 32]) -> bool {
+        self.revoked.contains(pubkey)
+    }
+
+    /// Replace the list with `state`, returning the keys it newly revokes,
+    /// or `None` if `state` is older than the synced one.
+    ///
+    /// Entries that don't parse as a pubkey are skipped with a warning.
+    pub fn replace(&mut self, state: &RevocationState) -> Option<Vec<[u8; 32]>> {
+        if state.version < self.version {
+            return None;
+        }
+        let revoked: HashSet<[u8; 32]> = state
+            .revoked
+            .iter()
+            .filter_map(|entry| match super::identity::parse_stellar_pubkey(entry) {
+                Ok(pubkey) => Some(pubkey),
+                Err(e) => {
+                    tracing::warn!(entry, error = %e, "Skipping invalid revoked key");
+                    None
+                }
+            })
+            .collect();
+        let mut newly_revoked: Vec<[u8; 32]> = revoked.difference(&self.revoked).copied().collect();
+        newly_revoked.sort_unstable();
+        self.version = state.version;
+        self.revoked = revoked;
+        Some(newly_revoked)
+    }
+}
+
+static REVOCATION_KEY: OnceLock<Option<ContractInstanceId>> = OnceLock::new();
+
+fn revocation_list() -> &'static RwLock<RevocationList> {
+    static LIST: OnceLock<RwLock<RevocationList>> = OnceLock::new();
+    LIST.get_or_init(Default::default)
+}
+
+/// Set the revocation contract from configuration.
+///
+/// Only the first call takes effect: the contract is fixed for the process
+/// lifetime, and without this call it is read from `LEPUS_REVOCATION_KEY`
+/// on first use.
+pub fn configure(key_hex: Option<&str>) {
+    if REVOCATION_KEY.set(parse_instance_id(key_hex)).is_err() {
+        tracing::debug!("Revocation contract already configured for this process");
+    }
+}
+
+fn parse_instance_id(key_hex: Option<&str>) -> Option<ContractInstanceId> {
+    let key_hex = key_hex?;
+    match hex::decode(key_hex.trim()).map(<[u8; 32]>::try_from) {
+        Ok(Ok(bytes)) => Some(ContractInstanceId::new(bytes)),
+        _ => {
+            tracing::warn!("Invalid revocation contract key, must be 32 bytes of hex");
+            None
+        }
+    }
+}
+
+/// The revocation contract's `ContractInstanceId`, if configured.
+pub fn revocation_instance_id() -> Option<ContractInstanceId> {
+    *REVOCATION_KEY
+        .get_or_init(|| parse_instance_id(std::env::var("LEPUS_REVOCATION_KEY").ok().as_deref()))
+}
+
+/// Whether `creator` is on the locally synced revocation list.
+pub fn is_revoked(creator: &[u8; 32]) -> bool {
+    revocation_list().read().contains(creator)
+}
+
+/// Check if an accepted state change is to the revocation contract and, if
+/// so, replace the synced list with it.
+///
+/// Returns the keys newly revoked by the update, or `None` for any other
+/// contract, a state that doesn't deserialize, or one older than the list.
+pub fn check_revocation_update(key: &ContractKey, state_bytes: &[u8]) -> Option<Vec<[u8; 32]>> {
+    if key.id() != &revocation_instance_id()? {
+        return None;
+    }
+    let state: RevocationState = match serde_json::from_slice(state_bytes) {
+        Ok(state) => state,
+        Err(e) => {
+            tracing::warn!(error = %e, "Lepus: failed to deserialize revocation state");
+            return None;
+        }
+    };
+    let newly_revoked = revocation_list().write().replace(&state)?;
+    tracing::info!(
+        version = state.version,
+        revoked = state.revoked.len(),
+        newly_revoked = newly_revoked.len(),
+        "Lepus: synced creator revocation list"
+    );
+    Some(newly_revoked)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replace_reports_newly_revoked() {
+        let mut list = RevocationList::default();
+        let first = RevocationState {
+            version: 1,
+            revoked: vec![hex::encode([1u8; 32])],
+        };
+        assert_eq!(list.replace(&first), Some(vec![[1u8; 32]]));
+        assert!(list.contains(&[1u8; 32]));
+
+        // StrKey entries are accepted, and already revoked keys aren't reported again
+        let second = RevocationState {
+            version: 2,
+            revoked: vec![
+                hex::encode([1u8; 32]),
+                "GABAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEJXA".to_string(),
+                "not a key".to_string(),
+            ],
+        };
+        assert_eq!(list.replace(&second), Some(vec![[2u8; 32]]));
+        assert!(list.contains(&[2u8; 32]));
+
+        // An older state doesn't roll the list back
+        assert_eq!(list.replace(&first), None);
+        assert!(list.contains(&[2u8; 32]));
+
+        // Dropping a key from the list reinstates it
+        let third = RevocationState {
+            version: 3,
+            revoked: vec![],
+        };
+        assert_eq!(list.replace(&third), Some(vec![]));
+        assert!(!list.contains(&[1u8; 32]));
+    }
+
+    #[test]
+    fn test_revocation_state_deserialize() {
+        let state: RevocationState =
+            serde_json::from_str(r#"{"revoked":["0101"]}"#).expect("valid state");
+        assert_eq!(state.version, 0);
+        assert_eq!(state.revoked, vec!["0101".to_string()]);
+    }
+
+    #[test]
+    fn test_parse_instance_id() {
+        assert!(parse_instance_id(None).is_none());
+        assert!(parse_instance_id(Some("abc")).is_none());
+        assert_eq!(
+            parse_instance_id(Some(&hex::encode([7u8; 32]))),
+            Some(ContractInstanceId::new([7u8; 32]))
+        );
+    }
+}
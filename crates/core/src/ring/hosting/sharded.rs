@@ -0,0 +1,239 @@
+//! Hosting cache split into independently locked shards.
+//!
+//! Each contract belongs to one shard, picked from its instance id, so
+//! accesses to different contracts rarely contend on the same lock. Every
+//! shard gets an equal slice of the global byte budget and runs its own
+//! eviction: watermarks, TTL, per-creator quotas, commitment pooling and
+//! budget partitions all apply within a shard. The expiry sweep, metrics and
+//! operator listings visit every shard and aggregate the results.
+//!
+//! With a single shard this behaves exactly like a plain `HostingCache`.
+
+use freenet_stdlib::prelude::{ContractInstanceId, ContractKey};
+use parking_lot::RwLock;
+
+use crate::util::time_source::TimeSource;
+
+#[cfg(feature = "lepus")]
+use super::cache::{CWPConfig, HostedContractPage, HostedContractSort};
+use super::cache::{EvictionCandidate, HostingCache};
+use super::metrics::HostingOccupancy;
+
+/// Hosting caches partitioned by contract instance id.
+pub(crate) struct ShardedHostingCache<T: TimeSource> {
+    shards: Vec<RwLock<HostingCache<T>>>,
+}
+
+impl<T: TimeSource> ShardedHostingCache<T> {
+    /// Split `budget_bytes` across `shard_count` caches built by `build`,
+    /// which receives each shard's budget. The first shard takes any
+    /// remainder so the shard budgets add up to the global one.
+    #[cfg_attr(not(feature = "lepus"), allow(dead_code))] // Shards are configured under `[lepus]`
+    pub fn new(
+        shard_count: usize,
+        budget_bytes: u64,
+        mut build: impl FnMut(u64) -> HostingCache<T>,
+    ) -> Self {
+        let shard_count = shard_count.max(1);
+        let per_shard = budget_bytes / shard_count as u64;
+        let remainder = budget_bytes % shard_count as u64;
+        let shards = (0..shard_count)
+            .map(|i| {
+                let budget = if i == 0 {
+                    per_shard + remainder
+                } else {
+                    per_shard
+                };
+                RwLock::new(build(budget))
+            })
+            .collect();
+        Self { shards }
+    }
+
+    /// Wrap a single cache as the only shard.
+    pub fn single(cache: HostingCache<T>) -> Self {
+        Self {
+            shards: vec![RwLock::new(cache)],
+        }
+    }
+
+    /// The shard holding contracts with this instance id.
+    pub fn shard(&self, instance_id: &ContractInstanceId) -> &RwLock<HostingCache<T>> {
+        let mut prefix = [0u8; 8];
+        prefix.copy_from_slice(&instance_id.as_bytes()[..8]);
+        let index = u64::from_le_bytes(prefix) % self.shards.len() as u64;
+        &self.shards[index as usize]
+    }
+
+    /// All shards, for operations that span the whole cache.
+    pub fn shards(&self) -> &[RwLock<HostingCache<T>>] {
+        &self.shards
+    }
+
+    pub fn contains(&self, key: &ContractKey) -> bool {
+        self.shard(key.id()).read().contains(key)
+    }
+
+    /// Hosted contracts across all shards.
+    pub fn len(&self) -> usize {
+        self.shards.iter().map(|shard| shard.read().len()).sum()
+    }
+
+    /// Hosted bytes across all shards.
+    pub fn current_bytes(&self) -> u64 {
+        self.shards
+            .iter()
+            .map(|shard| shard.read().current_bytes())
+            .sum()
+    }
+
+    /// Keys of every hosted contract, in no particular order.
+    pub fn keys(&self) -> Vec<ContractKey> {
+        self.shards
+            .iter()
+            .flat_map(|shard| shard.read().iter().collect::<Vec<_>>())
+            .collect()
+    }
+
+    /// Occupancy summed over all shards.
+    pub fn occupancy(&self) -> HostingOccupancy {
+        let mut occupancy = HostingOccupancy::default();
+        for shard in &self.shards {
+            occupancy.merge(&shard.read().occupancy());
+        }
+        occupancy
+    }
+
+    /// Contracts that would be evicted if `size_needed` more bytes were
+    /// hosted now.
+    ///
+    /// Where the new contract lands depends on its key, so with several
+    /// shards this reports the shard that would evict the most bytes.
+    pub fn simulate_eviction(&self, size_needed: u64) -> Vec<EvictionCandidate> {
+        self.shards
+            .iter()
+            .map(|shard| shard.read().simulate_eviction(size_needed))
+            .max_by_key(|victims| victims.iter().map(|victim| victim.size_bytes).sum::<u64>())
+            .unwrap_or_default()
+    }
+
+    /// Score reports for a page of hosted contracts across all shards.
+    #[cfg(feature = "lepus")]
+    pub fn list_score_reports(
+        &self,
+        sort: HostedContractSort,
+        descending: bool,
+        offset: usize,
+        limit: usize,
+    ) -> HostedContractPage {
+        let end = offset.saturating_add(limit);
+        let mut total = 0;
+        let mut reports = Vec::new();
+        for shard in &self.shards {
+            let page = shard.read().list_score_reports(sort, descending, 0, end);
+            total += page.total;
+            reports.extend(page.contracts);
+        }
+        reports.sort_by(|a, b| sort.compare(a, b, descending));
+        HostedContractPage {
+            total,
+            offset,
+            contracts: reports.into_iter().skip(offset).take(limit).collect(),
+        }
+    }
+
+    /// Swap the CWP configuration of every shard at once, returning the
+    /// previous one.
+    ///
+    /// Holds all shard locks (taken in shard order) while swapping, so no
+    /// access or eviction sees some shards on the old configuration and
+    /// others on the new one.
+    #[cfg(feature = "lepus")]
+    pub fn set_cwp_config(&self, cwp_config: CWPConfig) -> CWPConfig {
+        let mut shards: Vec<_> = self.shards.iter().map(|shard| shard.write()).collect();
+        let previous = shards[0].cwp_config().clone();
+        for shard in &mut shards {
+            shard.set_cwp_config(cwp_config.clone());
+        }
+        previous
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ring::hosting::AccessType;
+    use crate::util::time_source::SharedMockTimeSource;
+    use freenet_stdlib::prelude::CodeHash;
+    use std::time::Duration;
+
+    fn make_key(seed: u8) -> ContractKey {
+        ContractKey::from_id_and_code(
+            ContractInstanceId::new([seed; 32]),
+            CodeHash::new([seed.wrapping_add(1); 32]),
+        )
+    }
+
+    #[test]
+    fn test_shards_split_budget_and_evict_independently() {
+        let time_source = SharedMockTimeSource::new();
+        let sharded = ShardedHostingCache::new(3, 1000, |budget| {
+            HostingCache::new(budget, Duration::ZERO, time_source.clone())
+        });
+        let budgets: Vec<_> = sharded
+            .shards()
+            .iter()
+            .map(|shard| shard.read().budget_bytes())
+            .collect();
+        assert_eq!(budgets, vec![334, 333, 333]);
+
+        // Fill one shard past its budget; the others keep their contracts
+        let keys: Vec<_> = (0..=u8::MAX).map(make_key).collect();
+        let target = sharded.shard(keys[0].id()) as *const _;
+        let (same, other): (Vec<&ContractKey>, Vec<&ContractKey>) = keys
+            .iter()
+            .partition(|key| std::ptr::eq(sharded.shard(key.id()), target));
+        for key in other.iter().take(2) {
+            sharded
+                .shard(key.id())
+                .write()
+                .record_access(**key, 100, AccessType::Get);
+        }
+        for key in same.iter().take(4) {
+            time_source.advance_time(Duration::from_secs(1));
+            sharded
+                .shard(key.id())
+                .write()
+                .record_access(**key, 100, AccessType::Get);
+        }
+
+        assert!(other.iter().take(2).all(|key| sharded.contains(key)));
+        assert!(!sharded.contains(same[0]));
+        assert!(sharded.contains(same[3]));
+        assert_eq!(sharded.len(), 5);
+        assert_eq!(sharded.current_bytes(), 500);
+        let occupancy = sharded.occupancy();
+        assert_eq!(occupancy.budget_bytes, 1000);
+        assert_eq!(occupancy.contracts, 5);
+    }
+
+    #[cfg(feature = "lepus")]
+    #[test]
+    fn test_set_cwp_config_switches_every_shard() {
+        let time_source = SharedMockTimeSource::new();
+        let sharded = ShardedHostingCache::new(3, 1000, |budget| {
+            HostingCache::new(budget, Duration::ZERO, time_source.clone())
+        });
+        let tuned = CWPConfig {
+            penalty_weight: 0.5,
+            ..Default::default()
+        };
+
+        let previous = sharded.set_cwp_config(tuned.clone());
+        assert_eq!(previous, CWPConfig::default());
+        assert!(sharded
+            .shards()
+            .iter()
+            .all(|shard| *shard.read().cwp_config() == tuned));
+    }
+}
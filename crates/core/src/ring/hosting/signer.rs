@@ -0,0 +1,368 @@
+//! Node signing for Lepus identity operations.
+//!
+//! Answering a subscription identity challenge (and any later attestation)
+//! needs a signature from the node's Stellar key. [`NodeSigner`] hides where
+//! that key lives, so operators can keep it out of the node's memory:
+//!
+//! - [`LocalSigner`]: the secret from `LEPUS_STELLAR_SECRET`, or from the file
+//!   named by `LEPUS_STELLAR_SECRET_FILE`.
+//! - [`CommandSigner`]: an external program named by `LEPUS_SIGNER_COMMAND`,
+//!   e.g. a wrapper around an HSM or hardware wallet. The command is run as
+//!   `<command> pubkey`, printing the public key (hex or `G...`), and as
+//!   `<command> sign`, reading the hex message on stdin and printing the hex
+//!   signature. A command that doesn't finish within
+//!   [`SIGNER_COMMAND_TIMEOUT`] is killed.
+//!
+//! The command signer takes precedence when both are configured. The signer
+//! is set up when the ring starts, so request paths never wait on the
+//! `pubkey` command.
+
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+use anyhow::Context;
+use ed25519_dalek::{Signer, Verifier};
+
+/// Signs messages with the node's Stellar Ed25519 key.
+///
+/// `sign` may block (an external signer can wait on a device), so async
+/// callers should run it on a blocking thread.
+pub trait NodeSigner: Send + Sync + std::fmt::Debug {
+    /// The node's Stellar public key.
+    fn pubkey(&self) -> [u8; 32];
+
+    /// Sign `message`, returning the 64-byte Ed25519 signature.
+    fn sign(&self, message: &[u8]) -> anyhow::Result<[u8; 64]>;
+}
+
+/// Signs with a secret key held in process memory.
+pub struct LocalSigner {
+    key: ed25519_dalek::SigningKey,
+}
+
+impl LocalSigner {
+    pub fn new(key: ed25519_dalek::SigningKey) -> Self {
+        Self { key }
+    }
+
+    /// Load the secret from the file at `path`, as hex or an `S...` seed.
+    pub fn from_file(path: &Path) -> anyhow::Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        Ok(Self::new(parse_stellar_secret(&contents)?))
+    }
+}
+
+impl std::fmt::Debug for LocalSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LocalSigner")
+            .field("pubkey", &hex::encode(self.pubkey()))
+            .finish_non_exhaustive()
+    }
+}
+
+impl NodeSigner for LocalSigner {
+    fn pubkey(&self) -> [u8; 32] {
+        self.key.verifying_key().to_bytes()
+    }
+
+    fn sign(&self, message: &[u8]) -> anyhow::Result<[u8; 64]> {
+        Ok(self.key.sign(message).to_bytes())
+    }
+}
+
+/// Longest an external signer command may run before it is killed.
+pub const SIGNER_COMMAND_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How often a running signer command is checked for exit.
+const SIGNER_COMMAND_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Signs by running an external command; the secret never enters the node.
+#[derive(Debug)]
+pub struct CommandSigner {
+    program: String,
+    args: Vec<String>,
+    pubkey: [u8; 32],
+    timeout: Duration,
+}
+
+impl CommandSigner {
+    /// Set up a signer for `command` (a program and its arguments, separated
+    /// by whitespace), asking it for the public key once.
+    pub fn new(command: &str) -> anyhow::Result<Self> {
+        Self::with_timeout(command, SIGNER_COMMAND_TIMEOUT)
+    }
+
+    /// Like [`CommandSigner::new`], killing the command after `timeout`.
+    pub fn with_timeout(command: &str, timeout: Duration) -> anyhow::Result<Self> {
+        let mut parts = command.split_whitespace().map(str::to_string);
+        let program = parts.next().context("empty signer command")?;
+        let mut signer = Self {
+            program,
+            args: parts.collect(),
+            pubkey: [0u8; 32],
+            timeout,
+        };
+        let output = signer.run("pubkey", None)?;
+        signer.pubkey = super::identity::parse_stellar_pubkey(&output)
+            .context("signer command printed an invalid public key")?;
+        Ok(signer)
+    }
+
+    fn run(&self, action: &str, stdin: Option<&str>) -> anyhow::Result<String> {
+        let mut child = Command::new(&self.program)
+            .args(&self.args)
+            .arg(action)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("failed to run signer command {}", self.program))?;
+        let mut input = child
+            .stdin
+            .take()
+            .context("signer command stdin unavailable")?;
+        if let Some(message) = stdin {
+            input.write_all(message.as_bytes())?;
+        }
+        // Closing stdin lets the command see the end of its input
+        drop(input);
+        let deadline = Instant::now() + self.timeout;
+        while child.try_wait()?.is_none() {
+            if Instant::now() >= deadline {
+                // Ignore errors if it exited in the meantime
+                let _ = child.kill();
+                let _ = child.wait();
+                anyhow::bail!(
+                    "signer command {action} did not finish within {:?}",
+                    self.timeout
+                );
+            }
+            std::thread::sleep(SIGNER_COMMAND_POLL_INTERVAL);
+        }
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "signer command {action} exited with {}: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(String::from_utf8(output.stdout)?.trim().to_string())
+    }
+}
+
+impl NodeSigner for CommandSigner {
+    fn pubkey(&self) -> [u8; 32] {
+        self.pubkey
+    }
+
+    fn sign(&self, message: &[u8]) -> anyhow::Result<[u8; 64]> {
+        let output = self.run("sign", Some(&hex::encode(message)))?;
+        parse_signature(&output, &self.pubkey, message)
+    }
+}
+
+/// Decode a hex signature printed by an external signer and check it
+/// against `pubkey`, so a misconfigured signer fails here rather than at
+/// the peer.
+fn parse_signature(output: &str, pubkey: &[u8; 32], message: &[u8]) -> anyhow::Result<[u8; 64]> {
+    let bytes: [u8; 64] = hex::decode(output.trim())?
+        .try_into()
+        .map_err(|bytes: Vec<u8>| {
+            anyhow::anyhow!("expected a 64-byte signature, got {} bytes", bytes.len())
+        })?;
+    let vk = ed25519_dalek::VerifyingKey::from_bytes(pubkey)?;
+    vk.verify(message, &ed25519_dalek::Signature::from_bytes(&bytes))
+        .context("signer command returned a signature that doesn't match its public key")?;
+    Ok(bytes)
+}
+
+/// Parse a Stellar secret given as hex or an `S...` secret seed.
+pub(crate) fn parse_stellar_secret(value: &str) -> anyhow::Result<ed25519_dalek::SigningKey> {
+    use super::validator_set::{decode_strkey, STRKEY_SEED};
+
+    let value = value.trim();
+    let secret: [u8; 32] = if value.starts_with('S') {
+        decode_strkey(value, STRKEY_SEED)?
+    } else {
+        hex::decode(value)?.try_into().map_err(|bytes: Vec<u8>| {
+            anyhow::anyhow!(
+                "expected 32 bytes (64 hex chars), got {} bytes",
+                bytes.len()
+            )
+        })?
+    };
+    Ok(ed25519_dalek::SigningKey::from_bytes(&secret))
+}
+
+/// The in-memory signer from `LEPUS_STELLAR_SECRET_FILE` or
+/// `LEPUS_STELLAR_SECRET`, if either is set and valid.
+fn local_signer_from_env() -> Option<LocalSigner> {
+    if let Ok(path) = std::env::var("LEPUS_STELLAR_SECRET_FILE") {
+        return LocalSigner::from_file(Path::new(path.trim()))
+            .inspect_err(|e| tracing::warn!(error = %e, "Invalid LEPUS_STELLAR_SECRET_FILE"))
+            .ok();
+    }
+    let secret = std::env::var("LEPUS_STELLAR_SECRET").ok()?;
+    parse_stellar_secret(&secret)
+        .inspect_err(|e| tracing::warn!(error = %e, "Invalid LEPUS_STELLAR_SECRET"))
+        .ok()
+        .map(LocalSigner::new)
+}
+
+/// Sign `message` with `signer` on a blocking thread, for async callers.
+pub async fn sign_blocking(
+    signer: &'static dyn NodeSigner,
+    message: Vec<u8>,
+) -> anyhow::Result<[u8; 64]> {
+    tokio::task::spawn_blocking(move || signer.sign(&message)).await?
+}
+
+/// The node's signer, chosen from the environment on first use and kept for
+/// the process lifetime. The ring calls this at startup, since setting up a
+/// command signer runs the command.
+///
+/// Returns `None` when no signer is configured, or the configured one can't
+/// be set up.
+pub fn node_signer() -> Option<&'static dyn NodeSigner> {
+    static SIGNER: OnceLock<Option<Box<dyn NodeSigner>>> = OnceLock::new();
+    SIGNER
+        .get_or_init(|| {
+            if let Ok(command) = std::env::var("LEPUS_SIGNER_COMMAND") {
+                return match CommandSigner::new(&command) {
+                    Ok(signer) => {
+                        tracing::info!(
+                            pubkey = %hex::encode(signer.pubkey()),
+                            "Lepus: using external signer command"
+                        );
+                        Some(Box::new(signer) as Box<dyn NodeSigner>)
+                    }
+                    Err(e) => {
+                        tracing::warn!(error = %e, "Lepus: signer command unusable");
+                        None
+                    }
+                };
+            }
+            local_signer_from_env().map(|signer| Box::new(signer) as Box<dyn NodeSigner>)
+        })
+        .as_deref()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_local_signer_signs_with_its_key() {
+        let signer = LocalSigner::new(ed25519_dalek::SigningKey::from_bytes(&[42u8; 32]));
+        let sig = signer.sign(b"challenge").unwrap();
+        assert!(parse_signature(&hex::encode(sig), &signer.pubkey(), b"challenge").is_ok());
+    }
+
+    #[test]
+    fn test_parse_signature_rejects_mismatch() {
+        let signer = LocalSigner::new(ed25519_dalek::SigningKey::from_bytes(&[42u8; 32]));
+        let other = LocalSigner::new(ed25519_dalek::SigningKey::from_bytes(&[43u8; 32]));
+        let sig = hex::encode(signer.sign(b"challenge").unwrap());
+
+        assert!(parse_signature(&sig, &other.pubkey(), b"challenge").is_err());
+        assert!(parse_signature(&sig, &signer.pubkey(), b"other").is_err());
+        assert!(parse_signature("abcd", &signer.pubkey(), b"challenge").is_err());
+    }
+
+    #[test]
+    fn test_parse_stellar_secret() {
+        let from_hex = parse_stellar_secret(&hex::encode([42u8; 32])).unwrap();
+        let from_seed =
+            parse_stellar_secret("SAVCUKRKFIVCUKRKFIVCUKRKFIVCUKRKFIVCUKRKFIVCUKRKFIVCVLG5\n")
+                .unwrap();
+        assert_eq!(from_hex.to_bytes(), [42u8; 32]);
+        assert_eq!(from_seed.to_bytes(), [42u8; 32]);
+        assert!(parse_stellar_secret("abcd").is_err());
+    }
+
+    /// Write an executable signer script answering for `key`: `pubkey`
+    /// prints its public key, and `sign` prints the signature of `message`
+    /// after checking it was sent that message.
+    #[cfg(unix)]
+    fn write_signer_script(
+        dir: &Path,
+        key: &ed25519_dalek::SigningKey,
+        message: &[u8],
+    ) -> std::path::PathBuf {
+        use std::os::unix::fs::PermissionsExt;
+
+        let script = dir.join("signer.sh");
+        std::fs::write(
+            &script,
+            format!(
+                "#!/bin/sh\n\
+                 case \"$1\" in\n\
+                 pubkey) echo {pubkey} ;;\n\
+                 sign) [ \"$(cat)\" = {message} ] || exit 1; echo {signature} ;;\n\
+                 *) exit 2 ;;\n\
+                 esac\n",
+                pubkey = hex::encode(key.verifying_key().to_bytes()),
+                message = hex::encode(message),
+                signature = hex::encode(key.sign(message).to_bytes()),
+            ),
+        )
+        .unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+        script
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_command_signer_runs_script() {
+        let dir = tempfile::tempdir().unwrap();
+        let key = ed25519_dalek::SigningKey::from_bytes(&[42u8; 32]);
+        let script = write_signer_script(dir.path(), &key, b"challenge");
+
+        let signer = CommandSigner::new(script.to_str().unwrap()).unwrap();
+        assert_eq!(signer.pubkey(), key.verifying_key().to_bytes());
+        assert_eq!(
+            signer.sign(b"challenge").unwrap(),
+            key.sign(b"challenge").to_bytes()
+        );
+        // The script exits non-zero for any other message
+        assert!(signer.sign(b"other").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_command_signer_kills_hung_command() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join("hung.sh");
+        std::fs::write(&script, "#!/bin/sh\nsleep 5\n").unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let started = Instant::now();
+        let err = CommandSigner::with_timeout(script.to_str().unwrap(), Duration::from_millis(200))
+            .unwrap_err();
+        assert!(format!("{err:#}").contains("did not finish"), "{err:#}");
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_local_signer_from_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("stellar.secret");
+        std::fs::write(&path, format!("{}\n", hex::encode([42u8; 32]))).unwrap();
+
+        let signer = LocalSigner::from_file(&path).unwrap();
+        assert_eq!(
+            signer.pubkey(),
+            ed25519_dalek::SigningKey::from_bytes(&[42u8; 32])
+                .verifying_key()
+                .to_bytes()
+        );
+        assert!(LocalSigner::from_file(&dir.path().join("missing")).is_err());
+    }
+}
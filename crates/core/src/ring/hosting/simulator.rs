@@ -0,0 +1,375 @@
+//! Deterministic long-horizon simulation of the hosting cache (Lepus).
+//!
+//! Replays synthetic access and deposit traces through a `HostingCache` over
+//! simulated weeks or months, without a network or wall-clock waits, and
+//! reports how well each class of contracts survived. Running the same
+//! workload under two `CWPConfig`s shows what a weight change would do before
+//! it is deployed.
+//!
+//! Time advances in fixed steps. In each step every contract is accessed a
+//! number of times drawn from its class's rate, a missed access hosts it
+//! again, the oracle re-reports deposits on its own interval, and the cache
+//! runs its expiry sweep. All randomness comes from one seeded RNG, so a seed
+//! always replays the same trace.
+//!
+//! ```ignore
+//! let report = CwpSimulation {
+//!     classes: vec![
+//!         ContractClass::new("funded", 50, 10_000).with_deposit(100),
+//!         ContractClass::new("popular", 50, 10_000).with_accesses_per_day(24.0),
+//!     ],
+//!     ..CwpSimulation::new(CWPConfig::default(), 500_000)
+//! }
+//! .run();
+//! println!("{report}");
+//! ```
+
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::time::Duration;
+
+use freenet_stdlib::prelude::{CodeHash, ContractInstanceId, ContractKey};
+use tokio::time::Instant;
+
+use crate::simulation::SimulationRng;
+use crate::util::time_source::TimeSource;
+
+use super::cache::{AccessType, CWPConfig, HostingCache};
+
+const SECS_PER_DAY: f64 = 86_400.0;
+
+/// A group of contracts sharing a size, access rate and deposit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContractClass {
+    pub name: String,
+    pub contracts: usize,
+    pub size_bytes: u64,
+    /// Mean accesses per contract per simulated day.
+    pub accesses_per_day: f64,
+    /// Deposit the oracle reports for each contract. 0 for unfunded ones.
+    pub deposited_xlm: u64,
+    /// Bytes served to other peers on each access.
+    pub bytes_served_per_access: u64,
+}
+
+impl ContractClass {
+    /// Unfunded contracts accessed once a day that serve nothing.
+    pub fn new(name: impl Into<String>, contracts: usize, size_bytes: u64) -> Self {
+        Self {
+            name: name.into(),
+            contracts,
+            size_bytes,
+            accesses_per_day: 1.0,
+            deposited_xlm: 0,
+            bytes_served_per_access: 0,
+        }
+    }
+
+    pub fn with_accesses_per_day(mut self, accesses_per_day: f64) -> Self {
+        self.accesses_per_day = accesses_per_day;
+        self
+    }
+
+    pub fn with_deposit(mut self, deposited_xlm: u64) -> Self {
+        self.deposited_xlm = deposited_xlm;
+        self
+    }
+
+    pub fn with_bytes_served_per_access(mut self, bytes: u64) -> Self {
+        self.bytes_served_per_access = bytes;
+        self
+    }
+}
+
+/// A workload replayed through a single hosting cache.
+#[derive(Debug, Clone)]
+pub struct CwpSimulation {
+    pub cwp: CWPConfig,
+    pub budget_bytes: u64,
+    pub min_ttl: Duration,
+    /// Simulated time covered by the run.
+    pub duration: Duration,
+    /// Length of one simulation step.
+    pub step: Duration,
+    /// How often the oracle re-reports every hosted contract's deposit.
+    pub oracle_interval: Duration,
+    pub seed: u64,
+    pub classes: Vec<ContractClass>,
+}
+
+impl CwpSimulation {
+    /// 90 days in one-hour steps with daily oracle reports and no classes.
+    pub fn new(cwp: CWPConfig, budget_bytes: u64) -> Self {
+        Self {
+            cwp,
+            budget_bytes,
+            min_ttl: super::DEFAULT_MIN_TTL,
+            duration: Duration::from_secs(90 * 86_400),
+            step: Duration::from_secs(3_600),
+            oracle_interval: Duration::from_secs(86_400),
+            seed: 0,
+            classes: Vec::new(),
+        }
+    }
+
+    /// Run the workload to the end and report per-class survival.
+    pub fn run(&self) -> SimulationReport {
+        let clock = SimClock::new();
+        let mut cache = HostingCache::new_with_cwp(
+            self.budget_bytes,
+            self.min_ttl,
+            clock.clone(),
+            self.cwp.clone(),
+        );
+        let rng = SimulationRng::new(self.seed);
+        let step = self.step.max(Duration::from_secs(1));
+        let steps = (self.duration.as_secs() / step.as_secs()).max(1);
+        let step_days = step.as_secs_f64() / SECS_PER_DAY;
+
+        let contracts: Vec<(usize, ContractKey)> = self
+            .classes
+            .iter()
+            .enumerate()
+            .flat_map(|(class, spec)| (0..spec.contracts).map(move |i| (class, sim_key(class, i))))
+            .collect();
+        let class_of: HashMap<ContractKey, usize> = contracts
+            .iter()
+            .map(|(class, key)| (*key, *class))
+            .collect();
+        let mut tallies: Vec<ClassTally> = vec![ClassTally::default(); self.classes.len()];
+        let mut last_oracle_report = clock.now();
+
+        for _ in 0..steps {
+            clock.advance(step);
+            let now = clock.now();
+            for (class, key) in &contracts {
+                let spec = &self.classes[*class];
+                let tally = &mut tallies[*class];
+                let expected = spec.accesses_per_day * step_days;
+                let mut accesses = expected.trunc() as u64;
+                if rng.gen_bool(expected.fract()) {
+                    accesses += 1;
+                }
+                for _ in 0..accesses {
+                    tally.accesses += 1;
+                    let hosted = cache.contains(key);
+                    if hosted {
+                        tally.hits += 1;
+                    }
+                    cache.record_access(*key, spec.size_bytes, AccessType::Get);
+                    if !hosted && spec.deposited_xlm > 0 {
+                        // Hosted again with the deposit the known-contract tables remember
+                        cache.update_commitment(key, spec.deposited_xlm, now);
+                    }
+                    cache.record_bytes_served(key, spec.bytes_served_per_access);
+                }
+            }
+
+            if now.duration_since(last_oracle_report) >= self.oracle_interval {
+                last_oracle_report = now;
+                for (class, key) in &contracts {
+                    let deposited_xlm = self.classes[*class].deposited_xlm;
+                    if deposited_xlm > 0 {
+                        cache.update_commitment(key, deposited_xlm, now);
+                    }
+                }
+            }
+
+            cache.sweep_expired(|_| false);
+            for notice in cache.take_evictions() {
+                if let Some(class) = class_of.get(&notice.key) {
+                    tallies[*class].evictions += 1;
+                }
+            }
+            for (class, key) in &contracts {
+                if cache.contains(key) {
+                    tallies[*class].hosted_steps += 1;
+                }
+            }
+        }
+
+        let classes = self
+            .classes
+            .iter()
+            .zip(tallies)
+            .enumerate()
+            .map(|(class, (spec, tally))| {
+                let hosted_at_end = contracts
+                    .iter()
+                    .filter(|(c, key)| *c == class && cache.contains(key))
+                    .count();
+                ClassReport {
+                    name: spec.name.clone(),
+                    contracts: spec.contracts,
+                    hosted_at_end,
+                    survival_rate: ratio(hosted_at_end as u64, spec.contracts as u64),
+                    residency: ratio(tally.hosted_steps, spec.contracts as u64 * steps),
+                    hit_rate: ratio(tally.hits, tally.accesses),
+                    evictions: tally.evictions,
+                }
+            })
+            .collect();
+        SimulationReport {
+            simulated: Duration::from_secs(step.as_secs() * steps),
+            classes,
+        }
+    }
+}
+
+/// How one class of contracts fared over a simulation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClassReport {
+    pub name: String,
+    pub contracts: usize,
+    /// Contracts still hosted when the simulation ended.
+    pub hosted_at_end: usize,
+    /// `hosted_at_end / contracts`.
+    pub survival_rate: f64,
+    /// Fraction of contract-steps the class's contracts spent hosted.
+    pub residency: f64,
+    /// Fraction of accesses that found the contract hosted.
+    pub hit_rate: f64,
+    pub evictions: u64,
+}
+
+/// Per-class results of a `CwpSimulation` run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulationReport {
+    pub simulated: Duration,
+    pub classes: Vec<ClassReport>,
+}
+
+impl SimulationReport {
+    pub fn class(&self, name: &str) -> Option<&ClassReport> {
+        self.classes.iter().find(|class| class.name == name)
+    }
+}
+
+impl std::fmt::Display for SimulationReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{:.1} simulated days",
+            self.simulated.as_secs_f64() / SECS_PER_DAY
+        )?;
+        writeln!(
+            f,
+            "{:<16} {:>9} {:>9} {:>9} {:>9} {:>9}",
+            "class", "contracts", "survival", "residency", "hit rate", "evictions"
+        )?;
+        for class in &self.classes {
+            writeln!(
+                f,
+                "{:<16} {:>9} {:>8.1}% {:>8.1}% {:>8.1}% {:>9}",
+                class.name,
+                class.contracts,
+                class.survival_rate * 100.0,
+                class.residency * 100.0,
+                class.hit_rate * 100.0,
+                class.evictions
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct ClassTally {
+    accesses: u64,
+    hits: u64,
+    hosted_steps: u64,
+    evictions: u64,
+}
+
+/// Simulated clock shared between the simulation and its cache.
+#[derive(Clone)]
+struct SimClock {
+    start: Instant,
+    elapsed: Rc<Cell<Duration>>,
+}
+
+impl SimClock {
+    fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            elapsed: Rc::new(Cell::new(Duration::ZERO)),
+        }
+    }
+
+    fn advance(&self, by: Duration) {
+        self.elapsed.set(self.elapsed.get() + by);
+    }
+}
+
+impl TimeSource for SimClock {
+    fn now(&self) -> Instant {
+        self.start + self.elapsed.get()
+    }
+}
+
+fn sim_key(class: usize, index: usize) -> ContractKey {
+    let mut id = [0u8; 32];
+    id[..8].copy_from_slice(&(class as u64).to_le_bytes());
+    id[8..16].copy_from_slice(&(index as u64).to_le_bytes());
+    ContractKey::from_id_and_code(ContractInstanceId::new(id), CodeHash::new([0; 32]))
+}
+
+fn ratio(numerator: u64, denominator: u64) -> f64 {
+    if denominator == 0 {
+        0.0
+    } else {
+        numerator as f64 / denominator as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn simulation(cwp: CWPConfig) -> CwpSimulation {
+        CwpSimulation {
+            duration: Duration::from_secs(30 * 86_400),
+            min_ttl: Duration::from_secs(3_600),
+            seed: 7,
+            classes: vec![
+                ContractClass::new("funded", 20, 1_000)
+                    .with_accesses_per_day(0.5)
+                    .with_deposit(10),
+                ContractClass::new("unfunded", 20, 1_000).with_accesses_per_day(0.5),
+            ],
+            ..CwpSimulation::new(cwp, 25_000)
+        }
+    }
+
+    #[test]
+    fn test_same_seed_replays_same_report() {
+        let sim = simulation(CWPConfig::default());
+        let report = sim.run();
+        assert_eq!(report, sim.run());
+        assert_eq!(report.simulated, Duration::from_secs(30 * 86_400));
+        assert_eq!(report.classes.len(), 2);
+    }
+
+    #[test]
+    fn test_commitment_weight_keeps_funded_class_hosted() {
+        let report = simulation(CWPConfig::default()).run();
+        let funded = report.class("funded").unwrap();
+        let unfunded = report.class("unfunded").unwrap();
+        assert_eq!(funded.survival_rate, 1.0, "{report}");
+        assert!(unfunded.evictions > 0, "{report}");
+        assert!(funded.residency > unfunded.residency, "{report}");
+        assert!(funded.hit_rate > unfunded.hit_rate, "{report}");
+
+        // Without a commitment weight, the deposit buys nothing
+        let cwp = CWPConfig {
+            commitment_weight: 0.0,
+            identity_weight: 0.25,
+            contribution_weight: 0.15,
+            recency_weight: 0.60,
+            ..CWPConfig::default()
+        };
+        let report = simulation(cwp).run();
+        assert!(report.class("funded").unwrap().evictions > 0, "{report}");
+    }
+}
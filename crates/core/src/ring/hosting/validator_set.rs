@@ -0,0 +1,397 @@
+//! Tier-1 validator topology for deposit-index parameters (Lepus).
+//!
+//! The deposit-index checks SCP envelopes against the validator keys baked
+//! into its `DepositIndexParams`, so those keys have to match the network's
+//! Tier-1 organizations when a deposit-index is created. This module builds
+//! that material from a stellarbeat network API or a stellar-core quorum
+//! configuration instead of copying keys by hand, and lists what changed
+//! against parameters already in use.
+//!
+//! Organizations and their validators are sorted so the same topology always
+//! serializes to the same bytes, and so to the same `ContractKey`.
+
+use std::collections::BTreeMap;
+
+use anyhow::Context;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use super::deposit_index::{DepositIndexParams, ValidatorOrg};
+
+/// StrKey version byte of an ed25519 account ID (`G...`).
+pub(super) const STRKEY_ACCOUNT_ID: u8 = 6 << 3;
+/// StrKey version byte of an ed25519 secret seed (`S...`).
+pub(super) const STRKEY_SEED: u8 = 18 << 3;
+/// StrKey version byte of a contract ID (`C...`).
+pub(super) const STRKEY_CONTRACT: u8 = 2 << 3;
+
+/// The parts of a stellarbeat `/v1` network snapshot used here.
+#[derive(Debug, Deserialize)]
+pub struct StellarbeatNetwork {
+    pub organizations: Vec<StellarbeatOrganization>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StellarbeatOrganization {
+    pub name: String,
+    /// Validator public keys as StrKeys.
+    #[serde(default)]
+    pub validators: Vec<String>,
+    #[serde(default, alias = "tierOneOrganization")]
+    pub is_tier_one_organization: bool,
+}
+
+/// Fetch the network snapshot from a stellarbeat API and return its Tier-1
+/// organizations.
+pub async fn fetch_stellarbeat(
+    client: &reqwest::Client,
+    base_url: &str,
+) -> anyhow::Result<Vec<ValidatorOrg>> {
+    let url = format!("{}/v1", base_url.trim_end_matches('/'));
+    let network: StellarbeatNetwork = client
+        .get(&url)
+        .send()
+        .await
+        .and_then(|resp| resp.error_for_status())
+        .with_context(|| format!("fetching {url}"))?
+        .json()
+        .await
+        .with_context(|| format!("parsing {url}"))?;
+    tier_one_from_stellarbeat(&network)
+}
+
+/// Tier-1 organizations of a stellarbeat snapshot.
+pub fn tier_one_from_stellarbeat(
+    network: &StellarbeatNetwork,
+) -> anyhow::Result<Vec<ValidatorOrg>> {
+    let orgs = network
+        .organizations
+        .iter()
+        .filter(|org| org.is_tier_one_organization)
+        .map(|org| validator_org(&org.name, &org.validators))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    sorted_orgs(orgs)
+}
+
+/// Tier-1 organizations of a stellar-core quorum configuration: the home
+/// domains of quality `HIGH` and the validators under them.
+pub fn tier_one_from_quorum_config(config: &str) -> anyhow::Result<Vec<ValidatorOrg>> {
+    #[derive(Deserialize)]
+    struct QuorumConfig {
+        #[serde(rename = "HOME_DOMAINS", default)]
+        home_domains: Vec<HomeDomain>,
+        #[serde(rename = "VALIDATORS", default)]
+        validators: Vec<Validator>,
+    }
+
+    #[derive(Deserialize)]
+    struct HomeDomain {
+        #[serde(rename = "HOME_DOMAIN")]
+        home_domain: String,
+        #[serde(rename = "QUALITY")]
+        quality: String,
+    }
+
+    #[derive(Deserialize)]
+    struct Validator {
+        #[serde(rename = "HOME_DOMAIN")]
+        home_domain: String,
+        #[serde(rename = "PUBLIC_KEY")]
+        public_key: String,
+        #[serde(rename = "QUALITY", default)]
+        quality: Option<String>,
+    }
+
+    let config: QuorumConfig = toml::from_str(config).context("parsing quorum configuration")?;
+    let mut domains: BTreeMap<&str, Vec<String>> = config
+        .home_domains
+        .iter()
+        .filter(|domain| domain.quality.eq_ignore_ascii_case("HIGH"))
+        .map(|domain| (domain.home_domain.as_str(), Vec::new()))
+        .collect();
+    for validator in &config.validators {
+        // A validator's own quality overrides its home domain's
+        let high = match &validator.quality {
+            Some(quality) => quality.eq_ignore_ascii_case("HIGH"),
+            None => domains.contains_key(validator.home_domain.as_str()),
+        };
+        if high {
+            domains
+                .entry(validator.home_domain.as_str())
+                .or_default()
+                .push(validator.public_key.clone());
+        }
+    }
+    let orgs = domains
+        .into_iter()
+        .map(|(domain, validators)| validator_org(domain, &validators))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    sorted_orgs(orgs)
+}
+
+/// Deposit-index parameters for `organizations` on the network with
+/// `network_passphrase`, indexing the Soroban contract `hvym_contract`
+/// (`C...` StrKey). A `quorum_org_threshold` of 0 leaves the contract's
+/// default two-thirds-plus-one.
+pub fn deposit_index_params(
+    network_passphrase: &str,
+    organizations: Vec<ValidatorOrg>,
+    quorum_org_threshold: usize,
+    hvym_contract: &str,
+) -> anyhow::Result<DepositIndexParams> {
+    if quorum_org_threshold > organizations.len() {
+        anyhow::bail!(
+            "quorum threshold {quorum_org_threshold} exceeds the {} organizations",
+            organizations.len()
+        );
+    }
+    let hvym_contract_address = decode_strkey(hvym_contract, STRKEY_CONTRACT)
+        .with_context(|| format!("hvym contract {hvym_contract}"))?;
+    Ok(DepositIndexParams {
+        network_id: hex::encode(Sha256::digest(network_passphrase.as_bytes())),
+        organizations,
+        quorum_org_threshold,
+        hvym_contract_address: hex::encode(hvym_contract_address),
+    })
+}
+
+/// Human-readable differences in validator topology from `old` to `new`,
+/// one line per organization or validator added or removed.
+pub fn topology_changes(old: &DepositIndexParams, new: &DepositIndexParams) -> Vec<String> {
+    let validators = |params: &DepositIndexParams| -> BTreeMap<String, Vec<String>> {
+        params
+            .organizations
+            .iter()
+            .map(|org| (org.name.clone(), org.validators.clone()))
+            .collect()
+    };
+    let (old, new) = (validators(old), validators(new));
+    let mut changes = Vec::new();
+    for (name, validators) in &new {
+        match old.get(name) {
+            None => changes.push(format!(
+                "+ organization {name} ({} validators)",
+                validators.len()
+            )),
+            Some(previous) => {
+                for key in validators.iter().filter(|key| !previous.contains(key)) {
+                    changes.push(format!("+ {name}: validator {key}"));
+                }
+                for key in previous.iter().filter(|key| !validators.contains(key)) {
+                    changes.push(format!("- {name}: validator {key}"));
+                }
+            }
+        }
+    }
+    for (name, validators) in &old {
+        if !new.contains_key(name) {
+            changes.push(format!(
+                "- organization {name} ({} validators)",
+                validators.len()
+            ));
+        }
+    }
+    changes
+}
+
+fn validator_org(name: &str, public_keys: &[String]) -> anyhow::Result<ValidatorOrg> {
+    let mut validators = public_keys
+        .iter()
+        .map(|key| {
+            decode_strkey(key, STRKEY_ACCOUNT_ID)
+                .map(hex::encode)
+                .with_context(|| format!("validator {key} of {name}"))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    validators.sort();
+    validators.dedup();
+    Ok(ValidatorOrg {
+        name: name.to_string(),
+        validators,
+    })
+}
+
+fn sorted_orgs(mut orgs: Vec<ValidatorOrg>) -> anyhow::Result<Vec<ValidatorOrg>> {
+    orgs.retain(|org| !org.validators.is_empty());
+    if orgs.is_empty() {
+        anyhow::bail!("no Tier-1 organizations with validators found");
+    }
+    orgs.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(orgs)
+}
+
+/// Decode a 32-byte StrKey payload, checking its version byte and checksum.
+pub(super) fn decode_strkey(strkey: &str, version: u8) -> anyhow::Result<[u8; 32]> {
+    const ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+    let strkey = strkey.trim();
+    if strkey.len() != 56 {
+        anyhow::bail!("StrKey must be 56 characters");
+    }
+    let mut raw = Vec::with_capacity(35);
+    let (mut buffer, mut bits) = (0u32, 0u32);
+    for c in strkey.bytes() {
+        let value = ALPHABET
+            .iter()
+            .position(|&a| a == c)
+            .ok_or_else(|| anyhow::anyhow!("invalid StrKey character {:?}", c as char))?;
+        buffer = (buffer << 5) | value as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            raw.push((buffer >> bits) as u8);
+        }
+    }
+    let (body, checksum) = raw.split_at(33);
+    if crc16_xmodem(body).to_le_bytes() != checksum {
+        anyhow::bail!("StrKey checksum mismatch");
+    }
+    if body[0] != version {
+        anyhow::bail!("unexpected StrKey type");
+    }
+    let mut payload = [0u8; 32];
+    payload.copy_from_slice(&body[1..]);
+    Ok(payload)
+}
+
+fn crc16_xmodem(data: &[u8]) -> u16 {
+    let mut crc = 0u16;
+    for &byte in data {
+        crc ^= u16::from(byte) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::StellarNetwork;
+
+    const KEY_1: &str = "GAAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQDZ7H";
+    const KEY_2: &str = "GABAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEJXA";
+    const KEY_3: &str = "GABQGAYDAMBQGAYDAMBQGAYDAMBQGAYDAMBQGAYDAMBQGAYDAMBQHGPC";
+    const HVYM_CONTRACT: &str = "CD3KT3NS3GMAQTTNVS5HIMV7Q6ISZNRIFXF7LIOMUOC5JC5VMG4UVOHQ";
+
+    #[test]
+    fn test_decode_strkey() {
+        assert_eq!(decode_strkey(KEY_2, STRKEY_ACCOUNT_ID).unwrap(), [2u8; 32]);
+        assert_eq!(
+            hex::encode(decode_strkey(HVYM_CONTRACT, STRKEY_CONTRACT).unwrap()),
+            "f6a9edb2d998084e6dacba7432bf87912cb6282dcbf5a1cca385d48bb561b94a"
+        );
+        // Wrong type, bad checksum, bad length
+        assert!(decode_strkey(HVYM_CONTRACT, STRKEY_ACCOUNT_ID).is_err());
+        let corrupted = KEY_1.replace("DZ7H", "DZ7G");
+        assert!(decode_strkey(&corrupted, STRKEY_ACCOUNT_ID).is_err());
+        assert!(decode_strkey(&KEY_1[1..], STRKEY_ACCOUNT_ID).is_err());
+    }
+
+    #[test]
+    fn test_tier_one_from_stellarbeat() {
+        let network: StellarbeatNetwork = serde_json::from_value(serde_json::json!({
+            "nodes": [],
+            "organizations": [
+                { "name": "SDF", "validators": [KEY_2, KEY_1], "isTierOneOrganization": true },
+                { "name": "Hobbyist", "validators": [KEY_3] },
+                { "name": "Blockdaemon", "validators": [KEY_3], "tierOneOrganization": true }
+            ]
+        }))
+        .unwrap();
+        let orgs = tier_one_from_stellarbeat(&network).unwrap();
+        let names: Vec<_> = orgs.iter().map(|org| org.name.as_str()).collect();
+        assert_eq!(names, ["Blockdaemon", "SDF"]);
+        assert_eq!(
+            orgs[1].validators,
+            vec![hex::encode([1u8; 32]), hex::encode([2u8; 32])]
+        );
+    }
+
+    #[test]
+    fn test_tier_one_from_quorum_config() {
+        let config = format!(
+            r#"
+            [[HOME_DOMAINS]]
+            HOME_DOMAIN = "stellar.org"
+            QUALITY = "HIGH"
+
+            [[HOME_DOMAINS]]
+            HOME_DOMAIN = "example.com"
+            QUALITY = "MEDIUM"
+
+            [[VALIDATORS]]
+            NAME = "sdf_1"
+            HOME_DOMAIN = "stellar.org"
+            PUBLIC_KEY = "{KEY_1}"
+
+            [[VALIDATORS]]
+            NAME = "example_1"
+            HOME_DOMAIN = "example.com"
+            PUBLIC_KEY = "{KEY_2}"
+
+            [[VALIDATORS]]
+            NAME = "solo"
+            HOME_DOMAIN = "solo.net"
+            PUBLIC_KEY = "{KEY_3}"
+            QUALITY = "HIGH"
+            "#
+        );
+        let orgs = tier_one_from_quorum_config(&config).unwrap();
+        let names: Vec<_> = orgs.iter().map(|org| org.name.as_str()).collect();
+        assert_eq!(names, ["solo.net", "stellar.org"]);
+        assert_eq!(orgs[1].validators, vec![hex::encode([1u8; 32])]);
+
+        assert!(tier_one_from_quorum_config("").is_err());
+    }
+
+    #[test]
+    fn test_deposit_index_params_and_changes() {
+        let orgs = vec![
+            validator_org("SDF", &[KEY_1.to_string()]).unwrap(),
+            validator_org("LOBSTR", &[KEY_2.to_string()]).unwrap(),
+        ];
+        let old = deposit_index_params(
+            StellarNetwork::Mainnet.passphrase(),
+            orgs.clone(),
+            0,
+            HVYM_CONTRACT,
+        )
+        .unwrap();
+        assert_eq!(
+            old.network_id,
+            "7ac33997544e3175d266bd022439b22cdb16508c01163f26e5cb2a3e1045a979"
+        );
+        assert!(
+            deposit_index_params(StellarNetwork::Mainnet.passphrase(), orgs, 3, HVYM_CONTRACT)
+                .is_err()
+        );
+
+        let new = deposit_index_params(
+            StellarNetwork::Mainnet.passphrase(),
+            vec![
+                validator_org("SDF", &[KEY_1.to_string(), KEY_3.to_string()]).unwrap(),
+                validator_org("Blockdaemon", &[KEY_2.to_string()]).unwrap(),
+            ],
+            0,
+            HVYM_CONTRACT,
+        )
+        .unwrap();
+        let changes = topology_changes(&old, &new);
+        assert_eq!(
+            changes,
+            vec![
+                "+ organization Blockdaemon (1 validators)".to_string(),
+                format!("+ SDF: validator {}", hex::encode([3u8; 32])),
+                "- organization LOBSTR (1 validators)".to_string(),
+            ]
+        );
+        assert!(topology_changes(&new, &new).is_empty());
+    }
+}
@@ -36,8 +36,32 @@ mod connection_backoff;
 mod connection_manager;
 pub(crate) use connection_manager::ConnectionManager;
 mod connection;
+pub(crate) mod diagnostics;
 mod hosting;
-pub use hosting::{AccessType, RecordAccessResult};
+#[cfg(all(feature = "lepus", feature = "testing"))]
+pub use hosting::deposit_index::DepositProof;
+#[cfg(feature = "lepus")]
+pub use hosting::deposit_index::{DepositIndexParams, DepositInfo, ValidatorOrg};
+pub(crate) use hosting::metrics::HOSTING_METRICS;
+#[cfg(all(feature = "lepus", feature = "testing"))]
+pub use hosting::oracle::{set_test_proof_source, MockStellarProofSource};
+#[cfg(all(feature = "lepus", feature = "testing"))]
+pub use hosting::proof_fixtures::ProofFixtures;
+#[cfg(feature = "lepus")]
+pub(crate) use hosting::relayer_metrics::RELAYER_METRICS;
+#[cfg(feature = "lepus")]
+pub(crate) use hosting::signer::{node_signer, sign_blocking};
+#[cfg(all(feature = "lepus", feature = "testing"))]
+pub use hosting::simulator::{ClassReport, ContractClass, CwpSimulation, SimulationReport};
+#[cfg(feature = "lepus")]
+pub(crate) use hosting::validator_set;
+pub use hosting::{AccessType, EvictionCandidate, RecordAccessResult};
+#[cfg(feature = "lepus")]
+pub use hosting::{
+    CWPConfig, EvictionLogQuery, EvictionRecord, HostedContractPage, HostedContractSort,
+    OracleStatus, ScoreReport,
+};
+pub(crate) use hosting::{DEFAULT_HOSTING_BUDGET_BYTES, DEFAULT_MIN_TTL};
 pub mod interest;
 mod live_tx;
 mod location;
@@ -79,6 +103,12 @@ pub(crate) struct Ring {
     pub(crate) is_gateway: bool,
     /// Shared connection backoff tracker for all connection failure types.
     connection_backoff: Arc<parking_lot::Mutex<ConnectionBackoff>>,
+    /// Progress of the Soroban commitment oracle worker.
+    #[cfg(feature = "lepus")]
+    oracle_status: Mutex<OracleStatus>,
+    /// Stops the oracle worker with the node.
+    #[cfg(feature = "lepus")]
+    oracle_shutdown: hosting::oracle::OracleShutdown,
 }
 
 // /// A data type that represents the fact that a peer has been blacklisted
@@ -187,19 +217,74 @@ impl Ring {
         const TOPOLOGY_SNAPSHOT_INTERVAL: Duration = Duration::from_secs(1);
         const GET_SUBSCRIPTION_SWEEP_INTERVAL: Duration = Duration::from_secs(60);
 
+        #[cfg(feature = "lepus")]
+        let (oracle_shutdown, oracle_signal) = hosting::oracle::oracle_shutdown();
+
         // Just initialize with a fake location, this will be later updated when the peer has an actual location assigned.
         let ring = Ring {
             max_hops_to_live,
             router,
             connection_manager,
-            hosting_manager: hosting::HostingManager::new(),
+            #[cfg(not(feature = "lepus"))]
+            hosting_manager: hosting::HostingManager::with_limits(
+                config.config.hosting_budget_bytes(),
+                config.config.hosting_min_ttl(),
+            ),
+            #[cfg(feature = "lepus")]
+            hosting_manager: hosting::HostingManager::with_cwp_config(
+                config.config.lepus.cwp.clone(),
+                config.config.hosting_budget_bytes(),
+                config.config.hosting_min_ttl(),
+                config.config.lepus.hosting_shards,
+            ),
             live_tx_tracker: live_tx_tracker.clone(),
             event_register: Box::new(event_register),
             op_manager: RwLock::new(None),
             is_gateway,
             connection_backoff: Arc::new(Mutex::new(ConnectionBackoff::new())),
+            #[cfg(feature = "lepus")]
+            oracle_status: Mutex::new(OracleStatus::default()),
+            #[cfg(feature = "lepus")]
+            oracle_shutdown,
         };
 
+        #[cfg(feature = "lepus")]
+        if config.config.lepus.cold_tier.enabled {
+            #[cfg(feature = "redb")]
+            match hosting::cold_tier::ColdTier::open(
+                config
+                    .config
+                    .db_dir()
+                    .join(hosting::cold_tier::COLD_TIER_DIR),
+                config.config.lepus.cold_tier.budget_bytes,
+            ) {
+                Ok(tier) => ring.hosting_manager.set_cold_tier(tier),
+                Err(e) => tracing::warn!(
+                    error = %e,
+                    "Failed to open hosting cold tier; evicted contracts will not be archived"
+                ),
+            }
+            #[cfg(not(feature = "redb"))]
+            tracing::warn!("The hosting cold tier requires the redb storage backend; ignoring");
+        }
+
+        #[cfg(feature = "lepus")]
+        if config.config.lepus.eviction_log.enabled {
+            match hosting::eviction_log::EvictionLog::open(
+                config
+                    .config
+                    .db_dir()
+                    .join(hosting::eviction_log::EVICTION_LOG_FILE),
+                config.config.lepus.eviction_log.max_records,
+            ) {
+                Ok(log) => ring.hosting_manager.set_eviction_log(log),
+                Err(e) => tracing::warn!(
+                    error = %e,
+                    "Failed to open hosting eviction log; evictions will not be recorded"
+                ),
+            }
+        }
+
         if let Some(loc) = config.location {
             if config.own_addr.is_none() && is_gateway {
                 return Err(anyhow::anyhow!("own_addr is required for gateways"));
@@ -208,6 +293,7 @@ impl Ring {
         }
 
         let ring = Arc::new(ring);
+        diagnostics::register(&ring);
         let current_span = tracing::Span::current();
         let span = if current_span.is_none() {
             tracing::info_span!("connection_maintenance")
@@ -251,7 +337,30 @@ impl Ring {
 
         // Spawn the Soroban commitment oracle worker (Lepus)
         #[cfg(feature = "lepus")]
-        GlobalExecutor::spawn(hosting::oracle::OracleWorker::run(ring.clone()));
+        {
+            let oracle_config = hosting::oracle::OracleConfig::new(&config.config.lepus.oracle);
+            hosting::deposit_index::configure(
+                oracle_config.deposit_index_key.as_deref(),
+                oracle_config.deposit_index_code_hash.as_deref(),
+            );
+            hosting::revocation::configure(
+                std::env::var("LEPUS_REVOCATION_KEY")
+                    .ok()
+                    .or_else(|| config.config.lepus.revocation_key.clone())
+                    .as_deref(),
+            );
+            // Set up the signer now rather than on the first subscribe, as
+            // an external signer runs its command to report the public key
+            if node_signer().is_none() {
+                tracing::debug!("Lepus: no node signer configured");
+            }
+            GlobalExecutor::spawn(hosting::oracle::OracleWorker::run(
+                ring.clone(),
+                config.config.db_dir(),
+                oracle_config,
+                oracle_signal,
+            ));
+        }
 
         Ok(ring)
     }
@@ -414,6 +523,12 @@ impl Ring {
             // (first N in iteration order) would always be tried first, blocking later
             // contracts from ever being attempted when they hit the batch limit.
             GlobalRng::shuffle(&mut contracts_needing_renewal);
+            // Lepus: with renewals capped per interval, renew the most valuable
+            // contracts first. Backed-off contracts are skipped without using up
+            // the cap, and the shuffle still rotates contracts with equal scores.
+            #[cfg(feature = "lepus")]
+            ring.hosting_manager
+                .sort_by_persistence_score(&mut contracts_needing_renewal);
 
             // Get op_manager to spawn subscription requests
             let Some(op_manager) = ring.upgrade_op_manager() else {
@@ -493,9 +608,9 @@ impl Ring {
 
     /// Background task to sweep expired entries from the GET subscription cache.
     ///
-    /// When contracts are evicted (past max entries and beyond TTL), this task
-    /// cleans up the local subscription state. The upstream peer will eventually
-    /// prune us when updates fail to deliver.
+    /// When contracts are evicted (past max entries and beyond TTL), the
+    /// hosting manager drops their local subscription state and stored state.
+    /// The upstream peer will eventually prune us when updates fail to deliver.
     async fn sweep_get_subscription_cache(ring: Arc<Self>, interval_duration: Duration) {
         // Add random initial delay to prevent synchronized sweeps across peers
         let initial_delay = Duration::from_secs(GlobalRng::random_range(10u64..=30u64));
@@ -519,11 +634,10 @@ impl Ring {
                 "GET subscription cache sweep found expired entries"
             );
 
-            // Clean up local subscription state for each expired contract.
+            // The sweep already released each expired contract.
             // Note: contracts with client subscriptions are protected from eviction
             // by the should_retain predicate in sweep_expired_hosting().
             for key in expired {
-                ring.unsubscribe(&key);
                 tracing::info!(
                     %key,
                     "Cleaned up expired hosting subscription from local state"
@@ -611,16 +725,168 @@ impl Ring {
         size_bytes: u64,
         access_type: AccessType,
     ) -> RecordAccessResult {
-        self.hosting_manager
-            .record_contract_access(key, size_bytes, access_type)
+        let result = self
+            .hosting_manager
+            .record_contract_access(key, size_bytes, access_type);
+        self.notify_client_evictions();
+        #[cfg(feature = "lepus")]
+        self.report_committed_evictions();
+        #[cfg(feature = "lepus")]
+        self.replicate_evicted();
+        result
+    }
+
+    /// Record an access to a contract in the hosting cache, with the state
+    /// it was accessed with.
+    ///
+    /// Like `host_contract`, but with Lepus re-sending a state already seen
+    /// within the state refresh cooldown doesn't refresh the contract.
+    pub fn host_contract_state(
+        &self,
+        key: ContractKey,
+        state: &[u8],
+        access_type: AccessType,
+    ) -> RecordAccessResult {
+        #[cfg(feature = "lepus")]
+        {
+            let result = self
+                .hosting_manager
+                .record_state_access(key, state, access_type);
+            self.notify_client_evictions();
+            self.report_committed_evictions();
+            self.replicate_evicted();
+            result
+        }
+        #[cfg(not(feature = "lepus"))]
+        self.host_contract(key, state.len() as u64, access_type)
+    }
+
+    /// Re-host a contract from the cold tier if it was archived on eviction.
+    ///
+    /// Called by GET before the local state lookup, so an archived contract
+    /// is served as if it had never left the hosting cache.
+    #[cfg(all(feature = "lepus", feature = "redb"))]
+    pub fn rehydrate_from_cold_tier(
+        &self,
+        instance_id: &ContractInstanceId,
+    ) -> Option<ContractKey> {
+        let key = self.hosting_manager.rehydrate_from_cold_tier(instance_id);
+        if key.is_some() {
+            self.notify_client_evictions();
+            self.report_committed_evictions();
+            self.replicate_evicted();
+        }
+        key
     }
 
-    /// Record a GET access to a contract in the hosting cache.
+    /// Tell local clients subscribed to evicted contracts that we stopped
+    /// hosting them, so applications can re-PUT or escalate funding instead
+    /// of finding out on a later GET miss.
     ///
-    /// Returns a `RecordAccessResult` indicating whether this was a new addition
-    /// and which contracts were evicted (if any).
-    pub fn record_get_access(&self, key: ContractKey, size_bytes: u64) -> RecordAccessResult {
-        self.host_contract(key, size_bytes, AccessType::Get)
+    /// Delivered through the subscription error channel, with the eviction
+    /// reason and CWP score in the message.
+    fn notify_client_evictions(&self) {
+        let evictions = self.hosting_manager.take_client_evictions();
+        if evictions.is_empty() {
+            return;
+        }
+        let Some(op_manager) = self.upgrade_op_manager() else {
+            return;
+        };
+        GlobalExecutor::spawn(async move {
+            for eviction in evictions {
+                let instance_id = *eviction.key.id();
+                if let Err(e) = op_manager
+                    .notify_contract_handler(
+                        crate::contract::ContractHandlerEvent::NotifySubscriptionError {
+                            key: instance_id,
+                            reason: eviction.to_string(),
+                        },
+                    )
+                    .await
+                {
+                    tracing::debug!(
+                        contract = %instance_id,
+                        error = %e,
+                        "Failed to notify clients of hosting eviction"
+                    );
+                }
+            }
+        });
+    }
+
+    /// Report evictions of contracts with a deposit through the event
+    /// register, so telemetry can alert on funded datapods being dropped.
+    #[cfg(feature = "lepus")]
+    fn report_committed_evictions(&self) {
+        let events: Vec<_> = self
+            .hosting_manager
+            .take_committed_evictions()
+            .into_iter()
+            .filter_map(|eviction| {
+                NetEventLog::hosting(
+                    self,
+                    crate::tracing::HostingEvent::CommittedContractEvicted {
+                        key: eviction.key,
+                        reason: eviction.reason.as_str().to_string(),
+                        deposited_xlm: eviction.commitment.deposited_xlm,
+                        score: eviction.breakdown.score,
+                        size_bytes: eviction.size_bytes,
+                        used_bytes: eviction.used_bytes,
+                        budget_bytes: eviction.budget_bytes,
+                        timestamp: chrono::Utc::now().timestamp_millis() as u64,
+                    },
+                )
+            })
+            .collect();
+        if events.is_empty() {
+            return;
+        }
+        let event_register = self.event_register.trait_clone();
+        GlobalExecutor::spawn(async move {
+            event_register.register_events(Either::Right(events)).await;
+        });
+    }
+
+    /// Push contracts evicted with a high CWP score towards the peers
+    /// closest to their location before dropping their stored state, so
+    /// eviction doesn't remove the last nearby replica of a committed
+    /// contract. Contracts are pushed one after the other, each with up to
+    /// `replication_hint_attempts` PUTs.
+    #[cfg(feature = "lepus")]
+    fn replicate_evicted(&self) {
+        let hints = self.hosting_manager.take_replication_hints();
+        if hints.is_empty() {
+            return;
+        }
+        let Some(op_manager) = self.upgrade_op_manager() else {
+            for key in &hints {
+                self.hosting_manager.finish_replication(key);
+            }
+            return;
+        };
+        let attempts = self.hosting_manager.cwp_config().replication_hint_attempts;
+        GlobalExecutor::spawn(async move {
+            for key in hints {
+                let replicated =
+                    crate::operations::put::replicate_before_eviction(&op_manager, key, attempts)
+                        .await;
+                if !replicated {
+                    tracing::info!(
+                        contract = %key,
+                        "Could not replicate evicted contract, dropping it anyway"
+                    );
+                }
+                op_manager.ring.hosting_manager.finish_replication(&key);
+            }
+        });
+    }
+
+    /// Whether an evicted contract is being pushed to the closest peers, in
+    /// which case a PUT originated here must not host it again.
+    #[cfg(feature = "lepus")]
+    pub fn is_replicating(&self, key: &ContractKey) -> bool {
+        self.hosting_manager.is_replicating(key)
     }
 
     /// Whether this node is hosting this contract (has it in cache).
@@ -839,14 +1105,6 @@ impl Ring {
         self.hosting_manager.subscribe(contract)
     }
 
-    /// Unsubscribe from a contract.
-    ///
-    /// Removes the active subscription. The contract may still be hosted
-    /// (in the hosting cache) until evicted by LRU.
-    pub fn unsubscribe(&self, contract: &ContractKey) {
-        self.hosting_manager.unsubscribe(contract)
-    }
-
     /// Check if we have an active (non-expired) subscription to a contract.
     pub fn is_subscribed(&self, contract: &ContractKey) -> bool {
         self.hosting_manager.is_subscribed(contract)
@@ -919,6 +1177,70 @@ impl Ring {
         self.hosting_manager.hosting_contracts_count()
     }
 
+    /// Contracts that would be evicted if `size_needed` more bytes were
+    /// hosted now, lowest priority first, without evicting anything.
+    pub fn simulate_eviction(&self, size_needed: u64) -> Vec<EvictionCandidate> {
+        self.hosting_manager.simulate_eviction(size_needed)
+    }
+
+    /// Sub-scores and inputs behind a hosted contract's persistence score,
+    /// or `None` if it isn't hosted.
+    #[cfg(feature = "lepus")]
+    pub fn score_report(&self, instance_id: &ContractInstanceId) -> Option<ScoreReport> {
+        self.hosting_manager.score_report(instance_id)
+    }
+
+    /// Score reports for a page of hosted contracts in `sort` order.
+    #[cfg(feature = "lepus")]
+    pub fn list_hosted_contracts(
+        &self,
+        sort: HostedContractSort,
+        descending: bool,
+        offset: usize,
+        limit: usize,
+    ) -> HostedContractPage {
+        self.hosting_manager
+            .list_hosted_contracts(sort, descending, offset, limit)
+    }
+
+    /// The CWP configuration currently scoring evictions.
+    #[cfg(feature = "lepus")]
+    pub fn cwp_config(&self) -> CWPConfig {
+        self.hosting_manager.cwp_config()
+    }
+
+    /// Swap in a new CWP configuration without restarting the node,
+    /// returning the previous one.
+    #[cfg(feature = "lepus")]
+    pub fn reload_cwp_config(&self, cwp_config: CWPConfig) -> anyhow::Result<CWPConfig> {
+        self.hosting_manager.reload_cwp_config(cwp_config)
+    }
+
+    /// Progress of the Soroban commitment oracle worker.
+    #[cfg(feature = "lepus")]
+    pub fn oracle_status(&self) -> OracleStatus {
+        self.oracle_status.lock().clone()
+    }
+
+    /// Stop the oracle worker, waiting for the relayer to save its progress.
+    #[cfg(feature = "lepus")]
+    pub(crate) async fn shutdown_oracle(&self) {
+        self.oracle_shutdown.shut_down().await;
+    }
+
+    /// A contract's deposit in the deposit-index state this node last
+    /// synced.
+    #[cfg(feature = "lepus")]
+    pub fn deposit_info(&self, instance_id: &ContractInstanceId) -> DepositInfo {
+        self.hosting_manager.deposit_info(instance_id)
+    }
+
+    /// Logged evictions matching `query`, oldest first.
+    #[cfg(feature = "lepus")]
+    pub fn query_eviction_log(&self, query: &EvictionLogQuery) -> Vec<EvictionRecord> {
+        self.hosting_manager.query_eviction_log(query)
+    }
+
     /// Alias for backwards compatibility - use hosting_contracts_count instead.
     pub fn seeding_contracts_count(&self) -> usize {
         self.hosting_contracts_count()
@@ -954,10 +1276,21 @@ impl Ring {
 
     // ==================== CWP Contribution Tracking (Lepus) ====================
 
-    /// Record bytes served to other peers for a contract.
+    /// Record bytes served to other peers for a contract, attributed to
+    /// `peer` when the recipient is known.
     #[cfg(feature = "lepus")]
-    pub fn record_bytes_served(&self, key: &ContractKey, bytes: u64) {
-        self.hosting_manager.record_bytes_served(key, bytes);
+    pub fn record_bytes_served(&self, key: &ContractKey, peer: Option<&PeerKey>, bytes: u64) {
+        self.hosting_manager.record_bytes_served(key, peer, bytes);
+    }
+
+    /// Record bytes of a contract served to the peer connected at `addr`,
+    /// attributed to that peer when it is a known connection.
+    #[cfg(feature = "lepus")]
+    pub fn record_bytes_served_to(&self, key: &ContractKey, addr: Option<SocketAddr>, bytes: u64) {
+        let peer = addr
+            .and_then(|addr| self.connection_manager.get_peer_by_addr(addr))
+            .map(|pkl| PeerKey::from(pkl.pub_key.clone()));
+        self.record_bytes_served(key, peer.as_ref(), bytes);
     }
 
     /// Record bytes consumed (received) from other peers for a contract.
@@ -966,6 +1299,12 @@ impl Ring {
         self.hosting_manager.record_bytes_consumed(key, bytes);
     }
 
+    /// Record a PUT or UPDATE of a contract that failed validation.
+    #[cfg(feature = "lepus")]
+    pub fn record_validation_failure(&self, key: &ContractKey) {
+        self.hosting_manager.record_validation_failure(key);
+    }
+
     /// Get all hosted contract keys.
     #[cfg(feature = "lepus")]
     pub fn hosted_contract_keys(&self) -> Vec<ContractKey> {
@@ -992,11 +1331,14 @@ impl Ring {
 
     /// Check if a contract update is the deposit-index contract and feed
     /// deposit data into CWP commitment scores for hosted contracts.
+    ///
+    /// The full deposit map is cached so identities funding contracts hosted
+    /// elsewhere still count as funded.
     #[cfg(feature = "lepus")]
     pub fn check_deposit_index_update(&self, key: &ContractKey, state_bytes: &[u8]) {
         let hosted_keys = self.hosted_contract_keys();
         let now = tokio::time::Instant::now();
-        hosting::deposit_index::check_deposit_index_update(
+        let deposits = hosting::deposit_index::check_deposit_index_update(
             key,
             state_bytes,
             &hosted_keys,
@@ -1004,6 +1346,9 @@ impl Ring {
                 self.update_commitments_batch(updates, now);
             },
         );
+        if let Some(deposits) = deposits {
+            self.hosting_manager.set_deposit_snapshot(deposits);
+        }
     }
 
     /// Update subscriber identity from subscription handshake.
@@ -1044,7 +1389,13 @@ impl Ring {
     /// Returns contracts evicted from this cache. Contracts with client
     /// subscriptions are protected from eviction.
     pub fn sweep_expired_hosting(&self) -> Vec<ContractKey> {
-        self.hosting_manager.sweep_expired_hosting()
+        let expired = self.hosting_manager.sweep_expired_hosting();
+        self.notify_client_evictions();
+        #[cfg(feature = "lepus")]
+        self.report_committed_evictions();
+        #[cfg(feature = "lepus")]
+        self.replicate_evicted();
+        expired
     }
 
     // ==================== Legacy GET Auto-Subscription (delegating to hosting cache) ====================
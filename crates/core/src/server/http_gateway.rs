@@ -20,6 +20,7 @@ use crate::server::HostCallbackResult;
 
 use super::{errors::WebSocketApiError, path_handlers, ApiVersion, AuthToken, ClientConnection};
 
+mod admin;
 mod v1;
 mod v2;
 
@@ -76,6 +77,7 @@ impl HttpGateway {
     /// Returns the uninitialized axum router with a provided attested_contracts map.
     ///
     /// Merges V1 and V2 HTTP routes; both currently share the same handler logic.
+    /// Admin diagnostic routes are added only when bound to a loopback address.
     pub fn as_router_with_attested_contracts(
         socket: &SocketAddr,
         attested_contracts: AttestedContractMap,
@@ -92,8 +94,14 @@ impl HttpGateway {
 
         let config = Config { localhost };
 
-        let router = v1::routes(config.clone())
-            .merge(v2::routes(config))
+        let mut router = v1::routes(config.clone()).merge(v2::routes(config));
+        // An unspecified bind (0.0.0.0/::) also counts as `localhost` above but
+        // listens on every interface
+        if socket.ip().is_loopback() {
+            // Admin diagnostics expose node internals; only serve them locally
+            router = router.merge(admin::routes());
+        }
+        let router = router
             .layer(Extension(attested_contracts.clone()))
             .layer(Extension(HttpGatewayRequest(proxy_request_sender)));
 
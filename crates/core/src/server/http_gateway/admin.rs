@@ -0,0 +1,228 @@
+use axum::{
+    extract::Query,
+    http::{HeaderMap, StatusCode},
+    routing::get,
+    Extension, Json, Router,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::ring::diagnostics;
+
+/// Registers node admin diagnostic routes.
+pub(super) fn routes() -> Router {
+    Router::new()
+        .route("/v1/node/hosting/eviction-preview", get(eviction_preview))
+        .merge(lepus_routes())
+}
+
+#[cfg(feature = "lepus")]
+fn lepus_routes() -> Router {
+    Router::new()
+        .route("/v1/node/hosting/evictions", get(eviction_log))
+        .route("/v1/node/hosting/score-report", get(score_report))
+        .route("/v1/node/hosting/contracts", get(hosted_contracts))
+        .route(
+            "/v1/node/hosting/cwp-config",
+            get(get_cwp_config).put(put_cwp_config),
+        )
+        .route("/v1/node/oracle/status", get(oracle_status))
+}
+
+#[cfg(not(feature = "lepus"))]
+fn lepus_routes() -> Router {
+    Router::new()
+}
+
+#[derive(Debug, Deserialize)]
+struct EvictionPreviewQuery {
+    /// Bytes of a hypothetical new contract; also how far to shrink the
+    /// budget when asking what a smaller budget would cost.
+    #[serde(default)]
+    size_needed: u64,
+}
+
+#[derive(Debug, Serialize)]
+struct EvictionPreview {
+    size_needed: u64,
+    freed_bytes: u64,
+    victims: Vec<EvictionPreviewEntry>,
+}
+
+#[derive(Debug, Serialize)]
+struct EvictionPreviewEntry {
+    key: String,
+    size_bytes: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    score: Option<f64>,
+}
+
+/// What the hosting cache would evict to make room for `size_needed` bytes,
+/// in eviction order, without evicting anything. Names hosted contracts, so
+/// it requires a bearer token this gateway issued.
+async fn eviction_preview(
+    Extension(attested_contracts): Extension<super::AttestedContractMap>,
+    headers: HeaderMap,
+    Query(query): Query<EvictionPreviewQuery>,
+) -> Result<Json<EvictionPreview>, StatusCode> {
+    authorize(&headers, &attested_contracts)?;
+    let ring = diagnostics::local_ring().ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+    let victims: Vec<_> = ring
+        .simulate_eviction(query.size_needed)
+        .into_iter()
+        .map(|candidate| EvictionPreviewEntry {
+            key: candidate.key.to_string(),
+            size_bytes: candidate.size_bytes,
+            score: candidate.score,
+        })
+        .collect();
+    Ok(Json(EvictionPreview {
+        size_needed: query.size_needed,
+        freed_bytes: victims.iter().map(|victim| victim.size_bytes).sum(),
+        victims,
+    }))
+}
+
+#[cfg(feature = "lepus")]
+#[derive(Debug, Deserialize)]
+struct EvictionLogParams {
+    key: Option<String>,
+    /// Milliseconds since the Unix epoch.
+    since_ms: Option<u64>,
+    limit: Option<usize>,
+}
+
+/// Logged evictions, oldest first, optionally for one contract or since a
+/// point in time. Requires a bearer token this gateway issued.
+#[cfg(feature = "lepus")]
+async fn eviction_log(
+    Extension(attested_contracts): Extension<super::AttestedContractMap>,
+    headers: HeaderMap,
+    Query(params): Query<EvictionLogParams>,
+) -> Result<Json<Vec<crate::ring::EvictionRecord>>, StatusCode> {
+    authorize(&headers, &attested_contracts)?;
+    let ring = diagnostics::local_ring().ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+    let query = crate::ring::EvictionLogQuery {
+        key: params.key,
+        since_ms: params.since_ms,
+        limit: params.limit,
+    };
+    // Reads the log file, so keep it off the async workers
+    let records = tokio::task::spawn_blocking(move || ring.query_eviction_log(&query))
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok(Json(records))
+}
+
+#[cfg(feature = "lepus")]
+#[derive(Debug, Deserialize)]
+struct ScoreReportParams {
+    key: String,
+}
+
+/// Sub-scores and inputs behind a hosted contract's CWP persistence score.
+/// Requires a bearer token this gateway issued.
+#[cfg(feature = "lepus")]
+async fn score_report(
+    Extension(attested_contracts): Extension<super::AttestedContractMap>,
+    headers: HeaderMap,
+    Query(params): Query<ScoreReportParams>,
+) -> Result<Json<crate::ring::ScoreReport>, StatusCode> {
+    authorize(&headers, &attested_contracts)?;
+    let instance_id = freenet_stdlib::prelude::ContractInstanceId::from_bytes(&params.key)
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    let ring = diagnostics::local_ring().ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+    ring.score_report(&instance_id)
+        .map(Json)
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+/// Largest page `hosted_contracts` returns.
+#[cfg(feature = "lepus")]
+const MAX_HOSTED_CONTRACTS_PAGE: usize = 1000;
+
+#[cfg(feature = "lepus")]
+#[derive(Debug, Deserialize)]
+struct HostedContractsParams {
+    #[serde(default)]
+    sort: crate::ring::HostedContractSort,
+    #[serde(default)]
+    desc: bool,
+    #[serde(default)]
+    offset: usize,
+    limit: Option<usize>,
+}
+
+/// Hosted contracts with their score reports, a page at a time. Lists what
+/// the node holds, so it also requires a bearer token this gateway issued.
+#[cfg(feature = "lepus")]
+async fn hosted_contracts(
+    Extension(attested_contracts): Extension<super::AttestedContractMap>,
+    headers: HeaderMap,
+    Query(params): Query<HostedContractsParams>,
+) -> Result<Json<crate::ring::HostedContractPage>, StatusCode> {
+    authorize(&headers, &attested_contracts)?;
+    let ring = diagnostics::local_ring().ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+    let limit = params
+        .limit
+        .unwrap_or(MAX_HOSTED_CONTRACTS_PAGE)
+        .min(MAX_HOSTED_CONTRACTS_PAGE);
+    Ok(Json(ring.list_hosted_contracts(
+        params.sort,
+        params.desc,
+        params.offset,
+        limit,
+    )))
+}
+
+/// Accept only requests carrying a bearer token issued to a local client.
+fn authorize(
+    headers: &HeaderMap,
+    attested_contracts: &super::AttestedContractMap,
+) -> Result<(), StatusCode> {
+    use headers::{authorization::Bearer, Authorization, HeaderMapExt};
+
+    let bearer = headers
+        .typed_get::<Authorization<Bearer>>()
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+    let token = crate::client_events::AuthToken::from(bearer.token().to_owned());
+    if attested_contracts.contains_key(&token) {
+        Ok(())
+    } else {
+        Err(StatusCode::UNAUTHORIZED)
+    }
+}
+
+/// The CWP configuration currently scoring evictions.
+#[cfg(feature = "lepus")]
+async fn get_cwp_config() -> Result<Json<crate::ring::CWPConfig>, StatusCode> {
+    let ring = diagnostics::local_ring().ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+    Ok(Json(ring.cwp_config()))
+}
+
+/// Replace the CWP configuration without restarting the node. Omitted
+/// fields take their defaults, as in `[lepus.cwp]`. The change is not
+/// written back to the configuration file. Rewrites how every shard
+/// evicts, so it requires a bearer token this gateway issued.
+#[cfg(feature = "lepus")]
+async fn put_cwp_config(
+    Extension(attested_contracts): Extension<super::AttestedContractMap>,
+    headers: HeaderMap,
+    Json(cwp_config): Json<crate::ring::CWPConfig>,
+) -> Result<Json<crate::ring::CWPConfig>, (StatusCode, String)> {
+    authorize(&headers, &attested_contracts)
+        .map_err(|status| (status, "missing or unknown bearer token".to_string()))?;
+    let ring = diagnostics::local_ring().ok_or((
+        StatusCode::SERVICE_UNAVAILABLE,
+        "node not running".to_string(),
+    ))?;
+    ring.reload_cwp_config(cwp_config)
+        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+    Ok(Json(ring.cwp_config()))
+}
+
+/// Mode, progress and last error of the Soroban commitment oracle.
+#[cfg(feature = "lepus")]
+async fn oracle_status() -> Result<Json<crate::ring::OracleStatus>, StatusCode> {
+    let ring = diagnostics::local_ring().ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+    Ok(Json(ring.oracle_status()))
+}
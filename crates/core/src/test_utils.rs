@@ -405,8 +405,25 @@ pub async fn make_node_diagnostics(
 }
 
 pub fn load_contract(name: &str, params: Parameters<'static>) -> anyhow::Result<ContractContainer> {
+    const CRATE_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../../tests/");
+    load_contract_at(&PathBuf::from(CRATE_DIR).join(name), params)
+}
+
+/// Compile the Lepus deposit-index contract under `contracts/`.
+#[cfg(feature = "lepus")]
+pub fn load_deposit_index_contract(
+    params: Parameters<'static>,
+) -> anyhow::Result<ContractContainer> {
+    const CONTRACTS_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../../contracts/");
+    load_contract_at(&PathBuf::from(CONTRACTS_DIR).join("deposit-index"), params)
+}
+
+fn load_contract_at(
+    contract_path: &Path,
+    params: Parameters<'static>,
+) -> anyhow::Result<ContractContainer> {
     let contract_bytes = WrappedContract::new(
-        Arc::new(ContractCode::from(compile_contract(name)?)),
+        Arc::new(ContractCode::from(compile_contract(contract_path)?)),
         params,
     );
     let contract = ContractContainer::Wasm(ContractWasmAPIVersion::V1(contract_bytes));
@@ -422,12 +439,11 @@ pub fn load_delegate(name: &str, params: Parameters<'static>) -> anyhow::Result<
 }
 
 // TODO: refactor so we share the implementation with fdev (need to extract to )
-fn compile_contract(name: &str) -> anyhow::Result<Vec<u8>> {
-    let contract_path = {
-        const CRATE_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../../tests/");
-        let contracts = PathBuf::from(CRATE_DIR);
-        contracts.join(name)
-    };
+fn compile_contract(contract_path: &Path) -> anyhow::Result<Vec<u8>> {
+    let name = contract_path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| anyhow::anyhow!("invalid contract path: {contract_path:?}"))?;
 
     info!("module path: {contract_path:?}");
     let target = get_workspace_target_dir();
@@ -442,7 +458,7 @@ fn compile_contract(name: &str) -> anyhow::Result<Vec<u8>> {
             package_type: PackageType::Contract,
             debug: false,
         },
-        &contract_path,
+        contract_path,
     )?;
 
     let output_file = target
@@ -840,7 +856,9 @@ mod test {
 
     #[test]
     fn test_compile_contract() -> testresult::TestResult {
-        let contract = compile_contract("test-contract-integration")?;
+        const CRATE_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../../tests/");
+        let contract =
+            compile_contract(&PathBuf::from(CRATE_DIR).join("test-contract-integration"))?;
         assert!(!contract.is_empty());
         Ok(())
     }
@@ -1564,6 +1582,7 @@ impl TestContext {
                     crate::tracing::EventKind::Ignored => ("⏭️", "Ignored"),
                     crate::tracing::EventKind::TransportSnapshot(..) => ("📊", "TransportSnapshot"),
                     crate::tracing::EventKind::InterestSync(..) => ("🔃", "InterestSync"),
+                    crate::tracing::EventKind::Hosting(..) => ("🗄️", "Hosting"),
                 };
 
                 writeln!(
@@ -1609,6 +1628,7 @@ impl TestContext {
                 crate::tracing::EventKind::Ignored => ("⏭️", "Ignored"),
                 crate::tracing::EventKind::TransportSnapshot(..) => ("📊", "TransportSnapshot"),
                 crate::tracing::EventKind::InterestSync(..) => ("🔃", "InterestSync"),
+                crate::tracing::EventKind::Hosting(..) => ("🗄️", "Hosting"),
             };
 
             writeln!(
@@ -1674,6 +1694,7 @@ impl TestContext {
                             crate::tracing::EventKind::Timeout { .. } => "Timeout",
                             crate::tracing::EventKind::TransportSnapshot(..) => "TransportSnapshot",
                             crate::tracing::EventKind::InterestSync(..) => "InterestSync",
+                            crate::tracing::EventKind::Hosting(..) => "Hosting",
                             crate::tracing::EventKind::Ignored => "Ignored",
                         };
                         *by_type.entry(type_name.to_string()).or_default() += 1;
@@ -1733,6 +1754,7 @@ impl TestContext {
                                 ("📈", "TransportSnapshot")
                             }
                             crate::tracing::EventKind::InterestSync(..) => ("🔃", "InterestSync"),
+                            crate::tracing::EventKind::Hosting(..) => ("🗄️", "Hosting"),
                             crate::tracing::EventKind::Ignored => ("⏭️", "Ignored"),
                         };
 
@@ -1329,6 +1329,17 @@ impl<'a> NetEventLog<'a> {
         })
     }
 
+    /// Create a hosting cache event.
+    #[cfg_attr(not(feature = "lepus"), allow(dead_code))] // Only Lepus reports hosting events
+    pub fn hosting(ring: &Ring, event: HostingEvent) -> Option<Self> {
+        let peer_id = Self::get_own_peer_id(ring)?;
+        Some(NetEventLog {
+            tx: Transaction::NULL,
+            peer_id,
+            kind: EventKind::Hosting(event),
+        })
+    }
+
     /// Create a ResyncRequestReceived event.
     ///
     /// This is emitted when we receive a ResyncRequest from a peer, indicating
@@ -2323,6 +2334,8 @@ pub enum EventKind {
     /// Tracks ResyncRequests and ResyncResponses which indicate delta application
     /// failures. Useful for monitoring the health of the delta sync protocol.
     InterestSync(InterestSyncEvent),
+    /// Hosting cache events worth alerting on.
+    Hosting(HostingEvent),
 }
 
 impl EventKind {
@@ -2339,6 +2352,7 @@ impl EventKind {
     const LIFECYCLE: u8 = 10;
     const TRANSPORT_SNAPSHOT: u8 = 11;
     const INTEREST_SYNC: u8 = 12;
+    const HOSTING: u8 = 13;
 
     const fn varint_id(&self) -> u8 {
         match self {
@@ -2355,6 +2369,7 @@ impl EventKind {
             EventKind::Lifecycle(_) => Self::LIFECYCLE,
             EventKind::TransportSnapshot(_) => Self::TRANSPORT_SNAPSHOT,
             EventKind::InterestSync(_) => Self::INTEREST_SYNC,
+            EventKind::Hosting(_) => Self::HOSTING,
         }
     }
 
@@ -2421,6 +2436,7 @@ impl EventKind {
             EventKind::Timeout { .. } => "Timeout",
             EventKind::TransportSnapshot(_) => "TransportSnapshot",
             EventKind::InterestSync(_) => "InterestSync",
+            EventKind::Hosting(_) => "Hosting",
         }
     }
 }
@@ -3289,6 +3305,31 @@ pub enum InterestSyncEvent {
     },
 }
 
+/// Hosting cache events worth alerting on.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(test, derive(arbitrary::Arbitrary))]
+pub enum HostingEvent {
+    /// A contract with a nonzero deposit was evicted from the hosting cache.
+    ///
+    /// Funded datapods are expected to stay hosted, so this usually means
+    /// the byte budget is too small for the committed contracts competing
+    /// for it.
+    CommittedContractEvicted {
+        key: ContractKey,
+        /// Why it was evicted (`capacity`, `ttl`, ...).
+        reason: String,
+        /// Deposit the contract was scored on, in stroops.
+        deposited_xlm: u64,
+        /// CWP persistence score at eviction time.
+        score: f64,
+        size_bytes: u64,
+        /// Bytes hosted just before the eviction, against `budget_bytes`.
+        used_bytes: u64,
+        budget_bytes: u64,
+        timestamp: u64,
+    },
+}
+
 #[cfg(feature = "trace")]
 pub mod tracer {
     use std::io::IsTerminal;
@@ -33,6 +33,9 @@ use crate::config::GlobalRng;
 
 use crate::config::{GlobalExecutor, TelemetryConfig};
 use crate::message::Transaction;
+use crate::ring::HOSTING_METRICS;
+#[cfg(feature = "lepus")]
+use crate::ring::RELAYER_METRICS;
 use crate::router::RouteEvent;
 use crate::transport::TRANSPORT_METRICS;
 
@@ -285,7 +288,7 @@ impl TelemetryWorker {
                     self.flush().await;
                 },
                 _ = transport_snapshot_interval.tick() => {
-                    // Emit transport layer metrics snapshot (only if enabled)
+                    // Emit transport and hosting cache metrics snapshots (only if enabled)
                     if self.transport_snapshot_interval_secs > 0 {
                         if let Some(snapshot) = TRANSPORT_METRICS.take_snapshot() {
                             let event = TelemetryEvent {
@@ -297,6 +300,27 @@ impl TelemetryWorker {
                             };
                             self.handle_event(event).await;
                         }
+                        if let Some(snapshot) = HOSTING_METRICS.take_snapshot() {
+                            let event = TelemetryEvent {
+                                timestamp: current_timestamp_ms(),
+                                peer_id: String::new(), // Hosting cache is node-wide
+                                transaction_id: String::new(),
+                                event_type: "hosting_cache_snapshot".to_string(),
+                                event_data: serde_json::to_value(&snapshot).unwrap_or_default(),
+                            };
+                            self.handle_event(event).await;
+                        }
+                        #[cfg(feature = "lepus")]
+                        if let Some(snapshot) = RELAYER_METRICS.take_snapshot() {
+                            let event = TelemetryEvent {
+                                timestamp: current_timestamp_ms(),
+                                peer_id: String::new(), // The relayer is node-wide
+                                transaction_id: String::new(),
+                                event_type: "oracle_relayer_snapshot".to_string(),
+                                event_data: serde_json::to_value(&snapshot).unwrap_or_default(),
+                            };
+                            self.handle_event(event).await;
+                        }
                     }
                 },
             }
@@ -541,6 +565,14 @@ fn event_kind_to_string(kind: &EventKind) -> String {
                 }
             }
         }
+        EventKind::Hosting(hosting_event) => {
+            use crate::tracing::HostingEvent;
+            match hosting_event {
+                HostingEvent::CommittedContractEvicted { .. } => {
+                    "committed_contract_evicted".to_string()
+                }
+            }
+        }
     }
 }
 
@@ -1327,6 +1359,35 @@ fn event_kind_to_json(kind: &EventKind) -> serde_json::Value {
                 }
             }
         }
+        EventKind::Hosting(hosting_event) => {
+            use crate::tracing::HostingEvent;
+            match hosting_event {
+                HostingEvent::CommittedContractEvicted {
+                    key,
+                    reason,
+                    deposited_xlm,
+                    score,
+                    size_bytes,
+                    used_bytes,
+                    budget_bytes,
+                    timestamp,
+                } => {
+                    serde_json::json!({
+                        "type": "committed_contract_evicted",
+                        "contract_key": key.to_string(),
+                        "contract_id": key.id().to_string(),
+                        "reason": reason,
+                        "deposited_xlm": deposited_xlm,
+                        "score": score,
+                        "size_bytes": size_bytes,
+                        "used_bytes": used_bytes,
+                        "budget_bytes": budget_bytes,
+                        "pressure": *used_bytes as f64 / (*budget_bytes).max(1) as f64,
+                        "timestamp": timestamp,
+                    })
+                }
+            }
+        }
     }
 }
 
@@ -1442,4 +1503,34 @@ mod tests {
         assert_eq!(json["state_size"], 1024);
         assert_eq!(json["timestamp"], 67890);
     }
+
+    #[test]
+    fn test_event_kind_to_json_committed_contract_evicted() {
+        use crate::tracing::HostingEvent;
+        use freenet_stdlib::prelude::{ContractCode, ContractKey, Parameters};
+
+        let code = ContractCode::from(vec![1, 2, 3, 4]);
+        let params = Parameters::from(vec![5, 6, 7, 8]);
+        let key = ContractKey::from_params_and_code(&params, &code);
+
+        let event = EventKind::Hosting(HostingEvent::CommittedContractEvicted {
+            key,
+            reason: "capacity".to_string(),
+            deposited_xlm: 10_000_000,
+            score: 0.5,
+            size_bytes: 2048,
+            used_bytes: 1500,
+            budget_bytes: 1000,
+            timestamp: 12345,
+        });
+        assert_eq!(event_kind_to_string(&event), "committed_contract_evicted");
+        let json = event_kind_to_json(&event);
+        assert_eq!(json["type"], "committed_contract_evicted");
+        assert!(json["contract_key"].is_string());
+        assert_eq!(json["reason"], "capacity");
+        assert_eq!(json["deposited_xlm"], 10_000_000);
+        assert_eq!(json["score"], 0.5);
+        assert_eq!(json["pressure"], 1.5);
+        assert_eq!(json["timestamp"], 12345);
+    }
 }
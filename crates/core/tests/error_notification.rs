@@ -362,6 +362,8 @@ async fn test_connection_drop_error_notification() -> anyhow::Result<()> {
             blocked_addresses: None,
             transient_budget: None,
             transient_ttl_secs: None,
+            hosting_budget: None,
+            hosting_min_ttl_secs: None,
             total_bandwidth_limit: None,
             min_bandwidth_per_connection: None,
             ..Default::default()
@@ -419,6 +421,8 @@ async fn test_connection_drop_error_notification() -> anyhow::Result<()> {
             blocked_addresses: None,
             transient_budget: None,
             transient_ttl_secs: None,
+            hosting_budget: None,
+            hosting_min_ttl_secs: None,
             total_bandwidth_limit: None,
             min_bandwidth_per_connection: None,
             ..Default::default()
@@ -0,0 +1,271 @@
+//! End-to-end test of the Lepus deposit relayer.
+//!
+//! A gateway and a peer run with the deposit-index configured. One of them
+//! relays a deposit proof signed by fixture validators, read from a
+//! `MockStellarProofSource` instead of Stellar RPC; the deposit-index
+//! contract verifies it like a proof of a real ledger, and the deposit has
+//! to reach the commitment of the contract hosted on the peer.
+#![cfg(all(feature = "lepus", feature = "testing"))]
+
+use anyhow::{bail, ensure};
+use freenet::{
+    dev_tool::{
+        set_test_proof_source, AuthToken, ClientId, MockStellarProofSource, ProofFixtures,
+        TransportKeypair,
+    },
+    local_node::NodeConfig,
+    server::{serve_gateway, serve_gateway_for_test, AttestedContract},
+    test_utils::{create_empty_todo_list, load_contract, load_deposit_index_contract, make_put},
+};
+use freenet_stdlib::{
+    client_api::{ClientRequest, ContractResponse, HostResponse, WebApi},
+    prelude::*,
+};
+use futures::FutureExt;
+use std::{
+    net::{Ipv4Addr, TcpListener},
+    path::Path,
+    time::Duration,
+};
+use tokio::time::timeout;
+use tokio_tungstenite::connect_async;
+use tracing::info;
+
+const TEST_CONTRACT: &str = "test-contract-integration";
+
+/// Ledger the fixture proof attests to.
+const DEPOSIT_LEDGER: u32 = 1_000;
+
+/// Deposit relayed for the test contract (stroops).
+const DEPOSIT_STROOPS: i128 = 5_000_000_000;
+
+/// How long the deposit may take to land: the relayer waits up to 30 s
+/// before it starts, then backfills.
+const DEPOSIT_TIMEOUT: Duration = Duration::from_secs(120);
+
+fn node_config(
+    dir: &Path,
+    ws_port: u16,
+    network_port: Option<u16>,
+    gateway: Option<&freenet::config::InlineGwConfig>,
+    location: f64,
+) -> anyhow::Result<freenet::config::ConfigArgs> {
+    let keypair = TransportKeypair::new();
+    let keypair_path = dir.join("private.pem");
+    keypair.save(&keypair_path)?;
+    keypair.public().save(dir.join("public.pem"))?;
+    Ok(freenet::config::ConfigArgs {
+        ws_api: freenet::config::WebsocketApiArgs {
+            address: Some(Ipv4Addr::LOCALHOST.into()),
+            ws_api_port: Some(ws_port),
+            token_ttl_seconds: None,
+            token_cleanup_interval_seconds: None,
+        },
+        network_api: freenet::config::NetworkArgs {
+            public_address: Some(Ipv4Addr::LOCALHOST.into()),
+            public_port: network_port,
+            is_gateway: gateway.is_none(),
+            skip_load_from_network: true,
+            gateways: Some(
+                gateway
+                    .map(serde_json::to_string)
+                    .transpose()?
+                    .into_iter()
+                    .collect(),
+            ),
+            location: Some(location),
+            ignore_protocol_checking: true,
+            address: Some(Ipv4Addr::LOCALHOST.into()),
+            network_port,
+            ..Default::default()
+        },
+        config_paths: freenet::config::ConfigPathsArgs {
+            config_dir: Some(dir.to_path_buf()),
+            data_dir: Some(dir.to_path_buf()),
+            log_dir: Some(dir.to_path_buf()),
+        },
+        secrets: freenet::config::SecretArgs {
+            transport_keypair: Some(keypair_path),
+            ..Default::default()
+        },
+        ..Default::default()
+    })
+}
+
+async fn put(
+    client: &mut WebApi,
+    contract: ContractContainer,
+    state: Vec<u8>,
+    subscribe: bool,
+) -> anyhow::Result<()> {
+    let expected = contract.key();
+    make_put(client, WrappedState::from(state), contract, subscribe).await?;
+    match timeout(Duration::from_secs(60), client.recv()).await {
+        Ok(Ok(HostResponse::ContractResponse(ContractResponse::PutResponse { key }))) => {
+            ensure!(key == expected, "PUT returned {key}, expected {expected}");
+            Ok(())
+        }
+        other => bail!("unexpected PUT response: {other:?}"),
+    }
+}
+
+/// The peer's commitment for `instance_id`, from its admin score report.
+async fn deposited(
+    ws_port: u16,
+    token: &AuthToken,
+    instance_id: &ContractInstanceId,
+) -> anyhow::Result<u64> {
+    let url = format!("http://127.0.0.1:{ws_port}/v1/node/hosting/score-report?key={instance_id}");
+    let report: serde_json::Value = reqwest::Client::new()
+        .get(&url)
+        .bearer_auth(token.as_str())
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    report["deposited_xlm"]
+        .as_u64()
+        .ok_or_else(|| anyhow::anyhow!("no deposited_xlm in {report}"))
+}
+
+/// Note: This test uses real TCP networking, so it cannot use `start_paused = true`.
+#[test_log::test(tokio::test(flavor = "current_thread"))]
+async fn test_relayed_deposit_reaches_hosting_cache() -> anyhow::Result<()> {
+    let fixtures = ProofFixtures::new(3, 2);
+    let deposit_index =
+        load_deposit_index_contract(serde_json::to_vec(&fixtures.params())?.into())?;
+    let index_key = deposit_index.key();
+    let contract = load_contract(TEST_CONTRACT, vec![].into())?;
+    let instance_id = *contract.key().id();
+
+    // Both nodes subscribe to the deposit-index and are configured to relay;
+    // only the first relayer started reads the fixture proof, the other one
+    // fails against the unreachable RPC endpoint
+    std::env::set_var(
+        "LEPUS_DEPOSIT_INDEX_KEY",
+        hex::encode(index_key.id().as_bytes()),
+    );
+    std::env::set_var(
+        "LEPUS_DEPOSIT_INDEX_CODE_HASH",
+        hex::encode(**index_key.code_hash()),
+    );
+    std::env::set_var("LEPUS_RPC_URL", "http://127.0.0.1:9");
+    std::env::set_var("LEPUS_SOROBAN_CONTRACT_ID", hex::encode([0xAA; 32]));
+    std::env::set_var("LEPUS_POLL_INTERVAL_SECS", "5");
+    let proof = fixtures.deposit_proof(DEPOSIT_LEDGER, &[(instance_id, DEPOSIT_STROOPS)]);
+    set_test_proof_source(MockStellarProofSource::new(
+        [(DEPOSIT_LEDGER, proof)].into_iter().collect(),
+    ));
+
+    let gateway_network_socket = TcpListener::bind("127.0.0.1:0")?;
+    let gateway_ws_socket = TcpListener::bind("127.0.0.1:0")?;
+    let peer_ws_socket = TcpListener::bind("127.0.0.1:0")?;
+    let gateway_port = gateway_network_socket.local_addr()?.port();
+    let gateway_ws_port = gateway_ws_socket.local_addr()?.port();
+    let peer_ws_port = peer_ws_socket.local_addr()?.port();
+
+    let gateway_dir = tempfile::tempdir()?;
+    let gateway_config = node_config(
+        gateway_dir.path(),
+        gateway_ws_port,
+        Some(gateway_port),
+        None,
+        0.25,
+    )?;
+    let gateway_info = freenet::config::InlineGwConfig {
+        address: (Ipv4Addr::LOCALHOST, gateway_port).into(),
+        location: Some(0.25),
+        public_key_path: gateway_dir.path().join("public.pem"),
+    };
+    let peer_dir = tempfile::tempdir()?;
+    let peer_config = node_config(
+        peer_dir.path(),
+        peer_ws_port,
+        None,
+        Some(&gateway_info),
+        0.75,
+    )?;
+
+    // Free the sockets before starting nodes
+    std::mem::drop(gateway_network_socket);
+    std::mem::drop(gateway_ws_socket);
+    std::mem::drop(peer_ws_socket);
+
+    let gateway = async {
+        let config = gateway_config.build().await?;
+        let node = NodeConfig::new(config.clone())
+            .await?
+            .build(serve_gateway(config.ws_api).await?)
+            .await?;
+        node.run().await
+    }
+    .boxed_local();
+
+    // The admin routes serve the ring registered last, so the peer starts
+    // after the gateway and the score report is the peer's
+    // The admin routes require a token the peer's gateway issued
+    let token = AuthToken::generate();
+    let peer_token = token.clone();
+    let peer = async move {
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        let config = peer_config.build().await?;
+        let (gw, ws_proxy) = serve_gateway_for_test(config.ws_api).await?;
+        gw.attested_contracts().insert(
+            peer_token,
+            AttestedContract::new(instance_id, ClientId::next()),
+        );
+        let node = NodeConfig::new(config.clone())
+            .await?
+            .build([Box::new(gw), Box::new(ws_proxy)])
+            .await?;
+        node.run().await
+    }
+    .boxed_local();
+
+    let test = timeout(Duration::from_secs(240), async {
+        info!("Waiting for nodes to start up and connect...");
+        tokio::time::sleep(Duration::from_secs(20)).await;
+
+        let url =
+            format!("ws://localhost:{peer_ws_port}/v1/contract/command?encodingProtocol=native");
+        let (ws_stream, _) = connect_async(&url).await?;
+        let mut client = WebApi::start(ws_stream);
+
+        put(&mut client, contract, create_empty_todo_list(), false).await?;
+        let empty_index = serde_json::json!({"version": 0, "last_ledger_seq": 0, "deposits": []});
+        put(
+            &mut client,
+            deposit_index,
+            serde_json::to_vec(&empty_index)?,
+            true,
+        )
+        .await?;
+        info!("Contracts published, waiting for the relayed deposit");
+
+        let deadline = tokio::time::Instant::now() + DEPOSIT_TIMEOUT;
+        loop {
+            match deposited(peer_ws_port, &token, &instance_id).await {
+                Ok(stroops) if stroops > 0 => {
+                    assert_eq!(stroops, DEPOSIT_STROOPS as u64);
+                    break;
+                }
+                Ok(_) => {}
+                Err(e) => info!("No commitment yet: {e}"),
+            }
+            if tokio::time::Instant::now() > deadline {
+                bail!("relayed deposit never reached the peer's hosting cache");
+            }
+            tokio::time::sleep(Duration::from_secs(2)).await;
+        }
+
+        let _ = client.send(ClientRequest::Disconnect { cause: None }).await;
+        Ok::<(), anyhow::Error>(())
+    });
+
+    tokio::select! {
+        result = gateway => bail!("gateway exited unexpectedly: {result:?}"),
+        result = peer => bail!("peer exited unexpectedly: {result:?}"),
+        result = test => result?,
+    }
+}
@@ -114,6 +114,8 @@ async fn test_operations_blocked_before_join() -> anyhow::Result<()> {
             blocked_addresses: None,
             transient_budget: None,
             transient_ttl_secs: None,
+            hosting_budget: None,
+            hosting_min_ttl_secs: None,
             total_bandwidth_limit: None,
             min_bandwidth_per_connection: None,
             ..Default::default()
@@ -170,6 +172,8 @@ async fn test_operations_blocked_before_join() -> anyhow::Result<()> {
             blocked_addresses: None,
             transient_budget: None,
             transient_ttl_secs: None,
+            hosting_budget: None,
+            hosting_min_ttl_secs: None,
             total_bandwidth_limit: None,
             min_bandwidth_per_connection: None,
             ..Default::default()
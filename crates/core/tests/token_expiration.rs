@@ -40,6 +40,8 @@ async fn create_test_config(
             network_port: Some(network_socket.local_addr()?.port()),
             transient_budget: None,
             transient_ttl_secs: None,
+            hosting_budget: None,
+            hosting_min_ttl_secs: None,
             ..Default::default()
         },
         config_paths: freenet::config::ConfigPathsArgs {
@@ -135,6 +137,8 @@ async fn test_default_token_configuration() -> TestResult {
                 network_port: Some(network_socket.local_addr()?.port()),
                 transient_budget: None,
                 transient_ttl_secs: None,
+                hosting_budget: None,
+                hosting_min_ttl_secs: None,
                 ..Default::default()
             },
             config_paths: freenet::config::ConfigPathsArgs {
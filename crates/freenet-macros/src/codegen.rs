@@ -287,6 +287,8 @@ fn generate_node_setup(args: &FreenetTestArgs) -> TokenStream {
                             blocked_addresses: None,
                             transient_budget: None,
                             transient_ttl_secs: None,
+                            hosting_budget: None,
+                            hosting_min_ttl_secs: None,
                             total_bandwidth_limit: None,
                             min_bandwidth_per_connection: None,
                             ..Default::default()
@@ -425,6 +427,8 @@ fn generate_node_setup(args: &FreenetTestArgs) -> TokenStream {
                             blocked_addresses: None,
                             transient_budget: None,
                             transient_ttl_secs: None,
+                            hosting_budget: None,
+                            hosting_min_ttl_secs: None,
                             total_bandwidth_limit: None,
                             min_bandwidth_per_connection: None,
                             ..Default::default()